@@ -520,7 +520,7 @@ fn type_to_ide_string(sp!(_, t): &Type) -> String {
                 type_to_ide_string(ret)
             )
         }
-        Type_::Anything => "_".to_string(),
+        Type_::Anything | Type_::Hole => "_".to_string(),
         Type_::Var(_) => "invalid type (var)".to_string(),
         Type_::UnresolvedError => "unknown type (unresolved)".to_string(),
     }
@@ -1216,7 +1216,7 @@ fn get_mod_outer_defs(
         // process field structs first
         let mut field_defs = vec![];
         let mut field_types = vec![];
-        if let StructFields::Defined(fields) = &def.fields {
+        if let StructFields::Defined(_, fields) = &def.fields {
             for (fpos, fname, (_, t)) in fields {
                 let start = match get_start_loc(&fpos, files, file_id_mapping) {
                     Some(s) => s,
@@ -1585,7 +1585,11 @@ impl<'a> ParsingSymbolicator<'a> {
                 self.exp_symbols(e1);
                 self.exp_symbols(e2);
             }
-            E::Abort(e) => self.exp_symbols(e),
+            E::Abort(oe) => {
+                if let Some(e) = oe.as_ref() {
+                    self.exp_symbols(e)
+                }
+            }
             E::Return(_, oe) => {
                 if let Some(e) = oe.as_ref() {
                     self.exp_symbols(e)
@@ -1970,7 +1974,7 @@ impl<'a> TypingSymbolicator<'a> {
             self.add_type_param(&stp.param, &mut tp_scope);
         }
         self.type_params = tp_scope;
-        if let StructFields::Defined(fields) = &struct_def.fields {
+        if let StructFields::Defined(_, fields) = &struct_def.fields {
             for (fpos, fname, (_, t)) in fields {
                 self.add_type_id_use_def(t);
                 // enter self-definition for field name (unwrap safe - done when inserting def)
@@ -2146,7 +2150,7 @@ impl<'a> TypingSymbolicator<'a> {
                 var: v,
             } => self.add_local_use_def(&v.value.name, &v.loc, scope),
             E::Use(v) => self.add_local_use_def(&v.value.name, &v.loc, scope),
-            E::Constant(mod_ident, name) => {
+            E::Constant(mod_ident, name) | E::ErrorConstant(mod_ident, name) => {
                 self.add_const_use_def(mod_ident, &name.value(), &name.loc())
             }
             E::ModuleCall(mod_call) => self.mod_call_symbols(mod_call, scope),
@@ -2154,7 +2158,7 @@ impl<'a> TypingSymbolicator<'a> {
                 use BuiltinFunction_ as BF;
                 match &builtin_fun.value {
                     BF::Freeze(t) => self.add_type_id_use_def(t),
-                    BF::Assert(_) => (),
+                    BF::Assert(..) => (),
                 }
                 self.exp_symbols(exp, scope);
             }
@@ -0,0 +1,35 @@
+//# init --edition 2024.alpha
+
+//# publish
+module 0x42::m {
+
+    public fun t0(): u64 {
+        while (true) { break 5 }
+    }
+
+    public fun t1(n: u64): u64 {
+        let mut i = 0;
+        let x = while (!false) {
+            if (i == n) break i * 2;
+            i = i + 1;
+        };
+        x
+    }
+
+    public fun t2(): u64 {
+        'outer: while (true && true) {
+            while (true) { break 'outer 10 };
+        }
+    }
+
+}
+
+//# run
+module 0x42::main {
+use 0x42::m;
+fun main() {
+    assert!(m::t0() == 5, 0);
+    assert!(m::t1(3) == 6, 1);
+    assert!(m::t2() == 10, 2);
+}
+}
@@ -0,0 +1,72 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use move_compiler::{Compiler, PASS_TYPING};
+
+const NUM_CONSTANTS: usize = 1000;
+const NUM_VECTOR_ELEMENTS: usize = 5000;
+
+// A module with many constants, and functions whose bodies are dominated by inference: generic
+// calls, comparisons, and numeric literals whose type is only known from their use
+fn generated_module() -> String {
+    let mut module = String::from("module 0x42::m {\n");
+    for i in 0..NUM_CONSTANTS {
+        writeln!(module, "    const C{i}: u64 = {i} * 2 + 1;").unwrap();
+        writeln!(
+            module,
+            "    const V{i}: vector<u8> = vector[{}, 1, 2];",
+            i % 256
+        )
+        .unwrap();
+    }
+    module.push_str("    fun id<T>(x: T): T { x }\n");
+    module.push_str("    fun pair<T, U>(x: T, y: U): (T, U) { (x, y) }\n");
+    for i in 0..NUM_CONSTANTS {
+        writeln!(
+            module,
+            "    fun f{i}(): bool {{ let (a, b) = pair(id(C{i}), id(V{i})); \
+            a == id(1) && b != vector[] && id(a) + 1 > 0 }}"
+        )
+        .unwrap();
+    }
+    module.push_str("}\n");
+    module
+}
+
+// A module with a single large vector literal, whose element types are all inferred, as is common
+// for embedded byte tables
+fn generated_vector_module() -> String {
+    let elements = (0..NUM_VECTOR_ELEMENTS)
+        .map(|i| (i % 256).to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("module 0x42::m {{\n    public fun table(): vector<u8> {{ vector[{elements}] }}\n}}\n")
+}
+
+fn bench_typing(c: &mut Criterion, name: &str, module: String) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("m.move");
+    std::fs::write(&path, module).unwrap();
+    let paths = vec![path.to_string_lossy().to_string()];
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let (_, res) =
+                Compiler::from_files(paths.clone(), vec![], BTreeMap::<String, _>::new())
+                    .run::<PASS_TYPING>()
+                    .unwrap();
+            let (_, compiler) = res.unwrap();
+            compiler
+        })
+    });
+}
+
+fn typing(c: &mut Criterion) {
+    bench_typing(c, "typing_many_constants", generated_module());
+    bench_typing(c, "typing_large_vector_literal", generated_vector_module());
+}
+
+criterion_group!(typing_benches, typing);
+criterion_main!(typing_benches);
@@ -521,7 +521,8 @@ fn constant_(
 fn check_constant_value(context: &mut Context, e: &H::Exp) {
     use H::UnannotatedExp_ as E;
     match &e.exp.value {
-        E::Value(_) => (),
+        // the error was reported when the constant was typed
+        E::Value(_) | E::UnresolvedError => (),
         _ => context.env.add_diag(diag!(
             BytecodeGeneration::UnfoldableConstant,
             (e.exp.loc, CANNOT_FOLD)
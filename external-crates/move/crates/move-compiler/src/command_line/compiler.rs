@@ -4,7 +4,7 @@
 
 use crate::{
     cfgir::{self, visitor::AbsIntVisitorObj},
-    command_line::{DEFAULT_OUTPUT_DIR, MOVE_COMPILED_INTERFACES_DIR},
+    command_line::{self as cli, DEFAULT_OUTPUT_DIR, MOVE_COMPILED_INTERFACES_DIR},
     compiled_unit,
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::{
@@ -12,11 +12,13 @@ use crate::{
         *,
     },
     editions::Edition,
-    expansion, hlir, interface_generator, naming, parser,
+    expansion, hlir, interface_generator,
+    naming::{self, ast::BuiltinTypeName_},
+    parser,
     parser::{comments::*, *},
     shared::{
         CompilationEnv, Flags, IndexedPackagePath, NamedAddressMap, NamedAddressMaps,
-        NumericalAddress, PackageConfig, PackagePaths,
+        NumericalAddress, PackageConfig, PackagePaths, WrapperAccessors, DEFAULT_OFF_FILTERS,
     },
     to_bytecode,
     typing::{self, visitor::TypingVisitorObj},
@@ -52,6 +54,12 @@ pub struct Compiler<'a> {
     /// Predefined filter for compiler warnings.
     warning_filter: Option<WarningFilters>,
     known_warning_filters: Vec<(/* Prefix */ Option<Symbol>, Vec<WarningFilter>)>,
+    primitive_definers: Vec<(
+        BuiltinTypeName_,
+        /* address */ Symbol,
+        /* module */ Symbol,
+    )>,
+    wrapper_types: Vec<WrapperAccessors>,
     package_configs: BTreeMap<Symbol, PackageConfig>,
     default_config: Option<PackageConfig>,
 }
@@ -159,6 +167,8 @@ impl<'a> Compiler<'a> {
             visitors: vec![],
             warning_filter: None,
             known_warning_filters: vec![],
+            primitive_definers: vec![],
+            wrapper_types: vec![],
             package_configs,
             default_config: None,
         })
@@ -252,6 +262,26 @@ impl<'a> Compiler<'a> {
         self
     }
 
+    /// Registers the module `address::module` as the definer of the primitive type `prim`, as if it
+    /// were annotated with '#[defines_primitive(prim)]'. See `CompilationEnv::add_primitive_definer`
+    pub fn add_primitive_definer(
+        mut self,
+        prim: BuiltinTypeName_,
+        address: impl Into<Symbol>,
+        module: impl Into<Symbol>,
+    ) -> Self {
+        self.primitive_definers
+            .push((prim, address.into(), module.into()));
+        self
+    }
+
+    /// Registers a type whose value is accessed through functions. See
+    /// `CompilationEnv::add_wrapper_type`
+    pub fn add_wrapper_type(mut self, wrapper: WrapperAccessors) -> Self {
+        self.wrapper_types.push(wrapper);
+        self
+    }
+
     /// Sets the PackageConfig for files without a specified package
     pub fn set_default_config(mut self, config: PackageConfig) -> Self {
         assert!(self.default_config.is_none());
@@ -276,6 +306,8 @@ impl<'a> Compiler<'a> {
             visitors,
             warning_filter,
             known_warning_filters,
+            primitive_definers,
+            wrapper_types,
             package_configs,
             default_config,
         } = self;
@@ -284,6 +316,18 @@ impl<'a> Compiler<'a> {
             interface_files_dir_opt,
             &compiled_module_named_address_mapping,
         )?;
+        // only warnings that are off by default can be enabled by name
+        if let Some(unknown) = flags
+            .warn()
+            .iter()
+            .find(|name| !DEFAULT_OFF_FILTERS.contains(&name.as_str()))
+        {
+            anyhow::bail!(
+                "Unknown warning '{unknown}' for '--{}'. Expected one of: {}",
+                cli::WARN,
+                DEFAULT_OFF_FILTERS.join(", ")
+            );
+        }
         let mut compilation_env =
             CompilationEnv::new(flags, visitors, package_configs, default_config);
         if let Some(filter) = warning_filter {
@@ -292,6 +336,12 @@ impl<'a> Compiler<'a> {
         for (prefix, filters) in known_warning_filters {
             compilation_env.add_custom_known_filters(prefix, filters)?;
         }
+        for (prim, address, module) in primitive_definers {
+            compilation_env.add_primitive_definer(prim, address, module);
+        }
+        for wrapper in wrapper_types {
+            compilation_env.add_wrapper_type(wrapper);
+        }
 
         let (source_text, pprog, comments) =
             with_large_stack!(parse_program(&mut compilation_env, maps, targets, deps))?;
@@ -324,8 +374,9 @@ impl<'a> Compiler<'a> {
     }
 
     pub fn check_and_report(self) -> anyhow::Result<FilesSourceText> {
-        let (files, res) = self.check()?;
-        unwrap_or_report_diagnostics(&files, res);
+        let format = self.flags.diagnostics_format();
+        let (files, res) = self.run_and_print_typed_ast()?;
+        unwrap_or_report_diagnostics_in_format(&files, res.map(|_| ()), format);
         Ok(files)
     }
 
@@ -343,11 +394,34 @@ impl<'a> Compiler<'a> {
     }
 
     pub fn build_and_report(self) -> anyhow::Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)> {
-        let (files, units_res) = self.build()?;
-        let (units, warnings) = unwrap_or_report_diagnostics(&files, units_res);
-        report_warnings(&files, warnings);
+        let format = self.flags.diagnostics_format();
+        let (files, res) = self.run_and_print_typed_ast()?;
+        let units_res = res.map(|(_comments, stepped)| stepped.into_compiled_units());
+        let (units, warnings) = unwrap_or_report_diagnostics_in_format(&files, units_res, format);
+        report_warnings_in_format(&files, warnings, format);
         Ok((files, units))
     }
+
+    /// Runs all passes. If `Flags::print_typed_ast` is set, the typed program is printed to stdout
+    /// once typing is done, even if typing reported errors
+    fn run_and_print_typed_ast(
+        self,
+    ) -> anyhow::Result<(
+        FilesSourceText,
+        Result<(CommentMap, SteppedCompiler<'a, PASS_COMPILATION>), Diagnostics>,
+    )> {
+        let Some(filter) = self.flags.print_typed_ast().map(|f| f.map(str::to_owned)) else {
+            return self.run::<PASS_COMPILATION>();
+        };
+        let (files, res) = self.run::<PASS_TYPING>()?;
+        let res = res.and_then(|(comments, compiler)| {
+            let (compiler, prog) = compiler.into_ast();
+            print!("{}", typing::ast::print_program(&prog, filter.as_deref()));
+            let compiler = compiler.at_typing(prog).run::<PASS_COMPILATION>()?;
+            Ok((comments, compiler))
+        });
+        Ok((files, res))
+    }
 }
 
 impl<'a, const P: Pass> SteppedCompiler<'a, P> {
@@ -386,6 +460,10 @@ impl<'a, const P: Pass> SteppedCompiler<'a, P> {
     pub fn compilation_env(&mut self) -> &mut CompilationEnv {
         &mut self.compilation_env
     }
+
+    pub fn into_compilation_env(self) -> CompilationEnv {
+        self.compilation_env
+    }
 }
 
 macro_rules! ast_stepped_compilers {
@@ -459,17 +537,20 @@ macro_rules! ast_stepped_compilers {
                 }
 
                 pub fn check_and_report(self, files: &FilesSourceText)  {
+                    let format = self.compilation_env.flags().diagnostics_format();
                     let errors_result = self.check();
-                    unwrap_or_report_diagnostics(&files, errors_result);
+                    unwrap_or_report_diagnostics_in_format(&files, errors_result, format);
                 }
 
                 pub fn build_and_report(
                     self,
                     files: &FilesSourceText,
                 ) -> Vec<AnnotatedCompiledUnit> {
+                    let format = self.compilation_env.flags().diagnostics_format();
                     let units_result = self.build();
-                    let (units, warnings) = unwrap_or_report_diagnostics(&files, units_result);
-                    report_warnings(&files, warnings);
+                    let (units, warnings) =
+                        unwrap_or_report_diagnostics_in_format(&files, units_result, format);
+                    report_warnings_in_format(&files, warnings, format);
                     units
                 }
             }
@@ -827,6 +908,52 @@ pub fn move_check_for_errors(
     diags
 }
 
+/// Runs the compiler on the targets through naming, returning the naming AST along with the
+/// compilation environment holding the diagnostics reported so far. The AST is returned even if
+/// there are errors, unless compilation could not continue past parsing.
+pub fn compile_to_naming<'a, Paths: Into<Symbol>, NamedAddress: Into<Symbol>>(
+    targets: Vec<PackagePaths<Paths, NamedAddress>>,
+    deps: Vec<PackagePaths<Paths, NamedAddress>>,
+    flags: Flags,
+    pre_compiled_lib: Option<&'a FullyCompiledProgram>,
+) -> anyhow::Result<(
+    FilesSourceText,
+    Result<(CompilationEnv, naming::ast::Program), Diagnostics>,
+)> {
+    let (files, res) = Compiler::from_package_paths(targets, deps)?
+        .set_flags(flags)
+        .set_pre_compiled_lib_opt(pre_compiled_lib)
+        .run::<PASS_NAMING>()?;
+    let res = res.map(|(_comments, compiler)| {
+        let (compiler, nprog) = compiler.into_ast();
+        (compiler.into_compilation_env(), nprog)
+    });
+    Ok((files, res))
+}
+
+/// Runs the compiler on the targets through typing, returning the typing AST along with the
+/// compilation environment holding the diagnostics reported so far. The AST is returned even if
+/// there are errors, unless compilation could not continue past parsing.
+pub fn compile_to_typing<'a, Paths: Into<Symbol>, NamedAddress: Into<Symbol>>(
+    targets: Vec<PackagePaths<Paths, NamedAddress>>,
+    deps: Vec<PackagePaths<Paths, NamedAddress>>,
+    flags: Flags,
+    pre_compiled_lib: Option<&'a FullyCompiledProgram>,
+) -> anyhow::Result<(
+    FilesSourceText,
+    Result<(CompilationEnv, typing::ast::Program), Diagnostics>,
+)> {
+    let (files, res) = Compiler::from_package_paths(targets, deps)?
+        .set_flags(flags)
+        .set_pre_compiled_lib_opt(pre_compiled_lib)
+        .run::<PASS_TYPING>()?;
+    let res = res.map(|(_comments, compiler)| {
+        let (compiler, tprog) = compiler.into_ast();
+        (compiler.into_compilation_env(), tprog)
+    });
+    Ok((files, res))
+}
+
 //**************************************************************************************************
 // Translations
 //**************************************************************************************************
@@ -20,6 +20,8 @@ pub const SHADOW_SHORT: char = 'S';
 pub const SILENCE_WARNINGS: &str = "silence-warnings";
 pub const SILENCE_WARNINGS_SHORT: char = 'w';
 
+pub const JSON_ERRORS: &str = "json-errors";
+
 pub const SOURCE_MAP: &str = "source-map";
 pub const SOURCE_MAP_SHORT: char = 'm';
 
@@ -31,6 +33,22 @@ pub const VERIFY_SHORT: char = 'v';
 
 pub const WARNINGS_ARE_ERRORS: &str = "warnings-are-errors";
 
+pub const WARNINGS_ARE_ERRORS_EXCEPT: &str = "warnings-are-errors-except";
+
+pub const WARN: &str = "warn";
+
+pub const LARGE_COPY_FIELD_THRESHOLD: &str = "large-copy-field-threshold";
+
+pub const LARGE_EQUALITY_FIELD_THRESHOLD: &str = "large-equality-field-threshold";
+
+pub const POSITIONAL_STRUCT_FIELD_THRESHOLD: &str = "positional-struct-field-threshold";
+
+pub const MAX_MACRO_EXPANSION_DEPTH: &str = "max-macro-expansion-depth";
+
+pub const NO_IMPLICIT_METHODS: &str = "no-implicit-methods";
+
+pub const PRINT_TYPED_AST: &str = "print-typed-ast";
+
 pub const GENERATE_MIGRATION_DIFF: &str = "generate-migration-diff";
 
 pub const BYTECODE_VERSION: &str = "bytecode-version";
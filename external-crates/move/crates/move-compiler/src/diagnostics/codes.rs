@@ -208,6 +208,8 @@ codes!(
             { msg: "invalid visibility modifier", severity: NonblockingError },
         InvalidUseFun: { msg: "invalid 'use fun' declaration", severity: NonblockingError },
         UnknownAttribute: { msg: "unknown attribute", severity: Warning },
+        ShadowedMethod: { msg: "'use fun' shadows a method", severity: Warning },
+        InvalidSyntaxMethod: { msg: "invalid 'syntax' method", severity: NonblockingError },
     ],
     // errors name resolution, mostly expansion/translate and naming/translate
     NameResolution: [
@@ -228,6 +230,11 @@ codes!(
         UnboundLabel: { msg: "unbound label", severity: BlockingError },
         InvalidMut: { msg: "invalid 'mut' declaration", severity: NonblockingError },
         InvalidMacroParameter: { msg: "invalid macro parameter", severity: NonblockingError },
+        AmbiguousMethod: { msg: "ambiguous method", severity: Warning },
+        ShadowedVariable: { msg: "shadowed variable", severity: Warning },
+        ShadowedUseFun: { msg: "shadowed method", severity: Warning },
+        InvalidTypeHole: { msg: "invalid usage of type placeholder", severity: BlockingError },
+        InvalidConstantAccess: { msg: "invalid access of constant", severity: NonblockingError },
     ],
     // errors for typing rules. mostly typing/translate
     TypeSafety: [
@@ -270,6 +277,24 @@ codes!(
         UnexpectedFunctionType: { msg: "invalid usage of lambda type", severity: BlockingError },
         UnexpectedLambda: { msg: "invalid usage of lambda", severity: BlockingError },
         CannotExpandMacro: { msg: "unable to expand macro function", severity: BlockingError },
+        DeprecatedUsage: { msg: "use of deprecated item", severity: Warning },
+        ConstantEvaluation: { msg: "invalid constant value", severity: NonblockingError },
+        RedundantTypeArguments: { msg: "redundant type arguments", severity: Warning },
+        InvalidIndexAccess: { msg: "invalid index access", severity: BlockingError },
+        LargeCopyInLoop: { msg: "implicit copy of a large struct in a loop", severity: Warning },
+        InvalidConstFunction: { msg: "invalid '#[const_fun]' function", severity: BlockingError },
+        CyclicConstFunction:
+            { msg: "recursive '#[const_fun]' function", severity: BlockingError },
+        ImplicitFreezeInEquality:
+            { msg: "implicit freeze of a mutable reference in equality", severity: Warning },
+        LargeStructEquality: { msg: "equality of a large struct", severity: Warning },
+        InvalidTestOnlyUsage:
+            { msg: "invalid usage of a test-only item", severity: BlockingError },
+        LargePositionalStruct:
+            { msg: "construction of a large positional struct", severity: Warning },
+        ReadOnlyMutParam:
+            { msg: "mutable reference parameter is never used mutably", severity: Warning },
+        InvalidErrorConstant: { msg: "invalid '#[error]' constant", severity: NonblockingError },
     ],
     // errors for ability rules. mostly typing/translate
     AbilitySafety: [
@@ -313,6 +338,9 @@ codes!(
         MutModifier: { msg: "unused 'mut' modifiers", severity: Warning },
         MutReference: { msg: "unused mutable reference '&mut'", severity: Warning },
         MutParam: { msg: "unused mutable reference '&mut' parameter", severity: Warning },
+        TestOnlyFunction: { msg: "function only used in tests", severity: Warning },
+        PackageFunction: { msg: "unused 'public(package)' function", severity: Warning },
+        Struct: { msg: "unused struct", severity: Warning },
     ],
     Attributes: [
         Duplicate: { msg: "invalid duplicate attribute", severity: NonblockingError },
@@ -341,6 +369,7 @@ codes!(
     Migration: [
         NeedsPublic: { msg: "move 2024 migration: public struct", severity: BlockingError },
         NeedsLetMut: { msg: "move 2024 migration: let mut", severity: BlockingError },
+        NeedsAssertMacro: { msg: "move 2024 migration: assert macro", severity: BlockingError },
     ]
 );
 
@@ -12,8 +12,8 @@ use crate::{
     },
     shared::{
         ast_debug::AstDebug, known_attributes, FILTER_UNUSED_CONST, FILTER_UNUSED_FUNCTION,
-        FILTER_UNUSED_MUT_PARAM, FILTER_UNUSED_MUT_REF, FILTER_UNUSED_STRUCT_FIELD,
-        FILTER_UNUSED_TYPE_PARAMETER,
+        FILTER_UNUSED_MUT_PARAM, FILTER_UNUSED_MUT_REF, FILTER_UNUSED_PACKAGE_FUNCTION,
+        FILTER_UNUSED_STRUCT, FILTER_UNUSED_STRUCT_FIELD, FILTER_UNUSED_TYPE_PARAMETER,
     },
 };
 use codespan_reporting::{
@@ -29,7 +29,9 @@ use csr::files::Files;
 use move_command_line_common::{env::read_env_var, files::FileHash};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{
+    cmp::Reverse,
     collections::{BTreeMap, HashMap, HashSet},
     io::Write,
     iter::FromIterator,
@@ -56,6 +58,8 @@ pub struct Diagnostic {
     primary_label: (Loc, String),
     secondary_labels: Vec<(Loc, String)>,
     notes: Vec<String>,
+    /// Machine-applicable edits, each replacing the source at the location with the text
+    fixes: Vec<(Loc, String)>,
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
@@ -91,17 +95,61 @@ enum UnprefixedWarningFilters {
     Empty,
 }
 
+/// How diagnostics are reported by the compiler driver
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum DiagnosticsFormat {
+    /// Rendered for people to read, with source snippets
+    #[default]
+    Human,
+    /// One JSON object per line, see `JsonDiagnostic`
+    Json,
+}
+
+/// The JSON representation of a diagnostic. The shape of this is considered stable, and should
+/// only be extended
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    code: String,
+    severity: &'static str,
+    category: u8,
+    number: u8,
+    message: &'static str,
+    primary_label: JsonLabel,
+    secondary_labels: Vec<JsonLabel>,
+    notes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct JsonLabel {
+    file: String,
+    file_hash: String,
+    byte_start: usize,
+    byte_end: usize,
+    start: JsonPosition,
+    end: JsonPosition,
+    message: String,
+}
+
+/// 1-indexed line and column
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
+/// An edit to a single line, replacing the bytes `start..end` of the line with `text`
 #[derive(PartialEq, Eq, Clone, Debug)]
-enum MigrationChange {
-    AddMut,
-    AddPublic,
+struct MigrationChange {
+    start: usize,
+    end: usize,
+    text: String,
 }
 
 // All of the migration changes
 pub struct Migration {
     files: SimpleFiles<Symbol, String>,
     file_mapping: FileMapping,
-    changes: BTreeMap<FileId, BTreeMap<usize, Vec<(usize, MigrationChange)>>>,
+    changes: BTreeMap<FileId, BTreeMap<usize, Vec<MigrationChange>>>,
 }
 
 //**************************************************************************************************
@@ -109,39 +157,76 @@ pub struct Migration {
 //**************************************************************************************************
 
 pub fn report_diagnostics(files: &FilesSourceText, diags: Diagnostics) -> ! {
+    report_diagnostics_in_format(files, diags, DiagnosticsFormat::Human)
+}
+
+pub fn report_diagnostics_in_format(
+    files: &FilesSourceText,
+    diags: Diagnostics,
+    format: DiagnosticsFormat,
+) -> ! {
     let should_exit = true;
-    report_diagnostics_impl(files, diags, should_exit);
+    report_diagnostics_impl(files, diags, format, should_exit);
     std::process::exit(1)
 }
 
 pub fn report_warnings(files: &FilesSourceText, warnings: Diagnostics) {
+    report_warnings_in_format(files, warnings, DiagnosticsFormat::Human)
+}
+
+pub fn report_warnings_in_format(
+    files: &FilesSourceText,
+    warnings: Diagnostics,
+    format: DiagnosticsFormat,
+) {
     if warnings.is_empty() {
         return;
     }
     debug_assert!(warnings.max_severity().unwrap() == Severity::Warning);
-    report_diagnostics_impl(files, warnings, false)
+    report_diagnostics_impl(files, warnings, format, false)
 }
 
-fn report_diagnostics_impl(files: &FilesSourceText, diags: Diagnostics, should_exit: bool) {
-    let color_choice = match read_env_var(COLOR_MODE_ENV_VAR).as_str() {
-        "NONE" => ColorChoice::Never,
-        "ANSI" => ColorChoice::AlwaysAnsi,
-        "ALWAYS" => ColorChoice::Always,
-        _ => ColorChoice::Auto,
-    };
-    let mut writer = StandardStream::stderr(color_choice);
-    output_diagnostics(&mut writer, files, diags);
+fn report_diagnostics_impl(
+    files: &FilesSourceText,
+    diags: Diagnostics,
+    format: DiagnosticsFormat,
+    should_exit: bool,
+) {
+    match format {
+        DiagnosticsFormat::Human => {
+            let color_choice = match read_env_var(COLOR_MODE_ENV_VAR).as_str() {
+                "NONE" => ColorChoice::Never,
+                "ANSI" => ColorChoice::AlwaysAnsi,
+                "ALWAYS" => ColorChoice::Always,
+                _ => ColorChoice::Auto,
+            };
+            let mut writer = StandardStream::stderr(color_choice);
+            output_diagnostics(&mut writer, files, diags);
+        }
+        DiagnosticsFormat::Json => {
+            let buffer = report_diagnostics_to_json_buffer(files, diags);
+            std::io::stderr().write_all(&buffer).unwrap();
+        }
+    }
     if should_exit {
         std::process::exit(1);
     }
 }
 
 pub fn unwrap_or_report_diagnostics<T>(files: &FilesSourceText, res: Result<T, Diagnostics>) -> T {
+    unwrap_or_report_diagnostics_in_format(files, res, DiagnosticsFormat::Human)
+}
+
+pub fn unwrap_or_report_diagnostics_in_format<T>(
+    files: &FilesSourceText,
+    res: Result<T, Diagnostics>,
+    format: DiagnosticsFormat,
+) -> T {
     match res {
         Ok(t) => t,
         Err(diags) => {
             assert!(!diags.is_empty());
-            report_diagnostics(files, diags)
+            report_diagnostics_in_format(files, diags, format)
         }
     }
 }
@@ -169,36 +254,63 @@ fn output_diagnostics<W: WriteColor>(
         let id = files.add(*fname, source.as_str());
         file_mapping.insert(*fhash, id);
     }
+    let diags = sorted_unique_diagnostics(sources, diags);
     render_diagnostics(writer, &files, &file_mapping, diags);
 }
 
+/// Writes each diagnostic as a single line JSON object
+pub fn report_diagnostics_to_json_buffer(files: &FilesSourceText, diags: Diagnostics) -> Vec<u8> {
+    let mut buffer = vec![];
+    for diag in sorted_unique_diagnostics(files, diags) {
+        let json = json_diagnostic(files, diag);
+        serde_json::to_writer(&mut buffer, &json).unwrap();
+        buffer.push(b'\n');
+    }
+    buffer
+}
+
 fn render_diagnostics(
     writer: &mut dyn WriteColor,
     files: &SimpleFiles<Symbol, &str>,
     file_mapping: &FileMapping,
-    diags: Diagnostics,
+    diags: Vec<Diagnostic>,
 ) {
+    for diag in diags {
+        let rendered = render_diagnostic(file_mapping, diag);
+        emit(writer, &Config::default(), files, &rendered).unwrap()
+    }
+}
+
+/// The diagnostics to report, without duplicates or migration diagnostics.
+/// They are ordered by the file name and location of their primary label, then by severity (most
+/// severe first), then by code. As such, the order does not depend on the order in which modules
+/// and passes reported the diagnostics, except between diagnostics that only differ in their
+/// messages, which keep the order they were reported in
+fn sorted_unique_diagnostics(files: &FilesSourceText, diags: Diagnostics) -> Vec<Diagnostic> {
     let Diagnostics(Some(mut diags)) = diags else {
-        return;
+        return vec![];
     };
 
     // Do not render / report migration diagnostics.
     diags.diagnostics.retain(|diag| !diag.is_migration());
 
-    diags.diagnostics.sort_by(|e1, e2| {
-        let loc1: &Loc = &e1.primary_label.0;
-        let loc2: &Loc = &e2.primary_label.0;
-        loc1.cmp(loc2)
+    diags.diagnostics.sort_by_key(|diag| {
+        let loc = diag.primary_label.0;
+        let file_name = files.get(&loc.file_hash()).map(|(name, _)| *name);
+        (
+            file_name,
+            loc.start(),
+            loc.end(),
+            Reverse(diag.info.severity()),
+            diag.info.id(),
+        )
     });
     let mut seen: HashSet<Diagnostic> = HashSet::new();
-    for diag in diags.diagnostics {
-        if seen.contains(&diag) {
-            continue;
-        }
-        seen.insert(diag.clone());
-        let rendered = render_diagnostic(file_mapping, diag);
-        emit(writer, &Config::default(), files, &rendered).unwrap()
-    }
+    diags
+        .diagnostics
+        .into_iter()
+        .filter(|diag| seen.insert(diag.clone()))
+        .collect()
 }
 
 fn convert_loc(file_mapping: &FileMapping, loc: Loc) -> (FileId, Range<usize>) {
@@ -222,6 +334,7 @@ fn render_diagnostic(
         primary_label,
         secondary_labels,
         notes,
+        fixes: _,
     } = diag;
     let mut diag = csr::diagnostic::Diagnostic::new(info.severity().into_codespan_severity());
     let (code, message) = info.render();
@@ -238,6 +351,61 @@ fn render_diagnostic(
     diag
 }
 
+fn json_diagnostic(files: &FilesSourceText, diag: Diagnostic) -> JsonDiagnostic {
+    let Diagnostic {
+        info,
+        primary_label,
+        secondary_labels,
+        notes,
+        fixes: _,
+    } = diag;
+    let severity = match info.severity() {
+        Severity::Warning => "warning",
+        Severity::NonblockingError => "nonblocking_error",
+        Severity::BlockingError => "blocking_error",
+        Severity::Bug => "bug",
+    };
+    let category = info.category();
+    let number = info.code();
+    let (code, message) = info.render();
+    JsonDiagnostic {
+        code,
+        severity,
+        category,
+        number,
+        message,
+        primary_label: json_label(files, primary_label),
+        secondary_labels: secondary_labels
+            .into_iter()
+            .map(|label| json_label(files, label))
+            .collect(),
+        notes,
+    }
+}
+
+fn json_label(files: &FilesSourceText, (loc, message): (Loc, String)) -> JsonLabel {
+    let (file, source) = files.get(&loc.file_hash()).unwrap();
+    let range = loc.usize_range();
+    JsonLabel {
+        file: file.to_string(),
+        file_hash: loc.file_hash().to_string(),
+        byte_start: range.start,
+        byte_end: range.end,
+        start: json_position(source, range.start),
+        end: json_position(source, range.end),
+        message,
+    }
+}
+
+fn json_position(source: &str, byte_index: usize) -> JsonPosition {
+    let before = &source[..byte_index];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    JsonPosition {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
 //**************************************************************************************************
 // Migration Diff Reporting
 //**************************************************************************************************
@@ -379,6 +547,7 @@ impl Diagnostics {
                 primary_label,
                 secondary_labels,
                 notes,
+                fixes: _,
             } = diag;
             let csr_diag = (
                 info.severity().into_codespan_severity(),
@@ -436,6 +605,7 @@ impl Diagnostic {
                 .map(|(loc, msg)| (loc, msg.to_string()))
                 .collect(),
             notes: notes.into_iter().map(|msg| msg.to_string()).collect(),
+            fixes: vec![],
         }
     }
 
@@ -479,10 +649,24 @@ impl Diagnostic {
         self.notes.push(msg.to_string())
     }
 
+    /// Adds an edit replacing the source at `loc` with `replacement`. An empty `loc` inserts the
+    /// replacement. The edit must not span multiple lines
+    pub fn add_fix(&mut self, loc: Loc, replacement: impl ToString) {
+        self.fixes.push((loc, replacement.to_string()))
+    }
+
+    pub fn fixes(&self) -> &[(Loc, String)] {
+        &self.fixes
+    }
+
     pub fn info(&self) -> &DiagnosticInfo {
         &self.info
     }
 
+    pub fn primary_loc(&self) -> Loc {
+        self.primary_label.0
+    }
+
     pub fn is_migration(&self) -> bool {
         const MIGRATION_CATEGORY: u8 = codes::Category::Migration as u8;
         self.info.category() == MIGRATION_CATEGORY
@@ -704,7 +888,9 @@ impl UnprefixedWarningFilters {
     pub fn unused_warnings_filter_for_test() -> Self {
         let filtered_codes = [
             (UnusedItem::Function, FILTER_UNUSED_FUNCTION),
+            (UnusedItem::PackageFunction, FILTER_UNUSED_PACKAGE_FUNCTION),
             (UnusedItem::StructField, FILTER_UNUSED_STRUCT_FIELD),
+            (UnusedItem::Struct, FILTER_UNUSED_STRUCT),
             (UnusedItem::FunTypeParam, FILTER_UNUSED_TYPE_PARAMETER),
             (UnusedItem::Constant, FILTER_UNUSED_CONST),
             (UnusedItem::MutReference, FILTER_UNUSED_MUT_REF),
@@ -745,25 +931,37 @@ impl Migration {
     }
 
     fn add_diagnostic(&mut self, diag: Diagnostic) {
-        const NEEDS_MUT: u8 = codes::Migration::NeedsLetMut as u8;
-        const NEEDS_PUBLIC: u8 = codes::Migration::NeedsPublic as u8;
-
-        let (file_id, line, col) = self.find_file_location(&diag);
-        let file_change_entry = self.changes.entry(file_id).or_default();
-        let line_change_entry = file_change_entry.entry(line).or_default();
-        match diag.info().code() {
-            NEEDS_MUT => line_change_entry.push((col, MigrationChange::AddMut)),
-            NEEDS_PUBLIC => line_change_entry.push((col, MigrationChange::AddPublic)),
-            _ => unreachable!(),
+        debug_assert!(
+            !diag.fixes.is_empty(),
+            "ICE migration diagnostics should carry their fixes"
+        );
+        for (loc, text) in diag.fixes {
+            let (file_id, line, start, end) = self.find_file_location(loc);
+            let file_change_entry = self.changes.entry(file_id).or_default();
+            let line_change_entry = file_change_entry.entry(line).or_default();
+            // the same fix can be suggested by several diagnostics, e.g. for each mutable usage
+            let change = MigrationChange { start, end, text };
+            if !line_change_entry.contains(&change) {
+                line_change_entry.push(change);
+            }
         }
     }
 
-    fn find_file_location(&mut self, diag: &Diagnostic) -> (usize, usize, usize) {
-        let (loc, _msg) = &diag.primary_label;
-        let start_loc = loc.start() as usize;
+    /// The file, the 1-indexed line, and the byte range within that line of the location
+    fn find_file_location(&mut self, loc: Loc) -> (usize, usize, usize, usize) {
         let file_id = *self.file_mapping.get(&loc.file_hash()).unwrap();
-        let file_loc = self.files.location(file_id, start_loc).unwrap();
-        (file_id, file_loc.line_number, file_loc.column_number - 1)
+        let line_index = self
+            .files
+            .line_index(file_id, loc.start() as usize)
+            .unwrap();
+        let line_start = self.files.line_range(file_id, line_index).unwrap().start;
+        let start = loc.start() as usize - line_start;
+        let end = loc.end() as usize - line_start;
+        debug_assert!(
+            self.files.line_index(file_id, loc.end() as usize).unwrap() == line_index,
+            "ICE migration fixes cannot span multiple lines"
+        );
+        (file_id, line_index + 1, start, end)
     }
 
     fn get_line(&self, file_id: FileId, line_index: usize) -> String {
@@ -771,21 +969,14 @@ impl Migration {
         self.files.source(file_id).unwrap()[line_range].to_string()
     }
 
-    fn render_line(line_text: String, changes: &[(usize, MigrationChange)]) -> String {
+    fn render_line(line_text: String, changes: &[MigrationChange]) -> String {
         let mut line_prefix: &str = &line_text[..];
         let mut output = "".to_string();
-        for (col, change) in changes.iter().rev() {
-            let rest = &line_prefix[*col..];
-            match change {
-                MigrationChange::AddMut => {
-                    output = format!("mut {}{}", rest, output);
-                    line_prefix = &line_prefix[..*col];
-                }
-                MigrationChange::AddPublic => {
-                    output = format!("public {}{}", rest, output);
-                    line_prefix = &line_prefix[..*col];
-                }
-            }
+        for change in changes.iter().rev() {
+            let MigrationChange { start, end, text } = change;
+            let rest = &line_prefix[*end..];
+            output = format!("{}{}{}", text, rest, output);
+            line_prefix = &line_prefix[..*start];
         }
         output = format!("{}{}", line_prefix, output);
         output
@@ -804,7 +995,7 @@ impl Migration {
             let file_changes = changes.get_mut(&file_id).unwrap();
             output.push(format!("--- {}\n+++ {}\n", name, name));
             for (line_number, line_changes) in file_changes.iter_mut() {
-                line_changes.sort_by_key(|(col, _)| *col);
+                line_changes.sort_by_key(|change| change.start);
                 let line = self.get_line(file_id, *line_number - 1).to_string();
                 output.push(format!("@@ -{line_number},1 +{line_number},1 @@\n"));
                 output.push(format!("-{}", line));
@@ -840,7 +1031,7 @@ impl Migration {
             let mut output = vec![];
             for (ndx, line) in file.source().lines().enumerate() {
                 if let Some(line_changes) = file_changes.get_mut(&(ndx + 1)) {
-                    line_changes.sort_by_key(|(col, _)| *col);
+                    line_changes.sort_by_key(|change| change.start);
                     output.push(Self::render_line(line.to_string(), line_changes))
                 } else {
                     output.push(line.to_string());
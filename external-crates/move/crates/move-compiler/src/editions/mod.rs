@@ -40,6 +40,12 @@ pub enum FeatureGate {
     Move2024Paths,
     MacroFuns,
     Move2024Migration,
+    AbortWithoutCode,
+    AssertWithoutCode,
+    ErrorConstants,
+    SyntaxMethods,
+    TypeHoles,
+    ConstFunctions,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord, Default)]
@@ -119,6 +125,12 @@ const E2024_ALPHA_FEATURES: &[FeatureGate] = &[
     FeatureGate::Move2024Paths,
     FeatureGate::MacroFuns,
     FeatureGate::Move2024Optimizations,
+    FeatureGate::AbortWithoutCode,
+    FeatureGate::AssertWithoutCode,
+    FeatureGate::ErrorConstants,
+    FeatureGate::SyntaxMethods,
+    FeatureGate::TypeHoles,
+    FeatureGate::ConstFunctions,
 ];
 
 const E2024_MIGRATION_FEATURES: &[FeatureGate] = &[FeatureGate::Move2024Migration];
@@ -213,6 +225,12 @@ impl FeatureGate {
             FeatureGate::Move2024Paths => "Move 2024 paths are",
             FeatureGate::MacroFuns => "'macro' functions are",
             FeatureGate::Move2024Migration => "Move 2024 migration is",
+            FeatureGate::AbortWithoutCode => "'abort' without an abort code is",
+            FeatureGate::AssertWithoutCode => "'assert!' without an abort code is",
+            FeatureGate::ErrorConstants => "'#[error]' constants are",
+            FeatureGate::SyntaxMethods => "'syntax' methods are",
+            FeatureGate::TypeHoles => "Type placeholders '_' are",
+            FeatureGate::ConstFunctions => "'#[const_fun]' functions are",
         }
     }
 }
@@ -160,6 +160,7 @@ pub struct StructDefinition {
     pub index: usize,
     pub attributes: Attributes,
     pub loc: Loc,
+    pub visibility: Visibility,
     pub abilities: AbilitySet,
     pub type_parameters: Vec<StructTypeParameter>,
     pub fields: StructFields,
@@ -289,6 +290,7 @@ pub type LambdaLValues = Spanned<LambdaLValues_>;
 pub enum ExpDotted_ {
     Exp(Box<Exp>),
     Dot(Box<ExpDotted>, Name),
+    Index(Box<ExpDotted>, Box<Exp>),
 }
 pub type ExpDotted = Spanned<ExpDotted_>;
 
@@ -364,7 +366,7 @@ pub enum Exp_ {
     Assign(LValueList, Box<Exp>),
     FieldMutate(Box<ExpDotted>, Box<Exp>),
     Mutate(Box<Exp>, Box<Exp>),
-    Abort(Box<Exp>),
+    Abort(Option<Box<Exp>>),
     Return(Option<BlockLabel>, Box<Exp>),
     Break(Option<BlockLabel>, Box<Exp>),
     Continue(Option<BlockLabel>),
@@ -1044,6 +1046,7 @@ impl AstDebug for (StructName, &StructDefinition) {
                 index,
                 attributes,
                 loc: _loc,
+                visibility,
                 abilities,
                 type_parameters,
                 fields,
@@ -1052,6 +1055,7 @@ impl AstDebug for (StructName, &StructDefinition) {
         ) = self;
         warning_filter.ast_debug(w);
         attributes.ast_debug(w);
+        visibility.ast_debug(w);
         if let StructFields::Native(_) = fields {
             w.write("native ");
         }
@@ -1460,8 +1464,11 @@ impl AstDebug for Exp_ {
             }
 
             E::Abort(e) => {
-                w.write("abort ");
-                e.ast_debug(w);
+                w.write("abort");
+                if let Some(e) = e {
+                    w.write(" ");
+                    e.ast_debug(w);
+                }
             }
             E::Return(name, e) => {
                 w.write("return ");
@@ -1539,6 +1546,12 @@ impl AstDebug for ExpDotted_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", n))
             }
+            D::Index(e, i) => {
+                e.ast_debug(w);
+                w.write("[");
+                i.ast_debug(w);
+                w.write("]")
+            }
         }
     }
 }
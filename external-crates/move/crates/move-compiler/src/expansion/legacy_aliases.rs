@@ -66,6 +66,11 @@ impl AliasMap {
         }
     }
 
+    /// The location of the declaration of the member alias `n`, if any
+    pub fn member_alias_loc(&self, n: &Name) -> Option<Loc> {
+        self.members.get_loc(n).copied()
+    }
+
     pub fn member_alias_get(&mut self, n: &Name) -> Option<(ModuleIdent, Name)> {
         match self.members.get_mut(n) {
             None => None,
@@ -1,7 +1,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     diag,
@@ -24,11 +24,21 @@ pub fn modules(
     pre_compiled_lib_opt: Option<&FullyCompiledProgram>,
     modules: &UniqueMap<ModuleIdent, ModuleDefinition>,
 ) {
-    let mut definers = BTreeMap::new();
+    let pre_compiled_modules = pre_compiled_lib_opt
+        .into_iter()
+        .flat_map(|lib| lib.expansion.modules.key_cloned_iter());
+    let all_modules = modules
+        .key_cloned_iter()
+        .chain(pre_compiled_modules)
+        .map(|(mident, _)| mident);
+    // registered definers take precedence over annotated ones
+    let mut definers = registered_definers(env, all_modules);
+    let registered = definers.keys().copied().collect::<BTreeSet<_>>();
     for (mident, m) in modules.key_cloned_iter() {
         check_prim_definer(
             env,
             /* allow shadowing */ false,
+            &registered,
             &mut definers,
             mident,
             m,
@@ -39,6 +49,7 @@ pub fn modules(
             check_prim_definer(
                 env,
                 /* allow shadowing */ true,
+                &registered,
                 &mut definers,
                 mident,
                 m,
@@ -48,9 +59,28 @@ pub fn modules(
     env.set_primitive_type_definers(definers)
 }
 
+/// The modules registered with `CompilationEnv::add_primitive_definer`, for those registered
+/// modules that exist
+fn registered_definers(
+    env: &CompilationEnv,
+    modules: impl Iterator<Item = ModuleIdent>,
+) -> BTreeMap<BuiltinTypeName_, ModuleIdent> {
+    let registered = env.registered_primitive_definers().collect::<Vec<_>>();
+    let mut definers = BTreeMap::new();
+    for mident in modules {
+        for (prim, address, module) in &registered {
+            if mident.value.is(address, module) {
+                definers.entry(*prim).or_insert(mident);
+            }
+        }
+    }
+    definers
+}
+
 fn check_prim_definer(
     env: &mut CompilationEnv,
     allow_shadowing: bool,
+    registered: &BTreeSet<BuiltinTypeName_>,
     definers: &mut BTreeMap<BuiltinTypeName_, crate::expansion::ast::ModuleIdent>,
     mident: ModuleIdent,
     m: &ModuleDefinition,
@@ -97,7 +127,7 @@ fn check_prim_definer(
     };
 
     if let Some(prev) = definers.get(&prim) {
-        if !allow_shadowing {
+        if !allow_shadowing && !registered.contains(&prim) {
             let msg = format!("Duplicate definer annotated for primitive type '{}'", prim);
             env.add_diag(diag!(
                 Attributes::InvalidUsage,
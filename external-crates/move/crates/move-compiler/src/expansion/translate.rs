@@ -814,11 +814,172 @@ fn known_attributes(
                 .add_diag(diag!(Declarations::UnknownAttribute, (loc, msg)));
             None
         }
+        sp!(
+            loc,
+            E::AttributeName_::Known(n @ KnownAttribute::Deprecation(_))
+        ) => check_deprecation_attribute(context, &attr).then(|| (sp(loc, n), attr)),
+        sp!(loc, E::AttributeName_::Known(n @ KnownAttribute::Error(_))) => {
+            check_error_attribute(context, &attr).then(|| (sp(loc, n), attr))
+        }
+        sp!(loc, E::AttributeName_::Known(n @ KnownAttribute::Syntax(_))) => {
+            check_syntax_attribute(context, &attr).then(|| (sp(loc, n), attr))
+        }
+        sp!(
+            loc,
+            E::AttributeName_::Known(n @ KnownAttribute::ConstFun(_))
+        ) => check_const_fun_attribute(context, &attr).then(|| (sp(loc, n), attr)),
         sp!(loc, E::AttributeName_::Known(n)) => Some((sp(loc, n), attr)),
     }))
     .unwrap()
 }
 
+/// Checks that the attribute is either '#[deprecated]' or '#[deprecated(note = b"...")]'
+fn check_deprecation_attribute(context: &mut Context, sp!(loc, attr_): &E::Attribute) -> bool {
+    use known_attributes::DeprecationAttribute as DA;
+    let expected_msg = || {
+        format!(
+            "Expected '{}' or '{}({} = b\"<note>\")'",
+            DA::DEPRECATED,
+            DA::DEPRECATED,
+            DA::NOTE
+        )
+    };
+    let inners = match attr_ {
+        E::Attribute_::Name(_) => return true,
+        E::Attribute_::Parameterized(_, inners) => inners,
+        E::Attribute_::Assigned(_, _) => {
+            let msg = format!("Invalid '{}' attribute. {}", DA::DEPRECATED, expected_msg());
+            context
+                .env()
+                .add_diag(diag!(Attributes::InvalidUsage, (*loc, msg)));
+            return false;
+        }
+    };
+    let mut valid = true;
+    for (_, _, sp!(inner_loc, inner_)) in inners {
+        let value = match inner_ {
+            E::Attribute_::Assigned(n, value) if n.value.as_str() == DA::NOTE => value,
+            _ => {
+                let msg = format!("Invalid '{}' attribute. {}", DA::DEPRECATED, expected_msg());
+                context
+                    .env()
+                    .add_diag(diag!(Attributes::InvalidUsage, (*inner_loc, msg)));
+                valid = false;
+                continue;
+            }
+        };
+        match &value.value {
+            E::AttributeValue_::Value(sp!(_, E::Value_::Bytearray(bytes)))
+                if std::str::from_utf8(bytes).is_ok() => {}
+            _ => {
+                let msg = format!(
+                    "Invalid '{}' value. Expected a UTF-8 byte string, e.g. b\"<note>\"",
+                    DA::NOTE
+                );
+                context
+                    .env()
+                    .add_diag(diag!(Attributes::InvalidValue, (value.loc, msg)));
+                valid = false;
+            }
+        }
+    }
+    valid
+}
+
+/// Checks that the attribute is '#[error]', and that '#[error]' constants are supported
+fn check_error_attribute(context: &mut Context, sp!(loc, attr_): &E::Attribute) -> bool {
+    use known_attributes::ErrorAttribute as EA;
+    if !matches!(attr_, E::Attribute_::Name(_)) {
+        let msg = format!(
+            "Invalid '{}' attribute. Expected '{}' with no arguments",
+            EA::ERROR,
+            EA::ERROR
+        );
+        context
+            .env()
+            .add_diag(diag!(Attributes::InvalidUsage, (*loc, msg)));
+        return false;
+    }
+    let current_package = context.current_package;
+    context
+        .env()
+        .check_feature(FeatureGate::ErrorConstants, current_package, *loc)
+}
+
+/// Checks that the attribute is '#[syntax(index)]', and that 'syntax' methods are supported
+fn check_syntax_attribute(context: &mut Context, sp!(loc, attr_): &E::Attribute) -> bool {
+    use known_attributes::SyntaxAttribute as SA;
+    let is_index = match attr_ {
+        E::Attribute_::Parameterized(_, inners) if inners.len() == 1 => matches!(
+            &inners.iter().next().unwrap().2.value,
+            E::Attribute_::Name(n) if n.value.as_str() == SA::INDEX
+        ),
+        _ => false,
+    };
+    if !is_index {
+        let msg = format!(
+            "Invalid '{}' attribute. Expected '{}({})'",
+            SA::SYNTAX,
+            SA::SYNTAX,
+            SA::INDEX
+        );
+        context
+            .env()
+            .add_diag(diag!(Attributes::InvalidUsage, (*loc, msg)));
+        return false;
+    }
+    let current_package = context.current_package;
+    context
+        .env()
+        .check_feature(FeatureGate::SyntaxMethods, current_package, *loc)
+}
+
+/// Checks that the attribute is '#[const_fun]', and that '#[const_fun]' functions are supported
+fn check_const_fun_attribute(context: &mut Context, sp!(loc, attr_): &E::Attribute) -> bool {
+    use known_attributes::ConstFunAttribute as CA;
+    if !matches!(attr_, E::Attribute_::Name(_)) {
+        let msg = format!(
+            "Invalid '{}' attribute. Expected '{}' with no arguments",
+            CA::CONST_FUN,
+            CA::CONST_FUN
+        );
+        context
+            .env()
+            .add_diag(diag!(Attributes::InvalidUsage, (*loc, msg)));
+        return false;
+    }
+    let current_package = context.current_package;
+    context
+        .env()
+        .check_feature(FeatureGate::ConstFunctions, current_package, *loc)
+}
+
+/// Checks that '#[expected_failure]' is only given to functions that are also '#[test]'
+fn check_expected_failure_attribute(
+    context: &mut Context,
+    fn_loc: Loc,
+    attributes: &E::Attributes,
+) {
+    use known_attributes::TestingAttribute as TA;
+    let Some(abort_attribute) = attributes.get_(&TA::ExpectedFailure.into()) else {
+        return;
+    };
+    if attributes.contains_key_(&TA::Test.into()) {
+        return;
+    }
+    let fn_msg = format!(
+        "Only functions defined as a test with #[{}] can also have an #[{}] attribute",
+        TA::TEST,
+        TA::EXPECTED_FAILURE
+    );
+    let abort_msg = format!("Attributed as #[{}] here", TA::EXPECTED_FAILURE);
+    context.env().add_diag(diag!(
+        Attributes::InvalidUsage,
+        (fn_loc, fn_msg),
+        (abort_attribute.loc, abort_msg),
+    ));
+}
+
 fn unique_attributes(
     context: &mut Context,
     attr_position: AttributePosition,
@@ -879,7 +1040,7 @@ fn unique_attributes(
         if let Err((_, old_loc)) = attr_map.add(sp(nloc, name_), sp(loc, attr_)) {
             let msg = format!("Duplicate attribute '{}' attached to the same item", name_);
             context.env().add_diag(diag!(
-                Declarations::DuplicateItem,
+                Attributes::Duplicate,
                 (loc, msg),
                 (old_loc, "Attribute previously given here"),
             ));
@@ -1109,6 +1270,16 @@ trait PathExpander {
     ) -> Option<E::ModuleIdent>;
 }
 
+/// Records the alias used to name a module member if the alias renamed it, e.g. 'Bar' in
+/// 'use a::m::{Foo as Bar}', so that errors about the member can mention the alias
+fn record_member_alias(context: &mut DefnContext, usage: Name, alias_loc: Loc, member: &Name) {
+    if usage.value != member.value {
+        context
+            .env
+            .add_member_alias(usage.loc, sp(alias_loc, usage.value));
+    }
+}
+
 // -----------------------------------------------
 // Legacy Implementation
 
@@ -1124,6 +1295,17 @@ impl LegacyPathExpander {
             old_alias_maps: vec![],
         }
     }
+
+    fn member_alias_get(
+        &mut self,
+        context: &mut DefnContext,
+        n: Name,
+    ) -> Option<(ModuleIdent, Name)> {
+        let alias_loc = self.aliases.member_alias_loc(&n)?;
+        let (mident, mem) = self.aliases.member_alias_get(&n)?;
+        record_member_alias(context, n, alias_loc, &mem);
+        Some((mident, mem))
+    }
 }
 
 impl PathExpander for LegacyPathExpander {
@@ -1233,12 +1415,12 @@ impl PathExpander for LegacyPathExpander {
         let tn_ = match (access, ptn_) {
             (Access::ApplyPositional, PN::One(n))
             | (Access::ApplyNamed, PN::One(n))
-            | (Access::Type, PN::One(n)) => match self.aliases.member_alias_get(&n) {
+            | (Access::Type, PN::One(n)) => match self.member_alias_get(context, n) {
                 Some((mident, mem)) => EN::ModuleAccess(mident, mem),
                 None => EN::Name(n),
             },
             (Access::Term, PN::One(n)) if is_valid_struct_or_constant_name(n.value.as_str()) => {
-                match self.aliases.member_alias_get(&n) {
+                match self.member_alias_get(context, n) {
                     Some((mident, mem)) => EN::ModuleAccess(mident, mem),
                     None => EN::Name(n),
                 }
@@ -1428,12 +1610,13 @@ impl Move2024PathExpander {
 
         use E::ModuleAccess_ as EN;
         match self.aliases.resolve(namespace, &name) {
-            Some(AliasEntry::Member(_, mident, sp!(_, mem))) => {
+            Some(AliasEntry::Member(alias, mident, sp!(_, mem))) => {
                 // We are preserving the name's original location, rather than referring to where
                 // the alias was defined. The name represents JUST the member name, though, so we do
                 // not change location of the module as we don't have this information.
-                // TODO maybe we should also keep the alias reference (or its location)?
-                ModuleAccess(name.loc, EN::ModuleAccess(mident, sp(name.loc, mem)))
+                let mem = sp(name.loc, mem);
+                record_member_alias(context, name, alias.loc, &mem);
+                ModuleAccess(name.loc, EN::ModuleAccess(mident, mem))
             }
             Some(AliasEntry::Module(_, mident)) => {
                 // We are preserving the name's original location, rather than referring to where
@@ -2338,6 +2521,7 @@ fn struct_def_(
     let P::StructDefinition {
         attributes,
         loc,
+        visibility: pvisibility,
         name,
         abilities: abilities_vec,
         type_parameters: pty_params,
@@ -2357,6 +2541,7 @@ fn struct_def_(
         index,
         attributes,
         loc,
+        visibility: visibility(pvisibility),
         abilities,
         type_parameters,
         fields,
@@ -2518,6 +2703,7 @@ fn function_(
         body: pbody,
     } = pfunction;
     let attributes = flatten_attributes(context, AttributePosition::Function, pattributes);
+    check_expected_failure_attribute(context, name.loc(), &attributes);
     let warning_filter = warning_filter(context, &attributes);
     context
         .env()
@@ -2902,7 +3088,7 @@ fn exp(context: &mut Context, pe: Box<P::Exp>) -> Box<E::Exp> {
                 Some(LValue::FieldMutate(edotted)) => EE::FieldMutate(edotted, er),
             }
         }
-        PE::Abort(pe) => EE::Abort(exp(context, pe)),
+        PE::Abort(pe_opt) => EE::Abort(pe_opt.map(|pe| exp(context, pe))),
         PE::Return(name_opt, pe_opt) => {
             let ev = match pe_opt {
                 None => Box::new(sp(loc, EE::Unit { trailing: false })),
@@ -2974,11 +3160,13 @@ fn exp(context: &mut Context, pe: Box<P::Exp>) -> Box<E::Exp> {
             }
         }
         PE::Cast(e, ty) => EE::Cast(exp(context, e), type_(context, ty)),
-        PE::Index(..) => {
-            // TODO index syntax will be added
-            context.spec_deprecated(loc, /* is_error */ true);
-            EE::UnresolvedError
-        }
+        pdotted_ @ PE::Index(_, _) => match exp_dotted(context, Box::new(sp(loc, pdotted_))) {
+            Some(edotted) => EE::ExpDotted(E::DottedUsage::Use, edotted),
+            None => {
+                assert!(context.env().has_errors());
+                EE::UnresolvedError
+            }
+        },
         PE::Annotate(e, ty) => EE::Annotate(exp(context, e), type_(context, ty)),
         PE::Spec(_) => {
             context.spec_deprecated(loc, /* is_error */ false);
@@ -3083,7 +3271,7 @@ fn move_or_copy_path_(context: &mut Context, case: PathCase, pe: Box<P::Exp>) ->
                 return None;
             }
         }
-        E::ExpDotted_::Dot(_, _) => {
+        E::ExpDotted_::Dot(_, _) | E::ExpDotted_::Index(_, _) => {
             let current_package = context.current_package;
             context
                 .env()
@@ -3105,6 +3293,19 @@ fn exp_dotted(context: &mut Context, pdotted: Box<P::Exp>) -> Option<Box<E::ExpD
             let lhs = exp_dotted(context, plhs)?;
             EE::Dot(lhs, field)
         }
+        PE::Index(plhs, pidx) => {
+            let pkg = context.current_package;
+            if !context
+                .env()
+                .supports_feature(pkg, FeatureGate::SyntaxMethods)
+            {
+                // Outside of Move 2024, index syntax is only valid in the deprecated specs
+                context.spec_deprecated(loc, /* is_error */ true);
+                return None;
+            }
+            let lhs = exp_dotted(context, plhs)?;
+            EE::Index(lhs, exp(context, pidx))
+        }
         pe_ => EE::Exp(exp(context, Box::new(sp(loc, pe_)))),
     };
     Some(Box::new(sp(loc, edotted_)))
@@ -3314,7 +3515,7 @@ fn lvalues(context: &mut Context, e: Box<P::Exp>) -> Option<LValue> {
             let er = exp(context, pr);
             L::Mutate(er)
         }
-        pdotted_ @ PE::Dot(_, _) => {
+        pdotted_ @ (PE::Dot(_, _) | PE::Index(_, _)) => {
             let dotted = exp_dotted(context, Box::new(sp(loc, pdotted_)))?;
             L::FieldMutate(dotted)
         }
@@ -21,6 +21,7 @@ use crate::{
     FullyCompiledProgram,
 };
 
+use move_core_types::runtime_value::MoveValue;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use once_cell::sync::Lazy;
@@ -116,6 +117,8 @@ struct Context<'env> {
     named_block_types: UniqueMap<H::BlockLabel, H::Type>,
     /// collects all struct fields used in the current module
     pub used_fields: BTreeMap<Symbol, BTreeSet<Symbol>>,
+    /// the abort codes of the '#[error]' constants that are not a 'u64' in the current module
+    error_constant_codes: BTreeMap<ConstantName, u64>,
 }
 
 impl<'env> Context<'env> {
@@ -134,7 +137,7 @@ impl<'env> Context<'env> {
                 let mut fields = UniqueMap::new();
                 let field_map = match &sdef.fields {
                     N::StructFields::Native(_) => continue,
-                    N::StructFields::Defined(m) => m,
+                    N::StructFields::Defined(_, m) => m,
                 };
                 for (field, (idx, _)) in field_map.key_cloned_iter() {
                     fields.add(field, *idx).unwrap();
@@ -164,6 +167,7 @@ impl<'env> Context<'env> {
             used_fields: BTreeMap::new(),
             named_block_binders: UniqueMap::new(),
             named_block_types: UniqueMap::new(),
+            error_constant_codes: BTreeMap::new(),
         }
     }
 
@@ -292,6 +296,7 @@ fn module(
     } = mdef;
     context.current_package = package_name;
     context.env.add_warning_filter_scope(warning_filter.clone());
+    context.error_constant_codes = error_constant_codes(&tconstants);
     let structs = tstructs.map(|name, s| struct_def(context, name, s));
 
     let constants = tconstants.map(|name, c| constant(context, name, c));
@@ -305,6 +310,7 @@ fn module(
 
     gen_unused_warnings(context, is_source_module, &structs);
 
+    context.error_constant_codes = BTreeMap::new();
     context.current_package = None;
     context.env.pop_warning_filter_scope();
     (
@@ -323,6 +329,32 @@ fn module(
     )
 }
 
+/// The bit set in the abort code of an '#[error]' constant that is not a 'u64'. 'u64' '#[error]'
+/// constants with this bit set are rejected in typing. See the "Abort codes of `#[error]`
+/// constants" section of the book's abort-and-assert.md
+pub const ERROR_CONSTANT_ABORT_CODE_TAG: u64 = 1 << 63;
+
+/// Computes the abort codes for the '#[error]' constants of a module. A 'u64' constant aborts with
+/// its own value, so it has no entry here. Any other constant aborts with
+/// `ERROR_CONSTANT_ABORT_CODE_TAG` combined with the index of its declaration in the module
+fn error_constant_codes(
+    constants: &UniqueMap<ConstantName, T::Constant>,
+) -> BTreeMap<ConstantName, u64> {
+    constants
+        .key_cloned_iter()
+        .filter(|(_, cdef)| {
+            cdef.attributes
+                .contains_key_(&known_attributes::ErrorAttribute.into())
+                && !cdef
+                    .signature
+                    .value
+                    .builtin_name()
+                    .is_some_and(|sp!(_, b_)| *b_ == N::BuiltinTypeName_::U64)
+        })
+        .map(|(name, cdef)| (name, ERROR_CONSTANT_ABORT_CODE_TAG | cdef.index as u64))
+        .collect()
+}
+
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
@@ -334,6 +366,7 @@ fn function(context: &mut Context, _name: FunctionName, f: T::Function) -> H::Fu
         warning_filter,
         index,
         attributes,
+        test_only: _,
         visibility: evisibility,
         entry,
         macro_,
@@ -437,24 +470,42 @@ fn constant(context: &mut Context, _name: ConstantName, cdef: T::Constant) -> H:
         warning_filter,
         index,
         attributes,
+        test_only: _,
         loc,
         signature: tsignature,
         value: tvalue,
+        folded_value,
     } = cdef;
     context.env.add_warning_filter_scope(warning_filter.clone());
     let signature = base_type(context, tsignature);
     let eloc = tvalue.exp.loc;
-    let tseq = {
-        let mut v = VecDeque::new();
-        v.push_back(sp(eloc, T::SequenceItem_::Seq(Box::new(tvalue))));
-        v
-    };
-    let function_signature = H::FunctionSignature {
-        type_parameters: vec![],
-        parameters: vec![],
-        return_type: H::Type_::base(signature.clone()),
+    // a value folded during typing is returned as is, so it is not evaluated again in CFGIR
+    let value = match folded_value.and_then(|v| folded_value_(eloc, &signature, v)) {
+        Some(v) => {
+            let ty = H::Type_::base(signature.clone());
+            let exp = H::exp(ty, sp(eloc, H::UnannotatedExp_::Value(v)));
+            let ret_command = H::Command_::Return {
+                from_user: false,
+                exp,
+            };
+            let mut body = VecDeque::new();
+            body.push_back(make_command(eloc, ret_command));
+            (UniqueMap::new(), body)
+        }
+        None => {
+            let tseq = {
+                let mut v = VecDeque::new();
+                v.push_back(sp(eloc, T::SequenceItem_::Seq(Box::new(tvalue))));
+                v
+            };
+            let function_signature = H::FunctionSignature {
+                type_parameters: vec![],
+                parameters: vec![],
+                return_type: H::Type_::base(signature.clone()),
+            };
+            function_body_defined(context, &function_signature, loc, tseq)
+        }
     };
-    let (locals, body) = function_body_defined(context, &function_signature, loc, tseq);
     context.env.pop_warning_filter_scope();
     H::Constant {
         warning_filter,
@@ -462,10 +513,41 @@ fn constant(context: &mut Context, _name: ConstantName, cdef: T::Constant) -> H:
         attributes,
         loc,
         signature,
-        value: (locals, body),
+        value,
     }
 }
 
+/// Lowers a value folded during typing, taking the element type of a vector from its type `ty`
+fn folded_value_(loc: Loc, ty: &H::BaseType, v: MoveValue) -> Option<H::Value> {
+    use MoveValue as MV;
+    use H::Value_ as HV;
+    let v_ = match v {
+        MV::Address(a) => HV::Address(NumericalAddress::new(a.into_bytes(), NumberFormat::Hex)),
+        MV::U8(u) => HV::U8(u),
+        MV::U16(u) => HV::U16(u),
+        MV::U32(u) => HV::U32(u),
+        MV::U64(u) => HV::U64(u),
+        MV::U128(u) => HV::U128(u),
+        MV::U256(u) => HV::U256(u),
+        MV::Bool(b) => HV::Bool(b),
+        MV::Vector(vs) => {
+            let sp!(_, H::BaseType_::Apply(_, _, ty_args)) = ty else {
+                return None;
+            };
+            let [elem_ty] = ty_args.as_slice() else {
+                return None;
+            };
+            let vs = vs
+                .into_iter()
+                .map(|v| folded_value_(loc, elem_ty, v))
+                .collect::<Option<Vec<_>>>()?;
+            HV::Vector(Box::new(elem_ty.clone()), vs)
+        }
+        MV::Struct(_) | MV::Signer(_) => return None,
+    };
+    Some(sp(loc, v_))
+}
+
 //**************************************************************************************************
 // Structs
 //**************************************************************************************************
@@ -479,6 +561,8 @@ fn struct_def(
         warning_filter,
         index,
         attributes,
+        test_only: _,
+        visibility: _,
         abilities,
         type_parameters,
         fields,
@@ -499,7 +583,7 @@ fn struct_def(
 fn struct_fields(context: &mut Context, tfields: N::StructFields) -> H::StructFields {
     let tfields_map = match tfields {
         N::StructFields::Native(loc) => return H::StructFields::Native(loc),
-        N::StructFields::Defined(m) => m,
+        N::StructFields::Defined(_, m) => m,
     };
     let mut indexed_fields = tfields_map
         .into_iter()
@@ -560,7 +644,7 @@ fn base_type(context: &mut Context, sp!(loc, nb_): N::Type) -> H::BaseType {
         NT::Apply(Some(k), n, nbs) => HB::Apply(k, type_name(context, n), base_types(context, nbs)),
         NT::Param(tp) => HB::Param(tp),
         NT::UnresolvedError => HB::UnresolvedError,
-        NT::Anything => HB::Unreachable,
+        NT::Anything | NT::Hole => HB::Unreachable,
         NT::Ref(_, _) | NT::Unit | NT::Fun(_, _) => {
             context.env.add_diag(ice!((
                 loc,
@@ -892,7 +976,9 @@ fn value(
         // Expansion-y things
         // These could likely be discharged during expansion instead.
         //
-        E::Builtin(bt, arguments) if matches!(&*bt, sp!(_, T::BuiltinFunction_::Assert(None))) => {
+        E::Builtin(bt, arguments)
+            if matches!(&*bt, sp!(_, T::BuiltinFunction_::Assert(None, _))) =>
+        {
             use T::ExpListItem as TI;
             let [cond_item, code_item]: [TI; 2] = match arguments.exp.value {
                 E::ExpList(arg_list) => arg_list.try_into().unwrap(),
@@ -929,7 +1015,7 @@ fn value(
             unit_exp(eloc)
         }
         E::Builtin(bt, arguments)
-            if matches!(&*bt, sp!(_, T::BuiltinFunction_::Assert(Some(_)))) =>
+            if matches!(&*bt, sp!(_, T::BuiltinFunction_::Assert(Some(_), _))) =>
         {
             use T::ExpListItem as TI;
             let [cond_item, code_item]: [TI; 2] = match arguments.exp.value {
@@ -1266,6 +1352,11 @@ fn value(
         }
         E::Value(ev) => make_exp(HE::Value(process_value(context, ev))),
         E::Constant(_m, c) => make_exp(HE::Constant(c)), // only private constants (for now)
+        // only private constants, as with 'Constant' above
+        E::ErrorConstant(_m, c) => match context.error_constant_codes.get(&c) {
+            Some(code) => make_exp(HE::Value(sp(eloc, H::Value_::U64(*code)))),
+            None => make_exp(HE::Constant(c)),
+        },
         E::Move { from_user, var } => {
             let annotation = if from_user {
                 MoveOpAnnotation::FromUser
@@ -1623,6 +1714,7 @@ fn statement(context: &mut Context, block: &mut Block, e: T::Exp) {
         | E::Annotate(_, _)
         | E::BorrowLocal(_, _)
         | E::Constant(_, _)
+        | E::ErrorConstant(_, _)
         | E::Move { .. }
         | E::Copy { .. }
         | E::UnresolvedError
@@ -2129,7 +2221,7 @@ fn builtin(
             let args = value(context, block, None, *targ);
             E::Freeze(Box::new(args))
         }
-        TB::Assert(_) => unreachable!(),
+        TB::Assert(..) => unreachable!(),
     }
 }
 
@@ -27,6 +27,7 @@ pub mod expansion;
 pub mod hlir;
 pub mod interface_generator;
 pub mod ir_translation;
+pub mod linters;
 pub mod naming;
 pub mod parser;
 pub mod shared;
@@ -37,9 +38,9 @@ pub mod unit_test;
 
 pub use command_line::{
     compiler::{
-        construct_pre_compiled_lib, generate_interface_files, output_compiled_units, Compiler,
-        FullyCompiledProgram, SteppedCompiler, PASS_CFGIR, PASS_COMPILATION, PASS_EXPANSION,
-        PASS_HLIR, PASS_NAMING, PASS_PARSER, PASS_TYPING,
+        compile_to_naming, compile_to_typing, construct_pre_compiled_lib, generate_interface_files,
+        output_compiled_units, Compiler, FullyCompiledProgram, SteppedCompiler, PASS_CFGIR,
+        PASS_COMPILATION, PASS_EXPANSION, PASS_HLIR, PASS_NAMING, PASS_PARSER, PASS_TYPING,
     },
     MOVE_COMPILED_INTERFACES_DIR,
 };
@@ -0,0 +1,87 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints that apply to any Move code, regardless of flavor. Flavor specific lints, such as those in
+//! `sui_mode::linters`, share the `lint` filter attribute with these
+
+use crate::{
+    command_line::compiler::Visitor, diagnostics::codes::WarningFilter,
+    sui_mode::linters::LinterDiagCategory as SuiLinterDiagCategory, typing::visitor::TypingVisitor,
+};
+use move_symbol_pool::Symbol;
+
+pub mod redundant_cast;
+pub mod redundant_ref_deref;
+pub mod self_assignment;
+pub mod unmodified_loop_condition;
+pub mod while_true;
+
+pub const ALLOW_ATTR_CATEGORY: &str = "lint";
+pub const LINT_WARNING_PREFIX: &str = "Lint ";
+
+pub const REDUNDANT_CAST_FILTER_NAME: &str = "redundant_cast";
+pub const WHILE_TRUE_FILTER_NAME: &str = "while_true";
+pub const SELF_ASSIGNMENT_FILTER_NAME: &str = "self_assignment";
+pub const REDUNDANT_REF_DEREF_FILTER_NAME: &str = "redundant_ref_deref";
+pub const UNMODIFIED_LOOP_CONDITION_FILTER_NAME: &str = "unmodified_loop_condition";
+
+/// The categories follow those of `sui_mode::linters::LinterDiagCategory`, as both share the
+/// `LINT_WARNING_PREFIX`
+pub enum LinterDiagCategory {
+    RedundantCast = SuiLinterDiagCategory::LAST as isize + 1,
+    WhileTrue,
+    SelfAssignment,
+    RedundantRefDeref,
+    UnmodifiedLoopCondition,
+}
+
+/// A default code for each linter category (as long as only one code per category is used, no other
+/// codes are needed, otherwise they should be defined to be unique per-category).
+pub const LINTER_DEFAULT_DIAG_CODE: u8 = 1;
+
+pub fn known_filters() -> (Option<Symbol>, Vec<WarningFilter>) {
+    let filters = vec![
+        WarningFilter::All(Some(LINT_WARNING_PREFIX)),
+        WarningFilter::code(
+            Some(LINT_WARNING_PREFIX),
+            LinterDiagCategory::RedundantCast as u8,
+            LINTER_DEFAULT_DIAG_CODE,
+            Some(REDUNDANT_CAST_FILTER_NAME),
+        ),
+        WarningFilter::code(
+            Some(LINT_WARNING_PREFIX),
+            LinterDiagCategory::WhileTrue as u8,
+            LINTER_DEFAULT_DIAG_CODE,
+            Some(WHILE_TRUE_FILTER_NAME),
+        ),
+        WarningFilter::code(
+            Some(LINT_WARNING_PREFIX),
+            LinterDiagCategory::SelfAssignment as u8,
+            LINTER_DEFAULT_DIAG_CODE,
+            Some(SELF_ASSIGNMENT_FILTER_NAME),
+        ),
+        WarningFilter::code(
+            Some(LINT_WARNING_PREFIX),
+            LinterDiagCategory::RedundantRefDeref as u8,
+            LINTER_DEFAULT_DIAG_CODE,
+            Some(REDUNDANT_REF_DEREF_FILTER_NAME),
+        ),
+        WarningFilter::code(
+            Some(LINT_WARNING_PREFIX),
+            LinterDiagCategory::UnmodifiedLoopCondition as u8,
+            LINTER_DEFAULT_DIAG_CODE,
+            Some(UNMODIFIED_LOOP_CONDITION_FILTER_NAME),
+        ),
+    ];
+    (Some(ALLOW_ATTR_CATEGORY.into()), filters)
+}
+
+pub fn linter_visitors() -> Vec<Visitor> {
+    vec![
+        redundant_cast::RedundantCastVisitor.visitor(),
+        while_true::WhileTrueVisitor.visitor(),
+        self_assignment::SelfAssignmentVisitor.visitor(),
+        redundant_ref_deref::RedundantRefDerefVisitor.visitor(),
+        unmodified_loop_condition::UnmodifiedLoopConditionVisitor.visitor(),
+    ]
+}
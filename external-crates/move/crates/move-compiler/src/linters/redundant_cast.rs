@@ -0,0 +1,105 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags casts of an expression to the type it already has, e.g. `(x: u64) as u64`.
+//! Such casts have no effect and can be removed.
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    parser::ast::FunctionName,
+    shared::{program_info::TypingProgramInfo, CompilationEnv},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_ir_types::location::Loc;
+
+use super::{LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX};
+
+const REDUNDANT_CAST_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::RedundantCast as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "redundant cast",
+);
+
+pub struct RedundantCastVisitor;
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    // The body of the function being visited. Casts outside of it come from macro bodies, where
+    // the cast might only be redundant for this particular expansion
+    body_loc: Option<Loc>,
+}
+
+impl TypingVisitorConstructor for RedundantCastVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            body_loc: None,
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.body_loc = Some(fdef.body.loc);
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        let E::Cast(e, ty) = &exp.exp.value else {
+            return false;
+        };
+        // the types of number literals are inferred, and might only be determined by the cast
+        if matches!(e.exp.value, E::Value(_))
+            || !self
+                .body_loc
+                .is_some_and(|body_loc| body_loc.contains(exp.exp.loc))
+        {
+            return false;
+        }
+        let (Some(sp!(_, from)), Some(sp!(_, to))) =
+            (e.ty.value.builtin_name(), ty.value.builtin_name())
+        else {
+            return false;
+        };
+        if from == to {
+            let msg = format!("Redundant cast. The expression already has type '{to}'");
+            let mut d = diag!(
+                REDUNDANT_CAST_DIAG,
+                (exp.exp.loc, msg),
+                (e.ty.loc, format!("Given: '{from}'"))
+            );
+            d.add_note("Consider removing the cast");
+            self.env.add_diag(d);
+        }
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags a borrow of a dereference, e.g. `&*r`, or a dereference of a borrow, e.g.
+//! `*&x`, when the resulting type is the same as the type of the inner expression. Both operators
+//! can then be removed. A borrow that changes the mutability of a reference, e.g. `&*r` where `r`
+//! is a mutable reference, is not flagged.
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    naming::ast::Type_,
+    parser::ast::FunctionName,
+    shared::{program_info::TypingProgramInfo, CompilationEnv},
+    typing::{
+        ast as T,
+        core::{error_format, Subst},
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_ir_types::location::Loc;
+
+use super::{LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX};
+
+const REDUNDANT_REF_DEREF_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::RedundantRefDeref as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "redundant reference and dereference",
+);
+
+pub struct RedundantRefDerefVisitor;
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    // The body of the function being visited. Expressions outside of it come from macro bodies,
+    // where the operators might only be redundant for this particular expansion
+    body_loc: Option<Loc>,
+}
+
+impl TypingVisitorConstructor for RedundantRefDerefVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            body_loc: None,
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.body_loc = Some(fdef.body.loc);
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        let outer_loc = exp.exp.loc;
+        if !self
+            .body_loc
+            .is_some_and(|body_loc| body_loc.contains(outer_loc))
+        {
+            return false;
+        }
+        let (ops, inner_loc, innermost_loc) = match &exp.exp.value {
+            E::TempBorrow(mut_, inner) => {
+                let E::Dereference(innermost) = &inner.exp.value else {
+                    return false;
+                };
+                // '&*r' where 'r' is a mutable reference freezes it, and is not redundant
+                if !matches!(&innermost.ty.value, Type_::Ref(inner_mut, _) if inner_mut == mut_) {
+                    return false;
+                }
+                let ops = if *mut_ {
+                    "'&mut' and '*'"
+                } else {
+                    "'&' and '*'"
+                };
+                (ops, inner.exp.loc, innermost.exp.loc)
+            }
+            E::Dereference(inner) => {
+                let (mut_, innermost_loc) = match &inner.exp.value {
+                    E::TempBorrow(mut_, innermost) => (*mut_, innermost.exp.loc),
+                    E::BorrowLocal(mut_, var) => (*mut_, var.loc),
+                    _ => return false,
+                };
+                let ops = if mut_ {
+                    "'*' and '&mut'"
+                } else {
+                    "'*' and '&'"
+                };
+                (ops, inner.exp.loc, innermost_loc)
+            }
+            _ => return false,
+        };
+        // Borrows added by the compiler, e.g. for the receiver of a method call, share the location
+        // of the expression they borrow. Only operators written in the source start before it
+        if outer_loc.start() >= inner_loc.start() || inner_loc.start() >= innermost_loc.start() {
+            return false;
+        }
+        let msg = format!(
+            "Redundant reference and dereference. The expression already has type {}",
+            error_format(&exp.ty, &Subst::empty())
+        );
+        let mut d = diag!(REDUNDANT_REF_DEREF_DIAG, (outer_loc, msg));
+        d.add_note(format!("Consider removing the {ops}"));
+        // the operators can only be removed as a prefix, i.e. when there are no parentheses
+        if outer_loc.end() == inner_loc.end() && inner_loc.end() == innermost_loc.end() {
+            let file_hash = outer_loc.file_hash();
+            d.add_fix(
+                Loc::new(file_hash, outer_loc.start(), inner_loc.start()),
+                "",
+            );
+            d.add_fix(
+                Loc::new(file_hash, inner_loc.start(), innermost_loc.start()),
+                "",
+            );
+        }
+        self.env.add_diag(d);
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags assignments of a value to the place it was read from, e.g. `x = x;`,
+//! `*r = *r;`, or `s.f = s.f;`. Such assignments have no effect, and are usually a typo.
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    naming::ast::Var_,
+    parser::ast::FunctionName,
+    shared::{program_info::TypingProgramInfo, CompilationEnv},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_ir_types::location::Loc;
+use move_symbol_pool::Symbol;
+
+use super::{LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX};
+
+const SELF_ASSIGNMENT_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::SelfAssignment as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "self-assignment",
+);
+
+/// A local, followed by the fields accessed through it
+type Path = (Var_, Vec<Symbol>);
+
+pub struct SelfAssignmentVisitor;
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    // The body of the function being visited. Assignments outside of it come from macro bodies,
+    // where the two sides might only be the same for this particular expansion
+    body_loc: Option<Loc>,
+}
+
+impl TypingVisitorConstructor for SelfAssignmentVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            body_loc: None,
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.body_loc = Some(fdef.body.loc);
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        if !self
+            .body_loc
+            .is_some_and(|body_loc| body_loc.contains(exp.exp.loc))
+        {
+            return false;
+        }
+        let (lhs_loc, rhs_loc) = match &exp.exp.value {
+            // `x = x`
+            E::Assign(sp!(lloc, lvalues), _, rhs) => match (&lvalues[..], &rhs.exp.value) {
+                (
+                    [sp!(_, T::LValue_::Var { var: lhs, .. })],
+                    E::Copy { var: rhs_var, .. } | E::Move { var: rhs_var, .. },
+                ) if lhs.value == rhs_var.value => (*lloc, rhs.exp.loc),
+                _ => return false,
+            },
+            // `*r = *r` and `s.f = s.f`
+            E::Mutate(lhs, rhs) => match &rhs.exp.value {
+                E::Dereference(rhs_place) => match (path(lhs), path(rhs_place)) {
+                    (Some(lhs_path), Some(rhs_path)) if lhs_path == rhs_path => {
+                        (lhs.exp.loc, rhs.exp.loc)
+                    }
+                    _ => return false,
+                },
+                _ => return false,
+            },
+            _ => return false,
+        };
+        self.env.add_diag(diag!(
+            SELF_ASSIGNMENT_DIAG,
+            (lhs_loc, "This assignment has no effect"),
+            (
+                rhs_loc,
+                "The value assigned is the current value of the left-hand side"
+            )
+        ));
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
+
+/// The path of the place referred to by `e`, if `e` is a local or a chain of field borrows rooted at
+/// a local. Any other expression, e.g. a call or a dereference, has no path
+fn path(e: &T::Exp) -> Option<Path> {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Copy { var, .. } | E::Move { var, .. } | E::Use(var) | E::BorrowLocal(_, var) => {
+            Some((var.value, vec![]))
+        }
+        E::Borrow(_, inner, field) => {
+            let (var, mut fields) = path(inner)?;
+            fields.push(field.0.value);
+            Some((var, fields))
+        }
+        E::Builtin(bf, inner) if matches!(bf.value, T::BuiltinFunction_::Freeze(_)) => path(inner),
+        _ => None,
+    }
+}
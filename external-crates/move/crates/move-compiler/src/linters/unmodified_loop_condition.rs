@@ -0,0 +1,188 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags `while` loops whose condition only reads locals that are never modified in
+//! the body of the loop, e.g. `while (i < n) { .. }` where `i` and `n` are never assigned or
+//! mutably borrowed. Unless the body exits the loop, e.g. with a `break`, such a loop never
+//! terminates once its condition is `true`.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    naming::ast::{Type_, Var_},
+    parser::ast::FunctionName,
+    shared::{program_info::TypingProgramInfo, CompilationEnv},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_ir_types::location::Loc;
+
+use super::{LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX};
+
+const UNMODIFIED_LOOP_CONDITION_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::UnmodifiedLoopCondition as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "unmodified loop condition",
+);
+
+pub struct UnmodifiedLoopConditionVisitor;
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    // The body of the function being visited. Loops outside of it come from macro bodies, and
+    // would otherwise be reported once per expansion
+    body_loc: Option<Loc>,
+}
+
+impl TypingVisitorConstructor for UnmodifiedLoopConditionVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            body_loc: None,
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.body_loc = Some(fdef.body.loc);
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        let loc = exp.exp.loc;
+        let T::UnannotatedExp_::While(_, cond, body) = &mut exp.exp.value else {
+            return false;
+        };
+        if !self.body_loc.is_some_and(|body_loc| body_loc.contains(loc)) {
+            return false;
+        }
+        let mut condition = Condition {
+            vars: BTreeMap::new(),
+            supported: true,
+        };
+        condition.visit_exp(cond);
+        if !condition.supported || condition.vars.is_empty() {
+            return false;
+        }
+        let mut loop_body = LoopBody {
+            vars: &condition.vars,
+            modified: false,
+            exits: false,
+        };
+        loop_body.visit_exp(body);
+        if loop_body.modified || loop_body.exits {
+            return false;
+        }
+        let msg = "The variables in this condition are never modified in the loop body. \
+            This loop may never terminate";
+        let mut d = diag!(UNMODIFIED_LOOP_CONDITION_DIAG, (cond.exp.loc, msg));
+        for (var, var_loc) in &condition.vars {
+            if *var_loc != cond.exp.loc {
+                d.add_secondary_label((*var_loc, format!("'{}' is never modified", var.name)));
+            }
+        }
+        self.env.add_diag(d);
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
+
+/// Collects the locals read by a loop condition, with the location of their first use. The
+/// condition is only supported if it reads them directly, without any calls or other effects, and
+/// without going through a mutable reference that the body could write through
+struct Condition {
+    vars: BTreeMap<Var_, Loc>,
+    supported: bool,
+}
+
+impl TypingVisitorContext for Condition {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+    fn pop_warning_filter_scope(&mut self) {}
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::Copy { var, .. }
+            | E::Move { var, .. }
+            | E::Use(var)
+            | E::BorrowLocal(false, var) => {
+                if matches!(exp.ty.value, Type_::Ref(true, _)) {
+                    self.supported = false
+                } else {
+                    self.vars.entry(var.value).or_insert(var.loc);
+                }
+            }
+            E::Value(_)
+            | E::Constant(..)
+            | E::Dereference(_)
+            | E::UnaryExp(..)
+            | E::BinopExp(..)
+            | E::Borrow(false, _, _)
+            | E::TempBorrow(false, _)
+            | E::Cast(..)
+            | E::Annotate(..) => (),
+            E::Builtin(bf, _) if matches!(bf.value, T::BuiltinFunction_::Freeze(_)) => (),
+            _ => self.supported = false,
+        }
+        !self.supported
+    }
+}
+
+/// Checks whether the body of a loop modifies any of the locals of its condition, or exits the loop
+struct LoopBody<'a> {
+    vars: &'a BTreeMap<Var_, Loc>,
+    modified: bool,
+    exits: bool,
+}
+
+impl TypingVisitorContext for LoopBody<'_> {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+    fn pop_warning_filter_scope(&mut self) {}
+
+    fn visit_lvalue_custom(&mut self, lvalue: &mut T::LValue) -> bool {
+        if let T::LValue_::Var { var, .. } = &lvalue.value {
+            self.modified |= self.vars.contains_key(&var.value);
+        }
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            // covers '&mut' arguments, method receivers taken by '&mut', and field mutations
+            E::BorrowLocal(true, var) => self.modified |= self.vars.contains_key(&var.value),
+            // a 'break', or a 'return' or 'abort', can exit the loop
+            E::Give(..) | E::Return(_) | E::Abort(_) => self.exits = true,
+            _ => (),
+        }
+        self.modified || self.exits
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags `while` loops whose condition is always `true`, e.g. `while (true) { .. }`.
+//! Such loops can only be exited with a `break` or `return`, which is what `loop` expresses.
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    parser::ast::FunctionName,
+    shared::{program_info::TypingProgramInfo, CompilationEnv},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_ir_types::location::Loc;
+
+use super::{LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX};
+
+const WHILE_TRUE_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::WhileTrue as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "'while (true)' instead of 'loop'",
+);
+
+pub struct WhileTrueVisitor;
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    // The body of the function being visited. Loops outside of it come from macro bodies, and
+    // would otherwise be reported once per expansion
+    body_loc: Option<Loc>,
+}
+
+impl TypingVisitorConstructor for WhileTrueVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            body_loc: None,
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.body_loc = Some(fdef.body.loc);
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        let T::UnannotatedExp_::While(_, cond, _) = &exp.exp.value else {
+            return false;
+        };
+        if T::bool_literal_value(cond) == Some(true)
+            && self
+                .body_loc
+                .is_some_and(|body_loc| body_loc.contains(exp.exp.loc))
+        {
+            let msg = "This 'while' condition is always 'true'. Consider using 'loop' instead";
+            let mut d = diag!(
+                WHILE_TRUE_DIAG,
+                (exp.exp.loc, msg),
+                (cond.exp.loc, "Always 'true'")
+            );
+            d.add_note(
+                "Unlike 'while', 'loop' can also give a value with 'break', \
+                e.g. 'let x = loop { break 42 };'",
+            );
+            self.env.add_diag(d);
+        }
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
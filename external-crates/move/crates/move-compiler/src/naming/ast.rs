@@ -112,6 +112,9 @@ pub struct StructDefinition {
     // index in the original order as defined in the source file
     pub index: usize,
     pub attributes: Attributes,
+    /// The location of the item's '#[test_only]' attribute, if it has one
+    pub test_only: Option<Loc>,
+    pub visibility: Visibility,
     pub abilities: AbilitySet,
     pub type_parameters: Vec<StructTypeParameter>,
     pub fields: StructFields,
@@ -125,7 +128,7 @@ pub struct StructTypeParameter {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum StructFields {
-    Defined(Fields<Type>),
+    Defined(bool /* positional */, Fields<Type>),
     Native(Loc),
 }
 
@@ -153,6 +156,8 @@ pub struct Function {
     // index in the original order as defined in the source file
     pub index: usize,
     pub attributes: Attributes,
+    /// The location of the item's '#[test_only]' attribute, if it has one
+    pub test_only: Option<Loc>,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
     pub macro_: Option<Loc>,
@@ -170,6 +175,8 @@ pub struct Constant {
     // index in the original order as defined in the source file
     pub index: usize,
     pub attributes: Attributes,
+    /// The location of the item's '#[test_only]' attribute, if it has one
+    pub test_only: Option<Loc>,
     pub loc: Loc,
     pub signature: Type,
     pub value: Exp,
@@ -236,7 +243,14 @@ pub enum Type_ {
     Apply(Option<AbilitySet>, TypeName, Vec<Type>),
     Fun(Vec<Type>, Box<Type>),
     Var(TVar),
+    // The type of a diverging expression, e.g. 'abort' or 'return'. Accepted anywhere, but does not
+    // suppress any other checks
     Anything,
+    // A placeholder '_' written by the user, e.g. 'vector<_>'. Replaced by a fresh type variable
+    // when instantiated in typing
+    Hole,
+    // The type of an expression that had an error. Accepted anywhere, and suppresses the checks
+    // that depend on it, to avoid reporting follow-up errors
     UnresolvedError,
 }
 pub type Type = Spanned<Type_>;
@@ -281,6 +295,7 @@ pub type LambdaLValues = Spanned<LambdaLValues_>;
 pub enum ExpDotted_ {
     Exp(Box<Exp>),
     Dot(Box<ExpDotted>, Field),
+    Index(Box<ExpDotted>, Box<Exp>),
 }
 pub type ExpDotted = Spanned<ExpDotted_>;
 
@@ -427,6 +442,27 @@ impl UseFuns {
     }
 }
 
+impl UseFunKind {
+    /// When more than one 'use fun' is in scope for the same method, the candidate with the
+    /// highest precedence is used. Ties are broken by the innermost scope.
+    /// Explicit 'use fun' > 'use' alias > function declaration
+    pub fn precedence(&self) -> u8 {
+        match self {
+            UseFunKind::Explicit => 2,
+            UseFunKind::UseAlias => 1,
+            UseFunKind::FunctionDeclaration => 0,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            UseFunKind::Explicit => "'use fun' declaration",
+            UseFunKind::UseAlias => "'use' alias",
+            UseFunKind::FunctionDeclaration => "function declaration",
+        }
+    }
+}
+
 static BUILTIN_TYPE_ALL_NAMES: Lazy<BTreeSet<Symbol>> = Lazy::new(|| {
     [
         BuiltinTypeName_::ADDRESS,
@@ -713,7 +749,7 @@ impl Type_ {
             Type_::Ref(_, _) => Some(AbilitySet::references(loc)),
             Type_::Anything | Type_::UnresolvedError => Some(AbilitySet::all(loc)),
             Type_::Fun(_, _) => Some(AbilitySet::functions(loc)),
-            Type_::Var(_) => None,
+            Type_::Var(_) | Type_::Hole => None,
         }
     }
 
@@ -725,7 +761,7 @@ impl Type_ {
             Type_::Ref(_, _) => Some(AbilitySet::REFERENCES.contains(&ability)),
             Type_::Anything | Type_::UnresolvedError => Some(true),
             Type_::Fun(_, _) => Some(AbilitySet::FUNCTIONS.contains(&ability)),
-            Type_::Var(_) => None,
+            Type_::Var(_) | Type_::Hole => None,
         }
     }
 }
@@ -964,6 +1000,8 @@ impl AstDebug for (StructName, &StructDefinition) {
                 warning_filter,
                 index,
                 attributes,
+                test_only: _,
+                visibility,
                 abilities,
                 type_parameters,
                 fields,
@@ -971,13 +1009,17 @@ impl AstDebug for (StructName, &StructDefinition) {
         ) = self;
         warning_filter.ast_debug(w);
         attributes.ast_debug(w);
+        visibility.ast_debug(w);
         if let StructFields::Native(_) = fields {
             w.write("native ");
         }
         w.write(&format!("struct#{index} {name}"));
         type_parameters.ast_debug(w);
         ability_modifiers_ast_debug(w, abilities);
-        if let StructFields::Defined(fields) = fields {
+        if let StructFields::Defined(is_positional, fields) = fields {
+            if *is_positional {
+                w.write("#positional");
+            }
             w.block(|w| {
                 w.list(fields, ",", |w, (_, f, idx_st)| {
                     let (idx, st) = idx_st;
@@ -998,6 +1040,7 @@ impl AstDebug for (FunctionName, &Function) {
                 warning_filter,
                 index,
                 attributes,
+                test_only: _,
                 visibility,
                 macro_,
                 entry,
@@ -1107,6 +1150,7 @@ impl AstDebug for (ConstantName, &Constant) {
                 warning_filter,
                 index,
                 attributes,
+                test_only: _,
                 loc: _loc,
                 signature,
                 value,
@@ -1214,7 +1258,7 @@ impl AstDebug for Type_ {
                 result.ast_debug(w);
             }
             Type_::Var(tv) => w.write(&format!("#{}", tv.0)),
-            Type_::Anything => w.write("_"),
+            Type_::Anything | Type_::Hole => w.write("_"),
             Type_::UnresolvedError => w.write("_|_"),
         }
     }
@@ -1512,6 +1556,12 @@ impl AstDebug for ExpDotted_ {
                 e.ast_debug(w);
                 w.write(&format!(".{}", n))
             }
+            D::Index(e, i) => {
+                e.ast_debug(w);
+                w.write("[");
+                i.ast_debug(w);
+                w.write("]")
+            }
         }
     }
 }
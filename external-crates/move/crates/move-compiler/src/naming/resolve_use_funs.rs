@@ -4,7 +4,12 @@
 use crate::expansion::ast::{self as E, ModuleIdent};
 use crate::naming::ast as N;
 use crate::parser::ast::{FunctionName, Visibility};
-use crate::shared::{program_info::NamingProgramInfo, unique_map::UniqueMap, *};
+use crate::shared::{
+    known_attributes::AttributePosition,
+    program_info::{Deprecation, NamingProgramInfo},
+    unique_map::UniqueMap,
+    *,
+};
 use crate::typing::core;
 use crate::{diag, ice};
 use move_ir_types::location::*;
@@ -17,6 +22,7 @@ struct Context<'env, 'info> {
     env: &'env mut CompilationEnv,
     info: &'info NamingProgramInfo,
     current_module: ModuleIdent,
+    in_deprecated_function: bool,
 }
 
 impl<'env, 'info> Context<'env, 'info> {
@@ -29,6 +35,7 @@ impl<'env, 'info> Context<'env, 'info> {
             env,
             info,
             current_module,
+            in_deprecated_function: false,
         }
     }
 }
@@ -89,9 +96,12 @@ fn function(context: &mut Context, function: &mut N::Function) {
     context
         .env
         .add_warning_filter_scope(function.warning_filter.clone());
+    context.in_deprecated_function =
+        Deprecation::from_attributes(AttributePosition::Function, &function.attributes).is_some();
     if let N::FunctionBody_::Defined(seq) = &mut function.body.value {
         sequence(context, seq)
     }
+    context.in_deprecated_function = false;
     context.env.pop_warning_filter_scope();
 }
 
@@ -124,6 +134,7 @@ fn use_funs(context: &mut Context, uf: &mut N::UseFuns) {
                 None => false,
             };
             if is_valid {
+                check_deprecated_target(context, &nuf);
                 if let Some(public_loc) = nuf.is_public {
                     let defining_module = match &tn.value {
                         N::TypeName_::Multiple(_) => {
@@ -184,10 +195,22 @@ fn use_funs(context: &mut Context, uf: &mut N::UseFuns) {
     // remove any empty use funs
     resolved.retain(|_, methods| !methods.is_empty());
 
+    // warn on explicit use funs that shadow a function declaration from the type's module
+    for (tn, methods) in &*resolved {
+        for (method, nuf) in methods.key_cloned_iter() {
+            check_shadowed_declaration(context, implicit_candidates, tn, method, nuf)
+        }
+    }
+
     // resolve implicit candidates, removing if
     // - It is not a valid method (i.e. if it would be invalid to declare as a 'use fun')
     // - The name is already bound
+    // - It is from a function declaration, and implicit methods are disabled
+    let no_implicit_methods = context.env.flags().no_implicit_methods();
     for (method, implicit) in std::mem::take(implicit_candidates) {
+        if no_implicit_methods && implicit.kind == E::ImplicitUseFunKind::FunctionDeclaration {
+            continue;
+        }
         let E::ImplicitUseFunCandidate {
             loc,
             attributes,
@@ -247,6 +270,78 @@ fn use_funs(context: &mut Context, uf: &mut N::UseFuns) {
     }
 }
 
+fn check_deprecated_target(context: &mut Context, nuf: &N::UseFun) {
+    let (m, f) = &nuf.target_function;
+    if m == &context.current_module || context.in_deprecated_function {
+        return;
+    }
+    if let Some(deprecation) = context
+        .info
+        .deprecation(m, AttributePosition::Function, f.0.value)
+    {
+        let diag = deprecation.usage_diagnostic(nuf.loc, m, f);
+        context.env.add_diag(diag)
+    }
+}
+
+/// An explicit 'use fun' takes precedence over the method that the defining module's function
+/// declaration of the same name would otherwise provide. This is likely unintended, so warn
+fn check_shadowed_declaration(
+    context: &mut Context,
+    implicit_candidates: &UniqueMap<Name, E::ImplicitUseFunCandidate>,
+    tn: &N::TypeName,
+    method: Name,
+    nuf: &N::UseFun,
+) {
+    // without implicit methods, the declaration does not provide a method to shadow
+    if context.env.flags().no_implicit_methods() {
+        return;
+    }
+    let defining_module = match &tn.value {
+        N::TypeName_::Multiple(_) => return,
+        N::TypeName_::Builtin(sp!(_, bt_)) => match context.env.primitive_definer(*bt_) {
+            Some(m) => *m,
+            None => return,
+        },
+        N::TypeName_::ModuleType(m, _) => *m,
+    };
+    // a function declaration in the same scope is reported as a duplicate
+    if implicit_candidates
+        .get(&method)
+        .is_some_and(|implicit| implicit.function == (defining_module, method))
+    {
+        return;
+    }
+    let Some((shadowed_f, shadowed_tn)) = is_valid_method(context, &defining_module, method) else {
+        return;
+    };
+    if &shadowed_tn != tn || nuf.target_function == (defining_module, shadowed_f) {
+        return;
+    }
+    let finfo = context.info.function_info(&defining_module, &shadowed_f);
+    if defining_module != context.current_module && finfo.visibility == E::Visibility::Internal {
+        return;
+    }
+    let (target_m, target_f) = &nuf.target_function;
+    let msg = format!(
+        "This 'use fun' for '{tn}.{method}' shadows the method from the declaration of \
+        '{defining_module}::{shadowed_f}'. Calls to '{method}' will resolve to \
+        '{target_m}::{target_f}'"
+    );
+    let decl_msg = "Function declarations create an implicit 'use fun' when their first \
+        argument is a type defined in the same module";
+    let mut diag = diag!(
+        Declarations::ShadowedMethod,
+        (nuf.loc, msg),
+        (finfo.defined_loc, decl_msg)
+    );
+    diag.add_note(
+        "Explicit 'use fun' declarations take precedence over function declarations. \
+        Consider renaming the method to avoid confusion",
+    );
+    context.env.add_diag(diag);
+}
+
 fn is_valid_method(
     context: &mut Context,
     target_m: &ModuleIdent,
@@ -383,5 +478,9 @@ fn exp_dotted(context: &mut Context, sp!(_, ed_): &mut N::ExpDotted) {
     match ed_ {
         N::ExpDotted_::Exp(e) => exp(context, e),
         N::ExpDotted_::Dot(ed, _) => exp_dotted(context, ed),
+        N::ExpDotted_::Index(ed, i) => {
+            exp_dotted(context, ed);
+            exp(context, i)
+        }
     }
 }
@@ -4,8 +4,8 @@
 
 use crate::{
     debug_display, diag,
-    diagnostics::{self, codes::*},
-    editions::FeatureGate,
+    diagnostics::{self, codes::*, Diagnostic},
+    editions::{Edition, FeatureGate},
     expansion::{
         ast::{self as E, AbilitySet, ModuleIdent, Visibility},
         translate::is_valid_struct_or_constant_name as is_constant_name,
@@ -13,11 +13,16 @@ use crate::{
     ice,
     naming::ast::{self as N, BlockLabel, NominalBlockUsage},
     parser::ast::{self as P, ConstantName, Field, FunctionName, StructName, MACRO_MODIFIER},
-    shared::{program_info::NamingProgramInfo, unique_map::UniqueMap, *},
+    shared::{
+        known_attributes::{DefinesPrimitive, KnownAttribute, TestingAttribute},
+        program_info::NamingProgramInfo,
+        unique_map::UniqueMap,
+        *,
+    },
     FullyCompiledProgram,
 };
 use move_ir_types::location::*;
-use move_symbol_pool::Symbol;
+use move_symbol_pool::{symbol, Symbol};
 use std::collections::{BTreeMap, BTreeSet};
 
 use super::{ast::TParamID, fake_natives};
@@ -46,8 +51,11 @@ struct ResolvedModuleType {
 struct ModuleType {
     original_mident: ModuleIdent,
     decl_loc: Loc,
-    arity: usize,
+    /// The declared type parameter names, in declaration order
+    type_parameters: Vec<Symbol>,
     is_positional: bool,
+    /// The declared field names of a named struct, in declaration order
+    field_names: Vec<Symbol>,
 }
 
 enum ResolvedFunction {
@@ -91,8 +99,11 @@ struct Context<'env> {
     unscoped_types: BTreeMap<Symbol, ResolvedType>,
     scoped_functions: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
     scoped_constants: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
-    local_scopes: Vec<BTreeMap<Symbol, u16>>,
+    local_scopes: Vec<BTreeMap<Symbol, (u16, Loc)>>,
     local_count: BTreeMap<Symbol, u16>,
+    /// The 'macro' parameters of the function being translated, with their declared location and
+    /// type
+    syntax_parameters: BTreeMap<Symbol, (Loc, N::Type)>,
     used_locals: BTreeSet<N::Var_>,
     nominal_blocks: Vec<(Option<Symbol>, BlockLabel, NominalBlockType)>,
     nominal_block_id: u16,
@@ -101,6 +112,9 @@ struct Context<'env> {
     /// Indicates if the compiler is currently translating a function (set to true before starting
     /// to translate a function and to false after translation is over).
     translating_fun: bool,
+    /// Set while translating a function signature, holding the locations of the types already
+    /// reported with the wrong number of type arguments in it, so each site is reported once
+    signature_arity_errors: Option<BTreeSet<Loc>>,
     current_package: Option<Symbol>,
 }
 
@@ -128,14 +142,30 @@ impl<'env> Context<'env> {
                     .structs
                     .key_cloned_iter()
                     .map(|(s, sdef)| {
-                        let arity = sdef.type_parameters.len();
+                        let type_parameters = sdef
+                            .type_parameters
+                            .iter()
+                            .map(|tp| tp.name.value)
+                            .collect();
                         let sname = s.value();
                         let is_positional = matches!(sdef.fields, E::StructFields::Positional(_));
+                        let field_names = match &sdef.fields {
+                            E::StructFields::Named(fields) => {
+                                let mut fields = fields
+                                    .key_cloned_iter()
+                                    .map(|(f, (idx, _))| (*idx, f.value()))
+                                    .collect::<Vec<_>>();
+                                fields.sort_by_key(|(idx, _)| *idx);
+                                fields.into_iter().map(|(_, f)| f).collect()
+                            }
+                            E::StructFields::Positional(_) | E::StructFields::Native(_) => vec![],
+                        };
                         let type_info = ModuleType {
                             original_mident: mident,
                             decl_loc: s.loc(),
-                            arity,
+                            type_parameters,
                             is_positional,
+                            field_names,
                         };
                         (sname, type_info)
                     })
@@ -179,11 +209,13 @@ impl<'env> Context<'env> {
             unscoped_types,
             local_scopes: vec![],
             local_count: BTreeMap::new(),
+            syntax_parameters: BTreeMap::new(),
             nominal_blocks: vec![],
             nominal_block_id: 0,
             used_locals: BTreeSet::new(),
             used_fun_tparams: BTreeSet::new(),
             translating_fun: false,
+            signature_arity_errors: None,
             current_package: None,
         }
     }
@@ -205,28 +237,29 @@ impl<'env> Context<'env> {
     fn resolve_module_type(&mut self, loc: Loc, m: &ModuleIdent, n: &Name) -> Option<ModuleType> {
         let types = match self.scoped_types.get(m) {
             None => {
-                self.env.add_diag(diag!(
-                    NameResolution::UnboundModule,
-                    (m.loc, format!("Unbound module '{}'", m)),
-                ));
+                self.env.add_diag(unbound_module_diag(loc, m));
                 return None;
             }
             Some(members) => members,
         };
         match types.get(&n.value) {
             None => {
-                let msg = format!(
-                    "Invalid module access. Unbound struct '{}' in module '{}'",
-                    n, m
-                );
                 self.env
-                    .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
+                    .add_diag(unbound_module_member_diag(loc, m, n, "struct"));
                 None
             }
             Some(module_type) => Some(module_type.clone()),
         }
     }
 
+    fn struct_field_names(&self, m: &ModuleIdent, n: &StructName) -> Vec<Symbol> {
+        self.scoped_types
+            .get(m)
+            .and_then(|types| types.get(&n.value()))
+            .map(|module_type| module_type.field_names.clone())
+            .unwrap_or_default()
+    }
+
     fn resolve_module_function(
         &mut self,
         loc: Loc,
@@ -235,22 +268,15 @@ impl<'env> Context<'env> {
     ) -> Option<FunctionName> {
         let functions = match self.scoped_functions.get(m) {
             None => {
-                self.env.add_diag(diag!(
-                    NameResolution::UnboundModule,
-                    (m.loc, format!("Unbound module '{}'", m)),
-                ));
+                self.env.add_diag(unbound_module_diag(loc, m));
                 return None;
             }
             Some(members) => members,
         };
         match functions.get(&n.value).cloned() {
             None => {
-                let msg = format!(
-                    "Invalid module access. Unbound function '{}' in module '{}'",
-                    n, m
-                );
                 self.env
-                    .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
+                    .add_diag(unbound_module_member_diag(loc, m, n, "function"));
                 None
             }
             Some(_) => Some(FunctionName(*n)),
@@ -265,22 +291,30 @@ impl<'env> Context<'env> {
     ) -> Option<ConstantName> {
         let constants = match self.scoped_constants.get(m) {
             None => {
-                self.env.add_diag(diag!(
-                    NameResolution::UnboundModule,
-                    (m.loc, format!("Unbound module '{}'", m)),
-                ));
+                self.env.add_diag(unbound_module_diag(loc, m));
                 return None;
             }
             Some(members) => members,
         };
         match constants.get(&n.value).cloned() {
             None => {
-                let msg = format!(
-                    "Invalid module access. Unbound constant '{}' in module '{}'",
-                    n, m
-                );
-                self.env
-                    .add_diag(diag!(NameResolution::UnboundModuleMember, (loc, msg)));
+                let mut diag = unbound_module_member_diag(loc, m, &n, "constant");
+                let names = constants.keys().map(|name| name.as_str());
+                for similar in similar_names(n.value.as_str(), names) {
+                    diag.add_secondary_label((n.loc, format!("Did you mean '{similar}'?")));
+                }
+                self.env.add_diag(diag);
+                None
+            }
+            Some(defined_loc) if self.current_module.as_ref() != Some(m) => {
+                let msg = format!("Invalid access of '{}::{}'", m, n);
+                let internal_msg = "Constants are internal to their module. Consider exposing a \
+                                    public function returning the value";
+                self.env.add_diag(diag!(
+                    NameResolution::InvalidConstantAccess,
+                    (loc, msg),
+                    (defined_loc, internal_msg)
+                ));
                 None
             }
             Some(_) => Some(ConstantName(n)),
@@ -373,7 +407,7 @@ impl<'env> Context<'env> {
                     module_type:
                         ModuleType {
                             original_mident: m,
-                            arity,
+                            type_parameters,
                             is_positional,
                             ..
                         },
@@ -383,7 +417,7 @@ impl<'env> Context<'env> {
                 let tys_opt = etys_opt.map(|etys| {
                     let tys = types(self, etys);
                     let name_f = || format!("{}::{}", &m, &n);
-                    check_type_argument_arity(self, loc, name_f, tys, arity)
+                    check_type_argument_arity(self, loc, name_f, tys, &type_parameters)
                 });
                 Some((m, StructName(n), tys_opt, is_positional))
             }
@@ -441,7 +475,25 @@ impl<'env> Context<'env> {
             .entry(name)
             .and_modify(|c| *c += 1)
             .or_insert(default);
-        self.local_scopes.last_mut().unwrap().insert(name, id);
+        let shadowed = self
+            .local_scopes
+            .last_mut()
+            .unwrap()
+            .insert(name, (id, vloc));
+        if let Some((_, shadowed_loc)) = shadowed {
+            // parameters cannot shadow anything, duplicates are reported separately
+            if !is_parameter
+                && self.env.is_warning_enabled(FILTER_SHADOWED_VARIABLE)
+                && !name.as_str().starts_with('_')
+            {
+                let msg = format!("Local '{name}' shadows a previous local of the same name");
+                self.env.add_diag(diag!(
+                    NameResolution::ShadowedVariable,
+                    (vloc, msg),
+                    (shadowed_loc, format!("'{name}' previously declared here")),
+                ));
+            }
+        }
         // all locals start at color zero
         // they will be incremented when substituted for macros
         let nvar_ = N::Var_ { name, id, color: 0 };
@@ -455,7 +507,12 @@ impl<'env> Context<'env> {
         variable_msg: impl FnOnce(Symbol) -> S,
         sp!(vloc, name): Name,
     ) -> Option<N::Var> {
-        let id_opt = self.local_scopes.last().unwrap().get(&name).copied();
+        let id_opt = self
+            .local_scopes
+            .last()
+            .unwrap()
+            .get(&name)
+            .map(|(id, _)| *id);
         match id_opt {
             None => {
                 let msg = variable_msg(name);
@@ -507,41 +564,23 @@ impl<'env> Context<'env> {
             return None;
         };
         if *name_type == NominalBlockType::LambdaLoopCapture {
-            // lambdas capture break/continue even though it is not yet supported
+            // Lambdas are expanded at their call site, so a 'break' or 'continue' in a lambda
+            // refers to the loop enclosing the lambda, if there is one
+            let enclosing_loop_opt = self
+                .nominal_blocks
+                .iter()
+                .rev()
+                .find(|(_, _, name_type)| matches!(name_type, NominalBlockType::Loop(_)));
+            if let Some((_, loop_label, _)) = enclosing_loop_opt {
+                return Some(*loop_label);
+            }
             let msg =
                 format!("Invalid '{usage}'. This usage is not yet supported for lambdas or macros");
-            let mut diag = diag!(
+            self.env.add_diag(diag!(
                 TypeSafety::InvalidLoopControl,
                 (loc, msg),
                 (label.label.loc, "Inside this lambda")
-            );
-            // suggest adding a label to the loop
-            let most_recent_loop_opt =
-                self.nominal_blocks
-                    .iter()
-                    .rev()
-                    .find_map(|(name, label, name_type)| {
-                        if let NominalBlockType::Loop(loop_type) = name_type {
-                            Some((name, label, *loop_type))
-                        } else {
-                            None
-                        }
-                    });
-            if let Some((name, loop_label, loop_type)) = most_recent_loop_opt {
-                let msg = if let Some(loop_label) = name {
-                    format!(
-                        "To '{usage}' to this loop, specify the label, \
-                        e.g. `{usage} '{loop_label}`",
-                    )
-                } else {
-                    format!(
-                        "To '{usage}' to this loop, add a label, \
-                        e.g. `'label: {loop_type}` and `{usage} 'label`",
-                    )
-                };
-                diag.add_secondary_label((loop_label.label.loc, msg));
-            }
-            self.env.add_diag(diag);
+            ));
             return None;
         }
         Some(*label)
@@ -667,6 +706,33 @@ impl std::fmt::Display for NominalBlockType {
     }
 }
 
+/// Unbound module in a module access. The primary label covers the full access path, e.g. `a::m::S`,
+/// with a secondary label on the module segment when it is distinct
+fn unbound_module_diag(loc: Loc, m: &ModuleIdent) -> Diagnostic {
+    let mut diag = diag!(
+        NameResolution::UnboundModule,
+        (loc, format!("Unbound module '{}'", m)),
+    );
+    if m.loc != loc {
+        diag.add_secondary_label((m.loc, format!("No module '{}' is in scope", m)));
+    }
+    diag
+}
+
+/// Unbound member in a module access. The primary label covers the full access path, e.g.
+/// `a::m::S`, with a secondary label on the member segment when it is distinct
+fn unbound_module_member_diag(loc: Loc, m: &ModuleIdent, n: &Name, kind: &str) -> Diagnostic {
+    let msg = format!(
+        "Invalid module access. Unbound {} '{}' in module '{}'",
+        kind, n, m
+    );
+    let mut diag = diag!(NameResolution::UnboundModuleMember, (loc, msg));
+    if n.loc != loc {
+        diag.add_secondary_label((n.loc, format!("No {} named '{}' in '{}'", kind, n, m)));
+    }
+    diag
+}
+
 //**************************************************************************************************
 // Entry
 //**************************************************************************************************
@@ -857,15 +923,43 @@ fn explicit_use_fun(
     if let Some(pub_loc) = is_public {
         let current_module = context.current_module;
         if let Err(def_loc_opt) = use_fun_module_defines(context, current_module, &tn) {
-            let msg = "Invalid 'use fun'. Cannot publicly associate a function with a \
-                type defined in another module";
+            let msg = match &tn.value {
+                N::TypeName_::Builtin(sp!(_, b_)) => format!(
+                    "Invalid 'use fun'. Cannot publicly associate a function with the primitive \
+                     type '{}' outside of the module defining it",
+                    b_
+                ),
+                _ => "Invalid 'use fun'. Cannot publicly associate a function with a type \
+                    defined in another module"
+                    .to_owned(),
+            };
             let pub_msg = format!(
                 "Declared '{}' here. Consider removing to make a local 'use fun' instead",
                 Visibility::PUBLIC
             );
             let mut diag = diag!(Declarations::InvalidUseFun, (loc, msg), (pub_loc, pub_msg));
-            if let Some(def_loc) = def_loc_opt {
-                diag.add_secondary_label((def_loc, "Type defined in another module here"));
+            match &tn.value {
+                N::TypeName_::Builtin(sp!(_, b_)) => match context.env.primitive_definer(*b_) {
+                    Some(definer) => {
+                        let def_msg = format!(
+                            "The primitive type '{}' is defined by the module '{}', declared here",
+                            b_, definer
+                        );
+                        diag.add_secondary_label((definer.loc, def_msg))
+                    }
+                    None => diag.add_note(format!(
+                        "No module is configured to define the primitive type '{}'. A module \
+                         defines it if annotated with '#[{}({})]'",
+                        b_,
+                        DefinesPrimitive::DEFINES_PRIM,
+                        b_
+                    )),
+                },
+                _ => {
+                    if let Some(def_loc) = def_loc_opt {
+                        diag.add_secondary_label((def_loc, "Type defined in another module here"));
+                    }
+                }
             }
             context.env.add_diag(diag);
             return None;
@@ -1004,6 +1098,7 @@ fn function(
     assert!(!context.translating_fun);
     assert!(context.local_count.is_empty());
     assert!(context.local_scopes.is_empty());
+    assert!(context.syntax_parameters.is_empty());
     assert!(context.nominal_block_id == 0);
     assert!(context.used_fun_tparams.is_empty());
     assert!(context.used_locals.is_empty());
@@ -1026,10 +1121,12 @@ fn function(
         }
     }
 
+    let test_only = test_only_loc(&attributes);
     let mut f = N::Function {
         warning_filter,
         index,
         attributes,
+        test_only,
         visibility,
         macro_,
         entry,
@@ -1041,6 +1138,7 @@ fn function(
     remove_unused_bindings_function(context, &used_locals, &mut f);
     context.local_count = BTreeMap::new();
     context.local_scopes = vec![];
+    context.syntax_parameters = BTreeMap::new();
     context.nominal_block_id = 0;
     context.used_fun_tparams = BTreeSet::new();
     context.used_locals = BTreeSet::new();
@@ -1051,6 +1149,7 @@ fn function(
 
 fn function_signature(context: &mut Context, sig: E::FunctionSignature) -> N::FunctionSignature {
     let type_parameters = fun_type_parameters(context, sig.type_parameters);
+    context.signature_arity_errors = Some(BTreeSet::new());
 
     let mut declared = UniqueMap::new();
     let parameters = sig
@@ -1090,12 +1189,19 @@ fn function_signature(context: &mut Context, sig: E::FunctionSignature) -> N::Fu
                 }
             }
             let is_parameter = true;
+            let is_syntax_identifier = param.is_syntax_identifier();
             let nparam = context.declare_local(is_parameter, param.0);
-            let nparam_ty = type_(context, param_ty);
+            let nparam_ty = type_no_holes(context, "a function signature", param_ty);
+            if is_syntax_identifier {
+                context
+                    .syntax_parameters
+                    .insert(nparam.value.name, (nparam.loc, nparam_ty.clone()));
+            }
             (mut_, nparam, nparam_ty)
         })
         .collect();
-    let return_type = type_(context, sig.return_type);
+    let return_type = type_no_holes(context, "a function signature", sig.return_type);
+    context.signature_arity_errors = None;
     N::FunctionSignature {
         type_parameters,
         parameters,
@@ -1127,6 +1233,7 @@ fn struct_def(
         index,
         attributes,
         loc: _loc,
+        visibility,
         abilities,
         type_parameters,
         fields,
@@ -1135,36 +1242,51 @@ fn struct_def(
     let type_parameters = struct_type_parameters(context, type_parameters);
     let fields = struct_fields(context, fields);
     context.env.pop_warning_filter_scope();
+    let test_only = test_only_loc(&attributes);
     N::StructDefinition {
         warning_filter,
         index,
         attributes,
+        test_only,
+        visibility,
         abilities,
         type_parameters,
         fields,
     }
 }
 
+/// The location of a module member's '#[test_only]' attribute, if it has one
+fn test_only_loc(attributes: &E::Attributes) -> Option<Loc> {
+    attributes
+        .get_loc_(&KnownAttribute::from(TestingAttribute::TestOnly))
+        .copied()
+}
+
 fn positional_field_name(loc: Loc, idx: usize) -> Field {
     Field::add_loc(loc, format!("{idx}").into())
 }
 
+fn is_positional_field_name(f: &Field) -> bool {
+    f.value().as_str().chars().all(|c| c.is_ascii_digit())
+}
+
 fn struct_fields(context: &mut Context, efields: E::StructFields) -> N::StructFields {
     match efields {
         E::StructFields::Native(loc) => N::StructFields::Native(loc),
-        E::StructFields::Named(em) => {
-            N::StructFields::Defined(em.map(|_f, (idx, t)| (idx, type_(context, t))))
-        }
+        E::StructFields::Named(em) => N::StructFields::Defined(
+            false,
+            em.map(|_f, (idx, t)| (idx, type_no_holes(context, "a struct field", t))),
+        ),
         E::StructFields::Positional(tys) => {
             let fields = tys
                 .into_iter()
-                .map(|ty| type_(context, ty))
+                .map(|ty| type_no_holes(context, "a struct field", ty))
                 .enumerate()
                 .map(|(idx, ty)| {
                     let field_name = positional_field_name(ty.loc, idx);
                     (field_name, (idx, ty))
                 });
-            N::StructFields::Defined(UniqueMap::maybe_from_iter(fields).unwrap())
+            N::StructFields::Defined(true, UniqueMap::maybe_from_iter(fields).unwrap())
         }
     }
 }
@@ -1187,17 +1309,19 @@ fn constant(context: &mut Context, _name: ConstantName, econstant: E::Constant)
     assert!(context.used_locals.is_empty());
     context.env.add_warning_filter_scope(warning_filter.clone());
     context.local_scopes = vec![BTreeMap::new()];
-    let signature = type_(context, esignature);
+    let signature = type_no_holes(context, "a constant", esignature);
     let value = *exp(context, Box::new(evalue));
     context.local_scopes = vec![];
     context.local_count = BTreeMap::new();
     context.used_locals = BTreeSet::new();
     context.nominal_block_id = 0;
     context.env.pop_warning_filter_scope();
+    let test_only = test_only_loc(&attributes);
     N::Constant {
         warning_filter,
         index,
         attributes,
+        test_only,
         loc,
         signature,
         value,
@@ -1278,6 +1402,9 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
             assert!(context.env.has_errors());
             NT::UnresolvedError
         }
+        ET::Apply(sp!(_, E::ModuleAccess_::Name(n)), tys) if n.value == symbol!("_") => {
+            type_hole(context, loc, tys)
+        }
         ET::Apply(ma, tys) => match context.resolve_type(ma) {
             RT::Unbound => {
                 assert!(context.env.has_errors());
@@ -1287,7 +1414,7 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
                 let name_f = || format!("{}", &bn_);
                 let arity = bn_.tparam_constraints(loc).len();
                 let tys = types(context, tys);
-                let tys = check_type_argument_arity(context, loc, name_f, tys, arity);
+                let tys = check_builtin_type_argument_arity(context, loc, name_f, tys, arity);
                 NT::builtin_(sp(ma.loc, bn_), tys)
             }
             RT::TParam(_, tp) => {
@@ -1311,14 +1438,14 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
                     module_type:
                         ModuleType {
                             original_mident: m,
-                            arity,
+                            type_parameters,
                             ..
                         },
                 } = *mt;
                 let tn = sp(nloc, NN::ModuleType(m, StructName(n)));
                 let tys = types(context, tys);
                 let name_f = || format!("{}", tn);
-                let tys = check_type_argument_arity(context, loc, name_f, tys, arity);
+                let tys = check_type_argument_arity(context, loc, name_f, tys, &type_parameters);
                 NT::Apply(None, tn, tys)
             }
         },
@@ -1331,7 +1458,115 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
     sp(loc, ty_)
 }
 
+// A placeholder '_' for a type to be inferred, e.g. `let v: vector<_> = vector[]`. It becomes a
+// fresh type variable when instantiated in typing
+fn type_hole(context: &mut Context, loc: Loc, tys: Vec<E::Type>) -> N::Type_ {
+    if !context
+        .env
+        .check_feature(FeatureGate::TypeHoles, context.current_package, loc)
+    {
+        return N::Type_::UnresolvedError;
+    }
+    if !tys.is_empty() {
+        let msg = "Invalid type placeholder '_'. It cannot take type arguments";
+        context
+            .env
+            .add_diag(diag!(NameResolution::InvalidTypeHole, (loc, msg)));
+        return N::Type_::UnresolvedError;
+    }
+    N::Type_::Hole
+}
+
+/// For types that must be fully specified, e.g. in a function signature, where a placeholder '_'
+/// cannot be inferred
+fn type_no_holes(context: &mut Context, case: &str, ety: E::Type) -> N::Type {
+    let mut ty = type_(context, ety);
+    report_type_holes(context, case, &mut ty);
+    ty
+}
+
+fn report_type_holes(context: &mut Context, case: &str, sp!(loc, ty_): &mut N::Type) {
+    use N::Type_ as NT;
+    match ty_ {
+        NT::Hole => {
+            let msg = format!(
+                "Invalid type placeholder '_' in {case}. \
+                Types can only be inferred in annotations, e.g. 'let x: vector<_> = ...'"
+            );
+            context
+                .env
+                .add_diag(diag!(NameResolution::InvalidTypeHole, (*loc, msg)));
+            *ty_ = NT::UnresolvedError;
+        }
+        NT::Ref(_, inner) => report_type_holes(context, case, inner),
+        NT::Apply(_, _, tys) => {
+            for ty in tys {
+                report_type_holes(context, case, ty)
+            }
+        }
+        NT::Fun(args, result) => {
+            for ty in args {
+                report_type_holes(context, case, ty)
+            }
+            report_type_holes(context, case, result)
+        }
+        NT::Unit | NT::Param(_) | NT::Var(_) | NT::UnresolvedError => (),
+    }
+}
+
+/// Checks the type arguments of a struct against its declared type parameters. A missing type
+/// argument is filled in with the type parameter in scope of the same name, if any, since that is
+/// most likely what was intended, e.g. `fun f<K, V>(t: Table)`. This avoids any follow up errors
+/// or unused type parameter warnings. Otherwise it is filled in with an error type. Within a
+/// function signature, each site is reported only once.
 fn check_type_argument_arity<F: FnOnce() -> String>(
+    context: &mut Context,
+    loc: Loc,
+    name_f: F,
+    mut ty_args: Vec<N::Type>,
+    type_parameters: &[Symbol],
+) -> Vec<N::Type> {
+    let arity = type_parameters.len();
+    let args_len = ty_args.len();
+    if args_len != arity {
+        let already_reported = context
+            .signature_arity_errors
+            .as_mut()
+            .is_some_and(|reported| !reported.insert(loc));
+        if !already_reported {
+            let diag_code = if args_len > arity {
+                NameResolution::TooManyTypeArguments
+            } else {
+                NameResolution::TooFewTypeArguments
+            };
+            let msg = format!(
+                "Invalid instantiation of '{}<{}>'. Expected {} type argument(s) but got {}",
+                name_f(),
+                format_comma(type_parameters),
+                arity,
+                args_len
+            );
+            context.env.add_diag(diag!(diag_code, (loc, msg)));
+        }
+    }
+
+    ty_args.truncate(arity);
+    for name in &type_parameters[ty_args.len()..] {
+        let ty_ = match context.unscoped_types.get(name) {
+            Some(ResolvedType::TParam(_, tp)) => {
+                if context.translating_fun {
+                    context.used_fun_tparams.insert(tp.id);
+                }
+                N::Type_::Param(tp.clone())
+            }
+            _ => N::Type_::UnresolvedError,
+        };
+        ty_args.push(sp(loc, ty_))
+    }
+    ty_args
+}
+
+fn check_builtin_type_argument_arity<F: FnOnce() -> String>(
     context: &mut Context,
     loc: Loc,
     name_f: F,
@@ -1417,6 +1652,9 @@ fn exps(context: &mut Context, es: Vec<E::Exp>) -> Vec<N::Exp> {
     es.into_iter().map(|e| *exp(context, Box::new(e))).collect()
 }
 
+/// The abort code used for an 'abort' without an explicit code
+pub const IMPLICIT_ABORT_CODE: u64 = u64::MAX;
+
 fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
     use E::Exp_ as EE;
     use N::Exp_ as NE;
@@ -1522,6 +1760,9 @@ fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
                     assert!(context.env.has_errors());
                     NE::UnresolvedError
                 }
+                Some(ndot) if !check_syntax_parameter_mutation(context, &ndot) => {
+                    NE::UnresolvedError
+                }
                 Some(ndot) => NE::FieldMutate(ndot, ner),
             }
         }
@@ -1531,7 +1772,14 @@ fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
             NE::Mutate(nel, ner)
         }
 
-        EE::Abort(es) => NE::Abort(exp(context, es)),
+        EE::Abort(Some(es)) => NE::Abort(exp(context, es)),
+        EE::Abort(None) => {
+            let code = sp(
+                eloc,
+                NE::Value(sp(eloc, E::Value_::U64(IMPLICIT_ABORT_CODE))),
+            );
+            NE::Abort(Box::new(code))
+        }
         EE::Return(Some(block_name), es) => {
             let out_rhs = exp(context, es);
             context
@@ -1604,7 +1852,12 @@ fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
                     NE::UnresolvedError
                 }
                 Some((m, sn, tys_opt, is_positional)) => {
-                    if is_positional {
+                    // Positional fields can also be given by their index, e.g. 'S { 0: x, 1: y }'
+                    let by_index = !efields.is_empty()
+                        && efields
+                            .key_cloned_iter()
+                            .all(|(f, _)| is_positional_field_name(&f));
+                    if is_positional && !by_index {
                         let msg = "Invalid struct instantiation. Positional struct declarations \
                              require positional instantiations.";
                         context
@@ -1633,7 +1886,7 @@ fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
             Some(d) => NE::ExpDotted(case, d),
         },
 
-        EE::Cast(e, t) => NE::Cast(exp(context, e), type_(context, t)),
+        EE::Cast(e, t) => NE::Cast(exp(context, e), type_no_holes(context, "a cast", t)),
         EE::Annotate(e, t) => NE::Annotate(exp(context, e), type_(context, t)),
 
         EE::Call(ma, is_macro, tys_opt, rhs) if context.resolves_to_struct(&ma) => {
@@ -1656,9 +1909,16 @@ fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
                     if !is_positional {
                         let msg = "Invalid struct instantiation. Named struct declarations \
                                    require named instantiations.";
-                        context
-                            .env
-                            .add_diag(diag!(NameResolution::PositionalCallMismatch, (eloc, msg)));
+                        let mut diag = diag!(NameResolution::PositionalCallMismatch, (eloc, msg));
+                        let field_names = context.struct_field_names(&m, &sn);
+                        if !field_names.is_empty() {
+                            let fields = format_comma(field_names.iter().map(|f| format!("'{f}'")));
+                            diag.add_note(format!(
+                                "'{}::{}' declares the named field(s) {}",
+                                m, sn, fields
+                            ));
+                        }
+                        context.env.add_diag(diag);
                     }
                     NE::Pack(
                         m,
@@ -1692,6 +1952,13 @@ fn exp(context: &mut Context, e: Box<E::Exp>) -> Box<N::Exp> {
                             macro so that arguments are no longer eagerly evaluated",
                             BF::ASSERT_MACRO
                         );
+                        if context.env.edition(context.current_package) == Edition::E2024_MIGRATION
+                        {
+                            let mut diag =
+                                diag!(Migration::NeedsAssertMacro, (bloc, help_msg.clone()));
+                            diag.add_fix(Loc::new(bloc.file_hash(), bloc.end(), bloc.end()), "!");
+                            context.env.add_diag(diag)
+                        }
                         context.env.add_diag(diag!(
                             Uncategorized::DeprecatedWillBeRemoved,
                             (bloc, dep_msg),
@@ -1821,10 +2088,57 @@ fn dotted(context: &mut Context, edot: E::ExpDotted) -> Option<N::ExpDotted> {
             }
         }
         E::ExpDotted_::Dot(d, f) => N::ExpDotted_::Dot(Box::new(dotted(context, *d)?), Field(f)),
+        E::ExpDotted_::Index(d, i) => {
+            let nd = dotted(context, *d)?;
+            let ni = exp(context, i);
+            N::ExpDotted_::Index(Box::new(nd), ni)
+        }
     };
     Some(sp(loc, nedot_))
 }
 
+// Mutating through a 'macro' parameter is only meaningful if the parameter is a reference.
+// Otherwise, the argument is substituted as a fresh value and the mutation is lost. This is
+// reported once here, at the definition, instead of at each expansion of the macro.
+fn check_syntax_parameter_mutation(context: &mut Context, ndot: &N::ExpDotted) -> bool {
+    let mut cur = ndot;
+    let root = loop {
+        match &cur.value {
+            N::ExpDotted_::Exp(e) => break e,
+            N::ExpDotted_::Dot(d, _) | N::ExpDotted_::Index(d, _) => cur = d,
+        }
+    };
+    let N::Exp_::Var(sp!(_, v)) = &root.value else {
+        return true;
+    };
+    let Some((decl_loc, ty)) = context.syntax_parameters.get(&v.name) else {
+        return true;
+    };
+    if matches!(
+        &ty.value,
+        N::Type_::Ref(_, _) | N::Type_::Anything | N::Type_::UnresolvedError
+    ) {
+        return true;
+    }
+    let msg = format!(
+        "Cannot mutate the argument for parameter '{}'. \
+        Arguments must be used in value positions",
+        v.name
+    );
+    let decl_msg = format!(
+        "Parameter '{}' declared here, without a reference type",
+        v.name
+    );
+    let mut diag = diag!(
+        TypeSafety::CannotExpandMacro,
+        (ndot.loc, msg),
+        (*decl_loc, decl_msg)
+    );
+    diag.add_note(ASSIGN_SYNTAX_IDENTIFIER_NOTE);
+    context.env.add_diag(diag);
+    false
+}
+
 #[derive(Clone, Copy)]
 enum LValueCase {
     Bind,
@@ -1879,6 +2193,10 @@ fn lvalue(
                         v.0
                     );
                     let mut diag = diag!(TypeSafety::CannotExpandMacro, (loc, msg));
+                    if let Some((decl_loc, _)) = context.syntax_parameters.get(&n.value) {
+                        let decl_msg = format!("Parameter '{}' declared here", v.0);
+                        diag.add_secondary_label((*decl_loc, decl_msg));
+                    }
                     diag.add_note(ASSIGN_SYNTAX_IDENTIFIER_NOTE);
                     context.env.add_diag(diag);
                     return None;
@@ -2341,6 +2659,10 @@ fn remove_unused_bindings_exp_dotted(
     match ed_ {
         N::ExpDotted_::Exp(e) => remove_unused_bindings_exp(context, used, e),
         N::ExpDotted_::Dot(ed, _) => remove_unused_bindings_exp_dotted(context, used, ed),
+        N::ExpDotted_::Index(ed, i) => {
+            remove_unused_bindings_exp_dotted(context, used, ed);
+            remove_unused_bindings_exp(context, used, i)
+        }
     }
 }
 
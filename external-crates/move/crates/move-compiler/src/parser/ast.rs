@@ -221,6 +221,7 @@ pub struct StructTypeParameter {
 pub struct StructDefinition {
     pub attributes: Vec<Attributes>,
     pub loc: Loc,
+    pub visibility: Visibility,
     pub abilities: Vec<Ability>,
     pub name: StructName,
     pub type_parameters: Vec<StructTypeParameter>,
@@ -647,7 +648,8 @@ pub enum Exp_ {
     Assign(Box<Exp>, Box<Exp>),
 
     // abort e
-    Abort(Box<Exp>),
+    // abort
+    Abort(Option<Box<Exp>>),
     // return e
     Return(Option<BlockLabel>, Option<Box<Exp>>),
     // break
@@ -1286,12 +1288,14 @@ impl AstDebug for StructDefinition {
         let StructDefinition {
             attributes,
             loc: _loc,
+            visibility,
             abilities,
             name,
             type_parameters,
             fields,
         } = self;
         attributes.ast_debug(w);
+        visibility.ast_debug(w);
 
         w.list(abilities, " ", |w, ab_mod| {
             ab_mod.ast_debug(w);
@@ -1881,8 +1885,11 @@ impl AstDebug for Exp_ {
                 rhs.ast_debug(w);
             }
             E::Abort(e) => {
-                w.write("abort ");
-                e.ast_debug(w);
+                w.write("abort");
+                if let Some(e) = e {
+                    w.write(" ");
+                    e.ast_debug(w);
+                }
             }
             E::Return(name, e) => {
                 w.write("return");
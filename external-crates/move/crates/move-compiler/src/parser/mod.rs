@@ -157,6 +157,7 @@ fn parse_file(
     let mut source_buffer = String::new();
     f.read_to_string(&mut source_buffer)?;
     let file_hash = FileHash::new(&source_buffer);
+    compilation_env.add_file_package(file_hash, package);
     let buffer = match verify_string(file_hash, &source_buffer) {
         Err(ds) => {
             compilation_env.add_diags(ds);
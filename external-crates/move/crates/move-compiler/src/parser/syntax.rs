@@ -13,7 +13,7 @@ use move_symbol_pool::{symbol, Symbol};
 use crate::{
     diag,
     diagnostics::{Diagnostic, Diagnostics},
-    editions::{Edition, FeatureGate},
+    editions::{create_feature_error, Edition, FeatureGate},
     parser::{ast::*, lexer::*},
     shared::*,
     MatchedFileCommentMap,
@@ -475,6 +475,31 @@ fn parse_field(context: &mut Context) -> Result<Field, Box<Diagnostic>> {
     Ok(Field(parse_identifier(context)?))
 }
 
+// Parse the index of a positional field, used as its name:
+//      PositionalField = <Number>
+fn parse_positional_field_name(context: &mut Context) -> Result<Field, Box<Diagnostic>> {
+    let loc = current_token_loc(context.tokens);
+    context
+        .env
+        .check_feature(FeatureGate::PositionalFields, context.package_name, loc);
+    let contents = context.tokens.content();
+    consume_token(context.tokens, Tok::NumValue)?;
+    let name = match parse_u8(contents) {
+        Ok((parsed, NumberFormat::Decimal)) => format!("{parsed}").into(),
+        Ok((_, NumberFormat::Hex)) | Err(_) => {
+            let msg = format!(
+                "Invalid field name. Expected a decimal number less than or equal to {}",
+                u8::MAX
+            );
+            let mut diag = diag!(Syntax::UnexpectedToken, (loc, msg));
+            diag.add_note("Positional fields must be a decimal number in the range [0 .. 255] and not be typed, e.g. `0`");
+            context.env.add_diag(diag);
+            contents.into()
+        }
+    };
+    Ok(Field(Name::new(loc, name)))
+}
+
 // Parse a module name:
 //      ModuleName = <Identifier>
 fn parse_module_name(context: &mut Context) -> Result<ModuleName, Box<Diagnostic>> {
@@ -788,7 +813,14 @@ fn parse_attributes(context: &mut Context) -> Result<Vec<Attributes>, Box<Diagno
 
 // Parse a field name optionally followed by a colon and an expression argument:
 //      ExpField = <Field> <":" <Exp>>?
+//               | <PositionalField> ":" <Exp>
 fn parse_exp_field(context: &mut Context) -> Result<(Field, Exp), Box<Diagnostic>> {
+    if context.tokens.peek() == Tok::NumValue {
+        let f = parse_positional_field_name(context)?;
+        consume_token(context.tokens, Tok::Colon)?;
+        let arg = parse_exp(context)?;
+        return Ok((f, arg));
+    }
     let f = parse_field(context)?;
     let arg = if match_token(context.tokens, Tok::Colon)? {
         parse_exp(context)?
@@ -1426,9 +1458,25 @@ fn parse_control_exp(context: &mut Context) -> Result<(Exp, bool), Box<Diagnosti
             (Exp_::Return(label, e), ends_in_block)
         }
         Tok::Abort => {
+            let abort_loc = current_token_loc(context.tokens);
             context.tokens.advance()?;
-            let (e, ends_in_block) = parse_exp_or_sequence(context)?;
-            (Exp_::Abort(Box::new(e)), ends_in_block)
+            let (e, ends_in_block) = if !at_start_of_exp(context) {
+                if !context
+                    .env
+                    .supports_feature(context.package_name, FeatureGate::AbortWithoutCode)
+                {
+                    let edition = context.env.edition(context.package_name);
+                    let mut diag =
+                        create_feature_error(edition, FeatureGate::AbortWithoutCode, abort_loc);
+                    diag.add_note("Try providing an abort code, e.g. 'abort 0'");
+                    context.env.add_diag(diag);
+                }
+                (None, false)
+            } else {
+                let (e, ends_in_block) = parse_exp_or_sequence(context)?;
+                (Some(Box::new(e)), ends_in_block)
+            };
+            (Exp_::Abort(e), ends_in_block)
         }
         Tok::Break => {
             context.tokens.advance()?;
@@ -2491,7 +2539,7 @@ fn parse_struct_decl(
         macro_,
     } = modifiers;
 
-    check_struct_visibility(visibility, context);
+    check_struct_visibility(visibility.clone(), context);
 
     check_no_modifier(context, ENTRY_MODIFIER, entry, "struct");
     check_no_modifier(context, MACRO_MODIFIER, macro_, "struct");
@@ -2559,6 +2607,7 @@ fn parse_struct_decl(
     Ok(StructDefinition {
         attributes,
         loc,
+        visibility: visibility.unwrap_or(Visibility::Internal),
         abilities,
         name,
         type_parameters,
@@ -2695,9 +2744,12 @@ fn check_struct_visibility(visibility: Option<Visibility>, context: &mut Context
             );
             let note = "Visibility annotations are required on struct declarations from the Move 2024 edition onwards.";
             if context.env.edition(current_package) == Edition::E2024_MIGRATION {
-                context
-                    .env
-                    .add_diag(diag!(Migration::NeedsPublic, (loc, msg.clone())))
+                let mut diag = diag!(Migration::NeedsPublic, (loc, msg.clone()));
+                diag.add_fix(
+                    Loc::new(loc.file_hash(), loc.start(), loc.start()),
+                    "public ",
+                );
+                context.env.add_diag(diag)
             }
             let mut err = diag!(Syntax::InvalidModifier, (loc, msg));
             err.add_note(note);
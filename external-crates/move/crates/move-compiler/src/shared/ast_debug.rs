@@ -27,7 +27,8 @@
 //**************************************************************************************************
 // Macros
 //**************************************************************************************************
-use std::fmt::Display;
+use move_ir_types::location::Loc;
+use std::{collections::BTreeMap, fmt::Display};
 
 #[macro_export]
 macro_rules! debug_print {
@@ -97,6 +98,8 @@ pub struct AstWriter {
     verbose: bool,
     margin: usize,
     lines: Vec<String>,
+    /// Notes about the nodes at these locations, e.g. that they come from a macro expansion
+    markers: BTreeMap<Loc, String>,
 }
 
 impl AstWriter {
@@ -105,6 +108,7 @@ impl AstWriter {
             verbose,
             margin: 0,
             lines: vec![String::new()],
+            markers: BTreeMap::new(),
         }
     }
 
@@ -116,6 +120,18 @@ impl AstWriter {
         Self::new(true)
     }
 
+    /// A verbose writer, where nodes can write the note for their location with `marker`
+    pub fn verbose_with_markers(markers: BTreeMap<Loc, String>) -> Self {
+        Self {
+            markers,
+            ..Self::verbose()
+        }
+    }
+
+    pub fn marker(&self, loc: Loc) -> Option<&str> {
+        self.markers.get(&loc).map(|marker| marker.as_str())
+    }
+
     fn cur(&mut self) -> &mut String {
         self.lines.last_mut().unwrap()
     }
@@ -0,0 +1,41 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types that could not be inferred while typing, recorded if `Flags::record_inference_holes` is
+//! set. Each is also reported as an error, asking for an annotation. These entries are meant for
+//! tooling offering to insert that annotation.
+
+use move_ir_types::location::Loc;
+
+use crate::{
+    expansion::ast::{AbilitySet, ModuleIdent},
+    naming::ast::TVar,
+    parser::ast::FunctionName,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferenceHoleKind {
+    /// The type of the expression before a field access, e.g. `x` in `x.f`
+    FieldAccess,
+    /// The type of the expression before an index access, e.g. `x` in `x[i]`
+    IndexAccess,
+    /// The type of the receiver of a method call, e.g. `x` in `x.foo()`
+    MethodReceiver,
+    /// Any other type left uninferred once the function was typed, e.g. of a local
+    Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct InferenceHole {
+    /// Where the annotation is needed
+    pub loc: Loc,
+    pub kind: InferenceHoleKind,
+    /// The type variable left uninferred, if the type was not otherwise unknown
+    pub tvar: Option<TVar>,
+    /// If the type must be numeric, e.g. from a literal or an arithmetic operation
+    pub numeric: bool,
+    /// The abilities the type is required to have
+    pub abilities: AbilitySet,
+    /// The function being typed, if any
+    pub function: Option<(ModuleIdent, FunctionName)>,
+}
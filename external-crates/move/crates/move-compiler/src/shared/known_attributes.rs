@@ -24,6 +24,10 @@ pub enum KnownAttribute {
     Diagnostic(DiagnosticAttribute),
     DefinesPrimitive(DefinesPrimitive),
     External(ExternalAttribute),
+    Deprecation(DeprecationAttribute),
+    Error(ErrorAttribute),
+    Syntax(SyntaxAttribute),
+    ConstFun(ConstFunAttribute),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -61,6 +65,18 @@ pub struct DefinesPrimitive;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ExternalAttribute;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeprecationAttribute;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ErrorAttribute;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SyntaxAttribute;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConstFunAttribute;
+
 impl AttributePosition {
     const ALL: &'static [Self] = &[
         Self::AddressBlock,
@@ -86,6 +102,10 @@ impl KnownAttribute {
             DiagnosticAttribute::LINT_ALLOW => DiagnosticAttribute::LintAllow.into(),
             DefinesPrimitive::DEFINES_PRIM => DefinesPrimitive.into(),
             ExternalAttribute::EXTERNAL => ExternalAttribute.into(),
+            DeprecationAttribute::DEPRECATED => DeprecationAttribute.into(),
+            ErrorAttribute::ERROR => ErrorAttribute.into(),
+            SyntaxAttribute::SYNTAX => SyntaxAttribute.into(),
+            ConstFunAttribute::CONST_FUN => ConstFunAttribute.into(),
             _ => return None,
         })
     }
@@ -98,6 +118,10 @@ impl KnownAttribute {
             Self::Diagnostic(a) => a.name(),
             Self::DefinesPrimitive(a) => a.name(),
             Self::External(a) => a.name(),
+            Self::Deprecation(a) => a.name(),
+            Self::Error(a) => a.name(),
+            Self::Syntax(a) => a.name(),
+            Self::ConstFun(a) => a.name(),
         }
     }
 
@@ -109,6 +133,10 @@ impl KnownAttribute {
             Self::Diagnostic(a) => a.expected_positions(),
             Self::DefinesPrimitive(a) => a.expected_positions(),
             Self::External(a) => a.expected_positions(),
+            Self::Deprecation(a) => a.expected_positions(),
+            Self::Error(a) => a.expected_positions(),
+            Self::Syntax(a) => a.expected_positions(),
+            Self::ConstFun(a) => a.expected_positions(),
         }
     }
 }
@@ -266,6 +294,69 @@ impl ExternalAttribute {
     }
 }
 
+impl DeprecationAttribute {
+    pub const DEPRECATED: &'static str = "deprecated";
+    pub const NOTE: &'static str = "note";
+
+    pub const fn name(&self) -> &str {
+        Self::DEPRECATED
+    }
+
+    pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+        static DEPRECATION_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+            BTreeSet::from([
+                AttributePosition::Constant,
+                AttributePosition::Struct,
+                AttributePosition::Function,
+            ])
+        });
+        &DEPRECATION_POSITIONS
+    }
+}
+
+impl ErrorAttribute {
+    pub const ERROR: &'static str = "error";
+
+    pub const fn name(&self) -> &str {
+        Self::ERROR
+    }
+
+    pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+        static ERROR_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+            Lazy::new(|| BTreeSet::from([AttributePosition::Constant]));
+        &ERROR_POSITIONS
+    }
+}
+
+impl SyntaxAttribute {
+    pub const SYNTAX: &'static str = "syntax";
+    pub const INDEX: &'static str = "index";
+
+    pub const fn name(&self) -> &str {
+        Self::SYNTAX
+    }
+
+    pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+        static SYNTAX_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+            Lazy::new(|| BTreeSet::from([AttributePosition::Function]));
+        &SYNTAX_POSITIONS
+    }
+}
+
+impl ConstFunAttribute {
+    pub const CONST_FUN: &'static str = "const_fun";
+
+    pub const fn name(&self) -> &str {
+        Self::CONST_FUN
+    }
+
+    pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+        static CONST_FUN_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+            Lazy::new(|| BTreeSet::from([AttributePosition::Function]));
+        &CONST_FUN_POSITIONS
+    }
+}
+
 //**************************************************************************************************
 // Display
 //**************************************************************************************************
@@ -294,6 +385,10 @@ impl fmt::Display for KnownAttribute {
             Self::Diagnostic(a) => a.fmt(f),
             Self::DefinesPrimitive(a) => a.fmt(f),
             Self::External(a) => a.fmt(f),
+            Self::Deprecation(a) => a.fmt(f),
+            Self::Error(a) => a.fmt(f),
+            Self::Syntax(a) => a.fmt(f),
+            Self::ConstFun(a) => a.fmt(f),
         }
     }
 }
@@ -334,6 +429,30 @@ impl fmt::Display for ExternalAttribute {
     }
 }
 
+impl fmt::Display for DeprecationAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Display for ErrorAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Display for SyntaxAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Display for ConstFunAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 //**************************************************************************************************
 // From
 //**************************************************************************************************
@@ -368,3 +487,23 @@ impl From<ExternalAttribute> for KnownAttribute {
         Self::External(a)
     }
 }
+impl From<DeprecationAttribute> for KnownAttribute {
+    fn from(a: DeprecationAttribute) -> Self {
+        Self::Deprecation(a)
+    }
+}
+impl From<ErrorAttribute> for KnownAttribute {
+    fn from(a: ErrorAttribute) -> Self {
+        Self::Error(a)
+    }
+}
+impl From<SyntaxAttribute> for KnownAttribute {
+    fn from(a: SyntaxAttribute) -> Self {
+        Self::Syntax(a)
+    }
+}
+impl From<ConstFunAttribute> for KnownAttribute {
+    fn from(a: ConstFunAttribute) -> Self {
+        Self::ConstFun(a)
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Size metrics collected while typing each function, if `Flags::record_metrics` is set. These
+//! are meant for finding the functions responsible for long compile times, e.g. in generated code.
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+
+use crate::{expansion::ast::ModuleIdent, parser::ast::FunctionName};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionMetrics {
+    /// The number of typed expressions produced for the function, including those produced by
+    /// macro expansions
+    pub typed_nodes: usize,
+    /// The number of macro expansions performed, including nested ones
+    pub macro_expansions: usize,
+    /// The number of typed expressions produced by macro expansions
+    pub macro_expanded_nodes: usize,
+    /// The largest number of constraints waiting to be solved at once
+    pub max_constraints: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompilationMetrics {
+    #[serde(serialize_with = "serialize_functions")]
+    pub functions: BTreeMap<(ModuleIdent, FunctionName), FunctionMetrics>,
+}
+
+impl CompilationMetrics {
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    pub fn get(&self, m: &ModuleIdent, f: &FunctionName) -> Option<&FunctionMetrics> {
+        self.functions.get(&(*m, *f))
+    }
+}
+
+// Functions are keyed by their 'module::function' name when serialized, so that the metrics can
+// be written to formats that only support string keys
+fn serialize_functions<S: Serializer>(
+    functions: &BTreeMap<(ModuleIdent, FunctionName), FunctionMetrics>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        functions
+            .iter()
+            .map(|((m, f), metrics)| (format!("{m}::{f}"), metrics)),
+    )
+}
@@ -7,15 +7,21 @@ use crate::{
     command_line as cli,
     diagnostics::{
         codes::{Category, Declarations, DiagnosticsID, Severity, WarningFilter},
-        Diagnostic, Diagnostics, WarningFilters,
+        Diagnostic, Diagnostics, DiagnosticsFormat, WarningFilters,
     },
     editions::{check_feature_or_error as edition_check_feature, Edition, FeatureGate, Flavor},
     expansion::ast as E,
     naming::ast as N,
+    parser::ast::FunctionName,
+    shared::{
+        inference_holes::InferenceHole,
+        metrics::{CompilationMetrics, FunctionMetrics},
+    },
     sui_mode,
     typing::visitor::{TypingVisitor, TypingVisitorObj},
 };
 use clap::*;
+use move_command_line_common::files::FileHash;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
@@ -29,7 +35,9 @@ use std::{
 };
 
 pub mod ast_debug;
+pub mod inference_holes;
 pub mod known_attributes;
+pub mod metrics;
 pub mod program_info;
 pub mod remembering_unique_map;
 pub mod unique_map;
@@ -154,13 +162,36 @@ pub const FILTER_UNUSED_TRAILING_SEMI: &str = "unused_trailing_semi";
 pub const FILTER_UNUSED_ATTRIBUTE: &str = "unused_attribute";
 pub const FILTER_UNUSED_TYPE_PARAMETER: &str = "unused_type_parameter";
 pub const FILTER_UNUSED_FUNCTION: &str = "unused_function";
+pub const FILTER_UNUSED_PACKAGE_FUNCTION: &str = "unused_package_function";
 pub const FILTER_UNUSED_STRUCT_FIELD: &str = "unused_field";
+pub const FILTER_UNUSED_STRUCT: &str = "unused_struct";
 pub const FILTER_UNUSED_CONST: &str = "unused_const";
 pub const FILTER_DEAD_CODE: &str = "dead_code";
 pub const FILTER_UNUSED_LET_MUT: &str = "unused_let_mut";
 pub const FILTER_UNUSED_MUT_REF: &str = "unused_mut_ref";
 pub const FILTER_UNUSED_MUT_PARAM: &str = "unused_mut_parameter";
 pub const FILTER_IMPLICIT_CONST_COPY: &str = "implicit_const_copy";
+pub const FILTER_DEPRECATED_USAGE: &str = "deprecated_usage";
+pub const FILTER_SHADOWED_USE_FUN: &str = "shadowed_use_fun";
+pub const FILTER_IMPLICIT_FREEZE_EQUALITY: &str = "implicit_freeze_equality";
+pub const FILTER_LARGE_STRUCT_EQUALITY: &str = "large_struct_equality";
+pub const FILTER_LARGE_POSITIONAL_STRUCT: &str = "large_positional_struct";
+pub const FILTER_READ_ONLY_MUT_PARAM: &str = "read_only_mut_parameter";
+pub const FILTER_SHADOWED_VARIABLE: &str = "shadowed_variable";
+pub const FILTER_REDUNDANT_TYPE_ARGS: &str = "redundant_type_args";
+pub const FILTER_LARGE_COPY_IN_LOOP: &str = "large_copy_in_loop";
+
+/// The known filters of warnings that are off by default. Each is only reported if enabled by name
+/// with `Flags::warn`, e.g. '--warn=shadowed_variable', after which it can be allowed as usual
+pub const DEFAULT_OFF_FILTERS: &[&str] = &[
+    FILTER_SHADOWED_VARIABLE,
+    FILTER_REDUNDANT_TYPE_ARGS,
+    FILTER_LARGE_COPY_IN_LOOP,
+    FILTER_IMPLICIT_FREEZE_EQUALITY,
+    FILTER_LARGE_STRUCT_EQUALITY,
+    FILTER_LARGE_POSITIONAL_STRUCT,
+    FILTER_READ_ONLY_MUT_PARAM,
+];
 
 pub type NamedAddressMap = BTreeMap<Symbol, NumericalAddress>;
 
@@ -218,12 +249,36 @@ pub struct CompilationEnv {
     package_configs: BTreeMap<Symbol, PackageConfig>,
     /// Config for any package not found in `package_configs`, or for inputs without a package.
     default_config: PackageConfig,
+    /// The packages of each parsed file, so that the `warning_filter` of the package's config
+    /// applies to any diagnostic located in that file, even outside of a module's filter scope.
+    /// Locations only carry the hash of the file's contents, so a file that is byte-identical in
+    /// several packages is recorded once with all of them
+    file_packages: BTreeMap<FileHash, BTreeSet<Option<Symbol>>>,
+    /// The aliases that renamed a module member, e.g. 'Bar' in 'use a::m::{Foo as Bar}', keyed by
+    /// the location of each usage of the alias. Each alias is located at its declaration
+    member_aliases: BTreeMap<Loc, Name>,
     /// Maps warning filter key (filter name and filter attribute name) to the filter itself.
     known_filters: BTreeMap<FilterPrefix, BTreeMap<FilterName, BTreeSet<WarningFilter>>>,
     /// Maps a diagnostics ID to a known filter name.
     known_filter_names: BTreeMap<DiagnosticsID, (FilterPrefix, FilterName)>,
+    /// The filters named by `Flags::warnings_are_errors_except`, resolved against the known
+    /// filters. Kept up to date as custom known filters are added
+    warnings_are_errors_exceptions: WarningFilters,
+    /// The warnings of `DEFAULT_OFF_FILTERS` that were not enabled with `Flags::warn`. Unlike other
+    /// filtered warnings, these are dropped entirely
+    disabled_warnings: WarningFilters,
     prim_definers:
         BTreeMap<crate::naming::ast::BuiltinTypeName_, crate::expansion::ast::ModuleIdent>,
+    /// The modules registered as the definer of a primitive type, by address and module name, as if
+    /// they were annotated with '#[defines_primitive(..)]'
+    registered_prim_definers: BTreeMap<crate::naming::ast::BuiltinTypeName_, (Symbol, Symbol)>,
+    /// Types whose value is accessed through functions, for which field access errors suggest
+    /// those functions instead
+    wrapper_types: Vec<WrapperAccessors>,
+    /// Per function metrics, only collected if `Flags::record_metrics` is set
+    metrics: CompilationMetrics,
+    /// Types that could not be inferred, only collected if `Flags::record_inference_holes` is set
+    inference_holes: Vec<InferenceHole>,
     // TODO(tzakian): Remove the global counter and use this counter instead
     // pub counter: u64,
 }
@@ -273,8 +328,26 @@ impl CompilationEnv {
             known_code_filter!(FILTER_UNUSED_ASSIGNMENT, UnusedItem::Assignment),
             known_code_filter!(FILTER_UNUSED_TRAILING_SEMI, UnusedItem::TrailingSemi),
             known_code_filter!(FILTER_UNUSED_ATTRIBUTE, UnusedItem::Attribute),
-            known_code_filter!(FILTER_UNUSED_FUNCTION, UnusedItem::Function),
+            (
+                FILTER_UNUSED_FUNCTION.into(),
+                BTreeSet::from([
+                    WarningFilter::Code {
+                        prefix: None,
+                        category: Category::UnusedItem as u8,
+                        code: UnusedItem::Function as u8,
+                        name: Some(FILTER_UNUSED_FUNCTION),
+                    },
+                    WarningFilter::Code {
+                        prefix: None,
+                        category: Category::UnusedItem as u8,
+                        code: UnusedItem::TestOnlyFunction as u8,
+                        name: Some(FILTER_UNUSED_FUNCTION),
+                    },
+                ]),
+            ),
+            known_code_filter!(FILTER_UNUSED_PACKAGE_FUNCTION, UnusedItem::PackageFunction),
             known_code_filter!(FILTER_UNUSED_STRUCT_FIELD, UnusedItem::StructField),
+            known_code_filter!(FILTER_UNUSED_STRUCT, UnusedItem::Struct),
             (
                 FILTER_UNUSED_TYPE_PARAMETER.into(),
                 BTreeSet::from([
@@ -298,6 +371,27 @@ impl CompilationEnv {
             known_code_filter!(FILTER_UNUSED_MUT_REF, UnusedItem::MutReference),
             known_code_filter!(FILTER_UNUSED_MUT_PARAM, UnusedItem::MutParam),
             known_code_filter!(FILTER_IMPLICIT_CONST_COPY, TypeSafety::ImplicitConstantCopy),
+            known_code_filter!(FILTER_DEPRECATED_USAGE, TypeSafety::DeprecatedUsage),
+            known_code_filter!(FILTER_SHADOWED_USE_FUN, NameResolution::ShadowedUseFun),
+            known_code_filter!(
+                FILTER_IMPLICIT_FREEZE_EQUALITY,
+                TypeSafety::ImplicitFreezeInEquality
+            ),
+            known_code_filter!(
+                FILTER_LARGE_STRUCT_EQUALITY,
+                TypeSafety::LargeStructEquality
+            ),
+            known_code_filter!(
+                FILTER_LARGE_POSITIONAL_STRUCT,
+                TypeSafety::LargePositionalStruct
+            ),
+            known_code_filter!(FILTER_READ_ONLY_MUT_PARAM, TypeSafety::ReadOnlyMutParam),
+            known_code_filter!(FILTER_SHADOWED_VARIABLE, NameResolution::ShadowedVariable),
+            known_code_filter!(
+                FILTER_REDUNDANT_TYPE_ARGS,
+                TypeSafety::RedundantTypeArguments
+            ),
+            known_code_filter!(FILTER_LARGE_COPY_IN_LOOP, TypeSafety::LargeCopyInLoop),
         ]);
         let known_filters: BTreeMap<FilterPrefix, BTreeMap<FilterName, BTreeSet<WarningFilter>>> =
             BTreeMap::from([(None, known_filters_)]);
@@ -330,20 +424,34 @@ impl CompilationEnv {
         } else {
             vec![]
         };
-        Self {
+        let mut env = Self {
             flags,
             warning_filter,
             diags: Diagnostics::new(),
             visitors: Rc::new(Visitors::new(visitors)),
             package_configs,
             default_config: default_config.unwrap_or_default(),
+            file_packages: BTreeMap::new(),
+            member_aliases: BTreeMap::new(),
             known_filters,
             known_filter_names,
             prim_definers: BTreeMap::new(),
-        }
+            registered_prim_definers: BTreeMap::new(),
+            wrapper_types: vec![WrapperAccessors::std_option()],
+            metrics: CompilationMetrics::default(),
+            inference_holes: vec![],
+            warnings_are_errors_exceptions: WarningFilters::new_for_source(),
+            disabled_warnings: WarningFilters::new_for_source(),
+        };
+        env.resolve_warnings_are_errors_exceptions();
+        env.resolve_disabled_warnings();
+        env
     }
 
     pub fn add_diag(&mut self, mut diag: Diagnostic) {
+        if self.disabled_warnings.is_filtered(&diag) {
+            return;
+        }
         if !self.is_filtered(&diag) {
             // add help to suppress warning, if applicable
             // TODO do we want a centralized place for tips like this?
@@ -357,7 +465,8 @@ impl CompilationEnv {
                     );
                     diag.add_note(help)
                 }
-                if self.flags.warnings_are_errors() {
+                if self.flags.warnings_are_errors() && !self.is_warnings_are_errors_exception(&diag)
+                {
                     diag = diag.set_severity(Severity::NonblockingError)
                 }
             }
@@ -419,6 +528,32 @@ impl CompilationEnv {
         final_diags
     }
 
+    pub fn add_function_metrics(
+        &mut self,
+        m: E::ModuleIdent,
+        f: FunctionName,
+        metrics: FunctionMetrics,
+    ) {
+        debug_assert!(self.flags.record_metrics());
+        self.metrics.functions.insert((m, f), metrics);
+    }
+
+    /// Takes the metrics collected so far. Empty unless `Flags::record_metrics` is set
+    pub fn take_metrics(&mut self) -> CompilationMetrics {
+        std::mem::take(&mut self.metrics)
+    }
+
+    pub fn add_inference_hole(&mut self, hole: InferenceHole) {
+        debug_assert!(self.flags.record_inference_holes());
+        self.inference_holes.push(hole);
+    }
+
+    /// Takes the inference holes recorded so far. Empty unless `Flags::record_inference_holes` is
+    /// set
+    pub fn take_inference_holes(&mut self) -> Vec<InferenceHole> {
+        std::mem::take(&mut self.inference_holes)
+    }
+
     /// Add a new filter for warnings
     pub fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
         self.warning_filter.push(filter)
@@ -433,6 +568,67 @@ impl CompilationEnv {
             .iter()
             .rev()
             .any(|filter| filter.is_filtered(diag))
+            || self.is_filtered_by_package(diag)
+    }
+
+    /// Checks the warning against the `warning_filter` of the config of the package it is located
+    /// in. If the file is in several packages, the warning is filtered only if it is filtered by
+    /// each of them. As with other filters, errors are never filtered
+    fn is_filtered_by_package(&self, diag: &Diagnostic) -> bool {
+        let file_hash = diag.primary_loc().file_hash();
+        self.file_packages.get(&file_hash).is_some_and(|packages| {
+            packages.iter().all(|package| {
+                package
+                    .and_then(|package| self.package_configs.get(&package))
+                    .is_some_and(|config| config.warning_filter.is_filtered(diag))
+            })
+        })
+    }
+
+    /// Checks the warning against the filters named by `--warnings-are-errors-except`
+    fn is_warnings_are_errors_exception(&self, diag: &Diagnostic) -> bool {
+        self.warnings_are_errors_exceptions.is_filtered(diag)
+    }
+
+    /// Resolves the filter names of `--warnings-are-errors-except` against the known filters.
+    /// Custom known filters (e.g. for lints) might be registered after the environment is created,
+    /// so this is repeated whenever they are. Unknown names do not match any warning.
+    fn resolve_warnings_are_errors_exceptions(&mut self) {
+        let mut exceptions = WarningFilters::new_for_source();
+        for filter_name in self.flags.warnings_are_errors_except() {
+            let (prefix, name) = match filter_name
+                .split_once('(')
+                .and_then(|(prefix, rest)| Some((prefix, rest.strip_suffix(')')?)))
+            {
+                Some((prefix, name)) => (Some(prefix), name),
+                None => (None, filter_name.as_str()),
+            };
+            for filter in self.filter_from_str(prefix, name) {
+                exceptions.add(filter)
+            }
+        }
+        self.warnings_are_errors_exceptions = exceptions;
+    }
+
+    /// Resolves the warnings of `DEFAULT_OFF_FILTERS` that were not enabled with `Flags::warn`
+    fn resolve_disabled_warnings(&mut self) {
+        let mut disabled = WarningFilters::new_for_source();
+        for name in DEFAULT_OFF_FILTERS {
+            if self.is_warning_enabled(name) {
+                continue;
+            }
+            for filter in self.filter_from_str(None::<Symbol>, *name) {
+                disabled.add(filter)
+            }
+        }
+        self.disabled_warnings = disabled;
+    }
+
+    /// False for the warnings of a filter in `DEFAULT_OFF_FILTERS` that was not enabled with
+    /// `Flags::warn`. Analyses that only report such warnings can be skipped when this is false
+    pub fn is_warning_enabled(&self, filter_name: &str) -> bool {
+        !DEFAULT_OFF_FILTERS.contains(&filter_name)
+            || self.flags.warn().iter().any(|name| name == filter_name)
     }
 
     fn filter_for_dependency(&self) -> bool {
@@ -499,6 +695,7 @@ impl CompilationEnv {
             );
             filter_attr.entry(n).or_default().insert(filter);
         }
+        self.resolve_warnings_are_errors_exceptions();
         Ok(())
     }
 
@@ -529,6 +726,25 @@ impl CompilationEnv {
         self.package_config(package).edition
     }
 
+    /// Records a package of a parsed file, or `None` for an input without a package, see
+    /// `is_filtered_by_package`
+    pub fn add_file_package(&mut self, file_hash: FileHash, package: Option<Symbol>) {
+        self.file_packages
+            .entry(file_hash)
+            .or_default()
+            .insert(package);
+    }
+
+    /// Records that the module member named at `usage_loc` was named through a renaming alias
+    pub fn add_member_alias(&mut self, usage_loc: Loc, alias: Name) {
+        self.member_aliases.insert(usage_loc, alias);
+    }
+
+    /// The renaming alias used to name the module member at `usage_loc`, if any
+    pub fn member_alias(&self, usage_loc: Loc) -> Option<Name> {
+        self.member_aliases.get(&usage_loc).copied()
+    }
+
     pub fn package_config(&self, package: Option<Symbol>) -> &PackageConfig {
         package
             .and_then(|p| self.package_configs.get(&p))
@@ -545,6 +761,67 @@ impl CompilationEnv {
     pub fn primitive_definer(&self, t: N::BuiltinTypeName_) -> Option<&E::ModuleIdent> {
         self.prim_definers.get(&t)
     }
+
+    /// Registers the module `address::module` as the definer of the primitive type `t`, as if it
+    /// were annotated with '#[defines_primitive(t)]'. A registered definer takes precedence over
+    /// any annotated module. Must be called before expansion
+    pub fn add_primitive_definer(
+        &mut self,
+        t: N::BuiltinTypeName_,
+        address: impl Into<Symbol>,
+        module: impl Into<Symbol>,
+    ) {
+        self.registered_prim_definers
+            .insert(t, (address.into(), module.into()));
+    }
+
+    /// The modules registered as the definers of primitive types, by address and module name
+    pub fn registered_primitive_definers(
+        &self,
+    ) -> impl Iterator<Item = (N::BuiltinTypeName_, Symbol, Symbol)> + '_ {
+        self.registered_prim_definers
+            .iter()
+            .map(|(t, (address, module))| (*t, *address, *module))
+    }
+
+    /// Registers a type whose value is accessed through functions, so that field access errors on
+    /// it suggest those functions instead. 'std::option::Option' is registered by default
+    pub fn add_wrapper_type(&mut self, wrapper: WrapperAccessors) {
+        self.wrapper_types.push(wrapper);
+    }
+
+    /// The registered wrapper type `m::n`, if any
+    pub fn wrapper_type(&self, m: &E::ModuleIdent, n: Symbol) -> Option<&WrapperAccessors> {
+        self.wrapper_types
+            .iter()
+            .find(|w| m.value.is(w.address, w.module) && n == w.name)
+    }
+}
+
+/// A type that wraps a value, e.g. 'std::option::Option'. Users often try to access the wrapped
+/// value directly as a field, so field access errors on these types suggest the `accessors`
+/// instead
+#[derive(Clone, Debug)]
+pub struct WrapperAccessors {
+    pub address: Symbol,
+    pub module: Symbol,
+    pub name: Symbol,
+    /// The public functions of the module that access the wrapped value
+    pub accessors: Vec<Symbol>,
+    /// An example usage of the accessors, shown as a note
+    pub example: String,
+}
+
+impl WrapperAccessors {
+    fn std_option() -> Self {
+        Self {
+            address: "std".into(),
+            module: "option".into(),
+            name: "Option".into(),
+            accessors: vec!["borrow".into(), "extract".into(), "is_some".into()],
+            example: "if (opt.is_some()) *opt.borrow() else default".to_owned(),
+        }
+    }
 }
 
 pub fn format_allow_attr(attr_name: FilterPrefix, filter: FilterName) -> String {
@@ -585,10 +862,65 @@ pub fn format_comma<T: fmt::Display, I: IntoIterator<Item = T>>(items: I) -> Str
     format_delim(items, ", ")
 }
 
+/// The Levenshtein distance between two strings, i.e. the number of single character insertions,
+/// deletions, or substitutions needed to turn one into the other
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = prev_row[j] + usize::from(a_char != *b_char);
+            let deletion = prev_row[j + 1] + 1;
+            let insertion = row[j] + 1;
+            row.push(substitution.min(deletion).min(insertion));
+        }
+        prev_row = row;
+    }
+    prev_row[b.len()]
+}
+
+/// Candidates close enough to `name` to be suggested as possible typos, closest first
+pub fn similar_names<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    const MAX_SUGGESTIONS: usize = 3;
+    let max_distance = std::cmp::max(1, (name.len() + 1) / 3);
+    let mut similar = candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect::<Vec<_>>();
+    similar.sort();
+    similar.dedup();
+    similar
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
 //**************************************************************************************************
 // Flags
 //**************************************************************************************************
 
+/// The default bound on nested macro expansions, see `Flags::max_macro_expansion_depth`
+pub const DEFAULT_MAX_MACRO_EXPANSION_DEPTH: usize = 128;
+
+/// The default number of fields above which an implicit copy of a struct is considered large, see
+/// `Flags::large_copy_field_threshold`
+pub const DEFAULT_LARGE_COPY_FIELD_THRESHOLD: usize = 8;
+
+/// The default number of fields, including those of nested structs, above which comparing a struct
+/// with '==' or '!=' is considered expensive, see `Flags::large_equality_field_threshold`
+pub const DEFAULT_LARGE_EQUALITY_FIELD_THRESHOLD: usize = 16;
+
+/// The default number of fields at or above which constructing a positional struct is considered
+/// unclear, see `Flags::positional_struct_field_threshold`
+pub const DEFAULT_POSITIONAL_STRUCT_FIELD_THRESHOLD: usize = 4;
+
 #[derive(Clone, Debug, Eq, PartialEq, Parser)]
 pub struct Flags {
     /// Compile in test mode
@@ -604,6 +936,23 @@ pub struct Flags {
     )]
     warnings_are_errors: bool,
 
+    /// Warning filters, e.g. 'unused' or 'lint(share_owned)', for warnings that are not made
+    /// errors by `--warnings-are-errors`
+    #[clap(
+        long = cli::WARNINGS_ARE_ERRORS_EXCEPT,
+        value_delimiter = ',',
+    )]
+    warnings_are_errors_except: Vec<String>,
+
+    /// Warnings that are off by default to report, by filter name, e.g.
+    /// '--warn=shadowed_variable,large_copy_in_loop'. Any other name is rejected. See
+    /// `DEFAULT_OFF_FILTERS`
+    #[clap(
+        long = cli::WARN,
+        value_delimiter = ',',
+    )]
+    warn: Vec<String>,
+
     /// If set, all warnings are silenced
     #[clap(
         long = cli::SILENCE_WARNINGS,
@@ -611,6 +960,58 @@ pub struct Flags {
     )]
     silence_warnings: bool,
 
+    /// If set, diagnostics are reported as one JSON object per line, instead of being rendered
+    /// for people to read
+    #[clap(
+        long = cli::JSON_ERRORS,
+    )]
+    json_errors: bool,
+
+    /// The number of fields above which a struct is considered large when warning on implicit
+    /// copies in loops. If not set, `DEFAULT_LARGE_COPY_FIELD_THRESHOLD` is used.
+    #[clap(
+        long = cli::LARGE_COPY_FIELD_THRESHOLD,
+    )]
+    large_copy_field_threshold: Option<usize>,
+
+    /// The number of fields, including those of nested structs, above which a struct is considered
+    /// large when warning on equality. If not set, `DEFAULT_LARGE_EQUALITY_FIELD_THRESHOLD` is used.
+    #[clap(
+        long = cli::LARGE_EQUALITY_FIELD_THRESHOLD,
+    )]
+    large_equality_field_threshold: Option<usize>,
+
+    /// The number of fields at or above which a positional struct is considered large when warning
+    /// on its construction. If not set, `DEFAULT_POSITIONAL_STRUCT_FIELD_THRESHOLD` is used.
+    #[clap(
+        long = cli::POSITIONAL_STRUCT_FIELD_THRESHOLD,
+    )]
+    positional_struct_field_threshold: Option<usize>,
+
+    /// The number of nested macro expansions after which expansion stops with an error. If not
+    /// set, `DEFAULT_MAX_MACRO_EXPANSION_DEPTH` is used.
+    #[clap(
+        long = cli::MAX_MACRO_EXPANSION_DEPTH,
+    )]
+    max_macro_expansion_depth: Option<usize>,
+
+    /// If set, function declarations do not implicitly create methods for the type of their first
+    /// argument. Methods must then be declared with 'use fun', or brought into scope with 'use'
+    #[clap(
+        long = cli::NO_IMPLICIT_METHODS,
+    )]
+    no_implicit_methods: bool,
+
+    /// If set, the typed program is printed by the command line after typing, with the type of
+    /// every expression. The output can be restricted to the modules or functions of a given name,
+    /// e.g. '--print-typed-ast=m'
+    #[clap(
+        long = cli::PRINT_TYPED_AST,
+        value_name = "MODULE_OR_FUNCTION",
+        require_equals = true,
+    )]
+    print_typed_ast: Option<Option<String>>,
+
     /// If set, source files will not shadow dependency files. If the same file is passed to both,
     /// an error will be raised
     #[clap(
@@ -630,6 +1031,21 @@ pub struct Flags {
     /// included only in tests, without creating the unit test code regular tests do.
     #[clap(skip)]
     keep_testing_functions: bool,
+
+    /// Internal flag used by IDE tooling to record the type of every expression after typing,
+    /// which can then be queried by location with `TypingProgramInfo::type_at`.
+    #[clap(skip)]
+    record_type_locations: bool,
+
+    /// Internal flag used by tooling to collect size metrics for each function while typing,
+    /// which can then be taken with `CompilationEnv::take_metrics`.
+    #[clap(skip)]
+    record_metrics: bool,
+
+    /// Internal flag used by tooling to record the types that could not be inferred while
+    /// typing, which can then be taken with `CompilationEnv::take_inference_holes`.
+    #[clap(skip)]
+    record_inference_holes: bool,
 }
 
 impl Flags {
@@ -639,8 +1055,20 @@ impl Flags {
             shadow: false,
             bytecode_version: None,
             warnings_are_errors: false,
+            warnings_are_errors_except: vec![],
+            warn: vec![],
             silence_warnings: false,
+            json_errors: false,
+            large_copy_field_threshold: None,
+            large_equality_field_threshold: None,
+            positional_struct_field_threshold: None,
+            max_macro_expansion_depth: None,
+            no_implicit_methods: false,
+            print_typed_ast: None,
             keep_testing_functions: false,
+            record_type_locations: false,
+            record_metrics: false,
+            record_inference_holes: false,
         }
     }
 
@@ -650,8 +1078,20 @@ impl Flags {
             shadow: false,
             bytecode_version: None,
             warnings_are_errors: false,
+            warnings_are_errors_except: vec![],
+            warn: vec![],
             silence_warnings: false,
+            json_errors: false,
+            large_copy_field_threshold: None,
+            large_equality_field_threshold: None,
+            positional_struct_field_threshold: None,
+            max_macro_expansion_depth: None,
+            no_implicit_methods: false,
+            print_typed_ast: None,
             keep_testing_functions: false,
+            record_type_locations: false,
+            record_metrics: false,
+            record_inference_holes: false,
         }
     }
 
@@ -662,6 +1102,34 @@ impl Flags {
         }
     }
 
+    pub fn set_record_type_locations(self, value: bool) -> Self {
+        Self {
+            record_type_locations: value,
+            ..self
+        }
+    }
+
+    pub fn set_record_metrics(self, value: bool) -> Self {
+        Self {
+            record_metrics: value,
+            ..self
+        }
+    }
+
+    pub fn set_record_inference_holes(self, value: bool) -> Self {
+        Self {
+            record_inference_holes: value,
+            ..self
+        }
+    }
+
+    pub fn set_max_macro_expansion_depth(self, value: usize) -> Self {
+        Self {
+            max_macro_expansion_depth: Some(value),
+            ..self
+        }
+    }
+
     pub fn set_sources_shadow_deps(self, sources_shadow_deps: bool) -> Self {
         Self {
             shadow: sources_shadow_deps,
@@ -676,6 +1144,13 @@ impl Flags {
         }
     }
 
+    pub fn set_warnings_are_errors_except(self, value: Vec<String>) -> Self {
+        Self {
+            warnings_are_errors_except: value,
+            ..self
+        }
+    }
+
     pub fn set_silence_warnings(self, value: bool) -> Self {
         Self {
             silence_warnings: value,
@@ -683,6 +1158,55 @@ impl Flags {
         }
     }
 
+    pub fn set_json_errors(self, value: bool) -> Self {
+        Self {
+            json_errors: value,
+            ..self
+        }
+    }
+
+    pub fn set_warn(self, value: Vec<String>) -> Self {
+        Self {
+            warn: value,
+            ..self
+        }
+    }
+
+    pub fn set_large_copy_field_threshold(self, value: usize) -> Self {
+        Self {
+            large_copy_field_threshold: Some(value),
+            ..self
+        }
+    }
+
+    pub fn set_large_equality_field_threshold(self, value: usize) -> Self {
+        Self {
+            large_equality_field_threshold: Some(value),
+            ..self
+        }
+    }
+
+    pub fn set_positional_struct_field_threshold(self, value: usize) -> Self {
+        Self {
+            positional_struct_field_threshold: Some(value),
+            ..self
+        }
+    }
+
+    pub fn set_no_implicit_methods(self, value: bool) -> Self {
+        Self {
+            no_implicit_methods: value,
+            ..self
+        }
+    }
+
+    pub fn set_print_typed_ast(self, filter: Option<String>) -> Self {
+        Self {
+            print_typed_ast: Some(filter),
+            ..self
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self == &Self::empty()
     }
@@ -695,6 +1219,23 @@ impl Flags {
         self.test || self.keep_testing_functions
     }
 
+    pub fn record_type_locations(&self) -> bool {
+        self.record_type_locations
+    }
+
+    pub fn record_metrics(&self) -> bool {
+        self.record_metrics
+    }
+
+    pub fn record_inference_holes(&self) -> bool {
+        self.record_inference_holes
+    }
+
+    pub fn max_macro_expansion_depth(&self) -> usize {
+        self.max_macro_expansion_depth
+            .unwrap_or(DEFAULT_MAX_MACRO_EXPANSION_DEPTH)
+    }
+
     pub fn sources_shadow_deps(&self) -> bool {
         self.shadow
     }
@@ -707,9 +1248,52 @@ impl Flags {
         self.warnings_are_errors
     }
 
+    pub fn warnings_are_errors_except(&self) -> &[String] {
+        &self.warnings_are_errors_except
+    }
+
+    pub fn warn(&self) -> &[String] {
+        &self.warn
+    }
+
     pub fn silence_warnings(&self) -> bool {
         self.silence_warnings
     }
+
+    pub fn large_copy_field_threshold(&self) -> usize {
+        self.large_copy_field_threshold
+            .unwrap_or(DEFAULT_LARGE_COPY_FIELD_THRESHOLD)
+    }
+
+    pub fn large_equality_field_threshold(&self) -> usize {
+        self.large_equality_field_threshold
+            .unwrap_or(DEFAULT_LARGE_EQUALITY_FIELD_THRESHOLD)
+    }
+
+    pub fn positional_struct_field_threshold(&self) -> usize {
+        self.positional_struct_field_threshold
+            .unwrap_or(DEFAULT_POSITIONAL_STRUCT_FIELD_THRESHOLD)
+    }
+
+    pub fn no_implicit_methods(&self) -> bool {
+        self.no_implicit_methods
+    }
+
+    /// `Some` if the typed program should be printed, holding the module or function name the
+    /// output is restricted to, if any
+    pub fn print_typed_ast(&self) -> Option<Option<&str>> {
+        self.print_typed_ast
+            .as_ref()
+            .map(|filter| filter.as_deref())
+    }
+
+    pub fn diagnostics_format(&self) -> DiagnosticsFormat {
+        if self.json_errors {
+            DiagnosticsFormat::Json
+        } else {
+            DiagnosticsFormat::Human
+        }
+    }
 }
 
 //**************************************************************************************************
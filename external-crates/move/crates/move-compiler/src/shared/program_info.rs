@@ -1,26 +1,39 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 
 use crate::{
-    expansion::ast::{AbilitySet, Attributes, ModuleIdent, Visibility},
+    diag,
+    diagnostics::Diagnostic,
+    expansion::ast::{
+        AbilitySet, AttributeValue_, Attribute_, Attributes, ModuleIdent, Value_, Visibility,
+    },
     naming::ast::{
         self as N, FunctionSignature, ResolvedUseFuns, StructDefinition, StructTypeParameter, Type,
+        TypeName, UseFunKind,
     },
     parser::ast::{ConstantName, FunctionName, StructName},
+    shared::known_attributes::{AttributePosition, DeprecationAttribute},
     shared::unique_map::UniqueMap,
     shared::*,
-    typing::ast::{self as T},
+    typing::{
+        ast::{self as T},
+        type_locations::TypeLocations,
+    },
     FullyCompiledProgram,
 };
 
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
     pub attributes: Attributes,
+    pub test_only: Option<Loc>,
     pub defined_loc: Loc,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
@@ -31,10 +44,37 @@ pub struct FunctionInfo {
 #[derive(Debug, Clone)]
 pub struct ConstantInfo {
     pub attributes: Attributes,
+    pub test_only: Option<Loc>,
     pub defined_loc: Loc,
     pub signature: Type,
 }
 
+/// A method for a type, declared at the top level of a module either by an explicit 'use fun' or
+/// implicitly by a function declaration or 'use' alias
+#[derive(Debug, Clone)]
+pub struct MethodInfo {
+    pub method: Name,
+    /// The module declaring the method
+    pub module: ModuleIdent,
+    pub target_function: (ModuleIdent, FunctionName),
+    pub target_visibility: Visibility,
+    /// Whether the method is available outside of the declaring module. Methods from function
+    /// declarations are always public, but calls through them are still subject to
+    /// `target_visibility`
+    pub is_public: bool,
+    pub kind: UseFunKind,
+    pub loc: Loc,
+}
+
+/// A module member marked with '#[deprecated]'
+#[derive(Debug, Clone)]
+pub struct Deprecation {
+    /// The location of the attribute
+    pub loc: Loc,
+    pub position: AttributePosition,
+    pub note: Option<Symbol>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ModuleInfo {
     pub attributes: Attributes,
@@ -44,11 +84,41 @@ pub struct ModuleInfo {
     pub structs: UniqueMap<StructName, StructDefinition>,
     pub functions: UniqueMap<FunctionName, FunctionInfo>,
     pub constants: UniqueMap<ConstantName, ConstantInfo>,
+    /// Deprecated structs, functions, and constants, by kind and name
+    pub deprecations: BTreeMap<(AttributePosition, Symbol), Deprecation>,
+}
+
+/// The calls made by a single function
+#[derive(Debug, Clone, Default)]
+pub struct FunctionCalls {
+    /// The functions called, including methods, which are recorded as calls to the function they
+    /// resolve to. Macros are expanded at their call sites, so calls made by a macro body are
+    /// attributed to the function that invoked the macro
+    pub callees: BTreeSet<(ModuleIdent, FunctionName)>,
+    /// Whether the builtin 'freeze' is used
+    pub uses_freeze: bool,
+    /// Whether the builtin 'assert!' is used
+    pub uses_assert: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    calls: BTreeMap<(ModuleIdent, FunctionName), FunctionCalls>,
+    callers: BTreeMap<(ModuleIdent, FunctionName), BTreeSet<(ModuleIdent, FunctionName)>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ProgramInfo<const AFTER_TYPING: bool> {
     pub modules: UniqueMap<ModuleIdent, ModuleInfo>,
+    /// Types of expressions by location, for the modules typed in this compilation. Only present
+    /// after typing, if `Flags::record_type_locations` is set
+    pub type_locations: Option<TypeLocations>,
+    /// The functions called by each function, for the modules typed in this compilation. Only
+    /// populated after typing
+    pub call_graph: CallGraph,
+    /// The macro expanded at each call site, for the modules typed in this compilation. The
+    /// expanded body replaces the call, and keeps its location. Only populated after typing
+    pub macro_expansions: BTreeMap<Loc, (ModuleIdent, FunctionName)>,
 }
 pub type NamingProgramInfo = ProgramInfo<false>;
 pub type TypingProgramInfo = ProgramInfo<true>;
@@ -60,6 +130,7 @@ macro_rules! program_info {
             let structs = mdef.structs.clone();
             let functions = mdef.functions.ref_map(|fname, fdef| FunctionInfo {
                 attributes: fdef.attributes.clone(),
+                test_only: fdef.test_only,
                 defined_loc: fname.loc(),
                 visibility: fdef.visibility.clone(),
                 entry: fdef.entry,
@@ -68,9 +139,28 @@ macro_rules! program_info {
             });
             let constants = mdef.constants.ref_map(|cname, cdef| ConstantInfo {
                 attributes: cdef.attributes.clone(),
+                test_only: cdef.test_only,
                 defined_loc: cname.loc(),
                 signature: cdef.signature.clone(),
             });
+            let struct_deprecations = mdef.structs.key_cloned_iter().filter_map(|(n, sdef)| {
+                let d = Deprecation::from_attributes(AttributePosition::Struct, &sdef.attributes)?;
+                Some(((d.position, n.0.value), d))
+            });
+            let function_deprecations = mdef.functions.key_cloned_iter().filter_map(|(n, fdef)| {
+                let d =
+                    Deprecation::from_attributes(AttributePosition::Function, &fdef.attributes)?;
+                Some(((d.position, n.0.value), d))
+            });
+            let constant_deprecations = mdef.constants.key_cloned_iter().filter_map(|(n, cdef)| {
+                let d =
+                    Deprecation::from_attributes(AttributePosition::Constant, &cdef.attributes)?;
+                Some(((d.position, n.0.value), d))
+            });
+            let deprecations = struct_deprecations
+                .chain(function_deprecations)
+                .chain(constant_deprecations)
+                .collect();
             let use_funs = $module_use_funs
                 .as_mut()
                 .map(|module_use_funs| module_use_funs.remove(&mident).unwrap())
@@ -83,6 +173,7 @@ macro_rules! program_info {
                 structs,
                 functions,
                 constants,
+                deprecations,
             };
             (mident, minfo)
         }))
@@ -94,7 +185,12 @@ macro_rules! program_info {
                 }
             }
         }
-        ProgramInfo { modules }
+        ProgramInfo {
+            modules,
+            type_locations: None,
+            call_graph: CallGraph::default(),
+            macro_expansions: BTreeMap::new(),
+        }
     }};
 }
 
@@ -107,6 +203,66 @@ impl TypingProgramInfo {
         let mut module_use_funs = Some(&mut module_use_funs);
         program_info!(pre_compiled_lib, prog, typing, module_use_funs)
     }
+
+    /// The type of the expression or variable binding at exactly this location, if type locations
+    /// were recorded. Expressions inside of macro bodies are not recorded, and are instead covered
+    /// by the type of the macro call.
+    pub fn type_at(&self, loc: Loc) -> Option<&Type> {
+        self.type_locations.as_ref()?.type_at(loc)
+    }
+
+    /// The type of the dotted path segment at exactly this location, if type locations were
+    /// recorded. See `TypeLocations::dotted_segment_type_at`
+    pub fn dotted_segment_type_at(&self, loc: Loc) -> Option<&Type> {
+        self.type_locations.as_ref()?.dotted_segment_type_at(loc)
+    }
+
+    /// The calls made by the function `m::f`, if it was typed in this compilation
+    pub fn function_calls(&self, m: &ModuleIdent, f: &FunctionName) -> Option<&FunctionCalls> {
+        self.call_graph.function_calls(m, f)
+    }
+
+    /// The functions called by `m::f`
+    pub fn callees(
+        &self,
+        m: &ModuleIdent,
+        f: &FunctionName,
+    ) -> impl Iterator<Item = &(ModuleIdent, FunctionName)> {
+        self.call_graph.callees(m, f)
+    }
+
+    /// The functions that call `m::f`
+    pub fn callers(
+        &self,
+        m: &ModuleIdent,
+        f: &FunctionName,
+    ) -> impl Iterator<Item = &(ModuleIdent, FunctionName)> {
+        self.call_graph.callers(m, f)
+    }
+
+    /// The module level methods declared for `tn`, across all modules, ordered by declaring module
+    /// and then by method name. Methods local to a function body are not included
+    pub fn methods_for(&self, tn: &TypeName) -> Vec<MethodInfo> {
+        let mut methods = vec![];
+        for (mident, minfo) in self.modules.key_cloned_iter() {
+            let Some(module_methods) = minfo.use_funs.get(tn) else {
+                continue;
+            };
+            for (method, nuf) in module_methods.key_cloned_iter() {
+                let (target_m, target_f) = nuf.target_function;
+                methods.push(MethodInfo {
+                    method,
+                    module: mident,
+                    target_function: nuf.target_function,
+                    target_visibility: self.function_info(&target_m, &target_f).visibility,
+                    is_public: nuf.is_public.is_some(),
+                    kind: nuf.kind,
+                    loc: nuf.loc,
+                })
+            }
+        }
+        methods
+    }
 }
 
 impl NamingProgramInfo {
@@ -167,6 +323,15 @@ impl<const AFTER_TYPING: bool> ProgramInfo<AFTER_TYPING> {
         let constants = &self.module(m).constants;
         constants.get(n).expect("ICE should have failed in naming")
     }
+
+    pub fn deprecation(
+        &self,
+        m: &ModuleIdent,
+        position: AttributePosition,
+        member: Symbol,
+    ) -> Option<&Deprecation> {
+        self.module(m).deprecations.get(&(position, member))
+    }
 }
 
 impl NamingProgramInfo {
@@ -185,3 +350,82 @@ impl NamingProgramInfo {
             .collect()
     }
 }
+
+impl CallGraph {
+    pub fn new(calls: BTreeMap<(ModuleIdent, FunctionName), FunctionCalls>) -> Self {
+        let mut callers: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for (caller, fcalls) in &calls {
+            for callee in &fcalls.callees {
+                callers.entry(*callee).or_default().insert(*caller);
+            }
+        }
+        Self { calls, callers }
+    }
+
+    pub fn function_calls(&self, m: &ModuleIdent, f: &FunctionName) -> Option<&FunctionCalls> {
+        self.calls.get(&(*m, *f))
+    }
+
+    pub fn callees(
+        &self,
+        m: &ModuleIdent,
+        f: &FunctionName,
+    ) -> impl Iterator<Item = &(ModuleIdent, FunctionName)> {
+        self.function_calls(m, f)
+            .into_iter()
+            .flat_map(|fcalls| fcalls.callees.iter())
+    }
+
+    pub fn callers(
+        &self,
+        m: &ModuleIdent,
+        f: &FunctionName,
+    ) -> impl Iterator<Item = &(ModuleIdent, FunctionName)> {
+        self.callers.get(&(*m, *f)).into_iter().flatten()
+    }
+}
+
+impl Deprecation {
+    /// Reads the '#[deprecated]' attribute, if present. The attribute's shape is checked during
+    /// expansion, so any malformed note has already been reported
+    pub fn from_attributes(position: AttributePosition, attributes: &Attributes) -> Option<Self> {
+        let sp!(loc, attr_) = attributes.get_(&DeprecationAttribute.into())?;
+        let note = match attr_ {
+            Attribute_::Parameterized(_, inners) => {
+                inners.iter().find_map(|(_, _, inner)| match &inner.value {
+                    Attribute_::Assigned(_, value) => match &value.value {
+                        AttributeValue_::Value(sp!(_, Value_::Bytearray(bytes))) => {
+                            std::str::from_utf8(bytes).ok().map(Symbol::from)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+            }
+            Attribute_::Name(_) | Attribute_::Assigned(_, _) => None,
+        };
+        Some(Self {
+            loc: *loc,
+            position,
+            note,
+        })
+    }
+
+    pub fn usage_diagnostic(
+        &self,
+        loc: Loc,
+        m: &ModuleIdent,
+        member: impl fmt::Display,
+    ) -> Diagnostic {
+        let msg = format!("Use of deprecated {} '{m}::{member}'", self.position);
+        let mut diag = diag!(
+            TypeSafety::DeprecatedUsage,
+            (loc, msg),
+            (self.loc, "Marked as deprecated here")
+        );
+        if let Some(note) = self.note {
+            diag.add_note(note.as_str());
+        }
+        diag
+    }
+}
@@ -188,6 +188,7 @@ impl<'a> Context<'a> {
             | T::Ref(_, _)
             | T::Var(_)
             | T::Anything
+            | T::Hole
             | T::UnresolvedError
             | T::Fun(_, _) => None,
         }
@@ -226,7 +227,7 @@ impl<'a> Context<'a> {
         sname: P::StructName,
     ) -> Option<WrappingFieldInfo> {
         let sdef = self.program_info.struct_definition(&mident, &sname);
-        let N::StructFields::Defined(sfields) = &sdef.fields else {
+        let N::StructFields::Defined(_, sfields) = &sdef.fields else {
             return None;
         };
         sfields.iter().find_map(|(_, fname, (_, ftype))| {
@@ -7,6 +7,7 @@ use crate::{
     diagnostics::codes::WarningFilter,
     expansion::ast as E,
     hlir::ast::{BaseType_, SingleType, SingleType_},
+    linters,
     naming::ast as N,
     typing::visitor::TypingVisitor,
 };
@@ -59,8 +60,7 @@ pub const VEC_MAP_STRUCT_NAME: &str = "VecMap";
 pub const VEC_SET_MOD_NAME: &str = "vec_set";
 pub const VEC_SET_STRUCT_NAME: &str = "VecSet";
 
-pub const ALLOW_ATTR_CATEGORY: &str = "lint";
-pub const LINT_WARNING_PREFIX: &str = "Lint ";
+pub use crate::linters::{ALLOW_ATTR_CATEGORY, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX};
 
 pub const SHARE_OWNED_FILTER_NAME: &str = "share_owned";
 pub const SELF_TRANSFER_FILTER_NAME: &str = "self_transfer";
@@ -80,13 +80,15 @@ pub enum LinterDiagCategory {
     CollectionEquality,
 }
 
-/// A default code for each linter category (as long as only one code per category is used, no other
-/// codes are needed, otherwise they should be defined to be unique per-category).
-pub const LINTER_DEFAULT_DIAG_CODE: u8 = 1;
+impl LinterDiagCategory {
+    /// The last Sui category. The categories of `crate::linters::LinterDiagCategory` follow it
+    pub const LAST: Self = Self::CollectionEquality;
+}
 
+/// The Sui lints, along with those of `crate::linters` that apply to any Move code
 pub fn known_filters() -> (Option<Symbol>, Vec<WarningFilter>) {
-    let filters = vec![
-        WarningFilter::All(Some(LINT_WARNING_PREFIX)),
+    let (attr_name, mut filters) = linters::known_filters();
+    filters.extend([
         WarningFilter::code(
             Some(LINT_WARNING_PREFIX),
             LinterDiagCategory::ShareOwned as u8,
@@ -123,19 +125,22 @@ pub fn known_filters() -> (Option<Symbol>, Vec<WarningFilter>) {
             LINTER_DEFAULT_DIAG_CODE,
             Some(COLLECTION_EQUALITY_FILTER_NAME),
         ),
-    ];
-    (Some(ALLOW_ATTR_CATEGORY.into()), filters)
+    ]);
+    (attr_name, filters)
 }
 
+/// The Sui lints, along with those of `crate::linters` that apply to any Move code
 pub fn linter_visitors() -> Vec<Visitor> {
-    vec![
+    let mut visitors = linters::linter_visitors();
+    visitors.extend([
         share_owned::ShareOwnedVerifier.visitor(),
         self_transfer::SelfTransferVerifier.visitor(),
         custom_state_change::CustomStateChangeVerifier.visitor(),
         coin_field::CoinFieldVisitor.visitor(),
         freeze_wrapped::FreezeWrappedVisitor.visitor(),
         collection_equality::CollectionEqualityVisitor.visitor(),
-    ]
+    ]);
+    visitors
 }
 
 pub fn base_type(t: &N::Type) -> Option<&N::Type> {
@@ -143,7 +148,7 @@ pub fn base_type(t: &N::Type) -> Option<&N::Type> {
     match &t.value {
         T::Ref(_, inner_t) => base_type(inner_t),
         T::Apply(_, _, _) | T::Param(_) => Some(t),
-        T::Unit | T::Var(_) | T::Anything | T::UnresolvedError | T::Fun(_, _) => None,
+        T::Unit | T::Var(_) | T::Anything | T::Hole | T::UnresolvedError | T::Fun(_, _) => None,
     }
 }
 
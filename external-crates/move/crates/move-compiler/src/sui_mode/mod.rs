@@ -143,6 +143,34 @@ pub const PRIVATE_TRANSFER_CALL_DIAG: DiagnosticInfo = custom(
     /* code */ 9,
     "invalid private transfer call",
 );
+pub const ENTRY_FUN_RETURN_DIAG: DiagnosticInfo = custom(
+    SUI_DIAG_PREFIX,
+    Severity::NonblockingError,
+    /* category */ TYPING,
+    /* code */ 10,
+    "invalid 'entry' function return type",
+);
+pub const ENTRY_FUN_CLOCK_DIAG: DiagnosticInfo = custom(
+    SUI_DIAG_PREFIX,
+    Severity::NonblockingError,
+    /* category */ TYPING,
+    /* code */ 11,
+    "invalid 'entry' function 'Clock' parameter",
+);
+pub const ENTRY_FUN_REFERENCE_DIAG: DiagnosticInfo = custom(
+    SUI_DIAG_PREFIX,
+    Severity::NonblockingError,
+    /* category */ TYPING,
+    /* code */ 12,
+    "invalid 'entry' function reference parameter",
+);
+pub const ENTRY_FUN_NESTED_OBJECT_DIAG: DiagnosticInfo = custom(
+    SUI_DIAG_PREFIX,
+    Severity::NonblockingError,
+    /* category */ TYPING,
+    /* code */ 13,
+    "invalid 'entry' function nested object parameter",
+);
 
 // Bridge supported asset
 pub const BRIDGE_SUPPORTED_ASSET: &[&str] = &["btc", "eth", "usdc", "usdt"];
@@ -13,7 +13,10 @@ use crate::{
         self as N, BuiltinTypeName_, FunctionSignature, StructFields, Type, TypeName_, Type_, Var,
     },
     parser::ast::{Ability_, FunctionName, Mutability, StructName},
-    shared::{program_info::TypingProgramInfo, CompilationEnv, Identifier},
+    shared::{
+        program_info::{NamingProgramInfo, TypingProgramInfo},
+        CompilationEnv, Identifier,
+    },
     sui_mode::*,
     typing::{
         ast::{self as T, ModuleCall},
@@ -74,9 +77,7 @@ impl<'a> Context<'a> {
 
     fn set_module(&mut self, current_module: ModuleIdent) {
         self.current_module = Some(current_module);
-        self.otw_name = Some(Symbol::from(
-            current_module.value.module.0.value.as_str().to_uppercase(),
-        ));
+        self.otw_name = Some(otw_name(current_module));
         self.one_time_witness = None;
     }
 
@@ -89,6 +90,15 @@ impl<'a> Context<'a> {
     }
 }
 
+const ENTRY_PARAM_NOTE: &str = "'entry' parameters must be primitives (by-value), vectors of \
+    primitives, objects (by-reference or by-value), vectors of objects, or 'Receiving' arguments \
+    (by-reference or by-value)";
+
+const ENTRY_RETURN_NOTE: &str =
+    "The values returned by an 'entry' function are dropped at the end of the transaction, so \
+    they must have the 'drop' ability. References cannot be returned. Consider returning nothing \
+    instead, or emitting the value as an event, e.g. with 'sui::event::emit'";
+
 const OTW_NOTE: &str = "One-time witness types are structs with the following requirements: \
                         their name is the upper-case version of the module's name, \
                         they have no fields (or a single boolean field), \
@@ -122,7 +132,7 @@ impl<'a> TypingVisitorContext for Context<'a> {
         self.set_module(ident);
         self.in_test = mdef.attributes.is_test_or_test_only();
         if let Some(sdef) = mdef.structs.get_(&self.otw_name()) {
-            let valid_fields = if let N::StructFields::Defined(fields) = &sdef.fields {
+            let valid_fields = if let N::StructFields::Defined(_, fields) = &sdef.fields {
                 invalid_otw_field_loc(fields).is_none()
             } else {
                 true
@@ -133,13 +143,10 @@ impl<'a> TypingVisitorContext for Context<'a> {
             }
         }
 
+        // the rules for 'init' are checked during typing, but its one-time witness parameter is
+        // checked here along with the one-time witness declaration
         if let Some(fdef) = mdef.functions.get_(&INIT_FUNCTION_NAME) {
-            let name = mdef.functions.get_full_key_(&INIT_FUNCTION_NAME).unwrap();
-            init_signature(self, name, &fdef.signature)
-        }
-
-        for (name, sdef) in mdef.structs.key_cloned_iter() {
-            struct_def(self, name, sdef)
+            init_otw_parameter(self, &fdef.signature)
         }
 
         // do not skip module
@@ -149,11 +156,11 @@ impl<'a> TypingVisitorContext for Context<'a> {
     fn visit_function_custom(
         &mut self,
         module: ModuleIdent,
-        name: FunctionName,
+        _name: FunctionName,
         fdef: &mut T::Function,
     ) -> bool {
         debug_assert!(self.current_module.as_ref() == Some(&module));
-        function(self, name, fdef);
+        function(self, fdef);
         // skip since we have already visited the body
         true
     }
@@ -169,11 +176,21 @@ impl<'a> TypingVisitorContext for Context<'a> {
 // Structs
 //**************************************************************************************************
 
-fn struct_def(context: &mut Context, name: StructName, sdef: &N::StructDefinition) {
+const KEY_MSG: &str = "The 'key' ability is used to declare objects in Sui";
+
+/// Checks the Sui object rules for a struct with the 'key' ability. Called during typing (rather
+/// than by this visitor) so that errors are reported alongside the other struct declaration errors.
+pub(crate) fn object_struct_def(
+    env: &mut CompilationEnv,
+    name: StructName,
+    sdef: &N::StructDefinition,
+) {
     let N::StructDefinition {
         warning_filter: _,
         index: _,
         attributes: _,
+        test_only: _,
+        visibility: _,
         abilities,
         type_parameters: _,
         fields,
@@ -182,24 +199,50 @@ fn struct_def(context: &mut Context, name: StructName, sdef: &N::StructDefinitio
         // not an object, no extra rules
         return;
     };
+    if let Some(drop_loc) = abilities.ability_loc_(Ability_::Drop) {
+        let msg = format!(
+            "Invalid object '{}'. Structs with the '{}' ability cannot have the '{}' ability",
+            name,
+            Ability_::Key,
+            Ability_::Drop
+        );
+        let mut diag = diag!(OBJECT_DECL_DIAG, (drop_loc, msg), (key_loc, KEY_MSG));
+        diag.add_note(format!(
+            "Objects cannot be discarded implicitly, since '{}::{}::{}' does not have '{}'. \
+            The '{}' of an object must be deleted explicitly",
+            SUI_ADDR_NAME,
+            OBJECT_MODULE_NAME,
+            UID_TYPE_NAME,
+            Ability_::Drop,
+            ID_FIELD_NAME,
+        ));
+        env.add_diag(diag);
+    }
 
-    let StructFields::Defined(fields) = fields else {
+    let StructFields::Defined(_, fields) = fields else {
         return;
     };
-    let invalid_first_field = if fields.is_empty() {
+    let first_field = fields.iter().find(|(_, _, (idx, _))| *idx == 0);
+    // positional fields are named by their index, so the first field is named '0'
+    if let Some((loc, _, _)) = first_field.filter(|(_, name, _)| name.as_str() == "0") {
+        let mut diag = invalid_object_id_field_diag(key_loc, loc, name);
+        diag.add_note(format!(
+            "Positional structs cannot be objects, even if field '0' has type '{}::{}::{}'. \
+            Declare '{}' with named fields instead",
+            SUI_ADDR_NAME, OBJECT_MODULE_NAME, UID_TYPE_NAME, name
+        ));
+        env.add_diag(diag);
+        return;
+    }
+    let invalid_first_field = match first_field {
         // no fields
-        Some(name.loc())
-    } else {
-        fields
-            .iter()
-            .find(|(_, name, (idx, _))| *idx == 0 && **name != ID_FIELD_NAME)
-            .map(|(loc, _, _)| loc)
+        None => Some(name.loc()),
+        Some((loc, field_name, _)) if *field_name != ID_FIELD_NAME => Some(loc),
+        Some(_) => None,
     };
     if let Some(loc) = invalid_first_field {
         // no fields or an invalid 'id' field
-        context
-            .env
-            .add_diag(invalid_object_id_field_diag(key_loc, loc, name));
+        env.add_diag(invalid_object_id_field_diag(key_loc, loc, name));
         return;
     };
 
@@ -215,12 +258,13 @@ fn struct_def(context: &mut Context, name: StructName, sdef: &N::StructDefinitio
         );
         let mut diag = invalid_object_id_field_diag(key_loc, *id_field_loc, name);
         diag.add_secondary_label((id_field_type.loc, actual));
-        context.env.add_diag(diag);
+        env.add_diag(diag);
     }
 }
 
 fn invalid_object_id_field_diag(key_loc: Loc, loc: Loc, name: StructName) -> Diagnostic {
-    const KEY_MSG: &str = "The 'key' ability is used to declare objects in Sui";
+    const OBJECT_MSG: &str =
+        "Objects are identified by the unique 'sui::object::UID' held in their first field, 'id'";
 
     let msg = format!(
         "Invalid object '{}'. \
@@ -232,34 +276,31 @@ fn invalid_object_id_field_diag(key_loc: Loc, loc: Loc, name: StructName) -> Dia
         OBJECT_MODULE_NAME,
         UID_TYPE_NAME
     );
-    diag!(OBJECT_DECL_DIAG, (loc, msg), (key_loc, KEY_MSG))
+    let mut diag = diag!(OBJECT_DECL_DIAG, (loc, msg), (key_loc, KEY_MSG));
+    diag.add_note(OBJECT_MSG);
+    diag
 }
 
 //**************************************************************************************************
 // Functions
 //**********************************************************************************************
 
-fn function(context: &mut Context, name: FunctionName, fdef: &mut T::Function) {
+fn function(context: &mut Context, fdef: &mut T::Function) {
     let T::Function {
-        visibility,
-        signature,
+        visibility: _,
+        signature: _,
         body,
         warning_filter: _,
         index: _,
         macro_: _,
         attributes,
-        entry,
+        test_only: _,
+        entry: _,
     } = fdef;
     let prev_in_test = context.in_test;
     if attributes.is_test_or_test_only() {
         context.in_test = true;
     }
-    if name.0.value == INIT_FUNCTION_NAME {
-        init_visibility(context, name, *visibility, *entry);
-    }
-    if let Some(entry_loc) = entry {
-        entry_signature(context, *entry_loc, name, signature);
-    }
     if let sp!(_, T::FunctionBody_::Defined(seq)) = body {
         context.visit_seq(seq)
     }
@@ -270,24 +311,41 @@ fn function(context: &mut Context, name: FunctionName, fdef: &mut T::Function) {
 // init
 //**************************************************************************************************
 
+/// Checks the Sui rules for the 'init' function of a module: it must be internal and not 'entry',
+/// have no type parameters or return value, take the `TxContext` as its last parameter, and take
+/// the module's one-time witness as its first parameter if there are two. Called during typing
+/// (rather than by this visitor), once the signature has been expanded. The one-time witness type
+/// itself is checked by the visitor, see `init_otw_parameter`.
+pub(crate) fn init_function(
+    env: &mut CompilationEnv,
+    info: &NamingProgramInfo,
+    mident: ModuleIdent,
+    name: FunctionName,
+    visibility: Visibility,
+    entry: Option<Loc>,
+    signature: &FunctionSignature,
+) {
+    init_signature(env, info, mident, name, signature);
+    init_visibility(env, name, visibility, entry);
+}
+
 fn init_visibility(
-    context: &mut Context,
+    env: &mut CompilationEnv,
     name: FunctionName,
     visibility: Visibility,
     entry: Option<Loc>,
 ) {
     match visibility {
-        Visibility::Public(loc) | Visibility::Friend(loc) | Visibility::Package(loc) => {
-            context.env.add_diag(diag!(
+        Visibility::Public(loc) | Visibility::Friend(loc) | Visibility::Package(loc) => env
+            .add_diag(diag!(
                 INIT_FUN_DIAG,
                 (name.loc(), "Invalid 'init' function declaration"),
                 (loc, "'init' functions must be internal to their module"),
-            ))
-        }
+            )),
         Visibility::Internal => (),
     }
     if let Some(entry) = entry {
-        context.env.add_diag(diag!(
+        env.add_diag(diag!(
             INIT_FUN_DIAG,
             (name.loc(), "Invalid 'init' function declaration"),
             (entry, "'init' functions cannot be 'entry' functions"),
@@ -295,7 +353,13 @@ fn init_visibility(
     }
 }
 
-fn init_signature(context: &mut Context, name: FunctionName, signature: &FunctionSignature) {
+fn init_signature(
+    env: &mut CompilationEnv,
+    info: &NamingProgramInfo,
+    mident: ModuleIdent,
+    name: FunctionName,
+    signature: &FunctionSignature,
+) {
     let FunctionSignature {
         type_parameters,
         parameters,
@@ -303,7 +367,7 @@ fn init_signature(context: &mut Context, name: FunctionName, signature: &Functio
     } = signature;
     if !type_parameters.is_empty() {
         let tp_loc = type_parameters[0].user_specified_name.loc;
-        context.env.add_diag(diag!(
+        env.add_diag(diag!(
             INIT_FUN_DIAG,
             (name.loc(), "Invalid 'init' function declaration"),
             (tp_loc, "'init' functions cannot have type parameters"),
@@ -314,7 +378,7 @@ fn init_signature(context: &mut Context, name: FunctionName, signature: &Functio
             "'init' functions must have a return type of {}",
             error_format_(&Type_::Unit, &Subst::empty())
         );
-        context.env.add_diag(diag!(
+        env.add_diag(diag!(
             INIT_FUN_DIAG,
             (name.loc(), "Invalid 'init' function declaration"),
             (return_type.loc, msg),
@@ -336,27 +400,32 @@ fn init_signature(context: &mut Context, name: FunctionName, signature: &Functio
             m = TX_CONTEXT_MODULE_NAME,
             t = TX_CONTEXT_TYPE_NAME,
         );
-        context.env.add_diag(diag!(
+        env.add_diag(diag!(
             INIT_FUN_DIAG,
             (name.loc(), "Invalid 'init' function declaration"),
             (last_loc, msg),
         ))
     }
 
-    let otw_name: Symbol = context.otw_name();
-    if parameters.len() == 1
-        && context.one_time_witness.is_some()
-        && tx_ctx_kind != TxContextKind::None
-    {
+    let otw_name = otw_name(mident);
+    // the one-time witness declaration is only considered if its fields are valid, see
+    // `visit_module_custom`
+    let has_otw = info
+        .module(&mident)
+        .structs
+        .get_(&otw_name)
+        .is_some_and(|sdef| match &sdef.fields {
+            N::StructFields::Defined(_, fields) => invalid_otw_field_loc(fields).is_none(),
+            N::StructFields::Native(_) => true,
+        });
+    if parameters.len() == 1 && has_otw && tx_ctx_kind != TxContextKind::None {
         // if there is 1 parameter, and a OTW, this is an error since the OTW must be used
         let msg = format!(
             "Invalid first parameter to 'init'. \
             Expected this module's one-time witness type '{}::{otw_name}'",
-            context.current_module(),
+            mident,
         );
-        let otw_loc = context
-            .info
-            .struct_declared_loc_(context.current_module(), &otw_name);
+        let otw_loc = info.struct_declared_loc_(&mident, &otw_name);
         let otw_msg = "One-time witness declared here";
         let mut diag = diag!(
             INIT_FUN_DIAG,
@@ -364,23 +433,17 @@ fn init_signature(context: &mut Context, name: FunctionName, signature: &Functio
             (otw_loc, otw_msg),
         );
         diag.add_note(OTW_NOTE);
-        context.env.add_diag(diag)
+        env.add_diag(diag)
     } else if parameters.len() > 1 {
         // if there is more than one parameter, the first must be the OTW
         let (_, first_var, first_ty) = parameters.first().unwrap();
-        let is_otw = matches!(&first_ty.value, Type_::UnresolvedError | Type_::Var(_))
-            || matches!(
-                first_ty.value.type_name(),
-                Some(sp!(_, TypeName_::ModuleType(m, n)))
-                    if m == context.current_module() && n.value() == otw_name
-            );
-        if !is_otw {
+        if !is_otw_parameter(mident, otw_name, first_ty) {
             let msg = format!(
                 "Invalid parameter '{}' of type {}. \
                 Expected a one-time witness type, '{}::{otw_name}",
                 first_var.value.name,
                 error_format(first_ty, &Subst::empty()),
-                context.current_module(),
+                mident,
             );
             let mut diag = diag!(
                 INIT_FUN_DIAG,
@@ -388,26 +451,13 @@ fn init_signature(context: &mut Context, name: FunctionName, signature: &Functio
                 (first_ty.loc, msg)
             );
             diag.add_note(OTW_NOTE);
-            context.env.add_diag(diag)
-        } else if let Some(sdef) = context
-            .info
-            .module(context.current_module())
-            .structs
-            .get_(&otw_name)
-        {
-            let name = context
-                .info
-                .module(context.current_module())
-                .structs
-                .get_full_key_(&otw_name)
-                .unwrap();
-            check_otw_type(context, name, sdef, Some(first_ty.loc))
+            env.add_diag(diag)
         }
     }
     if parameters.len() > 2 {
         // no init function can take more than 2 parameters (the OTW and the TxContext)
         let (_, third_var, _) = &parameters[2];
-        context.env.add_diag(diag!(
+        env.add_diag(diag!(
             INIT_FUN_DIAG,
             (name.loc(), "Invalid 'init' function declaration"),
             (
@@ -418,6 +468,48 @@ fn init_signature(context: &mut Context, name: FunctionName, signature: &Functio
     }
 }
 
+/// Checks the one-time witness type when it is taken by 'init', so that any errors in its
+/// declaration point to that usage
+fn init_otw_parameter(context: &mut Context, signature: &FunctionSignature) {
+    let parameters = &signature.parameters;
+    if parameters.len() <= 1 {
+        return;
+    }
+    let (_, _, first_ty) = parameters.first().unwrap();
+    let otw_name = context.otw_name();
+    if !is_otw_parameter(*context.current_module(), otw_name, first_ty) {
+        return;
+    }
+    if let Some(sdef) = context
+        .info
+        .module(context.current_module())
+        .structs
+        .get_(&otw_name)
+    {
+        let name = context
+            .info
+            .module(context.current_module())
+            .structs
+            .get_full_key_(&otw_name)
+            .unwrap();
+        check_otw_type(context, name, sdef, Some(first_ty.loc))
+    }
+}
+
+fn otw_name(mident: ModuleIdent) -> Symbol {
+    Symbol::from(mident.value.module.0.value.as_str().to_uppercase())
+}
+
+fn is_otw_parameter(mident: ModuleIdent, otw_name: Symbol, ty: &Type) -> bool {
+    // already an error, so assume it is the one-time witness
+    matches!(&ty.value, Type_::UnresolvedError | Type_::Var(_))
+        || matches!(
+            ty.value.type_name(),
+            Some(sp!(_, TypeName_::ModuleType(m, n)))
+                if *m == mident && n.value() == otw_name
+        )
+}
+
 // While theoretically we could call this just once for the upper cased module struct, we break it
 // out into a separate function to help programmers understand the rules for one-time witness types,
 // when trying to write an 'init' function.
@@ -450,7 +542,7 @@ fn check_otw_type(
         valid = false;
     }
 
-    if let N::StructFields::Defined(fields) = &sdef.fields {
+    if let N::StructFields::Defined(_, fields) = &sdef.fields {
         let invalid_otw_opt = invalid_otw_field_loc(fields);
         if let Some(invalid_otw_opt) = invalid_otw_opt {
             let msg_base = format!(
@@ -542,8 +634,12 @@ fn invalid_otw_field_loc(fields: &Fields<Type>) -> Option<InvalidOTW> {
 // entry types
 //**************************************************************************************************
 
-fn entry_signature(
-    context: &mut Context,
+/// Checks the Sui rules for the parameter and return types of an 'entry' function. Called during
+/// typing (rather than by this visitor), once the signature has been expanded, so that errors are
+/// reported alongside the other errors in the signature.
+pub(crate) fn entry_signature(
+    env: &mut CompilationEnv,
+    info: &NamingProgramInfo,
     entry_loc: Loc,
     name: FunctionName,
     signature: &FunctionSignature,
@@ -559,8 +655,8 @@ fn entry_signature(
         }
         _ => parameters,
     };
-    entry_param(context, entry_loc, name, all_non_ctx_parameters);
-    entry_return(context, entry_loc, name, return_type);
+    entry_param(env, entry_loc, name, all_non_ctx_parameters);
+    entry_return(env, info, entry_loc, name, return_type);
 }
 
 fn tx_context_kind(sp!(_, last_param_ty_): &Type) -> TxContextKind {
@@ -600,13 +696,13 @@ pub enum TxContextKind {
 }
 
 fn entry_param(
-    context: &mut Context,
+    env: &mut CompilationEnv,
     entry_loc: Loc,
     name: FunctionName,
     parameters: &[(Mutability, Var, Type)],
 ) {
     for (_, var, ty) in parameters {
-        entry_param_ty(context, entry_loc, name, var, ty);
+        entry_param_ty(env, entry_loc, name, var, ty);
     }
 }
 
@@ -618,7 +714,7 @@ fn entry_param(
 /// - A reference to an object
 /// - A vector of objects
 fn entry_param_ty(
-    context: &mut Context,
+    env: &mut CompilationEnv,
     entry_loc: Loc,
     name: FunctionName,
     param: &Var,
@@ -632,30 +728,74 @@ fn entry_param_ty(
     let is_valid = is_entry_primitive_ty(param_ty)
         || is_entry_object_ty(param_ty)
         || is_entry_receiving_ty(param_ty);
-    if is_mut_clock || !is_valid {
+    if is_mut_clock {
         let pmsg = format!(
             "Invalid 'entry' parameter type for parameter '{}'",
             param.value.name
         );
-        let tmsg = if is_mut_clock {
-            format!(
-                "{a}::{m}::{n} must be passed by immutable reference, e.g. '&{a}::{m}::{n}'",
-                a = SUI_ADDR_NAME,
-                m = CLOCK_MODULE_NAME,
-                n = CLOCK_TYPE_NAME,
+        let tmsg = format!(
+            "{a}::{m}::{n} must be passed by immutable reference, e.g. '&{a}::{m}::{n}'",
+            a = SUI_ADDR_NAME,
+            m = CLOCK_MODULE_NAME,
+            n = CLOCK_TYPE_NAME,
+        );
+        let emsg = format!("'{name}' was declared 'entry' here");
+        env.add_diag(diag!(
+            ENTRY_FUN_CLOCK_DIAG,
+            (param.loc, pmsg),
+            (param_ty.loc, tmsg),
+            (entry_loc, emsg)
+        ));
+    } else if !is_valid {
+        let pmsg = format!(
+            "Invalid 'entry' parameter type for parameter '{}'",
+            param.value.name
+        );
+        let (info, tmsg) = if matches!(param_ty.value, Type_::Ref(_, _)) {
+            (
+                ENTRY_FUN_REFERENCE_DIAG,
+                "Only objects, primitives, and 'Receiving' arguments can be passed by reference",
+            )
+        } else if contains_object_ty(param_ty) {
+            (
+                ENTRY_FUN_NESTED_OBJECT_DIAG,
+                "Objects can only be passed directly or in a vector, not nested in other types",
             )
         } else {
-            "'entry' parameters must be primitives (by-value), vectors of primitives, objects \
-            (by-reference or by-value), vectors of objects, or 'Receiving' arguments (by-reference or by-value)"
-                .to_owned()
+            (
+                ENTRY_FUN_SIGNATURE_DIAG,
+                "Expected a primitive or an object, i.e. a type with the 'key' ability",
+            )
         };
         let emsg = format!("'{name}' was declared 'entry' here");
-        context.env.add_diag(diag!(
-            ENTRY_FUN_SIGNATURE_DIAG,
+        let mut diag = diag!(
+            info,
             (param.loc, pmsg),
             (param_ty.loc, tmsg),
             (entry_loc, emsg)
-        ));
+        );
+        diag.add_note(ENTRY_PARAM_NOTE);
+        env.add_diag(diag);
+    }
+}
+
+/// Whether the type is, or has as a type argument, an object or a type parameter with 'key'
+fn contains_object_ty(ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Param(tp) => tp.abilities.has_ability_(Ability_::Key),
+        Type_::Ref(_, t) => contains_object_ty(t),
+        Type_::Apply(abilities, _, targs) => {
+            abilities
+                .as_ref()
+                .is_some_and(|abilities| abilities.has_ability_(Ability_::Key))
+                || targs.iter().any(contains_object_ty)
+        }
+        Type_::Unit
+        | Type_::Var(_)
+        | Type_::Anything
+        | Type_::Hole
+        | Type_::UnresolvedError
+        | Type_::Fun(_, _) => false,
     }
 }
 
@@ -668,6 +808,7 @@ fn is_mut_clock(param_ty: &Type) -> bool {
         | Type_::Param(_)
         | Type_::Var(_)
         | Type_::Anything
+        | Type_::Hole
         | Type_::UnresolvedError
         | Type_::Fun(_, _) => false,
     }
@@ -732,7 +873,11 @@ fn is_entry_primitive_ty(param_ty: &Type) -> bool {
         Type_::Unit => false,
 
         // Error case nothing to do
-        Type_::UnresolvedError | Type_::Anything | Type_::Var(_) | Type_::Fun(_, _) => true,
+        Type_::UnresolvedError
+        | Type_::Anything
+        | Type_::Hole
+        | Type_::Var(_)
+        | Type_::Fun(_, _) => true,
     }
 }
 
@@ -764,6 +909,7 @@ fn is_entry_object_ty_inner(param_ty: &Type) -> bool {
         // Error case nothing to do
         Type_::UnresolvedError
         | Type_::Anything
+        | Type_::Hole
         | Type_::Var(_)
         | Type_::Unit
         | Type_::Fun(_, _) => true,
@@ -773,7 +919,8 @@ fn is_entry_object_ty_inner(param_ty: &Type) -> bool {
 }
 
 fn entry_return(
-    context: &mut Context,
+    env: &mut CompilationEnv,
+    info: &NamingProgramInfo,
     entry_loc: Loc,
     name: FunctionName,
     return_type @ sp!(tloc, return_type_): &Type,
@@ -784,18 +931,22 @@ fn entry_return(
         Type_::Ref(_, _) => {
             let fmsg = format!("Invalid return type for entry function '{}'", name);
             let tmsg = "Expected a non-reference type";
-            context.env.add_diag(diag!(
-                ENTRY_FUN_SIGNATURE_DIAG,
-                (entry_loc, fmsg),
-                (*tloc, tmsg)
-            ))
+            let mut diag = diag!(ENTRY_FUN_RETURN_DIAG, (entry_loc, fmsg), (*tloc, tmsg));
+            diag.add_note(ENTRY_RETURN_NOTE);
+            env.add_diag(diag)
+        }
+        // each element of a tuple is checked on its own, so each error points at its element
+        Type_::Apply(_, sp!(_, TypeName_::Multiple(_)), ty_args) => {
+            for ty_arg in ty_args {
+                entry_return(env, info, entry_loc, name, ty_arg)
+            }
         }
         Type_::Param(tp) => {
             if !tp.abilities.has_ability_(Ability_::Drop) {
                 let declared_loc_opt = Some(tp.user_specified_name.loc);
                 let declared_abilities = tp.abilities.clone();
                 invalid_entry_return_ty(
-                    context,
+                    env,
                     entry_loc,
                     name,
                     return_type,
@@ -808,15 +959,15 @@ fn entry_return(
         Type_::Apply(Some(abilities), sp!(_, tn_), ty_args) => {
             if !abilities.has_ability_(Ability_::Drop) {
                 let (declared_loc_opt, declared_abilities) = match tn_ {
-                    TypeName_::Multiple(_) => (None, AbilitySet::collection(*tloc)),
+                    TypeName_::Multiple(_) => unreachable!("ICE tuples are checked per element"),
                     TypeName_::ModuleType(m, n) => (
-                        Some(context.info.struct_declared_loc(m, n)),
-                        context.info.struct_declared_abilities(m, n).clone(),
+                        Some(info.struct_declared_loc(m, n)),
+                        info.struct_declared_abilities(m, n).clone(),
                     ),
                     TypeName_::Builtin(b) => (None, b.value.declared_abilities(b.loc)),
                 };
                 invalid_entry_return_ty(
-                    context,
+                    env,
                     entry_loc,
                     name,
                     return_type,
@@ -827,7 +978,11 @@ fn entry_return(
             }
         }
         // Error case nothing to do
-        Type_::UnresolvedError | Type_::Anything | Type_::Var(_) | Type_::Fun(_, _) => (),
+        Type_::UnresolvedError
+        | Type_::Anything
+        | Type_::Hole
+        | Type_::Var(_)
+        | Type_::Fun(_, _) => (),
         // Unreachable cases
         Type_::Apply(None, _, _) => unreachable!("ICE abilities should have been expanded"),
     }
@@ -839,7 +994,7 @@ fn get_abilities(sp!(loc, ty_): &Type) -> AbilitySet {
 }
 
 fn invalid_entry_return_ty<'a>(
-    context: &mut Context,
+    env: &mut CompilationEnv,
     entry_loc: Loc,
     name: FunctionName,
     ty: &Type,
@@ -848,7 +1003,7 @@ fn invalid_entry_return_ty<'a>(
     ty_args: impl IntoIterator<Item = (&'a Type, AbilitySet)>,
 ) {
     let fmsg = format!("Invalid return type for entry function '{}'", name);
-    let mut diag = diag!(ENTRY_FUN_SIGNATURE_DIAG, (entry_loc, fmsg));
+    let mut diag = diag!(ENTRY_FUN_RETURN_DIAG, (entry_loc, fmsg));
     ability_not_satisfied_tips(
         &Subst::empty(),
         &mut diag,
@@ -858,7 +1013,8 @@ fn invalid_entry_return_ty<'a>(
         declared_abilities,
         ty_args,
     );
-    context.env.add_diag(diag)
+    diag.add_note(ENTRY_RETURN_NOTE);
+    env.add_diag(diag)
 }
 
 //**************************************************************************************************
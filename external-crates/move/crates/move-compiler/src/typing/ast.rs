@@ -5,20 +5,21 @@
 use crate::{
     debug_display,
     diagnostics::WarningFilters,
-    expansion::ast::{Address, Attributes, Fields, Friend, ModuleIdent, Value, Visibility},
+    expansion::ast::{Address, Attributes, Fields, Friend, ModuleIdent, Value, Value_, Visibility},
     ice,
     naming::ast::{
         BlockLabel, FunctionSignature, Neighbor, StructDefinition, Type, TypeName_, Type_, UseFuns,
         Var,
     },
     parser::ast::{
-        BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, ENTRY_MODIFIER,
-        MACRO_MODIFIER, NATIVE_MODIFIER,
+        BinOp, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp, UnaryOp_,
+        ENTRY_MODIFIER, MACRO_MODIFIER, NATIVE_MODIFIER,
     },
     shared::{
         ast_debug::*, program_info::TypingProgramInfo, unique_map::UniqueMap, CompilationEnv, Name,
     },
 };
+use move_core_types::runtime_value::MoveValue;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use std::{
@@ -83,6 +84,8 @@ pub struct Function {
     // index in the original order as defined in the source file
     pub index: usize,
     pub attributes: Attributes,
+    /// The location of the item's '#[test_only]' attribute, if it has one
+    pub test_only: Option<Loc>,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
     pub macro_: Option<Loc>,
@@ -100,9 +103,13 @@ pub struct Constant {
     // index in the original order as defined in the source file
     pub index: usize,
     pub attributes: Attributes,
+    /// The location of the item's '#[test_only]' attribute, if it has one
+    pub test_only: Option<Loc>,
     pub loc: Loc,
     pub signature: Type,
     pub value: Exp,
+    /// The value of the constant, if it could be computed during typing
+    pub folded_value: Option<MoveValue>,
 }
 
 //**************************************************************************************************
@@ -145,7 +152,12 @@ pub struct ModuleCall {
 #[allow(clippy::large_enum_variant)]
 pub enum BuiltinFunction_ {
     Freeze(Type),
-    Assert(/* is_macro */ Option<Loc>),
+    /// If the abort code was omitted, it is filled in with `IMPLICIT_ABORT_CODE` and the code is
+    /// marked as implicit
+    Assert(
+        /* is_macro */ Option<Loc>,
+        /* implicit_code */ bool,
+    ),
 }
 pub type BuiltinFunction = Spanned<BuiltinFunction_>;
 
@@ -165,6 +177,9 @@ pub enum UnannotatedExp_ {
     },
     Use(Var),
     Constant(ModuleIdent, ConstantName),
+    /// An '#[error]' constant used as an abort code. It has type 'u64', as the constant is
+    /// encoded into the abort code
+    ErrorConstant(ModuleIdent, ConstantName),
 
     ModuleCall(Box<ModuleCall>),
     Builtin(Box<BuiltinFunction>, Box<Exp>),
@@ -234,7 +249,7 @@ impl BuiltinFunction_ {
         use BuiltinFunction_ as B;
         match self {
             B::Freeze(_) => NB::FREEZE,
-            B::Assert(_) => NB::ASSERT_MACRO,
+            B::Assert(..) => NB::ASSERT_MACRO,
         }
     }
 }
@@ -279,6 +294,23 @@ pub fn splat_item(env: &mut CompilationEnv, splat_loc: Loc, e: Exp) -> ExpListIt
     ExpListItem::Splat(splat_loc, e, ss)
 }
 
+/// The value of a boolean expression built only from literals, e.g. `true` or `!false && true`
+pub fn bool_literal_value(e: &Exp) -> Option<bool> {
+    use UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Value(sp!(_, Value_::Bool(b))) => Some(*b),
+        E::Annotate(e, _) => bool_literal_value(e),
+        E::UnaryExp(sp!(_, UnaryOp_::Not), e) => bool_literal_value(e).map(|b| !b),
+        E::BinopExp(lhs, sp!(_, BinOp_::And), _, rhs) => {
+            Some(bool_literal_value(lhs)? && bool_literal_value(rhs)?)
+        }
+        E::BinopExp(lhs, sp!(_, BinOp_::Or), _, rhs) => {
+            Some(bool_literal_value(lhs)? || bool_literal_value(rhs)?)
+        }
+        _ => None,
+    }
+}
+
 //**************************************************************************************************
 // Display
 //**************************************************************************************************
@@ -293,6 +325,50 @@ impl fmt::Display for BuiltinFunction_ {
 // Debug
 //**************************************************************************************************
 
+/// Prints the typed program with the type of every expression, marking expanded macros. With a
+/// filter, only the modules with that name are printed, or otherwise only the functions with that
+/// name. Library modules are never printed
+pub fn print_program(prog: &Program, filter: Option<&str>) -> String {
+    let markers = prog
+        .info
+        .macro_expansions
+        .iter()
+        .map(|(loc, (m, f))| (*loc, format!("expanded {}::{}!", m, f)))
+        .collect();
+    let mut w = AstWriter::verbose_with_markers(markers);
+    for (mident, mdef) in prog.inner.modules.key_cloned_iter() {
+        if !mdef.is_source_module {
+            continue;
+        }
+        let module_matches = filter.map_or(true, |filter| {
+            mident.value.module.value().as_str() == filter || mident.to_string() == filter
+        });
+        if module_matches {
+            w.write(&format!("module {}", mident));
+            w.block(|w| mdef.ast_debug(w));
+            w.new_line();
+            continue;
+        }
+        let functions = mdef
+            .functions
+            .key_cloned_iter()
+            .filter(|(name, _)| filter == Some(name.value().as_str()))
+            .collect::<Vec<_>>();
+        if functions.is_empty() {
+            continue;
+        }
+        w.write(&format!("module {}", mident));
+        w.block(|w| {
+            for fdef in functions {
+                fdef.ast_debug(w);
+                w.new_line();
+            }
+        });
+        w.new_line();
+    }
+    w.to_string()
+}
+
 impl AstDebug for Program {
     fn ast_debug(&self, w: &mut AstWriter) {
         self.inner.ast_debug(w)
@@ -334,9 +410,9 @@ impl AstDebug for ModuleDefinition {
         }
         attributes.ast_debug(w);
         if *is_source_module {
-            w.writeln("library module")
-        } else {
             w.writeln("source module")
+        } else {
+            w.writeln("library module")
         }
         w.writeln(&format!("dependency order #{}", dependency_order));
         for (mident, neighbor) in immediate_neighbors.key_cloned_iter() {
@@ -376,6 +452,7 @@ impl AstDebug for (FunctionName, &Function) {
                 warning_filter,
                 index,
                 attributes,
+                test_only: _,
                 visibility,
                 entry,
                 macro_,
@@ -419,9 +496,11 @@ impl AstDebug for (ConstantName, &Constant) {
                 warning_filter,
                 index,
                 attributes,
+                test_only: _,
                 loc: _loc,
                 signature,
                 value,
+                folded_value: _,
             },
         ) = self;
         warning_filter.ast_debug(w);
@@ -508,6 +587,7 @@ impl AstDebug for UnannotatedExp_ {
                 v.ast_debug(w)
             }
             E::Constant(m, c) => w.write(&format!("{}::{}", m, c)),
+            E::ErrorConstant(m, c) => w.write(&format!("error@{}::{}", m, c)),
             E::ModuleCall(mcall) => {
                 mcall.ast_debug(w);
             }
@@ -676,6 +756,12 @@ impl AstDebug for UnannotatedExp_ {
 impl AstDebug for Exp {
     fn ast_debug(&self, w: &mut AstWriter) {
         let Exp { ty, exp } = self;
+        // an expanded macro is a block with the location of the call
+        if let UnannotatedExp_::Block(_) = &exp.value {
+            if let Some(marker) = w.marker(exp.loc).map(|marker| format!("/*{marker}*/ ")) {
+                w.write(marker);
+            }
+        }
         w.annotate(|w| exp.ast_debug(w), ty)
     }
 }
@@ -688,16 +774,16 @@ impl AstDebug for ModuleCall {
             type_arguments,
             parameter_types,
             arguments,
-            method_name: _,
+            method_name,
         } = self;
         w.write(&format!("{}::{}", module, name));
+        if let Some(method) = method_name {
+            w.write(&format!("/*method {}*/", method));
+        }
         if !parameter_types.is_empty() {
-            w.write("[");
-            if !parameter_types.is_empty() {
-                w.write("parameter_types: [");
-                parameter_types.ast_debug(w);
-                w.write("]");
-            }
+            w.write("[parameter_types: [");
+            parameter_types.ast_debug(w);
+            w.write("]]");
         }
         w.write("<");
         type_arguments.ast_debug(w);
@@ -714,7 +800,7 @@ impl AstDebug for BuiltinFunction_ {
         use BuiltinFunction_ as F;
         let (n, bt_opt) = match self {
             F::Freeze(bt) => (NF::FREEZE, Some(bt)),
-            F::Assert(_) => (NF::ASSERT_MACRO, None),
+            F::Assert(..) => (NF::ASSERT_MACRO, None),
         };
         w.write(n);
         if let Some(bt) = bt_opt {
@@ -0,0 +1,258 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inlines calls to '#[const_fun]' functions into the constants of their module, so that the
+//! constants are again made only of constant expressions and can be evaluated as usual. The bodies
+//! of these functions are checked in `translate::const_function`, and any recursion between them
+//! is reported here.
+
+use crate::{
+    diag,
+    diagnostics::{codes::*, Diagnostic, WarningFilters},
+    expansion::ast::ModuleIdent,
+    naming::ast::Var_,
+    parser::ast::{ConstantName, FunctionName},
+    shared::{known_attributes::ConstFunAttribute, unique_map::UniqueMap, *},
+    typing::{ast as T, constant_eval, core::Context, visitor::TypingVisitorContext},
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use petgraph::{algo::tarjan_scc as petgraph_scc, graphmap::DiGraphMap};
+use std::collections::BTreeMap;
+
+struct ConstFun {
+    parameters: Vec<Var_>,
+    body: T::Sequence,
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn module(
+    context: &mut Context,
+    mident: ModuleIdent,
+    constants: &mut UniqueMap<ConstantName, T::Constant>,
+    functions: &UniqueMap<FunctionName, T::Function>,
+) {
+    let mut const_funs = functions
+        .key_cloned_iter()
+        .filter(|(_, f)| f.attributes.contains_key_(&ConstFunAttribute.into()))
+        .filter_map(|(name, f)| {
+            let T::FunctionBody_::Defined(body) = &f.body.value else {
+                return None;
+            };
+            let parameters = f.signature.parameters.iter().map(|(_, v, _)| v.value);
+            let const_fun = ConstFun {
+                parameters: parameters.collect(),
+                body: body.clone(),
+            };
+            Some((name.value(), const_fun))
+        })
+        .collect::<BTreeMap<_, _>>();
+    if const_funs.is_empty() {
+        return;
+    }
+    // inlining a recursive function would never terminate
+    if report_cycles(context, mident, &mut const_funs) {
+        return;
+    }
+    for (_, _, constant) in constants.iter_mut() {
+        let mut inliner = Inliner {
+            mident,
+            const_funs: &const_funs,
+            inlined: false,
+        };
+        inliner.visit_exp(&mut constant.value);
+        if inliner.inlined {
+            constant.folded_value = constant_eval::constant_value(context, &mut constant.value);
+        }
+    }
+}
+
+//**************************************************************************************************
+// Cycles
+//**************************************************************************************************
+
+/// Reports any cycle of calls between the '#[const_fun]' functions of the module. Returns true if a
+/// cycle was found
+fn report_cycles(
+    context: &mut Context,
+    mident: ModuleIdent,
+    const_funs: &mut BTreeMap<Symbol, ConstFun>,
+) -> bool {
+    let mut calls = Calls {
+        mident,
+        current: None,
+        calls: BTreeMap::new(),
+    };
+    for (name, const_fun) in const_funs.iter_mut() {
+        calls.current = Some(*name);
+        calls.visit_seq(&mut const_fun.body);
+    }
+    let calls = calls.calls;
+    let edges = calls.iter().flat_map(|(caller, callees)| {
+        callees
+            .keys()
+            .filter(|callee| const_funs.contains_key(callee))
+            .map(move |callee| (caller, callee))
+    });
+    let graph: DiGraphMap<&Symbol, ()> = DiGraphMap::from_edges(edges);
+    let mut has_cycles = false;
+    // - get the strongly connected components
+    // - filter out single nodes that do not call themselves
+    // - report those cycles
+    petgraph_scc(&graph)
+        .into_iter()
+        .filter(|scc| scc.len() > 1 || graph.contains_edge(scc[0], scc[0]))
+        .for_each(|scc| {
+            has_cycles = true;
+            context.add_diag(cycle_error(&calls, &graph, scc[0]))
+        });
+    has_cycles
+}
+
+fn cycle_error(
+    calls: &BTreeMap<Symbol, BTreeMap<Symbol, Loc>>,
+    graph: &DiGraphMap<&Symbol, ()>,
+    cycle_node: &Symbol,
+) -> Diagnostic {
+    let cycle = shortest_cycle(graph, cycle_node);
+    let cycle_strings = cycle
+        .iter()
+        .map(|f| format!("'{}'", f))
+        .collect::<Vec<_>>()
+        .join(" calls ");
+    // the cycle starts and ends with the same function
+    let len = cycle.len();
+    let (user, used) = (cycle[len - 2], cycle[len - 1]);
+    let call_loc = calls[user][used];
+    let call_msg = format!(
+        "Invalid call of '{}' in '#[{}]' function '{}'",
+        used,
+        ConstFunAttribute::CONST_FUN,
+        user
+    );
+    let cycle_msg = format!(
+        "'#[{}]' functions cannot be recursive. Calling this function creates a cycle: {}",
+        ConstFunAttribute::CONST_FUN,
+        cycle_strings
+    );
+    diag!(
+        TypeSafety::CyclicConstFunction,
+        (call_loc, call_msg),
+        (call_loc, cycle_msg)
+    )
+}
+
+/// The calls made by each '#[const_fun]' function to functions of the same module, with the
+/// location of the first such call
+struct Calls {
+    mident: ModuleIdent,
+    current: Option<Symbol>,
+    calls: BTreeMap<Symbol, BTreeMap<Symbol, Loc>>,
+}
+
+impl TypingVisitorContext for Calls {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+    fn pop_warning_filter_scope(&mut self) {}
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        if let (Some(current), T::UnannotatedExp_::ModuleCall(call)) =
+            (self.current, &exp.exp.value)
+        {
+            if call.module == self.mident {
+                self.calls
+                    .entry(current)
+                    .or_default()
+                    .entry(call.name.value())
+                    .or_insert(exp.exp.loc);
+            }
+        }
+        false
+    }
+}
+
+//**************************************************************************************************
+// Inlining
+//**************************************************************************************************
+
+/// Replaces each call to a '#[const_fun]' function with the body of that function, where each
+/// parameter is replaced by its argument
+struct Inliner<'a> {
+    mident: ModuleIdent,
+    const_funs: &'a BTreeMap<Symbol, ConstFun>,
+    inlined: bool,
+}
+
+impl TypingVisitorContext for Inliner<'_> {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+    fn pop_warning_filter_scope(&mut self) {}
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        let T::UnannotatedExp_::ModuleCall(call) = &mut exp.exp.value else {
+            return false;
+        };
+        if call.module != self.mident {
+            return false;
+        }
+        let Some(const_fun) = self.const_funs.get(&call.name.value()) else {
+            return false;
+        };
+        // inline any calls made in the arguments first
+        self.visit_exp(&mut call.arguments);
+        let arguments = call_arguments(&call.arguments);
+        let mut substitution = Substitution {
+            arguments: const_fun
+                .parameters
+                .iter()
+                .copied()
+                .zip(arguments.into_iter().cloned())
+                .collect(),
+        };
+        let mut body = const_fun.body.clone();
+        substitution.visit_seq(&mut body);
+        // the body might itself call other '#[const_fun]' functions
+        self.visit_seq(&mut body);
+        exp.exp.value = T::UnannotatedExp_::Block(body);
+        self.inlined = true;
+        true
+    }
+}
+
+fn call_arguments(arguments: &T::Exp) -> Vec<&T::Exp> {
+    use T::UnannotatedExp_ as E;
+    match &arguments.exp.value {
+        E::Unit { .. } => vec![],
+        E::ExpList(items) => items
+            .iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => e,
+            })
+            .collect(),
+        _ => vec![arguments],
+    }
+}
+
+/// Replaces the usages of the parameters of an inlined function with their arguments
+struct Substitution {
+    arguments: BTreeMap<Var_, T::Exp>,
+}
+
+impl TypingVisitorContext for Substitution {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+    fn pop_warning_filter_scope(&mut self) {}
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        let var = match &exp.exp.value {
+            E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => var,
+            _ => return false,
+        };
+        let Some(argument) = self.arguments.get(&var.value) else {
+            return false;
+        };
+        *exp = argument.clone();
+        true
+    }
+}
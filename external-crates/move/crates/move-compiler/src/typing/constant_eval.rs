@@ -0,0 +1,254 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Evaluates the value of a constant once it has been typed, reporting arithmetic errors such as
+//! overflow, division by zero, or an out of range shift at the expression where they occur.
+//! Expressions that cannot be evaluated here, e.g. references to other constants, are left to be
+//! folded in CFGIR.
+
+use crate::{
+    diag,
+    diagnostics::{codes::TypeSafety, Diagnostic},
+    expansion::ast::Value_,
+    naming::ast::BuiltinTypeName_,
+    parser::ast::{BinOp, BinOp_, UnaryOp_},
+    typing::{ast as T, core::Context},
+};
+use move_core_types::{account_address::AccountAddress, runtime_value::MoveValue, u256::U256};
+use move_ir_types::location::*;
+
+/// `Ok(None)` if the value could not be computed, and `Err` if computing it failed
+type EvalResult = Result<Option<MoveValue>, Box<Diagnostic>>;
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+/// Computes the value of the constant expression `e`, if possible. If the computation fails, the
+/// error is reported and `e` is replaced with an error expression
+pub fn constant_value(context: &mut Context, e: &mut T::Exp) -> Option<MoveValue> {
+    match exp(e) {
+        Ok(value) => value,
+        Err(diag) => {
+            context.add_diag(*diag);
+            // the error has been reported, so do not report it again when folding in CFGIR
+            e.exp.value = T::UnannotatedExp_::UnresolvedError;
+            None
+        }
+    }
+}
+
+//**************************************************************************************************
+// Expressions
+//**************************************************************************************************
+
+fn exp(e: &T::Exp) -> EvalResult {
+    use T::UnannotatedExp_ as E;
+    let loc = e.exp.loc;
+    match &e.exp.value {
+        E::Value(sp!(_, v)) => Ok(value(v)),
+        E::Annotate(er, _) => exp(er),
+        // the body of an inlined '#[const_fun]' call
+        E::Block((_, seq)) => match seq.front() {
+            Some(sp!(_, T::SequenceItem_::Seq(er))) if seq.len() == 1 => exp(er),
+            _ => Ok(None),
+        },
+        E::UnaryExp(sp!(_, UnaryOp_::Not), er) => match exp(er)? {
+            Some(MoveValue::Bool(b)) => Ok(Some(MoveValue::Bool(!b))),
+            _ => Ok(None),
+        },
+        E::BinopExp(el, op, _, er) => {
+            let lhs = exp(el)?;
+            let rhs = exp(er)?;
+            match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => binop(loc, op, lhs, rhs),
+                _ => Ok(None),
+            }
+        }
+        E::Cast(er, ty) => {
+            let Some(v) = exp(er)? else {
+                return Ok(None);
+            };
+            match ty.value.builtin_name().and_then(|sp!(_, bt_)| bits(bt_)) {
+                Some(bits) => cast(loc, bits, v),
+                None => Ok(None),
+            }
+        }
+        E::Vector(_, _, _, args) => {
+            let values = vector_args(args)
+                .into_iter()
+                .map(exp)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(values
+                .into_iter()
+                .collect::<Option<Vec<_>>>()
+                .map(MoveValue::Vector))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn vector_args(args: &T::Exp) -> Vec<&T::Exp> {
+    use T::UnannotatedExp_ as E;
+    match &args.exp.value {
+        E::Unit { .. } => vec![],
+        E::ExpList(items) => items
+            .iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => e,
+            })
+            .collect(),
+        _ => vec![args],
+    }
+}
+
+fn value(v: &Value_) -> Option<MoveValue> {
+    use MoveValue as MV;
+    use Value_ as V;
+    Some(match v {
+        V::Address(a) => MV::Address(AccountAddress::new(a.into_addr_bytes().into_bytes())),
+        // numbers are given a concrete type after inference
+        V::InferredNum(_) => return None,
+        V::U8(u) => MV::U8(*u),
+        V::U16(u) => MV::U16(*u),
+        V::U32(u) => MV::U32(*u),
+        V::U64(u) => MV::U64(*u),
+        V::U128(u) => MV::U128(*u),
+        V::U256(u) => MV::U256(*u),
+        V::Bool(b) => MV::Bool(*b),
+        V::Bytearray(bytes) => MV::Vector(bytes.iter().map(|b| MV::U8(*b)).collect()),
+    })
+}
+
+//**************************************************************************************************
+// Operations
+//**************************************************************************************************
+
+fn binop(loc: Loc, op: &BinOp, lhs: MoveValue, rhs: MoveValue) -> EvalResult {
+    use BinOp_ as B;
+    use MoveValue as MV;
+    let (l, r, bits) = match (&op.value, &lhs, &rhs) {
+        (B::And, MV::Bool(b1), MV::Bool(b2)) => return Ok(Some(MV::Bool(*b1 && *b2))),
+        (B::Or, MV::Bool(b1), MV::Bool(b2)) => return Ok(Some(MV::Bool(*b1 || *b2))),
+        (B::Eq, _, _) => return Ok(Some(MV::Bool(lhs == rhs))),
+        (B::Neq, _, _) => return Ok(Some(MV::Bool(lhs != rhs))),
+        _ => match (int(&lhs), int(&rhs)) {
+            (Some((l, bits)), Some((r, _))) => (l, r, bits),
+            _ => return Ok(None),
+        },
+    };
+    let result = match &op.value {
+        B::Add => l.checked_add(r),
+        B::Sub => l.checked_sub(r),
+        B::Mul => l.checked_mul(r),
+        B::Div | B::Mod if r == U256::zero() => {
+            let msg = format!("Invalid arithmetic in constant. '{lhs} {op} {rhs}' divides by zero");
+            return Err(Box::new(diag!(TypeSafety::ConstantEvaluation, (loc, msg))));
+        }
+        B::Div => l.checked_div(r),
+        B::Mod => l.checked_rem(r),
+        // the right hand side of a shift is a 'u8'
+        B::Shl | B::Shr if r >= U256::from(bits) => {
+            let msg = format!(
+                "Invalid shift in constant. The shift amount in '{lhs} {op} {rhs}' must be less \
+                than {bits}, the number of bits in type 'u{bits}'"
+            );
+            return Err(Box::new(diag!(TypeSafety::ConstantEvaluation, (loc, msg))));
+        }
+        // bits shifted past the size of the type are dropped
+        B::Shl => Some(truncate(l << r.unchecked_as_u8(), bits)),
+        B::Shr => Some(l >> r.unchecked_as_u8()),
+        B::BitOr => Some(l | r),
+        B::BitAnd => Some(l & r),
+        B::Xor => Some(l ^ r),
+        B::Lt => return Ok(Some(MV::Bool(l < r))),
+        B::Gt => return Ok(Some(MV::Bool(l > r))),
+        B::Le => return Ok(Some(MV::Bool(l <= r))),
+        B::Ge => return Ok(Some(MV::Bool(l >= r))),
+        B::And | B::Or | B::Eq | B::Neq | B::Range | B::Implies | B::Iff => return Ok(None),
+    };
+    match result {
+        Some(u) if fits(u, bits) => Ok(Some(from_int(u, bits))),
+        _ => {
+            let msg = format!(
+                "Invalid arithmetic in constant. '{lhs} {op} {rhs}' does not fit in type 'u{bits}'"
+            );
+            Err(Box::new(diag!(TypeSafety::ConstantEvaluation, (loc, msg))))
+        }
+    }
+}
+
+/// Casts are checked, as they are at runtime, so a value that does not fit in the target type is an
+/// error instead of being truncated
+fn cast(loc: Loc, bits: u16, v: MoveValue) -> EvalResult {
+    let Some((u, from_bits)) = int(&v) else {
+        return Ok(None);
+    };
+    if fits(u, bits) {
+        Ok(Some(from_int(u, bits)))
+    } else {
+        let msg = format!(
+            "Invalid cast in constant. The cast from 'u{from_bits}' value '{u}' does not fit in \
+            type 'u{bits}'"
+        );
+        Err(Box::new(diag!(TypeSafety::ConstantEvaluation, (loc, msg))))
+    }
+}
+
+//**************************************************************************************************
+// Integers
+//**************************************************************************************************
+
+/// The integer widened to a 'u256', along with the number of bits in its type
+fn int(v: &MoveValue) -> Option<(U256, u16)> {
+    use MoveValue as MV;
+    Some(match v {
+        MV::U8(u) => (U256::from(*u), 8),
+        MV::U16(u) => (U256::from(*u), 16),
+        MV::U32(u) => (U256::from(*u), 32),
+        MV::U64(u) => (U256::from(*u), 64),
+        MV::U128(u) => (U256::from(*u), 128),
+        MV::U256(u) => (*u, 256),
+        MV::Bool(_) | MV::Address(_) | MV::Vector(_) | MV::Struct(_) | MV::Signer(_) => {
+            return None
+        }
+    })
+}
+
+fn from_int(u: U256, bits: u16) -> MoveValue {
+    use MoveValue as MV;
+    match bits {
+        8 => MV::U8(u.unchecked_as_u8()),
+        16 => MV::U16(u.unchecked_as_u16()),
+        32 => MV::U32(u.unchecked_as_u32()),
+        64 => MV::U64(u.unchecked_as_u64()),
+        128 => MV::U128(u.unchecked_as_u128()),
+        256 => MV::U256(u),
+        _ => panic!("ICE unexpected integer size {bits}"),
+    }
+}
+
+fn bits(bt_: &BuiltinTypeName_) -> Option<u16> {
+    use BuiltinTypeName_ as BT;
+    match bt_ {
+        BT::U8 => Some(8),
+        BT::U16 => Some(16),
+        BT::U32 => Some(32),
+        BT::U64 => Some(64),
+        BT::U128 => Some(128),
+        BT::U256 => Some(256),
+        BT::Address | BT::Signer | BT::Vector | BT::Bool => None,
+    }
+}
+
+fn fits(u: U256, bits: u16) -> bool {
+    u.leading_zeros() >= 256 - bits as u32
+}
+
+fn truncate(u: U256, bits: u16) -> U256 {
+    if bits == 256 {
+        u
+    } else {
+        u & ((U256::one() << bits as u32) - U256::one())
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
         codes::{NameResolution, TypeSafety},
         Diagnostic,
     },
+    editions::{create_feature_error, FeatureGate},
     expansion::ast::{AbilitySet, ModuleIdent, ModuleIdent_, Visibility},
     ice,
     naming::ast::{
@@ -16,9 +17,17 @@ use crate::{
         Var,
     },
     parser::ast::{
-        Ability_, ConstantName, Field, FunctionName, Mutability, StructName, ENTRY_MODIFIER,
+        Ability, Ability_, ConstantName, Field, FunctionName, Mutability, StructName,
+        ENTRY_MODIFIER,
+    },
+    shared::{
+        inference_holes::{InferenceHole, InferenceHoleKind},
+        known_attributes::{AttributePosition, ErrorAttribute, SyntaxAttribute, TestingAttribute},
+        metrics::FunctionMetrics,
+        program_info::*,
+        unique_map::UniqueMap,
+        *,
     },
-    shared::{known_attributes::TestingAttribute, program_info::*, unique_map::UniqueMap, *},
     FullyCompiledProgram,
 };
 use move_ir_types::location::*;
@@ -32,12 +41,22 @@ use std::{
 // Context
 //**************************************************************************************************
 
+/// The maximum number of macro call sites reported for a diagnostic raised during macro expansion
+const MACRO_EXPANSION_BACKTRACE_LIMIT: usize = 4;
+
 pub struct UseFunsScope {
     color: Option<Color>,
     count: usize,
     use_funs: ResolvedUseFuns,
 }
 
+/// The macro definitions of a module. The module's methods are shared by all of its macros, so
+/// they are kept once here instead of with each body, and only wrap a body when it is expanded
+pub struct ModuleMacros {
+    pub use_funs: N::UseFuns,
+    pub bodies: UniqueMap<FunctionName, (Loc, N::Sequence)>,
+}
+
 pub enum Constraint {
     AbilityConstraint {
         loc: Loc,
@@ -64,6 +83,7 @@ pub struct Local {
 pub struct MacroCall {
     pub module: ModuleIdent,
     pub function: FunctionName,
+    pub type_arguments: Vec<Type>,
     pub invocation: Loc,
     pub scope_color: Color,
 }
@@ -77,7 +97,7 @@ pub enum MacroExpansion {
 
 pub struct Context<'env> {
     pub modules: NamingProgramInfo,
-    macros: UniqueMap<ModuleIdent, UniqueMap<FunctionName, N::Sequence>>,
+    macros: UniqueMap<ModuleIdent, ModuleMacros>,
     pub env: &'env mut CompilationEnv,
 
     use_funs: Vec<UseFunsScope>,
@@ -85,9 +105,16 @@ pub struct Context<'env> {
     pub current_module: Option<ModuleIdent>,
     pub current_function: Option<FunctionName>,
     pub in_macro_function: bool,
+    /// The number of loop bodies the current expression is nested in
+    pub loop_depth: usize,
+    /// The location of an 'if' without an 'else' whose value is used, with where and how it is
+    /// used. Used to explain the error if its 'then' branch has a value
+    pub missing_else_use: Option<(Loc, Loc, &'static str)>,
     max_variable_color: RefCell<u16>,
     pub return_type: Option<Type>,
     locals: UniqueMap<Var, Local>,
+    /// The last consuming usage of each non-'copy' local in the current straight-line sequence
+    moved_locals: BTreeMap<N::Var_, Loc>,
 
     pub subst: Subst,
     pub constraints: Constraints,
@@ -102,12 +129,55 @@ pub struct Context<'env> {
     /// collects all used module members (functions and constants) but it's a superset of these in
     /// that it may contain other identifiers that do not in fact represent a function or a constant
     pub used_module_members: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    /// collects all structs named in a type that was instantiated, e.g. in a signature, a field, a
+    /// type annotation or argument, or a pack/unpack
+    pub used_module_types: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    /// like `used_module_members` for constants and `used_module_types`, but for usages in a
+    /// testing context, see `is_testing_context`. Kept separately so that items only used by
+    /// tests can be reported
+    pub test_used_module_members: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    pub test_used_module_types: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    /// The functions called from somewhere other than a function body of their own module, e.g.
+    /// from a constant initializer or from the body of a macro expanded in another module. These
+    /// calls are not part of the module's call graph, so the functions are treated as used when
+    /// reporting unused functions. Calls from a testing context are kept separately
+    pub external_function_calls: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    pub test_external_function_calls: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    /// The functions in whose bodies each macro, keyed by its module and name, was expanded.
+    /// Expansions outside of a function body are recorded as `None`
+    pub macro_expansions:
+        BTreeMap<(ModuleIdent_, Symbol), BTreeSet<Option<(ModuleIdent_, Symbol)>>>,
+    /// The macro expanded at each call site, see `TypingProgramInfo::macro_expansions`
+    pub macro_expansion_sites: BTreeMap<Loc, (ModuleIdent, FunctionName)>,
     /// Current macros being expanded
     pub macro_expansion: Vec<MacroExpansion>,
     /// Stack of items from `macro_expansion` pushed/popped when entering/leaving a lambda expansion
     /// This is to prevent accidentally thinking we are in a recursive call if a macro is used
     /// inside a lambda body
     pub lambda_expansion: Vec<Vec<MacroExpansion>>,
+    /// Metrics for the function being typed, only collected if `Flags::record_metrics` is set
+    pub metrics: Option<FunctionMetrics>,
+    /// The locations of the elements of each expression list, e.g. 'a' and 'b' in '(a, b)', keyed
+    /// by the location of the expression list
+    pub expression_list_elements: BTreeMap<Loc, Vec<Loc>>,
+    /// The location of each single local bound to an expression list, with the length of the list
+    pub expression_list_binds: BTreeMap<Loc, usize>,
+    /// The types of the successfully typed segments of dotted paths in the function being typed,
+    /// e.g. 'a' and 'a.b' in 'a.b.c'. Only collected if `Flags::record_type_locations` is set
+    pub dotted_segments: Vec<(Loc, Type)>,
+    /// The segment types of all functions typed so far, with type variables resolved
+    pub dotted_segment_types: HashMap<Loc, Type>,
+    /// Set while solving the constraints of a function body. The abilities already reported as
+    /// missing from each of the function's type parameters, each reported once at the declaration
+    /// of the type parameter
+    pub reported_tparam_abilities: Option<BTreeSet<(TParamID, Ability_)>>,
+}
+
+/// The index functions for a type, as found by `Context::find_index_methods_and_mark_used`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IndexMethods {
+    pub index: Option<(ModuleIdent, FunctionName)>,
+    pub index_mut: Option<(ModuleIdent, FunctionName)>,
 }
 
 pub struct ResolvedFunctionType {
@@ -116,6 +186,9 @@ pub struct ResolvedFunctionType {
     pub ty_args: Vec<Type>,
     pub params: Vec<(Var, Type)>,
     pub return_: Type,
+    /// For method calls resolved through an explicit 'use fun', the method name and the location
+    /// of the 'use fun'
+    pub use_fun: Option<(Name, Loc)>,
 }
 
 impl UseFunsScope {
@@ -169,25 +242,38 @@ impl<'env> Context<'env> {
             current_module: None,
             current_function: None,
             in_macro_function: false,
+            loop_depth: 0,
+            missing_else_use: None,
             max_variable_color: RefCell::new(0),
             return_type: None,
             constraints: vec![],
             locals: UniqueMap::new(),
+            moved_locals: BTreeMap::new(),
             modules: info,
             macros: UniqueMap::new(),
             named_block_map: BTreeMap::new(),
             env,
             new_friends: BTreeSet::new(),
             used_module_members: BTreeMap::new(),
+            used_module_types: BTreeMap::new(),
+            test_used_module_members: BTreeMap::new(),
+            test_used_module_types: BTreeMap::new(),
+            external_function_calls: BTreeMap::new(),
+            test_external_function_calls: BTreeMap::new(),
+            macro_expansions: BTreeMap::new(),
+            macro_expansion_sites: BTreeMap::new(),
             macro_expansion: vec![],
             lambda_expansion: vec![],
+            metrics: None,
+            expression_list_elements: BTreeMap::new(),
+            expression_list_binds: BTreeMap::new(),
+            dotted_segments: vec![],
+            dotted_segment_types: HashMap::new(),
+            reported_tparam_abilities: None,
         }
     }
 
-    pub fn set_macros(
-        &mut self,
-        macros: UniqueMap<ModuleIdent, UniqueMap<FunctionName, N::Sequence>>,
-    ) {
+    pub fn set_macros(&mut self, macros: UniqueMap<ModuleIdent, ModuleMacros>) {
         debug_assert!(self.macros.is_empty());
         self.macros = macros;
     }
@@ -207,6 +293,7 @@ impl<'env> Context<'env> {
             cur.count += 1;
             return;
         }
+        self.report_shadowed_use_funs(&new_scope, color);
         self.use_funs.push(UseFunsScope {
             count: 1,
             use_funs: new_scope,
@@ -214,6 +301,53 @@ impl<'env> Context<'env> {
         })
     }
 
+    /// Reports methods in the new scope that shadow a method of the same kind from an outer scope,
+    /// but for a different function. Since the innermost one always wins, this is not reported
+    /// as ambiguous when the method is called.
+    /// Scopes inside of macro expansions are not checked, as any shadowing is up to the macro
+    fn report_shadowed_use_funs(&mut self, new_scope: &ResolvedUseFuns, color: Color) {
+        if !self.macro_expansion.is_empty() {
+            return;
+        }
+        let mut diags = vec![];
+        for (tn, methods) in new_scope {
+            for (method, use_fun) in methods.key_cloned_iter() {
+                if use_fun.kind == UseFunKind::FunctionDeclaration {
+                    continue;
+                }
+                let shadowed = self
+                    .use_funs
+                    .iter()
+                    .rev()
+                    .filter(|scope| scope.color == Some(color))
+                    .filter_map(|scope| scope.use_funs.get(tn)?.get(&method))
+                    .find(|outer| outer.kind == use_fun.kind);
+                let Some(outer) = shadowed else {
+                    continue;
+                };
+                if outer.target_function == use_fun.target_function {
+                    continue;
+                }
+                let (target_m, target_f) = &use_fun.target_function;
+                let (outer_m, outer_f) = &outer.target_function;
+                let kind = use_fun.kind.description();
+                let msg = format!(
+                    "This {kind} for '{tn}.{method}' shadows the one from an outer scope. \
+                    Calls to '{method}' in this scope will resolve to '{target_m}::{target_f}'"
+                );
+                let outer_msg = format!("Instead of '{outer_m}::{outer_f}' from this {kind}");
+                diags.push(diag!(
+                    NameResolution::ShadowedUseFun,
+                    (use_fun.loc, msg),
+                    (outer.loc, outer_msg)
+                ));
+            }
+        }
+        for diag in diags {
+            self.add_diag(diag)
+        }
+    }
+
     pub fn pop_use_funs_scope(&mut self) -> N::UseFuns {
         let cur = self.use_funs.last_mut().unwrap();
         if cur.count > 1 {
@@ -239,18 +373,18 @@ impl<'env> Context<'env> {
                     UseFunKind::Explicit => {
                         let msg =
                             format!("Unused 'use fun' of '{tn}.{method}'. Consider removing it");
-                        self.env.add_diag(diag!(UnusedItem::Alias, (*loc, msg)))
+                        self.add_diag(diag!(UnusedItem::Alias, (*loc, msg)))
                     }
                     UseFunKind::UseAlias => {
                         let msg = format!("Unused 'use' of alias '{method}'. Consider removing it");
-                        self.env.add_diag(diag!(UnusedItem::Alias, (*loc, msg)))
+                        self.add_diag(diag!(UnusedItem::Alias, (*loc, msg)))
                     }
                     UseFunKind::FunctionDeclaration => {
                         let diag = ice!((
                             *loc,
                             "ICE fun declaration 'use' funs should never be added to 'use' funs"
                         ));
-                        self.env.add_diag(diag);
+                        self.add_diag(diag);
                     }
                 }
             }
@@ -262,29 +396,225 @@ impl<'env> Context<'env> {
         }
     }
 
+    /// Finds the index functions for `tn`, i.e. the methods in scope marked '#[syntax(index)]'. The
+    /// mutability of the first parameter determines whether it is the immutable or the mutable
+    /// index function. As with other methods, candidates in inner scopes take precedence, and the
+    /// global scope is considered last
+    pub fn find_index_methods_and_mark_used(&mut self, tn: &TypeName) -> IndexMethods {
+        let cur_color = self.use_funs.last().unwrap().color;
+        let modules = &self.modules;
+        let mut methods = IndexMethods::default();
+        let scopes = self
+            .use_funs
+            .iter_mut()
+            .rev()
+            .filter(|scope| scope.color.is_none() || scope.color == cur_color);
+        for scope in scopes {
+            let Some(use_funs) = scope.use_funs.get_mut(tn) else {
+                continue;
+            };
+            for (_, _, use_fun) in use_funs.iter_mut() {
+                let (m, f) = &use_fun.target_function;
+                let finfo = modules.function_info(m, f);
+                if !finfo.attributes.contains_key_(&SyntaxAttribute.into()) {
+                    continue;
+                }
+                let slot = match finfo.signature.parameters.first() {
+                    Some((_, _, sp!(_, Type_::Ref(false, _)))) => &mut methods.index,
+                    Some((_, _, sp!(_, Type_::Ref(true, _)))) => &mut methods.index_mut,
+                    // invalid index functions are reported where they are declared
+                    _ => continue,
+                };
+                if slot.is_none() {
+                    *slot = Some(use_fun.target_function);
+                    use_fun.used = true;
+                }
+            }
+        }
+        methods
+    }
+
+    /// Finds the method for `tn.method`. Candidates declared in the current scopes are chosen by
+    /// their `UseFunKind::precedence`, with ties going to the innermost scope. The global scope of
+    /// public methods is only considered if there are no local candidates.
+    /// If a local candidate of a different kind, and with a different target, loses to the chosen
+    /// candidate, a warning is emitted. Shadowing between candidates of the same kind is normal
+    /// lexical scoping and is not reported.
+    /// Returns the target function of the method, along with the location of the 'use fun' it was
+    /// resolved through if that 'use fun' was explicitly declared
     pub fn find_method_and_mark_used(
         &mut self,
+        loc: Loc,
         tn: &TypeName,
         method: Name,
-    ) -> Option<(ModuleIdent, FunctionName)> {
+    ) -> Option<((ModuleIdent, FunctionName), Option<Loc>)> {
         let cur_color = self.use_funs.last().unwrap().color;
-        self.use_funs.iter_mut().rev().find_map(|scope| {
-            // scope color is None for global scope, which is always in consideration
-            // otherwise, the color must match the current color. In practice, we are preventing
-            // macro scopes from interfering with each the scopes in which they are expanded
-            if scope.color.is_some() && scope.color != cur_color {
-                return None;
-            }
-            let use_fun = scope.use_funs.get_mut(tn)?.get_mut(&method)?;
+        // scope color is None for global scope, which is always in consideration
+        // otherwise, the color must match the current color. In practice, we are preventing
+        // macro scopes from interfering with each the scopes in which they are expanded
+        let mut candidates = self
+            .use_funs
+            .iter_mut()
+            .rev()
+            .filter(|scope| scope.color.is_some() && scope.color == cur_color)
+            .filter_map(|scope| scope.use_funs.get_mut(tn)?.get_mut(&method))
+            .collect::<Vec<_>>();
+        // `max_by_key` returns the last maximum, so reverse to keep the innermost on ties
+        let winner_idx = candidates
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, uf)| uf.kind.precedence())
+            .map(|(idx, _)| idx);
+        let Some(winner_idx) = winner_idx else {
+            let global = self.use_funs.first_mut().unwrap();
+            debug_assert!(global.color.is_none());
+            let use_fun = global.use_funs.get_mut(tn)?.get_mut(&method)?;
             use_fun.used = true;
-            Some(use_fun.target_function)
-        })
+            let explicit_loc = (use_fun.kind == UseFunKind::Explicit).then_some(use_fun.loc);
+            return Some((use_fun.target_function, explicit_loc));
+        };
+        candidates[winner_idx].used = true;
+        let winner = &*candidates[winner_idx];
+        let ambiguous = candidates
+            .iter()
+            .filter(|uf| uf.kind != winner.kind && uf.target_function != winner.target_function)
+            // already reported when the 'use fun' was declared
+            .filter(|uf| {
+                !(winner.kind == UseFunKind::Explicit && uf.kind == UseFunKind::FunctionDeclaration)
+            })
+            .map(|uf| (uf.loc, uf.kind, uf.target_function))
+            .collect::<Vec<_>>();
+        let (winner_loc, winner_kind, target) = (winner.loc, winner.kind, winner.target_function);
+        if !ambiguous.is_empty() {
+            let (target_m, target_f) = &target;
+            let msg = format!(
+                "Ambiguous method '{tn}.{method}'. \
+                Multiple functions are in scope for this method"
+            );
+            let winner_msg = format!(
+                "Resolved to '{target_m}::{target_f}' from this {}",
+                winner_kind.description()
+            );
+            let mut diag = diag!(
+                NameResolution::AmbiguousMethod,
+                (loc, msg),
+                (winner_loc, winner_msg)
+            );
+            for (other_loc, other_kind, (other_m, other_f)) in ambiguous {
+                let other_msg = format!(
+                    "Instead of '{other_m}::{other_f}' from this {}",
+                    other_kind.description()
+                );
+                diag.add_secondary_label((other_loc, other_msg));
+            }
+            diag.add_note(
+                "Method resolution prefers 'use fun' declarations, then 'use' aliases, then \
+                function declarations. Consider removing or renaming one of the declarations",
+            );
+            self.add_diag(diag);
+        }
+        let explicit_loc = (winner_kind == UseFunKind::Explicit).then_some(winner_loc);
+        Some((target, explicit_loc))
+    }
+
+    /// All methods that could be called on the type, used for suggestions on failed resolution:
+    /// the methods in scope, and functions in the defining module that take the type as their first
+    /// argument. Only methods whose functions are visible from the current module are included
+    fn method_names(&self, tn: &TypeName, defining_module: Option<&ModuleIdent>) -> Vec<Symbol> {
+        let cur_color = self.use_funs.last().unwrap().color;
+        let mut names = self
+            .use_funs
+            .iter()
+            .filter(|scope| scope.color.is_none() || scope.color == cur_color)
+            .filter_map(|scope| scope.use_funs.get(tn))
+            .flat_map(|methods| methods.key_cloned_iter())
+            .filter(|(_, use_fun)| {
+                let (m, f) = &use_fun.target_function;
+                self.is_visible_from_current_module(m, &self.function_info(m, f).visibility)
+            })
+            .map(|(name, _)| name.value)
+            .collect::<Vec<_>>();
+        if let Some(m) = defining_module {
+            let functions = &self.modules.module(m).functions;
+            for (f, finfo) in functions.key_cloned_iter() {
+                if takes_as_first_argument(finfo, tn)
+                    && self.is_visible_from_current_module(m, &finfo.visibility)
+                {
+                    names.push(f.value());
+                }
+            }
+        }
+        names
+    }
+
+    /// Functions that could be called directly in place of the method, used for suggestions when
+    /// the receiver has no methods (e.g. a type parameter): the targets of the 'use fun' aliases
+    /// in scope with this name, for any type, and the visible functions with this name
+    pub fn method_call_alternatives(&self, method: &Name) -> BTreeSet<(ModuleIdent, FunctionName)> {
+        let cur_color = self.use_funs.last().unwrap().color;
+        let mut alternatives = self
+            .use_funs
+            .iter()
+            .filter(|scope| scope.color.is_none() || scope.color == cur_color)
+            .flat_map(|scope| scope.use_funs.values())
+            .filter_map(|methods| methods.get(method))
+            .map(|use_fun| use_fun.target_function)
+            .collect::<BTreeSet<_>>();
+        for (m, minfo) in self.modules.modules.key_cloned_iter() {
+            let is_current_module = self.is_current_module(&m);
+            for (f, finfo) in minfo.functions.key_cloned_iter() {
+                let visible =
+                    is_current_module || !matches!(finfo.visibility, Visibility::Internal);
+                if visible && f.value() == method.value {
+                    alternatives.insert((m, f));
+                }
+            }
+        }
+        alternatives
     }
 
     /// true iff it is safe to expand,
     /// false with an error otherwise (e.g. a recursive expansion)
-    pub fn add_macro_expansion(&mut self, m: ModuleIdent, f: FunctionName, loc: Loc) -> bool {
+    pub fn add_macro_expansion(
+        &mut self,
+        m: ModuleIdent,
+        f: FunctionName,
+        type_args: &[Type],
+        loc: Loc,
+    ) -> bool {
         let current_call_color = self.current_call_color();
+        let type_args: Vec<Type> = type_args
+            .iter()
+            .map(|t| ready_tvars(&self.subst, t.clone()))
+            .collect();
+
+        let depth = self
+            .macro_expansion
+            .iter()
+            .filter(|mexp| matches!(mexp, MacroExpansion::Call(_)))
+            .count();
+        let max_depth = self.env.flags().max_macro_expansion_depth();
+        if depth >= max_depth {
+            let msg = format!(
+                "Macro expansion depth limit of {max_depth} reached when expanding '{m}::{f}'"
+            );
+            let mut diag = diag!(TypeSafety::CannotExpandMacro, (loc, msg));
+            let invocations = self
+                .macro_expansion
+                .iter()
+                .rev()
+                .filter_map(|mexp| match mexp {
+                    MacroExpansion::Call(c) => Some(c.invocation),
+                    MacroExpansion::Argument { .. } => None,
+                })
+                .take(MACRO_EXPANSION_BACKTRACE_LIMIT);
+            for invocation in invocations {
+                diag.add_secondary_label((invocation, "From this macro expansion"));
+            }
+            self.env.add_diag(diag);
+            return false;
+        }
 
         let mut prev_opt = None;
         for (idx, mexp) in self.macro_expansion.iter().enumerate().rev() {
@@ -315,6 +645,18 @@ impl<'env> Context<'env> {
         }
 
         if let Some(idx) = prev_opt {
+            let MacroExpansion::Call(prev) = &self.macro_expansion[idx] else {
+                unreachable!()
+            };
+            if prev
+                .type_arguments
+                .iter()
+                .zip(&type_args)
+                .any(|(prev_t, t)| contains_type(t, prev_t))
+            {
+                self.report_growing_macro_expansion(idx, m, f, &type_args, loc);
+                return false;
+            }
             let msg = format!(
                 "Recursive macro expansion. '{}::{}' cannot recursively expand itself",
                 m, f
@@ -341,6 +683,7 @@ impl<'env> Context<'env> {
                 .push(MacroExpansion::Call(Box::new(MacroCall {
                     module: m,
                     function: f,
+                    type_arguments: type_args,
                     invocation: loc,
                     scope_color: current_call_color,
                 })));
@@ -348,6 +691,42 @@ impl<'env> Context<'env> {
         }
     }
 
+    /// A macro re-expanding itself with a type argument that contains the type argument of the
+    /// previous expansion would expand forever, growing the type at each step
+    fn report_growing_macro_expansion(
+        &mut self,
+        prev_idx: usize,
+        m: ModuleIdent,
+        f: FunctionName,
+        type_args: &[Type],
+        loc: Loc,
+    ) {
+        let instantiation = |m: &ModuleIdent, f: &FunctionName, tys: &[Type]| {
+            let tys_str = format_comma(tys.iter().map(|t| error_format_nested(t, &self.subst)));
+            format!("'{m}::{f}<{tys_str}>'")
+        };
+        let msg = format!(
+            "Macro expansion does not terminate. A type argument of '{m}::{f}' grows at each \
+            expansion, here expanded as {}",
+            instantiation(&m, &f, type_args)
+        );
+        let mut diag = diag!(TypeSafety::CannotExpandMacro, (loc, msg));
+        let chain = self.macro_expansion[prev_idx..]
+            .iter()
+            .filter_map(|case| match case {
+                MacroExpansion::Call(c) => Some(c),
+                MacroExpansion::Argument { .. } => None,
+            });
+        for c in chain {
+            let msg = format!(
+                "Expanded as {}",
+                instantiation(&c.module, &c.function, &c.type_arguments)
+            );
+            diag.add_secondary_label((c.invocation, msg));
+        }
+        self.env.add_diag(diag);
+    }
+
     pub fn pop_macro_expansion(&mut self, loc: Loc, m: &ModuleIdent, f: &FunctionName) -> bool {
         let c = match self.macro_expansion.pop() {
             Some(MacroExpansion::Call(c)) => c,
@@ -356,7 +735,7 @@ impl<'env> Context<'env> {
                     loc,
                     "ICE macro expansion stack should have a call when leaving a macro expansion"
                 ));
-                self.env.add_diag(diag);
+                self.add_diag(diag);
                 return false;
             }
         };
@@ -370,23 +749,154 @@ impl<'env> Context<'env> {
         true
     }
 
-    pub fn maybe_enter_macro_argument(
+    /// Reports the diagnostic, labeling each macro call currently being expanded (innermost first)
+    /// so that errors in a macro body can be traced back to the call that triggered the expansion.
+    /// Calls that already contain the error, e.g. for an error in a lambda argument, are skipped
+    pub fn add_diag(&mut self, mut diag: Diagnostic) {
+        let primary_loc = diag.primary_loc();
+        let mut invocations = self
+            .macro_expansion
+            .iter()
+            .rev()
+            .filter_map(|mexp| match mexp {
+                MacroExpansion::Call(c) => Some(c.invocation),
+                MacroExpansion::Argument { .. } => None,
+            })
+            .filter(|invocation| !invocation.contains(primary_loc));
+        for invocation in invocations.by_ref().take(MACRO_EXPANSION_BACKTRACE_LIMIT) {
+            diag.add_secondary_label((invocation, "In this macro expansion"));
+        }
+        let remaining = invocations.count();
+        if remaining > 0 {
+            diag.add_note(format!("... and {remaining} more macro expansions"));
+        }
+        self.env.add_diag(diag)
+    }
+
+    /// Checks that the current package's edition supports the feature, reporting an error through
+    /// `add_diag` if it does not
+    pub fn check_feature(&mut self, feature: FeatureGate, loc: Loc) -> bool {
+        let edition = self.env.edition(self.current_package);
+        let supports_feature = edition.supports(feature);
+        if !supports_feature {
+            self.add_diag(create_feature_error(edition, feature, loc));
+        }
+        supports_feature
+    }
+
+    /// Warns on a usage of a deprecated module member. Usages inside of the defining module
+    /// (including the bodies of its macros) and inside of deprecated functions are allowed
+    pub fn check_deprecated_usage(
         &mut self,
-        from_macro_argument: Option<N::MacroArgument>,
-        color: Color,
+        loc: Loc,
+        position: AttributePosition,
+        m: &ModuleIdent,
+        member: Symbol,
     ) {
-        if from_macro_argument.is_some() {
-            self.macro_expansion
-                .push(MacroExpansion::Argument { scope_color: color })
+        let in_deprecated_function = self
+            .current_module
+            .as_ref()
+            .zip(self.current_function.as_ref())
+            .is_some_and(|(cur_m, cur_f)| {
+                self.modules
+                    .deprecation(cur_m, AttributePosition::Function, cur_f.0.value)
+                    .is_some()
+            });
+        let in_exempt_macro = self.macro_expansion.iter().any(|mexp| match mexp {
+            MacroExpansion::Call(c) => {
+                &c.module == m
+                    || self
+                        .modules
+                        .deprecation(&c.module, AttributePosition::Function, c.function.0.value)
+                        .is_some()
+            }
+            MacroExpansion::Argument { .. } => false,
+        });
+        if self.current_module.as_ref() == Some(m) || in_deprecated_function || in_exempt_macro {
+            return;
         }
+        let Some(deprecation) = self.modules.deprecation(m, position, member) else {
+            return;
+        };
+        let diag = deprecation.usage_diagnostic(loc, m, member);
+        self.add_diag(diag)
     }
 
-    pub fn maybe_exit_macro_argument(
+    /// Reports a usage of a '#[test_only]' module member, or of a member of a '#[test_only]'
+    /// module, outside of a testing context. These members are removed when not compiling for
+    /// testing, so the usage would only fail to resolve then. `member_test_only` is the location
+    /// of the member's own '#[test_only]' attribute, if it has one
+    pub fn check_test_only_usage(
         &mut self,
         loc: Loc,
-        from_macro_argument: Option<N::MacroArgument>,
+        position: AttributePosition,
+        m: &ModuleIdent,
+        member: Symbol,
+        member_test_only: Option<Loc>,
     ) {
-        if from_macro_argument.is_some() {
+        if self.is_testing_context() {
+            return;
+        }
+        let kind = match position {
+            AttributePosition::Struct => "struct",
+            AttributePosition::Constant => "constant",
+            _ => "function",
+        };
+        let (test_only_loc, declared_msg) = match member_test_only {
+            Some(attr_loc) => (attr_loc, format!("'{}' is declared test-only here", member)),
+            None => match self
+                .module_info(m)
+                .attributes
+                .get_loc_(&TestingAttribute::TestOnly.into())
+            {
+                Some(attr_loc) => (
+                    *attr_loc,
+                    format!("Module '{}' is declared test-only here", m),
+                ),
+                None => return,
+            },
+        };
+        let msg = format!(
+            "Invalid usage of test-only {} '{}::{}' outside of a test",
+            kind, m, member
+        );
+        let mut diag = diag!(
+            TypeSafety::InvalidTestOnlyUsage,
+            (loc, msg),
+            (test_only_loc, declared_msg)
+        );
+        diag.add_note(format!(
+            "Test-only items are removed when not compiling for testing. \
+            Consider marking this usage's function or module '#[{}]'",
+            TestingAttribute::TEST_ONLY
+        ));
+        self.add_diag(diag)
+    }
+
+    /// Returns true if an argument scope was entered, in which case it must be exited with
+    /// `maybe_exit_macro_argument`. An argument that was already substituted by an outer expansion
+    /// can be wrapped again when passed to an inner macro call, so a block for the argument scope
+    /// we are already in does not re-enter it
+    pub fn maybe_enter_macro_argument(
+        &mut self,
+        from_macro_argument: Option<N::MacroArgument>,
+        color: Color,
+    ) -> bool {
+        if from_macro_argument.is_none() {
+            return false;
+        }
+        if let Some(MacroExpansion::Argument { scope_color }) = self.macro_expansion.last() {
+            if *scope_color == color {
+                return false;
+            }
+        }
+        self.macro_expansion
+            .push(MacroExpansion::Argument { scope_color: color });
+        true
+    }
+
+    pub fn maybe_exit_macro_argument(&mut self, loc: Loc, entered: bool) {
+        if entered {
             match self.macro_expansion.pop() {
                 Some(MacroExpansion::Argument { .. }) => (),
                 _ => {
@@ -394,7 +904,7 @@ impl<'env> Context<'env> {
                         loc,
                         "ICE macro expansion stack should have a lambda when leaving a lambda",
                     ));
-                    self.env.add_diag(diag);
+                    self.add_diag(diag);
                 }
             }
         }
@@ -404,17 +914,74 @@ impl<'env> Context<'env> {
         self.use_funs.last().unwrap().color.unwrap()
     }
 
+    pub fn mark_type_used(&mut self, m: &ModuleIdent, n: &StructName) {
+        let used_module_types = if self.is_testing_context() {
+            &mut self.test_used_module_types
+        } else {
+            &mut self.used_module_types
+        };
+        used_module_types
+            .entry(m.value)
+            .or_default()
+            .insert(n.value());
+    }
+
+    /// Records that the macro `m::f` was expanded at `call_loc` in the body of the current
+    /// function. Nested expansions are inlined, so they are also attributed to the current function
+    pub fn record_macro_expansion(&mut self, call_loc: Loc, m: &ModuleIdent, f: &FunctionName) {
+        self.macro_expansion_sites.insert(call_loc, (*m, *f));
+        let expanded_in = self
+            .current_module
+            .as_ref()
+            .zip(self.current_function.as_ref())
+            .map(|(cur_m, cur_f)| (cur_m.value, cur_f.value()));
+        self.macro_expansions
+            .entry((m.value, f.value()))
+            .or_default()
+            .insert(expanded_in);
+    }
+
+    /// Records a call to `m::f` if it is made from outside of the function bodies of `m`, see
+    /// `external_function_calls`
+    pub fn record_external_function_call(&mut self, m: &ModuleIdent, f: &FunctionName) {
+        if self.current_function.is_some() && self.current_module.as_ref() == Some(m) {
+            return;
+        }
+        let calls = if self.is_testing_context() {
+            &mut self.test_external_function_calls
+        } else {
+            &mut self.external_function_calls
+        };
+        calls.entry(m.value).or_default().insert(f.value());
+    }
+
+    pub fn mark_constant_used(&mut self, m: &ModuleIdent, c: &ConstantName) {
+        let used_module_members = if self.is_testing_context() {
+            &mut self.test_used_module_members
+        } else {
+            &mut self.used_module_members
+        };
+        used_module_members
+            .entry(m.value)
+            .or_default()
+            .insert(c.value());
+    }
+
     pub fn reset_for_module_item(&mut self) {
         self.named_block_map = BTreeMap::new();
         self.return_type = None;
         self.locals = UniqueMap::new();
+        self.moved_locals = BTreeMap::new();
         self.subst = Subst::empty();
         self.constraints = Constraints::new();
         self.current_function = None;
         self.in_macro_function = false;
+        self.loop_depth = 0;
         self.max_variable_color = RefCell::new(0);
         self.macro_expansion = vec![];
         self.lambda_expansion = vec![];
+        self.metrics = None;
+        self.dotted_segments = vec![];
     }
 
     pub fn error_type(&mut self, loc: Loc) -> Type {
@@ -461,6 +1028,62 @@ impl<'env> Context<'env> {
             .push(Constraint::SingleTypeConstraint(loc, msg.into(), t))
     }
 
+    /// Records the type `ty`, or the type it references, reported as uninferred, if
+    /// `Flags::record_inference_holes` is set. The constraints on its type variable that are not
+    /// yet solved are recorded with it
+    pub fn record_inference_hole(&mut self, loc: Loc, kind: InferenceHoleKind, ty: &Type) {
+        use Constraint as C;
+        if !self.env.flags().record_inference_holes() {
+            return;
+        }
+        let tvar = match ready_tvars(&self.subst, ty.clone()).value {
+            Type_::Var(tvar) => Some(tvar),
+            Type_::Ref(_, inner) => match inner.value {
+                Type_::Var(tvar) => Some(tvar),
+                _ => None,
+            },
+            _ => None,
+        };
+        let mut numeric = tvar.is_some_and(|tvar| self.subst.is_num_var(tvar));
+        let mut abilities = AbilitySet::empty();
+        for constraint in &self.constraints {
+            let constrained = match constraint {
+                C::AbilityConstraint { ty, .. }
+                | C::NumericConstraint(_, _, ty)
+                | C::BitsConstraint(_, _, ty)
+                | C::OrderedConstraint(_, _, ty)
+                | C::BaseTypeConstraint(_, _, ty)
+                | C::SingleTypeConstraint(_, _, ty) => ty,
+            };
+            let same_tvar = matches!(
+                ready_tvars(&self.subst, constrained.clone()).value,
+                Type_::Var(t) if Some(t) == tvar
+            );
+            if !same_tvar {
+                continue;
+            }
+            match constraint {
+                C::AbilityConstraint { constraints, .. } => {
+                    abilities = abilities.union(constraints);
+                }
+                // only integers can be compared with '<' and similar
+                C::NumericConstraint(..) | C::BitsConstraint(..) | C::OrderedConstraint(..) => {
+                    numeric = true;
+                }
+                C::BaseTypeConstraint(..) | C::SingleTypeConstraint(..) => (),
+            }
+        }
+        let hole = InferenceHole {
+            loc,
+            kind,
+            tvar,
+            numeric,
+            abilities,
+            function: self.current_module.zip(self.current_function),
+        };
+        self.env.add_inference_hole(hole);
+    }
+
     pub fn add_numeric_constraint(&mut self, loc: Loc, op: &'static str, t: Type) {
         self.constraints
             .push(Constraint::NumericConstraint(loc, op, t))
@@ -492,7 +1115,7 @@ impl<'env> Context<'env> {
     pub fn get_local_type(&mut self, var: &Var) -> Type {
         if !self.locals.contains_key(var) {
             let msg = format!("ICE unbound {var:?}. Should have failed in naming");
-            self.env.add_diag(ice!((var.loc, msg)));
+            self.add_diag(ice!((var.loc, msg)));
             return self.error_type(var.loc);
         }
 
@@ -502,7 +1125,7 @@ impl<'env> Context<'env> {
     pub fn mark_mutable_usage(&mut self, loc: Loc, var: &Var) -> (Loc, Mutability) {
         if !self.locals.contains_key(var) {
             let msg = format!("ICE unbound {var:?}. Should have failed in naming");
-            self.env.add_diag(ice!((loc, msg)));
+            self.add_diag(ice!((loc, msg)));
             return (loc, Mutability::None);
         }
 
@@ -513,6 +1136,22 @@ impl<'env> Context<'env> {
         (decl_loc, local.mut_)
     }
 
+    /// Records a consuming usage of `var` at `loc`. Returns the location of the previous consuming
+    /// usage, if `var` was already moved earlier in the current straight-line sequence
+    pub fn record_local_move(&mut self, var: &Var, loc: Loc) -> Option<Loc> {
+        self.moved_locals.insert(var.value, loc)
+    }
+
+    /// Forgets any earlier move of `var`, e.g. after it is assigned a new value
+    pub fn forget_local_move(&mut self, var: &Var) {
+        self.moved_locals.remove(&var.value);
+    }
+
+    /// Forgets all earlier moves, e.g. when entering or leaving a branch or loop
+    pub fn forget_local_moves(&mut self) {
+        self.moved_locals.clear();
+    }
+
     pub fn take_locals(&mut self) -> UniqueMap<Var, Local> {
         std::mem::take(&mut self.locals)
     }
@@ -548,6 +1187,18 @@ impl<'env> Context<'env> {
         })
     }
 
+    /// true iff a function of module `m` with this visibility can be called from the current
+    /// module, ignoring the visibility of test functions in a testing context
+    fn is_visible_from_current_module(&self, m: &ModuleIdent, visibility: &Visibility) -> bool {
+        self.is_current_module(m)
+            || match visibility {
+                Visibility::Public(_) => true,
+                Visibility::Package(_) => self.current_module_shares_package_and_address(m),
+                Visibility::Friend(_) => self.current_module_is_a_friend_of(m),
+                Visibility::Internal => false,
+            }
+    }
+
     fn current_module_is_a_friend_of(&self, m: &ModuleIdent) -> bool {
         match &self.current_module {
             None => false,
@@ -559,7 +1210,7 @@ impl<'env> Context<'env> {
     }
 
     /// current_module.is_test_only || current_function.is_test_only || current_function.is_test
-    fn is_testing_context(&self) -> bool {
+    pub fn is_testing_context(&self) -> bool {
         self.current_module.as_ref().is_some_and(|m| {
             let minfo = self.module_info(m);
             let is_test_only = minfo.attributes.is_test_or_test_only();
@@ -587,6 +1238,11 @@ impl<'env> Context<'env> {
         self.modules.struct_declared_loc(m, n)
     }
 
+    /// Returns the location of the struct's '#[test_only]' attribute, if it has one
+    pub fn struct_test_only(&self, m: &ModuleIdent, n: &StructName) -> Option<Loc> {
+        self.struct_definition(m, n).test_only
+    }
+
     pub fn struct_tparams(&self, m: &ModuleIdent, n: &StructName) -> &Vec<StructTypeParameter> {
         self.modules.struct_type_parameters(m, n)
     }
@@ -595,8 +1251,15 @@ impl<'env> Context<'env> {
         self.modules.function_info(m, n)
     }
 
-    pub fn macro_body(&self, m: &ModuleIdent, n: &FunctionName) -> Option<&N::Sequence> {
-        self.macros.get(m)?.get(n)
+    /// Returns the module's methods along with the location and body of the macro
+    pub fn macro_body(
+        &self,
+        m: &ModuleIdent,
+        n: &FunctionName,
+    ) -> Option<(&N::UseFuns, Loc, &N::Sequence)> {
+        let ModuleMacros { use_funs, bodies } = self.macros.get(m)?;
+        let (loc, body) = bodies.get(n)?;
+        Some((use_funs, *loc, body))
     }
 
     fn constant_info(&mut self, m: &ModuleIdent, n: &ConstantName) -> &ConstantInfo {
@@ -604,6 +1267,12 @@ impl<'env> Context<'env> {
         constants.get(n).expect("ICE should have failed in naming")
     }
 
+    /// Returns true if the constant is annotated with '#[error]'
+    pub fn is_error_constant(&mut self, m: &ModuleIdent, n: &ConstantName) -> bool {
+        let attributes = &self.constant_info(m, n).attributes;
+        attributes.contains_key_(&ErrorAttribute.into())
+    }
+
     // pass in a location for a better error location
     pub fn named_block_type(&mut self, name: BlockLabel, loc: Loc) -> Type {
         if let Some(ty) = self.named_block_map.get(&name) {
@@ -646,6 +1315,26 @@ impl<'env> Context<'env> {
 pub struct Subst {
     tvars: HashMap<TVar, Type>,
     num_vars: HashMap<TVar, Loc>,
+    // the changes made since the oldest open snapshot, most recent last, so they can be undone.
+    // Nothing is recorded while there are no open snapshots
+    undo_log: Vec<SubstUndo>,
+    open_snapshots: usize,
+}
+
+#[derive(Clone, Debug)]
+enum SubstUndo {
+    // the variable was bound, replacing the previous binding (if any)
+    Bind(TVar, Option<Type>),
+    // the variable was marked as a numeric variable
+    NumVar(TVar),
+}
+
+/// A point in the history of a `Subst`, to which it can be rolled back. Every snapshot must be
+/// either committed or rolled back, in the reverse order in which they were taken
+#[must_use]
+#[derive(Debug)]
+pub struct SubstSnapshot {
+    undo_len: usize,
 }
 
 impl Subst {
@@ -653,11 +1342,14 @@ impl Subst {
         Self {
             tvars: HashMap::new(),
             num_vars: HashMap::new(),
+            undo_log: vec![],
+            open_snapshots: 0,
         }
     }
 
     pub fn insert(&mut self, tvar: TVar, bt: Type) {
-        self.tvars.insert(tvar, bt);
+        let prev = self.tvars.insert(tvar, bt);
+        self.record(SubstUndo::Bind(tvar, prev));
     }
 
     pub fn get(&self, tvar: TVar) -> Option<&Type> {
@@ -667,11 +1359,15 @@ impl Subst {
     pub fn new_num_var(&mut self, loc: Loc) -> TVar {
         let tvar = TVar::next();
         assert!(self.num_vars.insert(tvar, loc).is_none());
+        self.record(SubstUndo::NumVar(tvar));
         tvar
     }
 
     pub fn set_num_var(&mut self, tvar: TVar, loc: Loc) {
-        self.num_vars.entry(tvar).or_insert(loc);
+        if !self.num_vars.contains_key(&tvar) {
+            self.num_vars.insert(tvar, loc);
+            self.record(SubstUndo::NumVar(tvar));
+        }
         if let Some(sp!(_, Type_::Var(next))) = self.get(tvar) {
             let next = *next;
             self.set_num_var(next, loc)
@@ -681,11 +1377,63 @@ impl Subst {
     pub fn is_num_var(&self, tvar: TVar) -> bool {
         self.num_vars.contains_key(&tvar)
     }
+
+    /// Starts recording changes, so that they can be undone with `rollback`. This avoids cloning
+    /// the substitution when trying to unify types that might not be compatible
+    pub fn snapshot(&mut self) -> SubstSnapshot {
+        self.open_snapshots += 1;
+        SubstSnapshot {
+            undo_len: self.undo_log.len(),
+        }
+    }
+
+    /// Undoes every change made since the snapshot was taken
+    pub fn rollback(&mut self, snapshot: SubstSnapshot) {
+        let SubstSnapshot { undo_len } = snapshot;
+        assert!(self.open_snapshots > 0, "ICE rollback without a snapshot");
+        while self.undo_log.len() > undo_len {
+            match self.undo_log.pop().unwrap() {
+                SubstUndo::Bind(tvar, None) => {
+                    self.tvars.remove(&tvar);
+                }
+                SubstUndo::Bind(tvar, Some(prev)) => {
+                    self.tvars.insert(tvar, prev);
+                }
+                SubstUndo::NumVar(tvar) => {
+                    self.num_vars.remove(&tvar);
+                }
+            }
+        }
+        self.close_snapshot()
+    }
+
+    /// Keeps every change made since the snapshot was taken
+    pub fn commit(&mut self, snapshot: SubstSnapshot) {
+        let SubstSnapshot { undo_len } = snapshot;
+        assert!(self.open_snapshots > 0, "ICE commit without a snapshot");
+        assert!(undo_len <= self.undo_log.len());
+        self.close_snapshot()
+    }
+
+    fn close_snapshot(&mut self) {
+        self.open_snapshots -= 1;
+        if self.open_snapshots == 0 {
+            self.undo_log.clear()
+        }
+    }
+
+    fn record(&mut self, undo: SubstUndo) {
+        if self.open_snapshots > 0 {
+            self.undo_log.push(undo)
+        }
+    }
 }
 
 impl ast_debug::AstDebug for Subst {
     fn ast_debug(&self, w: &mut ast_debug::AstWriter) {
-        let Subst { tvars, num_vars } = self;
+        let Subst {
+            tvars, num_vars, ..
+        } = self;
 
         w.write("tvars:");
         w.indent(4, |w| {
@@ -731,7 +1479,7 @@ fn error_format_impl(sp!(_, b_): &Type, subst: &Subst, nested: bool) -> String {
 fn error_format_impl_(b_: &Type_, subst: &Subst, nested: bool) -> String {
     use Type_::*;
     let res = match b_ {
-        UnresolvedError | Anything => "_".to_string(),
+        UnresolvedError | Anything | Hole => "_".to_string(),
         Unit => "()".to_string(),
         Var(id) => {
             let last_id = forward_tvar(subst, *id);
@@ -794,7 +1542,7 @@ pub fn infer_abilities<const INFO_PASS: bool>(
         T::Unit => AbilitySet::collection(loc),
         T::Ref(_, _) => AbilitySet::references(loc),
         T::Var(_) => unreachable!("ICE unfold_type failed, which is impossible"),
-        T::UnresolvedError | T::Anything => AbilitySet::all(loc),
+        T::UnresolvedError | T::Anything | T::Hole => AbilitySet::all(loc),
         T::Param(TParam { abilities, .. }) | T::Apply(Some(abilities), _, _) => abilities,
         T::Apply(None, n, ty_args) => {
             let (declared_abilities, ty_args) = match &n.value {
@@ -841,10 +1589,10 @@ fn debug_abilities_info(context: &mut Context, ty: &Type) -> (Option<Loc>, Abili
                 loc,
                 "ICE did not call unfold_type before debug_abiliites_info"
             ));
-            context.env.add_diag(diag);
+            context.add_diag(diag);
             (None, AbilitySet::all(loc), vec![])
         }
-        T::UnresolvedError | T::Anything => (None, AbilitySet::all(loc), vec![]),
+        T::UnresolvedError | T::Anything | T::Hole => (None, AbilitySet::all(loc), vec![]),
         T::Param(TParam {
             abilities,
             user_specified_name,
@@ -885,6 +1633,10 @@ pub fn make_struct_type(
     n: &StructName,
     ty_args_opt: Option<Vec<Type>>,
 ) -> (Type, Vec<Type>) {
+    context.check_deprecated_usage(loc, AttributePosition::Struct, m, n.0.value);
+    let test_only = context.struct_test_only(m, n);
+    context.check_test_only_usage(loc, AttributePosition::Struct, m, n.0.value, test_only);
+    context.mark_type_used(m, n);
     let tn = sp(loc, TypeName_::ModuleType(*m, *n));
     let sdef = context.struct_definition(m, n);
     match ty_args_opt {
@@ -946,11 +1698,10 @@ pub fn make_field_types(
     );
     match &sdef.fields {
         N::StructFields::Native(loc) => N::StructFields::Native(*loc),
-        N::StructFields::Defined(m) => {
-            N::StructFields::Defined(m.ref_map(|_, (idx, field_ty)| {
-                (*idx, subst_tparams(tparam_subst, field_ty.clone()))
-            }))
-        }
+        N::StructFields::Defined(positional, m) => N::StructFields::Defined(
+            *positional,
+            m.ref_map(|_, (idx, field_ty)| (*idx, subst_tparams(tparam_subst, field_ty.clone()))),
+        ),
     }
 }
 
@@ -964,22 +1715,41 @@ pub fn make_field_type(
     field: &Field,
 ) -> Type {
     let sdef = context.struct_definition(m, n);
-    let fields_map = match &sdef.fields {
+    let (positional, fields_map) = match &sdef.fields {
         N::StructFields::Native(nloc) => {
             let nloc = *nloc;
             let msg = format!("Unbound field '{}' for native struct '{}::{}'", field, m, n);
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 NameResolution::UnboundField,
                 (loc, msg),
                 (nloc, "Struct declared 'native' here")
             ));
             return context.error_type(loc);
         }
-        N::StructFields::Defined(m) => m,
+        N::StructFields::Defined(positional, m) => (*positional, m),
     };
     match fields_map.get(field).cloned() {
+        None if positional && field.value().as_str().parse::<usize>().is_ok() => {
+            let num_fields = fields_map.len();
+            let msg = format!(
+                "Invalid positional field access. Struct '{}::{}' has {} positional field{} but \
+                 index {} was requested",
+                m,
+                n,
+                num_fields,
+                if num_fields == 1 { "" } else { "s" },
+                field
+            );
+            let decl_msg = format!("Struct '{}' declared here", n);
+            context.add_diag(diag!(
+                NameResolution::UnboundField,
+                (loc, msg),
+                (context.struct_declared_loc(m, n), decl_msg),
+            ));
+            context.error_type(loc)
+        }
         None => {
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 NameResolution::UnboundField,
                 (loc, format!("Unbound field '{}' in '{}::{}'", field, m, n)),
             ));
@@ -1009,20 +1779,26 @@ pub fn make_constant_type(
     m: &ModuleIdent,
     c: &ConstantName,
 ) -> Type {
+    context.check_deprecated_usage(loc, AttributePosition::Constant, m, c.0.value);
+    let test_only = context.constant_info(m, c).test_only;
+    context.check_test_only_usage(loc, AttributePosition::Constant, m, c.0.value, test_only);
     let in_current_module = Some(m) == context.current_module.as_ref();
     let (defined_loc, signature) = {
         let ConstantInfo {
             attributes: _,
+            test_only: _,
             defined_loc,
             signature,
         } = context.constant_info(m, c);
         (*defined_loc, signature.clone())
     };
+    // Accesses written outside of the constant's module are rejected during naming, so this only
+    // catches constants used in a macro body that was expanded in another module
     if !in_current_module {
         let msg = format!("Invalid access of '{}::{}'", m, c);
-        let internal_msg = "Constants are internal to their module, and cannot can be accessed \
-                            outside of their module";
-        context.env.add_diag(diag!(
+        let internal_msg = "Constants are internal to their module. Consider exposing a \
+                            public function returning the value";
+        context.add_diag(diag!(
             TypeSafety::Visibility,
             (loc, msg),
             (defined_loc, internal_msg)
@@ -1036,6 +1812,19 @@ pub fn make_constant_type(
 // Functions
 //**************************************************************************************************
 
+/// true iff the first parameter of the function is the type, either by reference or by value,
+/// i.e. if a function declaration would implicitly provide a method for the type
+fn takes_as_first_argument(finfo: &FunctionInfo, tn: &TypeName) -> bool {
+    let Some((_, _, first_ty)) = finfo.signature.parameters.first() else {
+        return false;
+    };
+    let first_ty = match &first_ty.value {
+        Type_::Ref(_, inner) => inner,
+        _ => first_ty,
+    };
+    matches!(&first_ty.value, Type_::Apply(_, first_tn, _) if first_tn == tn)
+}
+
 pub fn make_method_call_type(
     context: &mut Context,
     loc: Loc,
@@ -1044,9 +1833,9 @@ pub fn make_method_call_type(
     method: Name,
     ty_args_opt: Option<Vec<Type>>,
 ) -> Option<(ModuleIdent, FunctionName, ResolvedFunctionType)> {
-    let target_function_opt = context.find_method_and_mark_used(tn, method);
+    let target_function_opt = context.find_method_and_mark_used(loc, tn, method);
     // try to find a function in the defining module for errors
-    let Some((target_m, target_f)) = target_function_opt else {
+    let Some(((target_m, target_f), use_fun_loc)) = target_function_opt else {
         let lhs_ty_str = error_format_nested(lhs_ty, &context.subst);
         let defining_module = match &tn.value {
             TypeName_::Multiple(_) => {
@@ -1054,7 +1843,7 @@ pub fn make_method_call_type(
                     loc,
                     format!("ICE method on tuple type {}", debug_display!(tn))
                 ));
-                context.env.add_diag(diag);
+                context.add_diag(diag);
                 return None;
             }
             TypeName_::Builtin(sp!(_, bt_)) => context.env.primitive_definer(*bt_),
@@ -1068,8 +1857,30 @@ pub fn make_method_call_type(
                 .get(&FunctionName(method))?;
             Some((m, finfo))
         });
-        // if we found a function with the method name, it must have the wrong type
-        if let Some((m, finfo)) = finfo_opt {
+        // if implicit methods are disabled, the function may have been the method
+        if let Some((m, finfo)) = finfo_opt.filter(|(_, finfo)| {
+            context.env.flags().no_implicit_methods() && takes_as_first_argument(finfo, tn)
+        }) {
+            let msg = format!(
+                "Invalid method call. \
+                No known method '{method}' on type '{lhs_ty_str}'"
+            );
+            let fmsg = format!(
+                "The function '{m}::{method}' would be the method '{tn}.{method}', but \
+                implicit methods from function declarations are disabled in this build"
+            );
+            let mut diag = diag!(
+                TypeSafety::InvalidMethodCall,
+                (loc, msg),
+                (finfo.defined_loc, fmsg)
+            );
+            diag.add_note(format!(
+                "Declare the method explicitly to call it with '.', \
+                e.g. 'use fun {m}::{method} as {tn}.{method};'"
+            ));
+            context.add_diag(diag);
+        } else if let Some((m, finfo)) = finfo_opt {
+            // if we found a function with the method name, it must have the wrong type
             let (first_ty_loc, first_ty) = match finfo
                 .signature
                 .parameters
@@ -1091,7 +1902,7 @@ pub fn make_method_call_type(
                 No known method '{method}' on type '{lhs_ty_str}'"
             );
             let fmsg = format!("The function '{m}::{method}' exists, {arg_msg}");
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::InvalidMethodCall,
                 (loc, msg),
                 (first_ty_loc, fmsg)
@@ -1109,16 +1920,23 @@ pub fn make_method_call_type(
             };
             let fmsg =
                 format!("No local 'use fun' alias was found for '{lhs_ty_str}.{method}'{decl_msg}");
-            context.env.add_diag(diag!(
+            let mut diag = diag!(
                 TypeSafety::InvalidMethodCall,
                 (loc, msg),
                 (method.loc, fmsg)
-            ));
+            );
+            let method_names = context.method_names(tn, defining_module);
+            let method_names = method_names.iter().map(|name| name.as_str());
+            for similar in similar_names(method.value.as_str(), method_names) {
+                diag.add_secondary_label((method.loc, format!("Did you mean '{similar}'?")));
+            }
+            context.add_diag(diag);
         }
         return None;
     };
 
-    let function_ty = make_function_type(context, loc, &target_m, &target_f, ty_args_opt);
+    let mut function_ty = make_function_type(context, loc, &target_m, &target_f, ty_args_opt);
+    function_ty.use_fun = use_fun_loc.map(|use_fun_loc| (method, use_fun_loc));
 
     Some((target_m, target_f, function_ty))
 }
@@ -1130,6 +1948,9 @@ pub fn make_function_type(
     f: &FunctionName,
     ty_args_opt: Option<Vec<Type>>,
 ) -> ResolvedFunctionType {
+    context.check_deprecated_usage(loc, AttributePosition::Function, m, f.0.value);
+    let test_only = context.function_info(m, f).test_only;
+    context.check_test_only_usage(loc, AttributePosition::Function, m, f.0.value, test_only);
     let in_current_module = match &context.current_module {
         Some(current) => m == current,
         None => false,
@@ -1264,6 +2085,7 @@ pub fn make_function_type(
         ty_args,
         params,
         return_: return_ty,
+        use_fun: None,
     }
 }
 
@@ -1321,17 +2143,22 @@ fn visibility_error(
             diag.add_secondary_label((test_loc, test_msg))
         }
     }
-    context.env.add_diag(diag)
+    context.add_diag(diag)
 }
 
+/// Checks the number of arguments given against the parameters of the call target. Each
+/// parameter is given by its name (if it has one) and type, and is used to describe any missing
+/// arguments. Any extra arguments are labeled by their location in `arg_locs`.
 pub fn check_call_arity<S: std::fmt::Display, F: Fn() -> S>(
     context: &mut Context,
     loc: Loc,
     msg: F,
-    arity: usize,
+    params: &[(Option<Symbol>, Type)],
     argloc: Loc,
-    given_len: usize,
+    arg_locs: &[Loc],
 ) {
+    let arity = params.len();
+    let given_len = arg_locs.len();
     if given_len == arity {
         return;
     }
@@ -1346,11 +2173,25 @@ pub fn check_call_arity<S: std::fmt::Display, F: Fn() -> S>(
         arity,
         given_len
     );
-    context.env.add_diag(diag!(
+    let mut diag = diag!(
         code,
         (loc, cmsg),
         (argloc, format!("Found {} argument(s) here", given_len)),
-    ));
+    );
+    for (idx, (name, ty)) in params.iter().enumerate().skip(given_len) {
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => idx.to_string(),
+        };
+        let ty = error_format(ty, &context.subst);
+        diag.add_note(format!(
+            "Missing an argument for parameter '{name}' of type {ty}"
+        ));
+    }
+    for extra_loc in arg_locs.iter().skip(arity) {
+        diag.add_secondary_label((*extra_loc, "Unexpected argument"));
+    }
+    context.add_diag(diag);
 }
 
 //**************************************************************************************************
@@ -1360,20 +2201,21 @@ pub fn check_call_arity<S: std::fmt::Display, F: Fn() -> S>(
 pub fn solve_constraints(context: &mut Context) {
     use BuiltinTypeName_ as BT;
     let num_vars = context.subst.num_vars.clone();
-    let mut subst = std::mem::replace(&mut context.subst, Subst::empty());
+    let subst = &mut context.subst;
     for (num_var, loc) in num_vars {
         let tvar = sp(loc, Type_::Var(num_var));
-        match unfold_type(&subst, tvar.clone()).value {
+        match unfold_type(subst, tvar.clone()).value {
             Type_::UnresolvedError | Type_::Anything => {
-                let next_subst = join(subst, &Type_::u64(loc), &tvar).unwrap().0;
-                subst = next_subst;
+                join(subst, &Type_::u64(loc), &tvar).unwrap();
             }
             _ => (),
         }
     }
-    context.subst = subst;
 
     let constraints = std::mem::take(&mut context.constraints);
+    if let Some(metrics) = &mut context.metrics {
+        metrics.max_constraints = std::cmp::max(metrics.max_constraints, constraints.len());
+    }
     for constraint in constraints {
         match constraint {
             Constraint::AbilityConstraint {
@@ -1421,6 +2263,21 @@ fn solve_ability_constraint(
             Some(s) => s.clone(),
             None => format!("'{}' constraint not satisifed", constraint),
         };
+        if let (Type_::Param(tp), Some(reported)) =
+            (&ty.value, &mut context.reported_tparam_abilities)
+        {
+            if reported.insert((tp.id, constraint.value)) {
+                let diag = tparam_ability_not_declared(
+                    tp,
+                    constraint,
+                    loc,
+                    constraint_msg,
+                    given_msg_opt.is_none(),
+                );
+                context.add_diag(diag)
+            }
+            continue;
+        }
         let mut diag = diag!(AbilitySafety::Constraint, (loc, constraint_msg));
         ability_not_satisfied_tips(
             &context.subst,
@@ -1434,6 +2291,9 @@ fn solve_ability_constraint(
                 (ty_arg, abilities)
             }),
         );
+        if declared_abilities.has_ability(&constraint) {
+            add_ability_derivation_notes(context, &mut diag, constraint.value, &ty);
+        }
 
         // is none if it is from a user constraint and not a part of the type system
         if given_msg_opt.is_none() {
@@ -1442,8 +2302,51 @@ fn solve_ability_constraint(
                 format!("'{}' constraint declared here", constraint),
             ));
         }
-        context.env.add_diag(diag)
+        context.add_diag(diag)
+    }
+}
+
+/// The body of a function requires an ability that one of its type parameters was not declared
+/// with. The error is reported at the declaration of the type parameter, as no instantiation of the
+/// function could satisfy it
+fn tparam_ability_not_declared(
+    tp: &TParam,
+    constraint: Ability,
+    loc: Loc,
+    constraint_msg: String,
+    is_user_constraint: bool,
+) -> Diagnostic {
+    let tp_name = &tp.user_specified_name;
+    let declared = if tp.abilities.is_empty() {
+        "without any abilities".to_owned()
+    } else {
+        let abilities = tp
+            .abilities
+            .iter()
+            .map(|a| a.value.to_string())
+            .collect::<Vec<_>>();
+        format!("with '{}'", abilities.join(" + "))
+    };
+    let msg = format!(
+        "'{tp_name}' is declared {declared} here but the body requires '{}'",
+        constraint.value
+    );
+    let mut diag = diag!(
+        AbilitySafety::Constraint,
+        (tp_name.loc, msg),
+        (loc, constraint_msg)
+    );
+    if is_user_constraint {
+        diag.add_secondary_label((
+            constraint.loc,
+            format!("'{}' constraint declared here", constraint),
+        ));
     }
+    diag.add_note(format!(
+        "Consider adding the '{}' ability to the constraints of '{tp_name}'",
+        constraint.value
+    ));
+    diag
 }
 
 pub fn ability_not_satisfied_tips<'a>(
@@ -1500,6 +2403,150 @@ pub fn ability_not_satisfied_tips<'a>(
     }
 }
 
+/// The maximum number of steps shown when explaining why a type is missing an ability
+const ABILITY_DERIVATION_MAX_STEPS: usize = 5;
+
+/// Adds notes explaining why `ty`, which was declared with the ability `constraint`, does not have
+/// it. The requirement is followed through the fields and type arguments of `ty`, down to the type
+/// that does not have the ability. Nothing is added if that type is an immediate type argument of
+/// `ty`, as it is already labeled by `ability_not_satisfied_tips`.
+fn add_ability_derivation_notes(
+    context: &mut Context,
+    diag: &mut Diagnostic,
+    constraint: Ability_,
+    ty: &Type,
+) {
+    let mut steps = vec![];
+    let mut seen = BTreeSet::new();
+    let mut cur = ready_tvars(&context.subst, ty.clone());
+    let mut ability = constraint;
+    let missing = loop {
+        cur = unfold_type(&context.subst, cur);
+        let (declared_loc_opt, declared_abilities, ty_args) = debug_abilities_info(context, &cur);
+        if !declared_abilities.has_ability_(ability) {
+            break Some((cur, ability, declared_loc_opt));
+        }
+        if steps.len() >= ABILITY_DERIVATION_MAX_STEPS {
+            break None;
+        }
+        let requirement = ability.requires();
+        let Some((idx, ty_arg)) =
+            non_phantom_ty_args(context, &cur, &ty_args).find(|(_, ty_arg)| {
+                let abilities =
+                    infer_abilities(&context.modules, &context.subst, (*ty_arg).clone());
+                !abilities.has_ability_(requirement)
+            })
+        else {
+            break None;
+        };
+        let ty_arg = ty_arg.clone();
+        let cur_str = error_format(&cur, &context.subst);
+        let (step, next) = match field_using_ty_arg(context, &cur, &ty_args, idx) {
+            Some((field, field_ty)) => {
+                let field_ty_str = error_format(&field_ty, &context.subst);
+                let step = format!(
+                    "{cur_str} requires '{requirement}' for field '{field}', which has type \
+                    {field_ty_str}"
+                );
+                (step, field_ty)
+            }
+            None => {
+                let ty_arg_str = error_format(&ty_arg, &context.subst);
+                let step = format!(
+                    "{cur_str} requires '{requirement}' for its type argument {ty_arg_str}"
+                );
+                (step, ty_arg)
+            }
+        };
+        if !seen.insert(step.clone()) {
+            break None;
+        }
+        steps.push(step);
+        cur = next;
+        ability = requirement;
+    };
+    if steps.len() < 2 {
+        return;
+    }
+    diag.add_notes(steps);
+    if let Some((missing_ty, ability, declared_loc_opt)) = missing {
+        let missing_ty_str = error_format(&missing_ty, &context.subst);
+        diag.add_note(format!(
+            "{missing_ty_str} does not have the ability '{ability}'"
+        ));
+        if let Some(dloc) = declared_loc_opt {
+            diag.add_secondary_label((
+                dloc,
+                format!(
+                    "To satisfy the constraint, the '{}' ability would need to be added here",
+                    ability
+                ),
+            ));
+        }
+    }
+}
+
+/// The type arguments of `ty`, along with their index, excluding those given for phantom type
+/// parameters
+fn non_phantom_ty_args<'a>(
+    context: &Context,
+    ty: &Type,
+    ty_args: &'a [Type],
+) -> impl Iterator<Item = (usize, &'a Type)> {
+    let phantoms = match &ty.value {
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, n)), _) => context
+            .struct_tparams(m, n)
+            .iter()
+            .map(|tparam| tparam.is_phantom)
+            .collect(),
+        _ => vec![],
+    };
+    ty_args
+        .iter()
+        .enumerate()
+        .filter(move |(idx, _)| !phantoms.get(*idx).copied().unwrap_or(false))
+}
+
+/// The first field of the struct `ty` whose declared type uses the type parameter at `idx`, along
+/// with the type of that field when instantiated with `ty_args`
+fn field_using_ty_arg(
+    context: &Context,
+    ty: &Type,
+    ty_args: &[Type],
+    idx: usize,
+) -> Option<(Field, Type)> {
+    let Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, n)), _) = &ty.value else {
+        return None;
+    };
+    let sdef = context.struct_definition(m, n);
+    let N::StructFields::Defined(_, fields) = &sdef.fields else {
+        return None;
+    };
+    let tparam = &sdef.type_parameters.get(idx)?.param;
+    let mut fields = fields.key_cloned_iter().collect::<Vec<_>>();
+    fields.sort_by_key(|(_, (field_idx, _))| *field_idx);
+    let (field, (_, field_ty)) = fields
+        .into_iter()
+        .find(|(_, (_, field_ty))| uses_tparam(field_ty, tparam))?;
+    let tparams = sdef.type_parameters.iter().map(|tp| &tp.param);
+    let tparam_subst = make_tparam_subst(tparams, ty_args.iter().cloned());
+    Some((field, subst_tparams(&tparam_subst, field_ty.clone())))
+}
+
+fn uses_tparam(ty: &Type, tparam: &TParam) -> bool {
+    match &ty.value {
+        Type_::Param(tp) => tp.id == tparam.id,
+        Type_::Ref(_, t) => uses_tparam(t, tparam),
+        Type_::Apply(_, _, tys) => tys.iter().any(|t| uses_tparam(t, tparam)),
+        Type_::Fun(args, result) => {
+            args.iter().any(|t| uses_tparam(t, tparam)) || uses_tparam(result, tparam)
+        }
+        Type_::Unit | Type_::Var(_) | Type_::Anything | Type_::Hole | Type_::UnresolvedError => {
+            false
+        }
+    }
+}
+
 fn solve_builtin_type_constraint(
     context: &mut Context,
     builtin_set: &BTreeSet<BuiltinTypeName_>,
@@ -1530,7 +2577,7 @@ fn solve_builtin_type_constraint(
         // already failed, ignore
         UnresolvedError => (),
         // Will fail later in compiling, either through dead code, or unknown type variable
-        Anything => (),
+        Anything | Hole => (),
         Apply(abilities_opt, sp!(_, Builtin(sp!(_, b))), args) if builtin_set.contains(b) => {
             if let Some(abilities) = abilities_opt {
                 assert!(
@@ -1542,7 +2589,7 @@ fn solve_builtin_type_constraint(
         }
         _ => {
             let tmsg = mk_tmsg();
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::BuiltinOperation,
                 (loc, format!("Invalid argument to '{}'", op)),
                 (tloc, tmsg)
@@ -1560,13 +2607,13 @@ fn solve_base_type_constraint(context: &mut Context, loc: Loc, msg: String, ty:
         Unit | Ref(_, _) | Apply(_, sp!(_, Multiple(_)), _) => {
             let tystr = error_format(ty, &context.subst);
             let tmsg = format!("Expected a single non-reference type, but found: {}", tystr);
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::ExpectedBaseType,
                 (loc, msg),
                 (tyloc, tmsg)
             ))
         }
-        UnresolvedError | Anything | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
+        UnresolvedError | Anything | Hole | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
     }
 }
 
@@ -1581,13 +2628,23 @@ fn solve_single_type_constraint(context: &mut Context, loc: Loc, msg: String, ty
                 "Expected a single type, but found expression list type: {}",
                 error_format(ty, &context.subst)
             );
-            context.env.add_diag(diag!(
-                TypeSafety::ExpectedSingleType,
-                (loc, msg),
-                (tyloc, tmsg)
-            ))
+            let mut diag = diag!(TypeSafety::ExpectedSingleType, (loc, msg), (tyloc, tmsg));
+            if let Some(n) = context.expression_list_binds.get(&loc) {
+                let names = (0..*n)
+                    .map(|idx| match u8::try_from(idx) {
+                        Ok(idx) if idx < 26 => ((b'a' + idx) as char).to_string(),
+                        _ => format!("x{}", idx),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                diag.add_note(format!(
+                    "Consider destructuring the expression list with 'let ({}) = ...'",
+                    names
+                ));
+            }
+            context.add_diag(diag)
         }
-        UnresolvedError | Anything | Ref(_, _) | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
+        UnresolvedError | Anything | Hole | Ref(_, _) | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
     }
 }
 
@@ -1647,7 +2704,7 @@ where
 pub fn subst_tparams(subst: &TParamSubst, sp!(loc, t_): Type) -> Type {
     use Type_::*;
     match t_ {
-        x @ Unit | x @ UnresolvedError | x @ Anything => sp(loc, x),
+        x @ Unit | x @ UnresolvedError | x @ Anything | x @ Hole => sp(loc, x),
         Var(_) => panic!("ICE tvar in subst_tparams"),
         Ref(mut_, t) => sp(loc, Ref(mut_, Box::new(subst_tparams(subst, *t)))),
         Param(tp) => subst
@@ -1672,7 +2729,7 @@ pub fn subst_tparams(subst: &TParamSubst, sp!(loc, t_): Type) -> Type {
 pub fn ready_tvars(subst: &Subst, sp!(loc, t_): Type) -> Type {
     use Type_::*;
     match t_ {
-        x @ UnresolvedError | x @ Unit | x @ Anything | x @ Param(_) => sp(loc, x),
+        x @ UnresolvedError | x @ Unit | x @ Anything | x @ Hole | x @ Param(_) => sp(loc, x),
         Ref(mut_, t) => sp(loc, Ref(mut_, Box::new(ready_tvars(subst, *t)))),
         Apply(k, n, tys) => {
             let tys = tys.into_iter().map(|t| ready_tvars(subst, t)).collect();
@@ -1694,6 +2751,35 @@ pub fn ready_tvars(subst: &Subst, sp!(loc, t_): Type) -> Type {
     }
 }
 
+/// true iff `inner` appears as a strict sub-term of `outer`. Both types should have their type
+/// variables resolved, e.g. with `ready_tvars`
+fn contains_type(outer: &Type, inner: &Type) -> bool {
+    use Type_::*;
+    let contains_or_equal = |t: &Type| same_type(t, inner) || contains_type(t, inner);
+    match &outer.value {
+        Unit | Param(_) | Var(_) | Anything | Hole | UnresolvedError => false,
+        Ref(_, t) => contains_or_equal(t),
+        Apply(_, _, tys) => tys.iter().any(contains_or_equal),
+        Fun(args, result) => args.iter().any(contains_or_equal) || contains_or_equal(result),
+    }
+}
+
+/// Structural type equality, ignoring locations and any inferred abilities
+pub fn same_type(sp!(_, t1): &Type, sp!(_, t2): &Type) -> bool {
+    use Type_::*;
+    let same_types = |tys1: &[Type], tys2: &[Type]| {
+        tys1.len() == tys2.len() && tys1.iter().zip(tys2).all(|(t1, t2)| same_type(t1, t2))
+    };
+    match (t1, t2) {
+        (Ref(mut1, t1), Ref(mut2, t2)) => mut1 == mut2 && same_type(t1, t2),
+        (Apply(_, n1, tys1), Apply(_, n2, tys2)) => n1 == n2 && same_types(tys1, tys2),
+        (Fun(args1, result1), Fun(args2, result2)) => {
+            same_types(args1, args2) && same_type(result1, result2)
+        }
+        (t1, t2) => t1 == t2,
+    }
+}
+
 //**************************************************************************************************
 // Instantiate
 //**************************************************************************************************
@@ -1703,7 +2789,7 @@ pub fn instantiate(context: &mut Context, sp!(loc, t_): Type) -> Type {
     let it_ = match t_ {
         Unit => Unit,
         UnresolvedError => UnresolvedError,
-        Anything => make_tvar(context, loc).value,
+        Anything | Hole => make_tvar(context, loc).value,
         Ref(mut_, b) => {
             let inner = *b;
             context.add_base_type_constraint(loc, "Invalid reference type", inner.clone());
@@ -1743,6 +2829,7 @@ fn instantiate_apply(
         }
         sp!(_, N::TypeName_::ModuleType(m, s)) => {
             debug_assert!(abilities_opt.is_none(), "ICE instantiated expanded type");
+            context.mark_type_used(m, s);
             let tps = context.struct_tparams(m, s);
             tps.iter().map(|tp| tp.param.abilities.clone()).collect()
         }
@@ -1792,18 +2879,14 @@ fn instantiate_type_args(
         .collect();
 
     assert!(ty_args.len() == tvars.len());
-    let mut res = vec![];
-    let subst = std::mem::replace(&mut context.subst, /* dummy value */ Subst::empty());
-    context.subst = tvars
+    tvars
         .into_iter()
         .zip(ty_args)
-        .fold(subst, |subst, (tvar, ty_arg)| {
+        .map(|(tvar, ty_arg)| {
             // tvar is just a type variable, so shouldn't throw ever...
-            let (subst, t) = join(subst, &tvar, &ty_arg).ok().unwrap();
-            res.push(t);
-            subst
-        });
-    res
+            join(&mut context.subst, &tvar, &ty_arg).ok().unwrap()
+        })
+        .collect()
 }
 
 fn check_type_argument_arity<F: FnOnce() -> String>(
@@ -1827,7 +2910,7 @@ fn check_type_argument_arity<F: FnOnce() -> String>(
             arity,
             args_len
         );
-        context.env.add_diag(diag!(code, (loc, msg)));
+        context.add_diag(diag!(code, (loc, msg)));
     }
 
     while ty_args.len() > arity {
@@ -1880,7 +2963,7 @@ fn make_tparams(
 // constraints until application
 pub fn give_tparams_all_abilities(sp!(_, ty_): &mut Type) {
     match ty_ {
-        Type_::Unit | Type_::Var(_) | Type_::UnresolvedError | Type_::Anything => (),
+        Type_::Unit | Type_::Var(_) | Type_::UnresolvedError | Type_::Anything | Type_::Hole => (),
         Type_::Ref(_, inner) => give_tparams_all_abilities(inner),
         Type_::Apply(_, _, ty_args) => {
             for ty_arg in ty_args {
@@ -1916,27 +2999,67 @@ enum TypingCase {
     Subtype,
 }
 
-pub fn subtype(subst: Subst, lhs: &Type, rhs: &Type) -> Result<(Subst, Type), TypingError> {
-    join_impl(subst, TypingCase::Subtype, lhs, rhs)
+/// Checks that `lhs` is a subtype of `rhs`, binding type variables in `subst` as needed. If the
+/// check fails, `subst` is left unchanged
+pub fn subtype(subst: &mut Subst, lhs: &Type, rhs: &Type) -> Result<Type, TypingError> {
+    join_or_rollback(subst, TypingCase::Subtype, lhs, rhs)
 }
 
-pub fn join(subst: Subst, lhs: &Type, rhs: &Type) -> Result<(Subst, Type), TypingError> {
-    join_impl(subst, TypingCase::Join, lhs, rhs)
+/// Joins `lhs` and `rhs`, binding type variables in `subst` as needed. If the types cannot be
+/// joined, `subst` is left unchanged
+pub fn join(subst: &mut Subst, lhs: &Type, rhs: &Type) -> Result<Type, TypingError> {
+    join_or_rollback(subst, TypingCase::Join, lhs, rhs)
+}
+
+/// If `lhs` and `rhs` are distinct unbound type variables, binds `rhs` directly to `lhs` and
+/// returns the variable for both. `join` would instead bind both to a new variable, so joining many
+/// types against the result, one at a time, would build a chain of variables as long as the number
+/// of types. Returns `None`, leaving `subst` unchanged, if the types should be joined as usual
+pub fn merge_unbound_tvars(subst: &mut Subst, lhs: &Type, rhs: &Type) -> Option<Type> {
+    let (sp!(_, Type_::Var(id1)), sp!(loc2, Type_::Var(id2))) = (lhs, rhs) else {
+        return None;
+    };
+    let last_id1 = forward_tvar(subst, *id1);
+    let last_id2 = forward_tvar(subst, *id2);
+    if last_id1 == last_id2 || subst.get(last_id1).is_some() || subst.get(last_id2).is_some() {
+        return None;
+    }
+    if let Some(nloc) = subst.num_vars.get(&last_id2) {
+        let nloc = *nloc;
+        subst.set_num_var(last_id1, nloc);
+    }
+    subst.insert(last_id2, sp(*loc2, Type_::Var(last_id1)));
+    Some(sp(*loc2, Type_::Var(last_id1)))
+}
+
+fn join_or_rollback(
+    subst: &mut Subst,
+    case: TypingCase,
+    lhs: &Type,
+    rhs: &Type,
+) -> Result<Type, TypingError> {
+    let snapshot = subst.snapshot();
+    let res = join_impl(subst, case, lhs, rhs);
+    match &res {
+        Ok(_) => subst.commit(snapshot),
+        Err(_) => subst.rollback(snapshot),
+    }
+    res
 }
 
 fn join_impl(
-    mut subst: Subst,
+    subst: &mut Subst,
     case: TypingCase,
     lhs: &Type,
     rhs: &Type,
-) -> Result<(Subst, Type), TypingError> {
+) -> Result<Type, TypingError> {
     use TypeName_::*;
     use Type_::*;
     use TypingCase::*;
     match (lhs, rhs) {
-        (sp!(_, Anything), other) | (other, sp!(_, Anything)) => Ok((subst, other.clone())),
+        (sp!(_, Anything), other) | (other, sp!(_, Anything)) => Ok(other.clone()),
 
-        (sp!(_, Unit), sp!(loc, Unit)) => Ok((subst, sp(*loc, Unit))),
+        (sp!(_, Unit), sp!(loc, Unit)) => Ok(sp(*loc, Unit)),
 
         (sp!(loc1, Ref(mut1, t1)), sp!(loc2, Ref(mut2, t2))) => {
             let (loc, mut_) = match (case, mut1, mut2) {
@@ -1958,13 +3081,13 @@ fn join_impl(
                     ))
                 }
             };
-            let (subst, t) = join_impl(subst, case, t1, t2)?;
-            Ok((subst, sp(loc, Ref(mut_, Box::new(t)))))
+            let t = join_impl(subst, case, t1, t2)?;
+            Ok(sp(loc, Ref(mut_, Box::new(t))))
         }
         (sp!(_, Param(TParam { id: id1, .. })), sp!(_, Param(TParam { id: id2, .. })))
             if id1 == id2 =>
         {
-            Ok((subst, rhs.clone()))
+            Ok(rhs.clone())
         }
         (sp!(_, Apply(_, sp!(_, Multiple(n1)), _)), sp!(_, Apply(_, sp!(_, Multiple(n2)), _)))
             if n1 != n2 =>
@@ -1985,8 +3108,8 @@ fn join_impl(
                 k1,
                 k2
             );
-            let (subst, tys) = join_impl_types(subst, case, tys1, tys2)?;
-            Ok((subst, sp(*loc, Apply(k2.clone(), n2.clone(), tys))))
+            let tys = join_impl_types(subst, case, tys1, tys2)?;
+            Ok(sp(*loc, Apply(k2.clone(), n2.clone(), tys)))
         }
         (sp!(_, Fun(a1, _)), sp!(_, Fun(a2, _))) if a1.len() != a2.len() => {
             Err(TypingError::FunArityMismatch(
@@ -1999,23 +3122,23 @@ fn join_impl(
         (sp!(_, Fun(a1, r1)), sp!(loc, Fun(a2, r2))) => {
             // TODO this is going to likely lead to some strange error locations/messages
             // since the RHS in subtyping is currently assumed to be an annotation
-            let (subst, args) = match case {
+            let args = match case {
                 Join => join_impl_types(subst, case, a1, a2)?,
                 Subtype => join_impl_types(subst, case, a2, a1)?,
             };
-            let (subst, result) = join_impl(subst, case, r1, r2)?;
-            Ok((subst, sp(*loc, Fun(args, Box::new(result)))))
+            let result = join_impl(subst, case, r1, r2)?;
+            Ok(sp(*loc, Fun(args, Box::new(result))))
         }
         (sp!(loc1, Var(id1)), sp!(loc2, Var(id2))) => {
             if *id1 == *id2 {
-                Ok((subst, sp(*loc2, Var(*id2))))
+                Ok(sp(*loc2, Var(*id2)))
             } else {
                 join_tvar(subst, case, *loc1, *id1, *loc2, *id2)
             }
         }
         (sp!(loc, Var(id)), other) if subst.get(*id).is_none() => {
-            if join_bind_tvar(&mut subst, *loc, *id, other.clone())? {
-                Ok((subst, sp(*loc, Var(*id))))
+            if join_bind_tvar(subst, *loc, *id, other.clone())? {
+                Ok(sp(*loc, Var(*id)))
             } else {
                 Err(TypingError::Incompatible(
                     Box::new(sp(*loc, Var(*id))),
@@ -2024,8 +3147,8 @@ fn join_impl(
             }
         }
         (other, sp!(loc, Var(id))) if subst.get(*id).is_none() => {
-            if join_bind_tvar(&mut subst, *loc, *id, other.clone())? {
-                Ok((subst, sp(*loc, Var(*id))))
+            if join_bind_tvar(subst, *loc, *id, other.clone())? {
+                Ok(sp(*loc, Var(*id)))
             } else {
                 Err(TypingError::Incompatible(
                     Box::new(other.clone()),
@@ -2044,9 +3167,7 @@ fn join_impl(
             join_tvar(subst, case, other.loc, new_tvar, *loc, *id)
         }
 
-        (sp!(_, UnresolvedError), other) | (other, sp!(_, UnresolvedError)) => {
-            Ok((subst, other.clone()))
-        }
+        (sp!(_, UnresolvedError), other) | (other, sp!(_, UnresolvedError)) => Ok(other.clone()),
         _ => Err(TypingError::Incompatible(
             Box::new(lhs.clone()),
             Box::new(rhs.clone()),
@@ -2055,33 +3176,31 @@ fn join_impl(
 }
 
 fn join_impl_types(
-    mut subst: Subst,
+    subst: &mut Subst,
     case: TypingCase,
     tys1: &[Type],
     tys2: &[Type],
-) -> Result<(Subst, Vec<Type>), TypingError> {
+) -> Result<Vec<Type>, TypingError> {
     // if tys1.len() != tys2.len(), we will get an error when instantiating the type elsewhere
     // as all types are instantiated as a sanity check
     let mut tys = vec![];
     for (ty1, ty2) in tys1.iter().zip(tys2) {
-        let (nsubst, t) = join_impl(subst, case, ty1, ty2)?;
-        subst = nsubst;
-        tys.push(t)
+        tys.push(join_impl(subst, case, ty1, ty2)?)
     }
-    Ok((subst, tys))
+    Ok(tys)
 }
 
 fn join_tvar(
-    mut subst: Subst,
+    subst: &mut Subst,
     case: TypingCase,
     loc1: Loc,
     id1: TVar,
     loc2: Loc,
     id2: TVar,
-) -> Result<(Subst, Type), TypingError> {
+) -> Result<Type, TypingError> {
     use Type_::*;
-    let last_id1 = forward_tvar(&subst, id1);
-    let last_id2 = forward_tvar(&subst, id2);
+    let last_id1 = forward_tvar(subst, id1);
+    let last_id2 = forward_tvar(subst, id2);
     let ty1 = match subst.get(last_id1) {
         None => sp(loc1, Anything),
         Some(t) => t.clone(),
@@ -2104,12 +3223,12 @@ fn join_tvar(
     subst.insert(last_id1, sp(loc1, Var(new_tvar)));
     subst.insert(last_id2, sp(loc2, Var(new_tvar)));
 
-    let (mut subst, new_ty) = join_impl(subst, case, &ty1, &ty2)?;
+    let new_ty = join_impl(subst, case, &ty1, &ty2)?;
     match subst.get(new_tvar) {
         Some(sp!(tloc, _)) => Err(TypingError::RecursiveType(*tloc)),
         None => {
-            if join_bind_tvar(&mut subst, loc2, new_tvar, new_ty)? {
-                Ok((subst, sp(loc2, Var(new_tvar))))
+            if join_bind_tvar(subst, loc2, new_tvar, new_ty)? {
+                Ok(sp(loc2, Var(new_tvar)))
             } else {
                 let ty1 = match ty1 {
                     sp!(loc, Anything) => sp(loc, Var(id1)),
@@ -2159,7 +3278,7 @@ fn join_bind_tvar(subst: &mut Subst, loc: Loc, tvar: TVar, ty: Type) -> Result<b
                     .for_each(|inner| used_tvars(used, inner));
                 used_tvars(used, inner_ret)
             }
-            T::Unit | T::Param(_) | T::Anything | T::UnresolvedError => (),
+            T::Unit | T::Param(_) | T::Anything | T::Hole | T::UnresolvedError => (),
         }
     }
 
@@ -2188,7 +3307,7 @@ fn check_num_tvar(subst: &Subst, _loc: Loc, tvar: TVar, ty: &Type) -> bool {
 fn check_num_tvar_(subst: &Subst, ty: &Type) -> bool {
     use Type_::*;
     match &ty.value {
-        UnresolvedError | Anything => true,
+        UnresolvedError | Anything | Hole => true,
         Apply(_, sp!(_, TypeName_::Builtin(sp!(_, bt))), _) => bt.is_numeric(),
 
         Var(v) => {
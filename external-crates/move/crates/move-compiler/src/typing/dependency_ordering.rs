@@ -267,7 +267,7 @@ fn function_signature(context: &mut Context, sig: &N::FunctionSignature) {
 //**************************************************************************************************
 
 fn struct_def(context: &mut Context, sdef: &N::StructDefinition) {
-    if let N::StructFields::Defined(fields) = &sdef.fields {
+    if let N::StructFields::Defined(_, fields) = &sdef.fields {
         fields.iter().for_each(|(_, _, (_, bt))| type_(context, bt));
     }
 }
@@ -292,7 +292,7 @@ fn type_(context: &mut Context, sp!(_, ty_): &N::Type) {
             types(context, tys);
             type_(context, t);
         }
-        T::Unit | T::Param(_) | T::Var(_) | T::Anything | T::UnresolvedError => (),
+        T::Unit | T::Param(_) | T::Var(_) | T::Anything | T::Hole | T::UnresolvedError => (),
     }
 }
 
@@ -435,6 +435,7 @@ fn exp(context: &mut Context, e: &T::Exp) {
         | E::Copy { .. }
         | E::Use(_)
         | E::Constant(..)
+        | E::ErrorConstant(..)
         | E::Continue(_)
         | E::BorrowLocal(..)
         | E::UnresolvedError => (),
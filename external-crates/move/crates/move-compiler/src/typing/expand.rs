@@ -10,6 +10,7 @@ use crate::{
     ice,
     naming::ast::{BuiltinTypeName_, FunctionSignature, Type, TypeName_, Type_},
     parser::ast::Ability_,
+    shared::inference_holes::InferenceHoleKind,
     typing::ast as T,
 };
 use move_core_types::u256::U256;
@@ -52,7 +53,7 @@ fn types(context: &mut Context, ss: &mut Vec<Type>) {
 pub fn type_(context: &mut Context, ty: &mut Type) {
     use Type_::*;
     match &mut ty.value {
-        Anything | UnresolvedError | Param(_) | Unit => (),
+        Anything | Hole | UnresolvedError | Param(_) | Unit => (),
         Ref(_, b) => type_(context, b),
         Var(tvar) => {
             let ty_tvar = sp(ty.loc, Var(*tvar));
@@ -63,14 +64,13 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
                         ty.loc,
                         "ICE unfold_type_base failed to expand type inf. var"
                     ));
-                    context.env.add_diag(diag);
+                    context.add_diag(diag);
                     sp(loc, UnresolvedError)
                 }
                 sp!(loc, Anything) => {
                     let msg = "Could not infer this type. Try adding an annotation";
-                    context
-                        .env
-                        .add_diag(diag!(TypeSafety::UninferredType, (ty.loc, msg)));
+                    context.add_diag(diag!(TypeSafety::UninferredType, (ty.loc, msg)));
+                    context.record_inference_hole(ty.loc, InferenceHoleKind::Type, ty);
                     sp(loc, UnresolvedError)
                 }
                 sp!(loc, Fun(_, _)) if !context.in_macro_function => {
@@ -89,7 +89,7 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
                 ty.loc,
                 format!("ICE expanding pre-expanded type {}", debug_display!(aty))
             ));
-            context.env.add_diag(diag);
+            context.add_diag(diag);
             *ty = sp(ty.loc, UnresolvedError)
         }
         Apply(None, _, _) => {
@@ -101,7 +101,7 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
                 }
                 _ => {
                     let diag = ice!((ty.loc, "ICE type-apply switched to non-apply"));
-                    context.env.add_diag(diag);
+                    context.add_diag(diag);
                     *ty = sp(ty.loc, UnresolvedError)
                 }
             }
@@ -119,15 +119,10 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
 }
 
 fn unexpected_lambda_type(context: &mut Context, loc: Loc) {
-    if context
-        .env
-        .check_feature(FeatureGate::MacroFuns, context.current_package, loc)
-    {
+    if context.check_feature(FeatureGate::MacroFuns, loc) {
         let msg = "Unexpected lambda type. \
             Lambdas can only be used with 'macro' functions, as parameters or direct arguments";
-        context
-            .env
-            .add_diag(diag!(TypeSafety::UnexpectedFunctionType, (loc, msg)));
+        context.add_diag(diag!(TypeSafety::UnexpectedFunctionType, (loc, msg)));
     }
 }
 
@@ -205,7 +200,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
                         e.exp.loc,
                         format!("ICE failed to infer number type for {}", debug_display!(e))
                     ));
-                    context.env.add_diag(diag);
+                    context.add_diag(diag);
                     let _ = std::mem::replace(&mut e.ty.value, Type_::UnresolvedError);
                     let _ = std::mem::replace(&mut e.exp.value, E::UnresolvedError);
                     return;
@@ -250,7 +245,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
                     value=v,
                     type=fix_bt,
                 );
-                context.env.add_diag(diag!(
+                context.add_diag(diag!(
                     TypeSafety::InvalidNum,
                     (e.exp.loc, "Invalid numerical literal"),
                     (e.ty.loc, msg),
@@ -275,6 +270,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
         E::Unit { .. }
         | E::Value(_)
         | E::Constant(_, _)
+        | E::ErrorConstant(_, _)
         | E::Move { .. }
         | E::Copy { .. }
         | E::BorrowLocal(_, _)
@@ -392,7 +388,7 @@ fn builtin_function(context: &mut Context, b: &mut T::BuiltinFunction) {
         B::Freeze(bt) => {
             type_(context, bt);
         }
-        B::Assert(_) => (),
+        B::Assert(..) => (),
     }
 }
 
@@ -78,7 +78,7 @@ impl<'a> Context<'a> {
         use N::Type_::*;
         match targ_ {
             Var(_) => panic!("ICE tvar after expansion"),
-            Unit | Anything | UnresolvedError => (),
+            Unit | Anything | Hole | UnresolvedError => (),
             Ref(_, t) => {
                 let info = EdgeInfo {
                     edge: Edge::Nested,
@@ -225,6 +225,7 @@ fn exp(context: &mut Context, e: &T::Exp) {
         E::Unit { .. }
         | E::Value(_)
         | E::Constant(_, _)
+        | E::ErrorConstant(_, _)
         | E::Move { .. }
         | E::Copy { .. }
         | E::BorrowLocal(_, _)
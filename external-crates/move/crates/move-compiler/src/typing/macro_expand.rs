@@ -58,7 +58,8 @@ pub(crate) fn call(
 ) -> Option<ExpandedMacro> {
     let next_color = context.next_variable_color();
     // If none, there is no body to expand, likely because of an error in the macro definition
-    let macro_body = context.macro_body(&m, &f)?;
+    let (module_use_funs, body_loc, macro_body) = context.macro_body(&m, &f)?;
+    let macro_body = wrap_body(module_use_funs, body_loc, macro_body);
     let macro_info = context.function_info(&m, &f);
     let (macro_type_params, macro_params, mut macro_body, return_label, max_color) =
         match recolor_macro(call_loc, &m, &f, macro_info, macro_body, next_color) {
@@ -68,7 +69,7 @@ pub(crate) fn call(
                 return None;
             }
             Err(Some(diag)) => {
-                context.env.add_diag(*diag);
+                context.add_diag(*diag);
                 return None;
             }
         };
@@ -99,6 +100,8 @@ pub(crate) fn call(
     let mut lambdas = BTreeMap::new();
     let mut by_name_args = BTreeMap::new();
     let mut by_value_args = vec![];
+    // the parameter types were already instantiated, and checked against the arguments, when
+    // typing the call, so they are not needed here
     for ((_, param, _param_ty), arg) in macro_params.into_iter().zip(args) {
         let param_loc = param.loc;
         let param = if param.value.name == symbol!("_") {
@@ -176,12 +179,26 @@ pub(crate) fn call(
     })
 }
 
+// The module's methods are kept in their own scope, wrapping the body of the macro. This way,
+// method resolution in the macro body follows the same scoping and precedence rules as it
+// would for a normal function in the module.
+fn wrap_body(module_use_funs: &N::UseFuns, body_loc: Loc, body: &N::Sequence) -> N::Sequence {
+    let block = N::Block {
+        name: None,
+        from_macro_argument: None,
+        seq: body.clone(),
+    };
+    let e = Box::new(sp(body_loc, N::Exp_::Block(block)));
+    let item = sp(body_loc, N::SequenceItem_::Seq(e));
+    (module_use_funs.clone(), VecDeque::from([item]))
+}
+
 fn recolor_macro(
     call_loc: Loc,
     _m: &ModuleIdent,
     _f: &FunctionName,
     macro_info: &FunctionInfo,
-    macro_body: &N::Sequence,
+    mut macro_body: N::Sequence,
     color: u16,
 ) -> Result<
     (
@@ -225,14 +242,11 @@ fn recolor_macro(
         .iter()
         .map(|(mut_, v, t)| (*mut_, recolor_var_owned(recolor, *v), t.clone()))
         .collect();
-    let body = {
-        let mut body = macro_body.clone();
-        recolor_seq(recolor, &mut body);
-        N::Block {
-            name: None,
-            from_macro_argument: None,
-            seq: body,
-        }
+    recolor_seq(recolor, &mut macro_body);
+    let body = N::Block {
+        name: None,
+        from_macro_argument: None,
+        seq: macro_body,
     };
     let max_color = recolor.max_color();
     debug_assert_eq!(color, max_color, "ICE should only have one color in macros");
@@ -257,9 +271,7 @@ fn bind_lambda(
                 "Unable to bind lambda to parameter '{}'. The lambda must be passed directly",
                 param.name
             );
-            context
-                .env
-                .add_diag(diag!(TypeSafety::CannotExpandMacro, (arg.loc, msg)));
+            context.add_diag(diag!(TypeSafety::CannotExpandMacro, (arg.loc, msg)));
             None
         }
     }
@@ -577,6 +589,10 @@ fn recolor_exp_dotted(ctx: &mut Recolor, sp!(_, ed_): &mut N::ExpDotted) {
     match ed_ {
         N::ExpDotted_::Exp(e) => recolor_exp(ctx, e),
         N::ExpDotted_::Dot(ed, _) => recolor_exp_dotted(ctx, ed),
+        N::ExpDotted_::Index(ed, i) => {
+            recolor_exp_dotted(ctx, ed);
+            recolor_exp(ctx, i)
+        }
     }
 }
 
@@ -608,9 +624,7 @@ fn report_unused_argument(context: &mut core::Context, loc: EvalStrategy<Loc, Lo
     };
     let msg = "Unused macro argument. \
     Its expression will not be type checked and it will not evaluated";
-    context
-        .env
-        .add_diag(diag!(UnusedItem::DeadCode, (loc, msg)));
+    context.add_diag(diag!(UnusedItem::DeadCode, (loc, msg)));
 }
 
 fn types(context: &mut Context, tys: &mut [Type]) {
@@ -805,13 +819,15 @@ fn exp(context: &mut Context, sp!(eloc, e_): &mut N::Exp) {
             context.core.set_max_variable_color(recolor.max_color());
             // check arity before expanding
             let argloc = *argloc;
+            let params: Vec<_> = param_tys.iter().map(|ty| (None, ty.clone())).collect();
+            let arg_locs: Vec<_> = es.iter().map(|e| e.loc).collect();
             core::check_call_arity(
                 context.core,
                 *eloc,
                 || format!("Invalid lambda call of '{}'", v_.name),
-                param_tys.len(),
+                &params,
                 argloc,
-                es.len(),
+                &arg_locs,
             );
             // expand the call, replacing with a dummy value to take the args by value
             let N::Exp_::VarCall(_, sp!(_, args)) =
@@ -885,7 +901,7 @@ fn exp(context: &mut Context, sp!(eloc, e_): &mut N::Exp) {
         N::Exp_::VarCall(sp!(_, v_), _) if context.by_name_args.contains_key(v_) => {
             context.mark_used(v_);
             let (arg, _expected_ty) = context.by_name_args.get(v_).unwrap();
-            context.core.env.add_diag(diag!(
+            context.core.add_diag(diag!(
                 TypeSafety::CannotExpandMacro,
                 (*eloc, "Cannot call non-lambda argument"),
                 (arg.loc, "Expected a lambda argument")
@@ -945,6 +961,10 @@ fn exp_dotted(context: &mut Context, sp!(_, ed_): &mut N::ExpDotted) {
     match ed_ {
         N::ExpDotted_::Exp(e) => exp(context, e),
         N::ExpDotted_::Dot(ed, _) => exp_dotted(context, ed),
+        N::ExpDotted_::Index(ed, i) => {
+            exp_dotted(context, ed);
+            exp(context, i)
+        }
     }
 }
 
@@ -3,11 +3,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ast;
+mod const_functions;
+mod constant_eval;
 pub mod core;
 mod dependency_ordering;
 mod expand;
 mod infinite_instantiations;
 mod macro_expand;
+mod read_only_mut_params;
 mod recursive_structs;
 pub(crate) mod translate;
+pub mod type_locations;
+mod use_after_move;
 pub mod visitor;
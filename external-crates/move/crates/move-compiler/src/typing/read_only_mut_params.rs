@@ -0,0 +1,228 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports '&mut' parameters that are only ever read through, e.g. `fun f(x: &mut S): u64 { x.f }`,
+//! as they require callers to have exclusive access to the value for no reason. This runs after
+//! types are expanded, since whether a call argument is used mutably depends on the type of the
+//! parameter it is passed to. Any usage of the parameter that is not known to be read-only, such
+//! as binding it to a local or returning it, is treated as a mutable usage.
+
+use super::core::{self, Context};
+use crate::{
+    diag,
+    diagnostics::codes::TypeSafety,
+    naming::ast::{self as N, Type, Type_, Var, Var_},
+    parser::ast::BinOp_,
+    typing::ast as T,
+};
+use move_ir_types::location::*;
+use std::collections::BTreeMap;
+
+struct Usage {
+    /// The '&mut' parameters that are not (yet) known to be used mutably, with their declared
+    /// types
+    read_only: BTreeMap<Var_, (Var, Type)>,
+    /// Set if the body contains an error, in which case usages might be missing
+    has_errors: bool,
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn function(context: &mut Context, signature: &N::FunctionSignature, body: &T::FunctionBody) {
+    let T::FunctionBody_::Defined(seq) = &body.value else {
+        return;
+    };
+    let read_only = signature
+        .parameters
+        .iter()
+        .filter(|(_, v, _)| !v.value.starts_with_underscore())
+        .filter(|(_, _, ty)| matches!(&ty.value, Type_::Ref(true, _)))
+        .map(|(_, v, ty)| (v.value, (*v, ty.clone())))
+        .collect::<BTreeMap<_, _>>();
+    if read_only.is_empty() {
+        return;
+    }
+    let mut usage = Usage {
+        read_only,
+        has_errors: false,
+    };
+    sequence(&mut usage, seq);
+    if usage.has_errors {
+        return;
+    }
+    for (v, sp!(tyloc, ty_)) in usage.read_only.into_values() {
+        let Type_::Ref(true, inner) = ty_ else {
+            continue;
+        };
+        let imm_ty = sp(tyloc, Type_::Ref(false, inner));
+        let msg = format!(
+            "Parameter '{}' is declared as '&mut' but is never used mutably",
+            v.value.name
+        );
+        let ty_msg = format!(
+            "Consider changing this to {}",
+            core::error_format(&imm_ty, &context.subst)
+        );
+        context.add_diag(diag!(
+            TypeSafety::ReadOnlyMutParam,
+            (v.loc, msg),
+            (tyloc, ty_msg)
+        ));
+    }
+}
+
+//**************************************************************************************************
+// Expressions
+//**************************************************************************************************
+
+fn sequence(usage: &mut Usage, (_, seq): &T::Sequence) {
+    for item in seq {
+        match &item.value {
+            T::SequenceItem_::Seq(e) => exp(usage, e),
+            T::SequenceItem_::Declare(_) => (),
+            T::SequenceItem_::Bind(_, _, e) => exp(usage, e),
+        }
+    }
+}
+
+/// The parameter used by `e`, if `e` is a usage of one
+fn parameter_usage(e: &T::Exp) -> Option<&Var> {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => Some(var),
+        _ => None,
+    }
+}
+
+/// `e` is only read through, e.g. it is dereferenced or immutably borrowed from
+fn read(usage: &mut Usage, e: &T::Exp) {
+    if parameter_usage(e).is_none() {
+        exp(usage, e)
+    }
+}
+
+fn exp(usage: &mut Usage, e: &T::Exp) {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => {
+            usage.read_only.remove(&var.value);
+        }
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Constant(_, _)
+        | E::ErrorConstant(_, _)
+        | E::BorrowLocal(_, _)
+        | E::Continue(_) => (),
+        E::UnresolvedError => usage.has_errors = true,
+
+        E::ModuleCall(call) => {
+            let T::ModuleCall {
+                arguments,
+                parameter_types,
+                ..
+            } = &**call;
+            match &arguments.exp.value {
+                E::ExpList(items) if items.len() == parameter_types.len() => {
+                    for (item, param_ty) in items.iter().zip(parameter_types) {
+                        match item {
+                            T::ExpListItem::Single(e, _) => argument(usage, e, param_ty),
+                            T::ExpListItem::Splat(_, e, _) => exp(usage, e),
+                        }
+                    }
+                }
+                _ if parameter_types.len() == 1 => argument(usage, arguments, &parameter_types[0]),
+                _ => exp(usage, arguments),
+            }
+        }
+        E::Builtin(b, args) => match &b.value {
+            T::BuiltinFunction_::Freeze(_) => read(usage, args),
+            T::BuiltinFunction_::Assert(_, _) => exp(usage, args),
+        },
+        E::Vector(_, _, _, args) => exp(usage, args),
+
+        E::IfElse(eb, et, ef) => {
+            exp(usage, eb);
+            exp(usage, et);
+            exp(usage, ef);
+        }
+        E::While(_, eb, eloop) => {
+            exp(usage, eb);
+            exp(usage, eloop);
+        }
+        E::Loop { body: eloop, .. } => exp(usage, eloop),
+        E::NamedBlock(_, seq) | E::Block(seq) => sequence(usage, seq),
+        E::Assign(assigns, _, er) => {
+            exp(usage, er);
+            lvalues(usage, assigns);
+        }
+
+        E::Dereference(er) | E::Borrow(false, er, _) => read(usage, er),
+        E::BinopExp(el, sp!(_, BinOp_::Eq | BinOp_::Neq), _, er) => {
+            read(usage, el);
+            read(usage, er);
+        }
+        E::Return(er)
+        | E::Abort(er)
+        | E::Give(_, er)
+        | E::UnaryExp(_, er)
+        | E::Borrow(true, er, _)
+        | E::TempBorrow(_, er)
+        | E::Cast(er, _)
+        | E::Annotate(er, _) => exp(usage, er),
+        E::Mutate(el, er) => {
+            exp(usage, er);
+            exp(usage, el)
+        }
+        E::BinopExp(el, _, _, er) => {
+            exp(usage, el);
+            exp(usage, er);
+        }
+
+        E::Pack(_, _, _, fields) => {
+            for (_, _, (_, (_, fe))) in fields.iter() {
+                exp(usage, fe)
+            }
+        }
+        E::ExpList(el) => {
+            for item in el {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => exp(usage, e),
+                }
+            }
+        }
+    }
+}
+
+/// An argument passed to a parameter of type `param_ty` is only read through if the parameter is
+/// an immutable reference
+fn argument(usage: &mut Usage, e: &T::Exp, param_ty: &Type) {
+    match &param_ty.value {
+        Type_::Ref(false, _) => read(usage, e),
+        _ => exp(usage, e),
+    }
+}
+
+fn lvalues(usage: &mut Usage, binds: &T::LValueList) {
+    for b in &binds.value {
+        lvalue(usage, b)
+    }
+}
+
+/// Assigning to the parameter is treated as using it mutably, as the reference might be replaced
+/// by one that is
+fn lvalue(usage: &mut Usage, b: &T::LValue) {
+    use T::LValue_ as L;
+    match &b.value {
+        L::Ignore => (),
+        L::Var { var, .. } => {
+            usage.read_only.remove(&var.value);
+        }
+        L::BorrowUnpack(_, _, _, _, fields) | L::Unpack(_, _, _, fields) => {
+            for (_, _, (_, (_, innerb))) in fields.iter() {
+                lvalue(usage, innerb)
+            }
+        }
+    }
+}
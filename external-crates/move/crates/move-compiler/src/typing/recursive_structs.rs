@@ -86,7 +86,7 @@ fn struct_def(context: &mut Context, sname: StructName, sdef: &N::StructDefiniti
     context.current_struct = Some(sname);
     match &sdef.fields {
         N::StructFields::Native(_) => (),
-        N::StructFields::Defined(fields) => {
+        N::StructFields::Defined(_, fields) => {
             fields.iter().for_each(|(_, _, (_, ty))| type_(context, ty))
         }
     };
@@ -97,7 +97,7 @@ fn type_(context: &mut Context, sp!(loc, ty_): &N::Type) {
     use N::Type_::*;
     match ty_ {
         Var(_) => panic!("ICE tvar in struct field type"),
-        Unit | Anything | UnresolvedError | Param(_) => (),
+        Unit | Anything | Hole | UnresolvedError | Param(_) => (),
         Ref(_, t) => type_(context, t),
         Apply(_, sp!(_, tn_), tys) => {
             if let TypeName_::ModuleType(m, s) = tn_ {
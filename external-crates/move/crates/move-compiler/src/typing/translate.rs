@@ -3,35 +3,53 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
+    const_functions, constant_eval,
     core::{self, Context, Local, Subst},
-    expand, infinite_instantiations, recursive_structs,
+    expand, infinite_instantiations, read_only_mut_params, recursive_structs, use_after_move,
 };
 use crate::{
     diag,
-    diagnostics::{codes::*, Diagnostic},
-    editions::{Edition, FeatureGate, Flavor},
+    diagnostics::{codes::*, Diagnostic, WarningFilters},
+    editions::{create_feature_error, Edition, FeatureGate, Flavor},
     expansion::ast::{
-        Attribute, AttributeValue_, Attribute_, DottedUsage, Fields, Friend, ModuleAccess_,
-        ModuleIdent, ModuleIdent_, Value_, Visibility,
+        Attribute, AttributeValue_, Attribute_, Attributes, DottedUsage, Fields, Friend,
+        ModuleAccess_, ModuleIdent, ModuleIdent_, Value_, Visibility,
     },
+    hlir::translate::ERROR_CONSTANT_ABORT_CODE_TAG,
     ice,
-    naming::ast::{self as N, BlockLabel, TParam, TParamID, Type, TypeName_, Type_},
+    naming::{
+        ast::{self as N, BlockLabel, BuiltinTypeName_, TParam, TParamID, Type, TypeName_, Type_},
+        translate::IMPLICIT_ABORT_CODE,
+    },
     parser::ast::{
         Ability_, BinOp, BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp_,
     },
     shared::{
-        known_attributes::TestingAttribute, process_binops, program_info::TypingProgramInfo,
-        unique_map::UniqueMap, *,
+        inference_holes::InferenceHoleKind,
+        known_attributes::{
+            AttributePosition, ConstFunAttribute, ErrorAttribute, SyntaxAttribute, TestingAttribute,
+        },
+        metrics::FunctionMetrics,
+        process_binops,
+        program_info::{CallGraph, FunctionCalls, TypingProgramInfo},
+        unique_map::UniqueMap,
+        *,
     },
     sui_mode,
     typing::{
         ast as T,
-        core::{make_tvar, public_testing_visibility, PublicForTesting, ResolvedFunctionType},
-        dependency_ordering, macro_expand,
+        core::{
+            make_tvar, public_testing_visibility, IndexMethods, PublicForTesting,
+            ResolvedFunctionType,
+        },
+        dependency_ordering, macro_expand, type_locations,
+        visitor::TypingVisitorContext,
     },
     FullyCompiledProgram,
 };
+use move_core_types::runtime_value::MoveValue;
 use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 //**************************************************************************************************
@@ -50,13 +68,15 @@ pub fn program(
     let mut context = Box::new(Context::new(compilation_env, pre_compiled_lib, info));
 
     extract_macros(&mut context, &nmodules);
-    let mut modules = modules(&mut context, nmodules);
+    let (mut modules, call_graph) = modules(&mut context, nmodules);
 
     assert!(context.constraints.is_empty());
     dependency_ordering::program(context.env, &mut modules);
     recursive_structs::modules(context.env, &modules);
     infinite_instantiations::modules(context.env, &modules);
     let mut prog = T::Program_ { modules };
+    let dotted_segment_types = std::mem::take(&mut context.dotted_segment_types);
+    let macro_expansion_sites = std::mem::take(&mut context.macro_expansion_sites);
     // we extract module use funs into the module info context
     let module_use_funs = context
         .modules
@@ -64,53 +84,57 @@ pub fn program(
         .into_iter()
         .map(|(mident, minfo)| (mident, minfo.use_funs))
         .collect();
-    let module_info = TypingProgramInfo::new(pre_compiled_lib, &prog, module_use_funs);
+    let mut module_info = TypingProgramInfo::new(pre_compiled_lib, &prog, module_use_funs);
+    module_info.call_graph = call_graph;
+    module_info.macro_expansions = macro_expansion_sites;
+    if compilation_env.flags().record_type_locations() {
+        module_info.type_locations = Some(type_locations::program(&mut prog, dotted_segment_types));
+    }
     for v in &compilation_env.visitors().typing {
         let mut v = v.borrow_mut();
         v.visit(compilation_env, &module_info, &mut prog);
     }
-    T::Program {
+    while_true_to_loop(&mut prog.modules);
+    let prog = T::Program {
         info: module_info,
         inner: prog,
-    }
+    };
+    prog
 }
 
 fn extract_macros(context: &mut Context, modules: &UniqueMap<ModuleIdent, N::ModuleDefinition>) {
-    // Merges the methods of the module into the local methods for each macro.
-    fn merge_use_funs(module_use_funs: &N::UseFuns, mut macro_use_funs: N::UseFuns) -> N::UseFuns {
+    // The module's methods are kept once per module, and not with each macro body. They are added
+    // in their own scope around the body when it is expanded, see `macro_expand::call`
+    let all_macro_definitions = modules.ref_map(|_mident, mdef| {
         let N::UseFuns {
-            color: _,
+            color,
             resolved,
             implicit_candidates,
-        } = module_use_funs;
-        for (tn, module_methods) in resolved {
-            let macro_methods = macro_use_funs.resolved.entry(tn.clone()).or_default();
-            for (name, method) in module_methods.key_cloned_iter() {
-                if !macro_methods.contains_key(&name) {
-                    macro_methods.add(name, method.clone()).unwrap();
-                }
-            }
-        }
-        for (name, module_candidate) in implicit_candidates.key_cloned_iter() {
-            if !macro_use_funs.implicit_candidates.contains_key(&name) {
-                macro_use_funs
-                    .implicit_candidates
-                    .add(name, module_candidate.clone())
-                    .unwrap();
-            }
-        }
-        macro_use_funs
-    }
-    let all_macro_definitions = modules.ref_map(|_mident, mdef| {
-        mdef.functions.ref_filter_map(|_name, f| {
+        } = &mdef.use_funs;
+        assert!(
+            implicit_candidates.is_empty(),
+            "ICE use fun candidates should have been resolved"
+        );
+        let bodies = mdef.functions.ref_filter_map(|_name, f| {
             let _macro_loc = f.macro_?;
-            if let N::FunctionBody_::Defined((use_funs, body)) = &f.body.value {
-                let use_funs = merge_use_funs(&mdef.use_funs, use_funs.clone());
-                Some((use_funs, body.clone()))
+            if let N::FunctionBody_::Defined(body) = &f.body.value {
+                Some((f.body.loc, body.clone()))
             } else {
                 None
             }
-        })
+        });
+        // skip cloning the methods for modules without macros
+        let resolved = if bodies.is_empty() {
+            BTreeMap::new()
+        } else {
+            resolved.clone()
+        };
+        let use_funs = N::UseFuns {
+            color: *color,
+            resolved,
+            implicit_candidates: UniqueMap::new(),
+        };
+        core::ModuleMacros { use_funs, bodies }
     });
 
     context.set_macros(all_macro_definitions);
@@ -119,7 +143,7 @@ fn extract_macros(context: &mut Context, modules: &UniqueMap<ModuleIdent, N::Mod
 fn modules(
     context: &mut Context,
     modules: UniqueMap<ModuleIdent, N::ModuleDefinition>,
-) -> UniqueMap<ModuleIdent, T::ModuleDefinition> {
+) -> (UniqueMap<ModuleIdent, T::ModuleDefinition>, CallGraph) {
     let mut all_new_friends = BTreeMap::new();
     let mut typed_modules = modules.map(|ident, mdef| {
         let (typed_mdef, new_friends) = module(context, ident, mdef);
@@ -145,11 +169,13 @@ fn modules(
             .expect("ICE compiler added duplicate friends to public(package) friend list");
     }
 
-    for (_, mident, mdef) in &typed_modules {
-        unused_module_members(context, mident, mdef);
+    let call_graph = call_graph(&mut typed_modules);
+    let package_calls = package_calls(&typed_modules, &call_graph);
+    for (mident, mdef) in typed_modules.key_cloned_iter() {
+        unused_module_members(context, mident, mdef, &call_graph, &package_calls);
     }
 
-    typed_modules
+    (typed_modules, call_graph)
 }
 
 fn module(
@@ -176,12 +202,16 @@ fn module(
     context.current_package = package_name;
     context.env.add_warning_filter_scope(warning_filter.clone());
     context.add_use_funs_scope(use_funs);
+    let config = context.env.package_config(package_name);
+    let check_sui_rules = config.flavor == Flavor::Sui && !config.is_dependency && is_source_module;
     structs
-        .iter_mut()
-        .for_each(|(_, _, s)| struct_def(context, s));
+        .key_cloned_iter_mut()
+        .for_each(|(name, s)| struct_def(context, name, s, check_sui_rules));
     process_attributes(context, &attributes);
-    let constants = nconstants.map(|name, c| constant(context, name, c));
-    let functions = nfunctions.map(|name, f| function(context, name, f));
+    let mut constants = nconstants.map(|name, c| constant(context, name, c));
+    let functions = nfunctions.map(|name, f| function(context, name, f, check_sui_rules));
+    const_functions::module(context, ident, &mut constants, &functions);
+    check_error_constant_values(context, &constants);
     assert!(context.constraints.is_empty());
     context.current_package = None;
     let use_funs = context.pop_use_funs_scope();
@@ -210,11 +240,17 @@ fn module(
 // Functions
 //**************************************************************************************************
 
-fn function(context: &mut Context, name: FunctionName, f: N::Function) -> T::Function {
+fn function(
+    context: &mut Context,
+    name: FunctionName,
+    f: N::Function,
+    check_sui_rules: bool,
+) -> T::Function {
     let N::Function {
         warning_filter,
         index,
         attributes,
+        test_only,
         visibility,
         entry,
         macro_,
@@ -226,6 +262,9 @@ fn function(context: &mut Context, name: FunctionName, f: N::Function) -> T::Fun
     context.reset_for_module_item();
     context.current_function = Some(name);
     context.in_macro_function = macro_.is_some();
+    if context.env.flags().record_metrics() {
+        context.metrics = Some(FunctionMetrics::default());
+    }
     process_attributes(context, &attributes);
     let visibility =
         match public_testing_visibility(context.env, context.current_package, &name, entry) {
@@ -234,32 +273,192 @@ fn function(context: &mut Context, name: FunctionName, f: N::Function) -> T::Fun
         };
     function_signature(context, macro_, &signature);
     expand::function_signature(context, &mut signature);
+    if attributes.contains_key_(&SyntaxAttribute.into()) {
+        index_function_signature(context, name, &signature);
+    }
+    // 'entry' is already reported as meaningless on macros
+    if let (Some(entry_loc), true, None) = (entry, check_sui_rules, macro_) {
+        sui_mode::typing::entry_signature(
+            context.env,
+            &context.modules,
+            entry_loc,
+            name,
+            &signature,
+        );
+    }
+    if check_sui_rules && name.value() == sui_mode::INIT_FUNCTION_NAME {
+        sui_mode::typing::init_function(
+            context.env,
+            &context.modules,
+            context.current_module.unwrap(),
+            name,
+            visibility,
+            entry,
+            &signature,
+        );
+    }
 
     let body = if macro_.is_some() {
         sp(n_body.loc, T::FunctionBody_::Macro)
     } else {
         let body = function_body(context, n_body);
         unused_let_muts(context);
+        if context.env.is_warning_enabled(FILTER_READ_ONLY_MUT_PARAM) {
+            read_only_mut_params::function(context, &signature, &body);
+        }
         body
     };
-    context.current_function = None;
-    context.in_macro_function = false;
-    context.env.pop_warning_filter_scope();
-    T::Function {
+    if let Some(metrics) = context.metrics.take() {
+        let m = context.current_module.unwrap();
+        context.env.add_function_metrics(m, name, metrics);
+    }
+    let fdef = T::Function {
         warning_filter,
         index,
         attributes,
+        test_only,
         visibility,
         entry,
         macro_,
         signature,
         body,
+    };
+    if let Some(attr) = fdef.attributes.get_(&ConstFunAttribute.into()) {
+        const_function(context, name, attr.loc, &fdef);
+    }
+    context.current_function = None;
+    context.in_macro_function = false;
+    context.env.pop_warning_filter_scope();
+    fdef
+}
+
+/// Checks that a function marked '#[const_fun]' can be inlined into a constant: it must be a
+/// private, non-generic function with a body made only of expressions that are valid in constants
+fn const_function(context: &mut Context, name: FunctionName, attr_loc: Loc, fdef: &T::Function) {
+    let invalid = |context: &mut Context, loc: Loc, msg: &str| {
+        let attr_msg = format!(
+            "'{name}' is declared as a '#[{}]' function here",
+            ConstFunAttribute::CONST_FUN
+        );
+        context.add_diag(diag!(
+            TypeSafety::InvalidConstFunction,
+            (loc, msg),
+            (attr_loc, attr_msg)
+        ));
+    };
+    let body = match &fdef.body.value {
+        T::FunctionBody_::Defined(body) => body,
+        T::FunctionBody_::Native => {
+            invalid(
+                context,
+                name.0.loc,
+                "Native functions cannot be evaluated in constants",
+            );
+            return;
+        }
+        T::FunctionBody_::Macro => {
+            invalid(
+                context,
+                name.0.loc,
+                "Macro functions cannot be evaluated in constants",
+            );
+            return;
+        }
+    };
+    if let Some(vis_loc) = fdef.visibility.loc() {
+        let msg = format!(
+            "'#[{}]' functions must be private",
+            ConstFunAttribute::CONST_FUN
+        );
+        invalid(context, vis_loc, &msg);
+    }
+    if let Some(entry_loc) = fdef.entry {
+        let msg = format!(
+            "'#[{}]' functions cannot be 'entry'",
+            ConstFunAttribute::CONST_FUN
+        );
+        invalid(context, entry_loc, &msg);
+    }
+    if let Some(tparam) = fdef.signature.type_parameters.first() {
+        let msg = format!(
+            "'#[{}]' functions cannot have type parameters",
+            ConstFunAttribute::CONST_FUN
+        );
+        invalid(context, tparam.user_specified_name.loc, &msg);
+    }
+    for (_, _, ty) in &fdef.signature.parameters {
+        check_valid_constant::signature(
+            context,
+            || "Unpermitted parameter type",
+            TypeSafety::InvalidConstFunction,
+            ty,
+        );
+    }
+    check_valid_constant::signature(
+        context,
+        || "Unpermitted return type",
+        TypeSafety::InvalidConstFunction,
+        &fdef.signature.return_type,
+    );
+    check_valid_constant::sequence(context, body);
+}
+
+/// Checks that a function marked '#[syntax(index)]' takes a reference to the indexed type as its
+/// first parameter, and returns a reference of the same mutability
+fn index_function_signature(context: &mut Context, name: FunctionName, sig: &N::FunctionSignature) {
+    use Type_::*;
+    let fmsg = || {
+        format!(
+            "Invalid index function '{name}', declared with '#[{}({})]'",
+            SyntaxAttribute::SYNTAX,
+            SyntaxAttribute::INDEX,
+        )
+    };
+    let subject_mut = match sig.parameters.first() {
+        Some((_, _, sp!(_, Ref(mut_, inner)))) if matches!(&inner.value, Apply(_, _, _)) => *mut_,
+        Some((_, _, ty)) => {
+            let msg = format!(
+                "Expected a reference to a struct or builtin type as the first parameter. Got: {}",
+                core::error_format(ty, &Subst::empty())
+            );
+            context.add_diag(diag!(
+                Declarations::InvalidSyntaxMethod,
+                (name.0.loc, fmsg()),
+                (ty.loc, msg),
+            ));
+            return;
+        }
+        None => {
+            let msg = format!(
+                "{}. Expected a reference to the indexed type as the first parameter",
+                fmsg()
+            );
+            context.add_diag(diag!(Declarations::InvalidSyntaxMethod, (name.0.loc, msg)));
+            return;
+        }
+    };
+    let ret_ty = &sig.return_type;
+    if !matches!(&ret_ty.value, Ref(mut_, _) if *mut_ == subject_mut) {
+        let expected = if subject_mut { "&mut" } else { "&" };
+        let msg = format!(
+            "Expected a '{expected}' return type, matching the first parameter. Got: {}",
+            core::error_format(ret_ty, &Subst::empty())
+        );
+        context.add_diag(diag!(
+            Declarations::InvalidSyntaxMethod,
+            (name.0.loc, fmsg()),
+            (ret_ty.loc, msg),
+        ));
     }
 }
 
 fn function_signature(context: &mut Context, macro_: Option<Loc>, sig: &N::FunctionSignature) {
     assert!(context.constraints.is_empty());
 
+    for (_, _, param_ty) in &sig.parameters {
+        check_declared_type(context, param_ty, true);
+    }
+    check_declared_type(context, &sig.return_type, true);
     for (mut_, param, param_ty) in &sig.parameters {
         let mut param_ty = param_ty.clone();
         if macro_.is_some() {
@@ -282,32 +481,140 @@ fn function_signature(context: &mut Context, macro_: Option<Loc>, sig: &N::Funct
     core::solve_constraints(context);
 }
 
+/// Warns on any deprecated structs named in a declared type, including the type annotations and
+/// casts in function bodies. If `check_test_only` is set, also reports any test-only structs named
+/// outside of a testing context
+fn check_declared_type(context: &mut Context, sp!(_, ty_): &Type, check_test_only: bool) {
+    match ty_ {
+        Type_::Apply(_, sp!(tn_loc, tn_), tys) => {
+            if let TypeName_::ModuleType(m, n) = tn_ {
+                context.check_deprecated_usage(*tn_loc, AttributePosition::Struct, m, n.0.value);
+                if check_test_only {
+                    let test_only = context.struct_test_only(m, n);
+                    context.check_test_only_usage(
+                        *tn_loc,
+                        AttributePosition::Struct,
+                        m,
+                        n.0.value,
+                        test_only,
+                    );
+                }
+            }
+            for ty in tys {
+                check_declared_type(context, ty, check_test_only)
+            }
+        }
+        Type_::Ref(_, ty) => check_declared_type(context, ty, check_test_only),
+        Type_::Fun(args, ret) => {
+            for ty in args {
+                check_declared_type(context, ty, check_test_only)
+            }
+            check_declared_type(context, ret, check_test_only)
+        }
+        Type_::Unit
+        | Type_::Param(_)
+        | Type_::Var(_)
+        | Type_::Anything
+        | Type_::Hole
+        | Type_::UnresolvedError => (),
+    }
+}
+
 fn function_body(context: &mut Context, sp!(loc, nb_): N::FunctionBody) -> T::FunctionBody {
     assert!(context.constraints.is_empty());
     let mut b_ = match nb_ {
         N::FunctionBody_::Native => T::FunctionBody_::Native,
         N::FunctionBody_::Defined(es) => {
-            let seq = sequence(context, es);
+            let ret_loc = match &context.return_type {
+                Some(sp!(_, Type_::Unit)) | None => None,
+                Some(ret_ty) => Some(ret_ty.loc),
+            };
+            if let (Some(sp!(_, N::SequenceItem_::Seq(last))), Some(ret_loc)) =
+                (es.1.back(), ret_loc)
+            {
+                if is_if_without_else(last) {
+                    let msg = "The 'if' is the value returned by the function";
+                    context.missing_else_use = Some((last.loc, ret_loc, msg));
+                }
+            }
+            let mut seq = sequence(context, es);
+            if let Some(sp!(_, T::SequenceItem_::Seq(e))) = seq.1.back_mut() {
+                check_return_temp_borrow(context, e);
+            }
             let ety = sequence_type(&seq);
             let ret_ty = context.return_type.clone().unwrap();
-            let (_, seq_items) = &seq;
-            let sloc = seq_items.back().unwrap().loc;
-            subtype(
-                context,
-                sloc,
-                || "Invalid return expression",
-                ety.clone(),
-                ret_ty,
-            );
+            let lhs = core::ready_tvars(&context.subst, ety.clone());
+            let rhs = core::ready_tvars(&context.subst, ret_ty.clone());
+            // On failure, `core::subtype` leaves the substitution unchanged
+            if core::subtype(&mut context.subst, &lhs, &rhs).is_err() {
+                // Report the error at the tail expressions instead of at the entire final
+                // expression, which can span many lines if it is a block or an if-else
+                let mut tails = vec![];
+                sequence_tails(&seq, &mut tails);
+                for (tloc, tty) in tails {
+                    subtype(
+                        context,
+                        tloc,
+                        || "Invalid return expression",
+                        tty,
+                        ret_ty.clone(),
+                    );
+                }
+            }
             T::FunctionBody_::Defined(seq)
         }
     };
+    context.reported_tparam_abilities = Some(BTreeSet::new());
     core::solve_constraints(context);
+    context.reported_tparam_abilities = None;
+    record_dotted_segments(context, loc);
     expand::function_body_(context, &mut b_);
+    use_after_move::function_body_(context, &mut b_);
     // freeze::function_body_(context, &mut b_);
     sp(loc, b_)
 }
 
+/// Keeps the dotted path segment types of the function, now that its constraints are solved.
+/// Segments outside of the body were substituted in from a macro body, and are skipped
+fn record_dotted_segments(context: &mut Context, body_loc: Loc) {
+    for (loc, ty) in std::mem::take(&mut context.dotted_segments) {
+        if body_loc.contains(loc) {
+            let ty = core::ready_tvars(&context.subst, ty);
+            context.dotted_segment_types.entry(loc).or_insert(ty);
+        }
+    }
+}
+
+/// Reports returning a borrow of a value created in the function, e.g. `&S { f: 0 }` or
+/// `&make()`. The borrows pass in CFGIR also rejects these, but reports them at the temporary
+/// local introduced for the value. Once reported here, the return is replaced by an error so it is
+/// not reported again
+fn check_return_temp_borrow(context: &mut Context, e: &mut T::Exp) {
+    use T::UnannotatedExp_ as TE;
+    let TE::TempBorrow(_, borrowed) = &e.exp.value else {
+        return;
+    };
+    let is_temporary = match &borrowed.exp.value {
+        TE::Pack(..) | TE::Vector(..) => true,
+        TE::ModuleCall(_) => !matches!(
+            core::unfold_type(&context.subst, borrowed.ty.clone()).value,
+            Type_::Ref(_, _)
+        ),
+        _ => false,
+    };
+    if !is_temporary {
+        return;
+    }
+    let msg = "Cannot return a reference to a temporary value created in this function";
+    let tmp_msg = "The temporary value is created here, and is dropped when the function returns";
+    context.add_diag(diag!(
+        ReferenceSafety::InvalidReturn,
+        (e.exp.loc, msg),
+        (borrowed.exp.loc, tmp_msg),
+    ));
+    e.exp.value = TE::UnresolvedError;
+}
+
 //**************************************************************************************************
 // Constants
 //**************************************************************************************************
@@ -320,6 +627,7 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
         warning_filter,
         index,
         attributes,
+        test_only,
         loc,
         signature,
         value: nvalue,
@@ -332,7 +640,6 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
     let mut signature = core::instantiate(context, signature);
     check_valid_constant::signature(
         context,
-        signature.loc,
         || "Unpermitted constant type",
         TypeSafety::TypeForConstant,
         &signature,
@@ -354,25 +661,59 @@ fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant)
     expand::exp(context, &mut value);
 
     check_valid_constant::exp(context, &value);
+    let folded_value = constant_eval::constant_value(context, &mut value);
     context.env.pop_warning_filter_scope();
 
     T::Constant {
         warning_filter,
         index,
         attributes,
+        test_only,
         loc,
         signature,
         value: *value,
+        folded_value,
+    }
+}
+
+/// Reports the 'u64' '#[error]' constants that have the bit of `ERROR_CONSTANT_ABORT_CODE_TAG`
+/// set. They abort with their own value, which could not be told apart from the abort code of
+/// another '#[error]' constant
+fn check_error_constant_values(
+    context: &mut Context,
+    constants: &UniqueMap<ConstantName, T::Constant>,
+) {
+    for (loc, name, cdef) in constants {
+        if !cdef.attributes.contains_key_(&ErrorAttribute.into()) {
+            continue;
+        }
+        let Some(MoveValue::U64(value)) = &cdef.folded_value else {
+            continue;
+        };
+        if *value & ERROR_CONSTANT_ABORT_CODE_TAG == 0 {
+            continue;
+        }
+        let msg = format!(
+            "Invalid '#[{}]' constant '{name}'. A 'u64' error constant must be less than {}",
+            ErrorAttribute::ERROR,
+            ERROR_CONSTANT_ABORT_CODE_TAG,
+        );
+        let mut diag = diag!(TypeSafety::InvalidErrorConstant, (loc, msg));
+        diag.add_note(format!(
+            "Abort codes from {} are used by the '#[{}]' constants that are not a 'u64'",
+            ERROR_CONSTANT_ABORT_CODE_TAG,
+            ErrorAttribute::ERROR,
+        ));
+        context.add_diag(diag);
     }
 }
 
 mod check_valid_constant {
-    use super::subtype_no_report;
     use crate::{
         diag,
         diagnostics::codes::DiagnosticCode,
-        naming::ast::{Type, Type_},
-        shared::*,
+        naming::ast::{BuiltinTypeName_, Type, TypeName_, Type_},
+        shared::{known_attributes::ConstFunAttribute, *},
         typing::{
             ast as T,
             core::{self, Context, Subst},
@@ -382,14 +723,17 @@ mod check_valid_constant {
 
     pub(crate) fn signature<T: ToString, F: FnOnce() -> T>(
         context: &mut Context,
-        sloc: Loc,
         fmsg: F,
         code: impl DiagnosticCode,
         ty: &Type,
     ) {
-        let loc = ty.loc;
-
-        let mut acceptable_types = vec![
+        let ty = core::ready_tvars(&context.subst, ty.clone());
+        let Some(invalid_ty) = invalid_signature_type(&ty) else {
+            return;
+        };
+        // report the innermost invalid type, e.g. 'signer' in 'vector<vector<signer>>'
+        let loc = invalid_ty.loc;
+        let acceptable_types = [
             Type_::u8(loc),
             Type_::u16(loc),
             Type_::u32(loc),
@@ -398,40 +742,40 @@ mod check_valid_constant {
             Type_::u256(loc),
             Type_::bool(loc),
             Type_::address(loc),
+            Type_::vector(loc, sp(loc, Type_::Anything)),
         ];
-        let ty_is_an_acceptable_type = acceptable_types.iter().any(|acceptable_type| {
-            let old_subst = context.subst.clone();
-            let result = subtype_no_report(context, ty.clone(), acceptable_type.clone());
-            context.subst = old_subst;
-            result.is_ok()
-        });
-        if ty_is_an_acceptable_type {
-            return;
-        }
-
-        let inner_tvar = core::make_tvar(context, sloc);
-        let vec_ty = Type_::vector(sloc, inner_tvar.clone());
-        let old_subst = context.subst.clone();
-        let is_vec = subtype_no_report(context, ty.clone(), vec_ty.clone()).is_ok();
-        let inner = core::ready_tvars(&context.subst, inner_tvar);
-        context.subst = old_subst;
-        if is_vec {
-            signature(context, sloc, fmsg, code, &inner);
-            return;
-        }
-
-        acceptable_types.push(vec_ty);
         let tys = acceptable_types
             .iter()
             .map(|t| core::error_format(t, &Subst::empty()));
         let tmsg = format!(
             "Found: {}. But expected one of: {}",
-            core::error_format(ty, &Subst::empty()),
+            core::error_format(invalid_ty, &Subst::empty()),
             format_comma(tys),
         );
-        context
-            .env
-            .add_diag(diag!(code, (sloc, fmsg()), (loc, tmsg)))
+        // the found type is at the same loc as the primary label, so it is given as a note
+        let mut diag = diag!(code, (loc, fmsg()));
+        diag.add_note(tmsg);
+        context.add_diag(diag)
+    }
+
+    /// Returns the innermost type that is not permitted in a constant signature, looking through
+    /// any number of nested vectors. Errors and unresolved types are reported elsewhere.
+    fn invalid_signature_type(ty: &Type) -> Option<&Type> {
+        use BuiltinTypeName_ as BT;
+        match &ty.value {
+            Type_::UnresolvedError | Type_::Anything | Type_::Hole | Type_::Var(_) => None,
+            Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, bt))), tys) => match bt {
+                BT::U8 | BT::U16 | BT::U32 | BT::U64 | BT::U128 | BT::U256 => None,
+                BT::Bool | BT::Address => None,
+                BT::Vector => tys.iter().find_map(invalid_signature_type),
+                BT::Signer => Some(ty),
+            },
+            Type_::Unit
+            | Type_::Ref(_, _)
+            | Type_::Param(_)
+            | Type_::Fun(_, _)
+            | Type_::Apply(_, _, _) => Some(ty),
+        }
     }
 
     pub fn exp(context: &mut Context, e: &T::Exp) {
@@ -478,9 +822,15 @@ mod check_valid_constant {
                 return;
             }
 
-            // NB: module scoping is checked during constant type creation, so we don't need to
-            // relitigate here.
-            E::Constant(_, _) => {
+            // NB: module scoping is checked during naming and constant type creation, so we don't
+            // need to relitigate here.
+            E::Constant(_, _) | E::ErrorConstant(_, _) => {
+                return;
+            }
+
+            // '#[const_fun]' functions are inlined into the constant
+            E::ModuleCall(call) if is_const_fun_call(context, call) => {
+                exp(context, &call.arguments);
                 return;
             }
 
@@ -544,12 +894,22 @@ mod check_valid_constant {
                 "Structs are"
             }
         };
-        context.env.add_diag(diag!(
+        context.add_diag(diag!(
             TypeSafety::UnsupportedConstant,
             (*loc, format!("{} not supported in constants", error_case))
         ));
     }
 
+    /// Calls to '#[const_fun]' functions are only valid in the module of the function, as these
+    /// functions are private
+    fn is_const_fun_call(context: &Context, call: &T::ModuleCall) -> bool {
+        context.current_module.as_ref() == Some(&call.module)
+            && context
+                .function_info(&call.module, &call.name)
+                .attributes
+                .contains_key_(&ConstFunAttribute.into())
+    }
+
     fn exp_list(context: &mut Context, items: &[T::ExpListItem]) {
         for item in items {
             exp_list_item(context, item)
@@ -568,7 +928,7 @@ mod check_valid_constant {
         }
     }
 
-    fn sequence(context: &mut Context, (_, seq): &T::Sequence) {
+    pub fn sequence(context: &mut Context, (_, seq): &T::Sequence) {
         for item in seq {
             sequence_item(context, item)
         }
@@ -589,9 +949,7 @@ mod check_valid_constant {
             }
         };
         let msg = format!("{} are not supported in constants", error_case);
-        context
-            .env
-            .add_diag(diag!(TypeSafety::UnsupportedConstant, (*loc, msg),))
+        context.add_diag(diag!(TypeSafety::UnsupportedConstant, (*loc, msg),))
     }
 }
 
@@ -599,7 +957,12 @@ mod check_valid_constant {
 // Structs
 //**************************************************************************************************
 
-fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
+fn struct_def(
+    context: &mut Context,
+    name: StructName,
+    s: &mut N::StructDefinition,
+    check_sui_objects: bool,
+) {
     assert!(context.constraints.is_empty());
     context.reset_for_module_item();
     context
@@ -608,11 +971,17 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
 
     let field_map = match &mut s.fields {
         N::StructFields::Native(_) => return,
-        N::StructFields::Defined(m) => m,
+        N::StructFields::Defined(_, m) => m,
     };
 
-    // instantiate types and check constraints
+    for (_field_loc, _field, idx_ty) in field_map.iter_mut() {
+        check_field_function_types(context, &mut idx_ty.1);
+    }
+
+    // instantiate types and check constraints. Test-only structs can have test-only fields
+    let check_test_only = !s.attributes.is_test_or_test_only();
     for (_field_loc, _field, idx_ty) in field_map.iter() {
+        check_declared_type(context, &idx_ty.1, check_test_only);
         let loc = idx_ty.1.loc;
         let inst_ty = core::instantiate(context, idx_ty.1.clone());
         context.add_base_type_constraint(loc, "Invalid field type", inst_ty.clone());
@@ -628,10 +997,21 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
             .iter()
             .map(|tp| sp(tp.param.user_specified_name.loc, Type_::Anything)),
     );
-    for (_field_loc, _field, idx_ty) in field_map.iter() {
+    // Sui objects cannot have 'drop', which is reported by `object_struct_def` rather than as the
+    // 'id' field missing an ability
+    let object_with_drop = check_sui_objects
+        && declared_abilities.has_ability_(Ability_::Key)
+        && declared_abilities.has_ability_(Ability_::Drop);
+    for (_field_loc, field, idx_ty) in field_map.iter() {
         let loc = idx_ty.1.loc;
         let subst_ty = core::subst_tparams(tparam_subst, idx_ty.1.clone());
         for declared_ability in declared_abilities {
+            if object_with_drop
+                && declared_ability.value == Ability_::Drop
+                && *field == sui_mode::ID_FIELD_NAME
+            {
+                continue;
+            }
             let required = declared_ability.value.requires();
             let msg = format!(
                 "Invalid field type. The struct was declared with the ability '{}' so all fields \
@@ -647,9 +1027,39 @@ fn struct_def(context: &mut Context, s: &mut N::StructDefinition) {
         expand::type_(context, &mut idx_ty.1);
     }
     check_type_params_usage(context, &s.type_parameters, field_map);
+    if check_sui_objects {
+        sui_mode::typing::object_struct_def(context.env, name, s);
+    }
     context.env.pop_warning_filter_scope();
 }
 
+/// Function types cannot be stored, so they are reported and replaced with an error type before
+/// any constraints are checked on the field, to avoid further less specific errors
+fn check_field_function_types(context: &mut Context, ty: &mut Type) {
+    match &mut ty.value {
+        Type_::Fun(_, _) => {
+            if context.check_feature(FeatureGate::MacroFuns, ty.loc) {
+                let msg = "Function types are only usable in 'macro' signatures and cannot be \
+                    stored in fields";
+                context.add_diag(diag!(TypeSafety::UnexpectedFunctionType, (ty.loc, msg)));
+            }
+            *ty = sp(ty.loc, Type_::UnresolvedError)
+        }
+        Type_::Ref(_, inner) => check_field_function_types(context, inner),
+        Type_::Apply(_, _, ty_args) => {
+            for ty_arg in ty_args {
+                check_field_function_types(context, ty_arg)
+            }
+        }
+        Type_::Param(_)
+        | Type_::Unit
+        | Type_::Var(_)
+        | Type_::Anything
+        | Type_::Hole
+        | Type_::UnresolvedError => (),
+    }
+}
+
 fn check_type_params_usage(
     context: &mut Context,
     type_parameters: &[N::StructTypeParameter],
@@ -771,6 +1181,8 @@ fn visit_type_params(
                 }
             }
         },
+        // Function types cannot appear in structs, as they are replaced with an error type in
+        // `struct_def`, but we still report them as a non-phantom position for full information.
         Type_::Fun(args, result) => {
             for ty in args {
                 visit_type_params(context, ty, ParamPos::NonPhantom(NonPhantomPos::TypeArg), f)
@@ -782,7 +1194,7 @@ fn visit_type_params(
                 f,
             )
         }
-        Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => {}
+        Type_::Var(_) | Type_::Anything | Type_::Hole | Type_::UnresolvedError => {}
         Type_::Unit => {}
     }
 }
@@ -800,7 +1212,7 @@ fn invalid_phantom_use_error(
         }
     };
     let decl_msg = format!("'{}' declared here as phantom", &param.user_specified_name);
-    context.env.add_diag(diag!(
+    context.add_diag(diag!(
         Declarations::InvalidPhantomUse,
         (ty_loc, msg),
         (param.user_specified_name.loc, decl_msg),
@@ -819,9 +1231,7 @@ fn check_non_phantom_param_usage(
                 "Unused type parameter '{}'. Consider declaring it as phantom",
                 name
             );
-            context
-                .env
-                .add_diag(diag!(UnusedItem::StructTypeParam, (name.loc, msg)))
+            context.add_diag(diag!(UnusedItem::StructTypeParam, (name.loc, msg)))
         }
         Some(false) => {
             let msg = format!(
@@ -829,9 +1239,7 @@ fn check_non_phantom_param_usage(
                  adding a phantom declaration here",
                 name
             );
-            context
-                .env
-                .add_diag(diag!(Declarations::InvalidNonPhantomUse, (name.loc, msg)))
+            context.add_diag(diag!(Declarations::InvalidNonPhantomUse, (name.loc, msg)))
         }
         Some(true) => {}
     }
@@ -845,7 +1253,7 @@ fn has_unresolved_error_type(ty: &Type) -> bool {
         Type_::Fun(args, result) => {
             args.iter().any(has_unresolved_error_type) || has_unresolved_error_type(result)
         }
-        Type_::Param(_) | Type_::Var(_) | Type_::Anything | Type_::Unit => false,
+        Type_::Param(_) | Type_::Var(_) | Type_::Anything | Type_::Hole | Type_::Unit => false,
     }
 }
 
@@ -957,7 +1365,9 @@ fn typing_error<T: ToString, F: FnOnce() -> T>(
                     t2_str
                 )
             };
-            diag!(TypeSafety::JoinError, (loc, msg), (loc1, m1), (loc2, m2))
+            let mut diag = diag!(TypeSafety::JoinError, (loc, msg), (loc1, m1), (loc2, m2));
+            add_expression_list_elements(context, &mut diag, &t1, &t2);
+            diag
         }
         RecursiveType(rloc) => diag!(
             TypeSafety::RecursiveType,
@@ -967,18 +1377,46 @@ fn typing_error<T: ToString, F: FnOnce() -> T>(
     }
 }
 
-fn subtype_no_report(
-    context: &mut Context,
-    pre_lhs: Type,
-    pre_rhs: Type,
-) -> Result<Type, core::TypingError> {
-    let subst = std::mem::replace(&mut context.subst, Subst::empty());
-    let lhs = core::ready_tvars(&subst, pre_lhs);
-    let rhs = core::ready_tvars(&subst, pre_rhs);
-    core::subtype(subst, &lhs, &rhs).map(|(next_subst, ty)| {
-        context.subst = next_subst;
-        ty
-    })
+/// If an expression list is used where a single value is expected, or vice versa, labels each
+/// element of the expression list with its type. The locations of the elements are only known if
+/// the expression list is written out, e.g. '(a, b)', otherwise their types are listed in a note
+fn add_expression_list_elements(context: &Context, diag: &mut Diagnostic, t1: &Type, t2: &Type) {
+    use TypeName_::Multiple;
+    use Type_::*;
+    let subst = &context.subst;
+    let is_single = |ty: &Type| match &ty.value {
+        Apply(_, sp!(_, Multiple(_)), _) | Unit | Var(_) | Anything | Hole | UnresolvedError => {
+            false
+        }
+        Apply(_, _, _) | Ref(_, _) | Param(_) | Fun(_, _) => true,
+    };
+    let t1 = core::unfold_type(subst, t1.clone());
+    let t2 = core::unfold_type(subst, t2.clone());
+    let (list_loc, tys) = match (&t1, &t2) {
+        (sp!(loc, Apply(_, sp!(_, Multiple(_)), tys)), single) if is_single(single) => (loc, tys),
+        (single, sp!(loc, Apply(_, sp!(_, Multiple(_)), tys))) if is_single(single) => (loc, tys),
+        _ => return,
+    };
+    match context.expression_list_elements.get(list_loc) {
+        Some(locs) if locs.len() == tys.len() => {
+            for (idx, (ty, loc)) in tys.iter().zip(locs).enumerate() {
+                let ty_str = core::error_format(ty, subst);
+                diag.add_secondary_label((*loc, format!("Element {} has type {}", idx + 1, ty_str)))
+            }
+        }
+        _ => {
+            let tys_str = tys
+                .iter()
+                .map(|ty| core::error_format(ty, subst))
+                .collect::<Vec<_>>()
+                .join(", ");
+            diag.add_note(format!(
+                "The expression list has {} elements, of types {}",
+                tys.len(),
+                tys_str
+            ))
+        }
+    }
 }
 
 fn subtype_impl<T: ToString, F: FnOnce() -> T>(
@@ -988,20 +1426,15 @@ fn subtype_impl<T: ToString, F: FnOnce() -> T>(
     pre_lhs: Type,
     pre_rhs: Type,
 ) -> Result<Type, Type> {
-    let subst = std::mem::replace(&mut context.subst, Subst::empty());
-    let lhs = core::ready_tvars(&subst, pre_lhs);
-    let rhs = core::ready_tvars(&subst, pre_rhs);
-    match core::subtype(subst.clone(), &lhs, &rhs) {
+    let lhs = core::ready_tvars(&context.subst, pre_lhs);
+    let rhs = core::ready_tvars(&context.subst, pre_rhs);
+    match core::subtype(&mut context.subst, &lhs, &rhs) {
         Err(e) => {
-            context.subst = subst;
             let diag = typing_error(context, /* from_subtype */ true, loc, msg, e);
-            context.env.add_diag(diag);
+            context.add_diag(diag);
             Err(rhs)
         }
-        Ok((next_subst, ty)) => {
-            context.subst = next_subst;
-            Ok(ty)
-        }
+        Ok(ty) => Ok(ty),
     }
 }
 
@@ -1031,6 +1464,73 @@ fn subtype<T: ToString, F: FnOnce() -> T>(
     }
 }
 
+/// Like `subtype`, but adds the secondary `labels` to the error, if any
+fn subtype_labeled<T: ToString, F: FnOnce() -> T>(
+    context: &mut Context,
+    loc: Loc,
+    msg: F,
+    labels: impl IntoIterator<Item = (Loc, String)>,
+    pre_lhs: Type,
+    pre_rhs: Type,
+) {
+    let lhs = core::ready_tvars(&context.subst, pre_lhs);
+    let rhs = core::ready_tvars(&context.subst, pre_rhs);
+    if let Err(e) = core::subtype(&mut context.subst, &lhs, &rhs) {
+        let mut diag = typing_error(context, /* from_subtype */ true, loc, msg, e);
+        for label in labels {
+            diag.add_secondary_label(label);
+        }
+        context.add_diag(diag);
+    }
+}
+
+/// If the module member `m::n` was named through a renaming alias, e.g. 'Bar' in
+/// 'use a::m::{Foo as Bar}', a suffix for error messages about the member mentioning the alias,
+/// and a label for the declaration of the alias. Otherwise an empty suffix and no label
+fn member_alias(context: &Context, m: &ModuleIdent, n: Name) -> (String, Option<(Loc, String)>) {
+    match context.env.member_alias(n.loc) {
+        None => (String::new(), None),
+        Some(alias) => {
+            let suffix = format!(" (aliased as '{alias}' here)");
+            let label = format!("'{alias}' is declared as an alias for '{m}::{n}' here");
+            (suffix, Some((alias.loc, label)))
+        }
+    }
+}
+
+const LAMBDA_RETURN_NOTE: &str = "A 'return' inside of a lambda returns from the lambda, not \
+    from the enclosing function. To exit an enclosing block instead, label that block and use \
+    a labeled return, e.g. 'return 'label value'";
+
+/// Like `subtype`, but for the value of a 'return' in a lambda, which might have been intended to
+/// return from the enclosing function
+fn subtype_lambda_return(context: &mut Context, loc: Loc, pre_lhs: Type, pre_rhs: Type) {
+    let lhs = core::ready_tvars(&context.subst, pre_lhs);
+    let rhs = core::ready_tvars(&context.subst, pre_rhs);
+    if let Err(e) = core::subtype(&mut context.subst, &lhs, &rhs) {
+        let msg = || "Invalid return";
+        let mut diag = typing_error(context, /* from_subtype */ true, loc, msg, e);
+        diag.add_note(LAMBDA_RETURN_NOTE);
+        context.add_diag(diag);
+    }
+}
+
+/// Like `join`, but for the type of a lambda body and the type of the values returned from it
+fn join_lambda_return(context: &mut Context, loc: Loc, pre_t1: Type, pre_t2: Type) -> Type {
+    let t1 = core::ready_tvars(&context.subst, pre_t1);
+    let t2 = core::ready_tvars(&context.subst, pre_t2);
+    match core::join(&mut context.subst, &t1, &t2) {
+        Err(e) => {
+            let msg = || "Invalid lambda return";
+            let mut diag = typing_error(context, /* from_subtype */ false, loc, msg, e);
+            diag.add_note(LAMBDA_RETURN_NOTE);
+            context.add_diag(diag);
+            context.error_type(loc)
+        }
+        Ok(ty) => ty,
+    }
+}
+
 fn join_opt<T: ToString, F: FnOnce() -> T>(
     context: &mut Context,
     loc: Loc,
@@ -1038,20 +1538,15 @@ fn join_opt<T: ToString, F: FnOnce() -> T>(
     pre_t1: Type,
     pre_t2: Type,
 ) -> Option<Type> {
-    let subst = std::mem::replace(&mut context.subst, Subst::empty());
-    let t1 = core::ready_tvars(&subst, pre_t1);
-    let t2 = core::ready_tvars(&subst, pre_t2);
-    match core::join(subst.clone(), &t1, &t2) {
+    let t1 = core::ready_tvars(&context.subst, pre_t1);
+    let t2 = core::ready_tvars(&context.subst, pre_t2);
+    match core::join(&mut context.subst, &t1, &t2) {
         Err(e) => {
-            context.subst = subst;
             let diag = typing_error(context, /* from_subtype */ false, loc, msg, e);
-            context.env.add_diag(diag);
+            context.add_diag(diag);
             None
         }
-        Ok((next_subst, ty)) => {
-            context.subst = next_subst;
-            Some(ty)
-        }
+        Ok(ty) => Some(ty),
     }
 }
 
@@ -1112,11 +1607,18 @@ fn sequence(context: &mut Context, (use_funs, seq): N::Sequence) -> T::Sequence
                 work_queue.push_front(SeqCase::Seq(loc, e));
             }
             NS::Declare(nbind, ty_opt) => {
+                if let Some(ty) = &ty_opt {
+                    check_declared_type(context, ty, false);
+                }
                 let instantiated_ty_op = ty_opt.map(|t| core::instantiate(context, t));
                 let b = bind_list(context, nbind, instantiated_ty_op);
                 work_queue.push_front(SeqCase::Declare { loc, b });
             }
             NS::Bind(nbind, nr) => {
+                if is_if_without_else(&nr) {
+                    let msg = "The value of the 'if' is bound here";
+                    context.missing_else_use = Some((nr.loc, nbind.loc, msg));
+                }
                 let e = exp(context, nr);
                 let b = bind_list(context, nbind, Some(e.ty.clone()));
                 work_queue.push_front(SeqCase::Bind { loc, b, e });
@@ -1149,6 +1651,74 @@ fn sequence_type((_, seq): &T::Sequence) -> &Type {
     }
 }
 
+/// Like `sequence_type`, but follows the final expression through blocks and if-else branches,
+/// collecting the loc and type of each expression that can produce the value of the sequence
+fn sequence_tails((_, seq): &T::Sequence, tails: &mut Vec<(Loc, Type)>) {
+    use T::SequenceItem_ as TS;
+    match seq.back().unwrap() {
+        sp!(_, TS::Bind(_, _, _)) | sp!(_, TS::Declare(_)) => {
+            panic!("ICE unit should have been inserted past bind/decl")
+        }
+        sp!(_, TS::Seq(last_e)) => exp_tails(last_e, tails),
+    }
+}
+
+fn exp_tails(e: &T::Exp, tails: &mut Vec<(Loc, Type)>) {
+    use T::UnannotatedExp_ as TE;
+    match &e.exp.value {
+        TE::Block(seq) => sequence_tails(seq, tails),
+        TE::IfElse(_, et, ef) => {
+            exp_tails(et, tails);
+            exp_tails(ef, tails);
+        }
+        _ => tails.push((e.exp.loc, e.ty.clone())),
+    }
+}
+
+/// An 'if' without an 'else' is given a '()' 'else' branch at the location of the entire 'if'
+fn is_missing_else(if_loc: Loc, nf: &N::Exp) -> bool {
+    nf.loc == if_loc && matches!(nf.value, N::Exp_::Unit { trailing: false })
+}
+
+fn is_if_without_else(e: &N::Exp) -> bool {
+    match &e.value {
+        N::Exp_::IfElse(_, _, nf) => is_missing_else(e.loc, nf),
+        _ => false,
+    }
+}
+
+/// Joins the branches of an 'if' without an 'else', reporting a 'then' branch with a value
+/// separately from incompatible branches, since the fix is to add the 'else' branch
+fn join_missing_else(
+    context: &mut Context,
+    eloc: Loc,
+    et: &T::Exp,
+    else_ty: Type,
+    value_use: Option<(Loc, &'static str)>,
+) -> Type {
+    let then_ty = core::ready_tvars(&context.subst, et.ty.clone());
+    let else_ty = core::ready_tvars(&context.subst, else_ty);
+    if let Ok(ty) = core::join(&mut context.subst, &then_ty, &else_ty) {
+        return ty;
+    }
+    let msg = "Invalid 'if' without an 'else'. An 'if' without an 'else' has type '()', \
+        but its 'then' branch has a value";
+    let then_msg = format!(
+        "The 'then' branch has type {}",
+        core::error_format(&then_ty, &context.subst)
+    );
+    let mut diag = diag!(TypeSafety::JoinError, (eloc, msg), (et.exp.loc, then_msg));
+    if let Some((use_loc, use_msg)) = value_use {
+        diag.add_secondary_label((use_loc, use_msg));
+    }
+    diag.add_note(
+        "Add an 'else' branch with a value of the same type, or discard the value of the 'then' \
+        branch, e.g. 'if (cond) { e; }'",
+    );
+    context.add_diag(diag);
+    context.error_type(eloc)
+}
+
 fn exp_vec(context: &mut Context, es: Vec<N::Exp>) -> Vec<T::Exp> {
     es.into_iter().map(|e| *exp(context, Box::new(e))).collect()
 }
@@ -1185,11 +1755,7 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
 
         NE::Constant(m, c) => {
             let ty = core::make_constant_type(context, eloc, &m, &c);
-            context
-                .used_module_members
-                .entry(m.value)
-                .or_default()
-                .insert(c.value());
+            context.mark_constant_used(&m, &c);
             (ty, TE::Constant(m, c))
         }
 
@@ -1273,6 +1839,14 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
         }
 
         NE::IfElse(nb, nt, nf) => {
+            let missing_else = is_missing_else(eloc, &nf);
+            let value_use = match context.missing_else_use {
+                Some((if_loc, use_loc, msg)) if if_loc == eloc => {
+                    context.missing_else_use = None;
+                    Some((use_loc, msg))
+                }
+                _ => None,
+            };
             let eb = exp(context, nb);
             let bloc = eb.exp.loc;
             subtype(
@@ -1284,13 +1858,17 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
             );
             let et = exp(context, nt);
             let ef = exp(context, nf);
-            let ty = join(
-                context,
-                eloc,
-                || "Incompatible branches",
-                et.ty.clone(),
-                ef.ty.clone(),
-            );
+            let ty = if missing_else {
+                join_missing_else(context, eloc, &et, ef.ty.clone(), value_use)
+            } else {
+                join(
+                    context,
+                    eloc,
+                    || "Incompatible branches",
+                    et.ty.clone(),
+                    ef.ty.clone(),
+                )
+            };
             (ty, TE::IfElse(eb, et, ef))
         }
         NE::While(name, nb, nloop) => {
@@ -1303,7 +1881,19 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
                 eb.ty.clone(),
                 Type_::bool(bloc),
             );
-            let (_has_break, ty, body) = loop_body(context, eloc, name, false, nloop);
+            // A 'while (true)' can only be exited by a 'break', so it is typed as a 'loop' and its
+            // breaks can carry a value
+            let is_loop = T::bool_literal_value(&eb) == Some(true);
+            let (has_break, ty, body) = loop_body(context, eloc, name, is_loop, nloop);
+            if is_loop && !has_break {
+                subtype(
+                    context,
+                    eloc,
+                    || "Invalid 'while' loop",
+                    ty.clone(),
+                    sp(eloc, Type_::Unit),
+                );
+            }
             (sp(eloc, ty.value), TE::While(name, eb, body))
         }
         NE::Loop(name, nloop) => {
@@ -1320,18 +1910,23 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
             from_macro_argument,
             seq: nseq,
         }) => {
-            context.maybe_enter_macro_argument(from_macro_argument, nseq.0.color);
+            let entered = context.maybe_enter_macro_argument(from_macro_argument, nseq.0.color);
             let seq = sequence(context, nseq);
             let seq_ty = sequence_type(&seq).clone();
             let res = if let Some(name) = name {
                 let final_type = if let Some(local_return_type) = context.named_block_type_opt(name)
                 {
-                    let msg = if let Some(N::MacroArgument::Lambda(_)) = from_macro_argument {
-                        || "Invalid lambda return"
+                    if let Some(N::MacroArgument::Lambda(_)) = from_macro_argument {
+                        join_lambda_return(context, eloc, seq_ty, local_return_type)
                     } else {
-                        || "Invalid named block"
-                    };
-                    join(context, eloc, msg, seq_ty, local_return_type)
+                        join(
+                            context,
+                            eloc,
+                            || "Invalid named block",
+                            seq_ty,
+                            local_return_type,
+                        )
+                    }
                 } else {
                     seq_ty
                 };
@@ -1339,19 +1934,14 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
             } else {
                 (seq_ty, TE::Block(seq))
             };
-            context.maybe_exit_macro_argument(eloc, from_macro_argument);
+            context.maybe_exit_macro_argument(eloc, entered);
             res
         }
 
         NE::Lambda(_) => {
-            if context
-                .env
-                .check_feature(FeatureGate::MacroFuns, context.current_package, eloc)
-            {
+            if context.check_feature(FeatureGate::MacroFuns, eloc) {
                 let msg = "Lambdas can only be used directly as arguments to 'macro' functions";
-                context
-                    .env
-                    .add_diag(diag!(TypeSafety::UnexpectedLambda, (eloc, msg)))
+                context.add_diag(diag!(TypeSafety::UnexpectedLambda, (eloc, msg)))
             }
             (context.error_type(eloc), TE::UnresolvedError)
         }
@@ -1380,13 +1970,14 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
         }
 
         NE::Return(nret) => {
-            let eret = exp(context, nret);
+            let mut eret = exp(context, nret);
+            check_return_temp_borrow(context, &mut eret);
             let ret_ty = context.return_type.clone().unwrap();
             subtype(context, eloc, || "Invalid return", eret.ty.clone(), ret_ty);
             (sp(eloc, Type_::Anything), TE::Return(eret))
         }
         NE::Abort(ncode) => {
-            let ecode = exp(context, ncode);
+            let ecode = Box::new(abort_code(context, *exp(context, ncode)));
             let code_ty = Type_::u64(eloc);
             subtype(context, eloc, || "Invalid abort", ecode.ty.clone(), code_ty);
             (sp(eloc, Type_::Anything), TE::Abort(ecode))
@@ -1394,38 +1985,48 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
         NE::Give(usage, name, rhs) => {
             let break_rhs = exp(context, rhs);
             let loop_ty = context.named_block_type(name, eloc);
-            subtype(
-                context,
-                eloc,
-                || format!("Invalid {usage}"),
-                break_rhs.ty.clone(),
-                loop_ty,
-            );
+            // an unlabeled 'return' can only target the return label of an enclosing lambda
+            if usage == N::NominalBlockUsage::Return && name.is_implicit {
+                subtype_lambda_return(context, eloc, break_rhs.ty.clone(), loop_ty);
+            } else {
+                subtype(
+                    context,
+                    eloc,
+                    || format!("Invalid {usage}"),
+                    break_rhs.ty.clone(),
+                    loop_ty,
+                );
+            }
             (sp(eloc, Type_::Anything), TE::Give(name, break_rhs))
         }
         NE::Continue(name) => (sp(eloc, Type_::Anything), TE::Continue(name)),
 
         NE::Dereference(nref) => {
             let eref = exp(context, nref);
-            let inner = core::make_tvar(context, eloc);
-            let ref_ty = sp(eloc, Type_::Ref(false, Box::new(inner.clone())));
-            subtype(
-                context,
-                eloc,
-                || "Invalid dereference.",
-                eref.ty.clone(),
-                ref_ty,
-            );
-            context.add_ability_constraint(
-                eloc,
-                Some(format!(
-                    "Invalid dereference. Dereference requires the '{}' ability",
-                    Ability_::Copy
-                )),
-                inner.clone(),
-                Ability_::Copy,
-            );
-            (inner, TE::Dereference(eref))
+            if let Some(diag) = invalid_dereference(context, eloc, &eref.ty) {
+                context.add_diag(diag);
+                (context.error_type(eloc), TE::UnresolvedError)
+            } else {
+                let inner = core::make_tvar(context, eloc);
+                let ref_ty = sp(eloc, Type_::Ref(false, Box::new(inner.clone())));
+                subtype(
+                    context,
+                    eloc,
+                    || "Invalid dereference.",
+                    eref.ty.clone(),
+                    ref_ty,
+                );
+                context.add_ability_constraint(
+                    eloc,
+                    Some(format!(
+                        "Invalid dereference. Dereference requires the '{}' ability",
+                        Ability_::Copy
+                    )),
+                    inner.clone(),
+                    Ability_::Copy,
+                );
+                (inner, TE::Dereference(eref))
+            }
         }
         NE::UnaryExp(uop, nr) => {
             use UnaryOp_::*;
@@ -1444,7 +2045,8 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
         NE::ExpList(nes) => {
             assert!(!nes.is_empty());
             let es = exp_vec(context, nes);
-            let locs = es.iter().map(|e| e.exp.loc).collect();
+            let locs: Vec<_> = es.iter().map(|e| e.exp.loc).collect();
+            context.expression_list_elements.insert(eloc, locs.clone());
             let tvars = core::make_expr_list_tvars(
                 context,
                 eloc,
@@ -1469,26 +2071,32 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
             let typed_nfields =
                 add_field_types(context, eloc, "argument", &m, &n, targs.clone(), nfields);
 
+            let (alias_suffix, alias_label) = member_alias(context, &m, n.0);
             let tfields = typed_nfields.map(|f, (idx, (fty, narg))| {
                 let arg = exp(context, Box::new(narg));
-                subtype(
+                subtype_labeled(
                     context,
                     arg.exp.loc,
-                    || format!("Invalid argument for field '{}' for '{}::{}'", f, &m, &n),
+                    || {
+                        format!(
+                            "Invalid argument for field '{}' for '{}::{}'{}",
+                            f, &m, &n, alias_suffix
+                        )
+                    },
+                    alias_label.clone(),
                     arg.ty.clone(),
                     fty.clone(),
                 );
                 (idx, (fty, *arg))
             });
+            warn_on_large_positional_pack(context, eloc, &m, &n);
             if !context.is_current_module(&m) {
                 let msg = format!(
                     "Invalid instantiation of '{}::{}'.\nAll structs can only be constructed in \
                      the module in which they are declared",
                     &m, &n,
                 );
-                context
-                    .env
-                    .add_diag(diag!(TypeSafety::Visibility, (eloc, msg)));
+                context.add_diag(diag!(TypeSafety::Visibility, (eloc, msg)));
             }
             (bt, TE::Pack(m, n, targs, tfields))
         }
@@ -1531,9 +2139,7 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
                     } else {
                         "Invalid 'move'. Expected a variable or path."
                     };
-                    context
-                        .env
-                        .add_diag(diag!(TypeSafety::InvalidMoveOp, (loc, msg)));
+                    context.add_diag(diag!(TypeSafety::InvalidMoveOp, (loc, msg)));
                     (context.error_type(eloc), TE::UnresolvedError)
                 }
             }
@@ -1549,19 +2155,13 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
                     },
                 ),
                 er_ @ TE::Constant(_, _) => {
-                    context.env.check_feature(
-                        FeatureGate::Move2024Paths,
-                        context.current_package(),
-                        loc,
-                    );
+                    context.check_feature(FeatureGate::Move2024Paths, loc);
                     (er.ty, er_)
                 }
                 TE::UnresolvedError => (er.ty, TE::UnresolvedError),
                 _ => {
                     let msg = "Invalid 'copy'. Expected a variable or path.".to_owned();
-                    context
-                        .env
-                        .add_diag(diag!(TypeSafety::InvalidCopyOp, (loc, msg)));
+                    context.add_diag(diag!(TypeSafety::InvalidCopyOp, (loc, msg)));
                     (context.error_type(eloc), TE::UnresolvedError)
                 }
             };
@@ -1593,6 +2193,7 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
 
         NE::Cast(nl, ty) => {
             let el = exp(context, nl);
+            check_declared_type(context, &ty, false);
             let rhs = core::instantiate(context, ty);
             context.add_numeric_constraint(el.exp.loc, "as", el.ty.clone());
             context.add_numeric_constraint(el.exp.loc, "as", rhs.clone());
@@ -1603,6 +2204,7 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
             let el = exp(context, nl);
             let annot_loc = ty_annot.loc;
             let msg = || "Invalid type annotation";
+            check_declared_type(context, &ty_annot, false);
             let rhs = core::instantiate(context, ty_annot);
             subtype(context, annot_loc, msg, el.ty.clone(), rhs.clone());
             let e_ = TE::Annotate(el, Box::new(rhs.clone()));
@@ -1615,9 +2217,16 @@ fn exp(context: &mut Context, ne: Box<N::Exp>) -> Box<T::Exp> {
 
         NE::BinopExp(..) => unreachable!(),
     };
+    count_typed_node(context);
     Box::new(T::exp(ty, sp(eloc, e_)))
 }
 
+fn count_typed_node(context: &mut Context) {
+    if let Some(metrics) = &mut context.metrics {
+        metrics.typed_nodes += 1;
+    }
+}
+
 fn binop(
     context: &mut Context,
     el: Box<T::Exp>,
@@ -1672,8 +2281,10 @@ fn binop(
                 Ability_::Drop,
             );
             context.add_ability_constraint(er.exp.loc, ability_msg, er.ty.clone(), Ability_::Drop);
+            warn_on_implicit_freeze_in_equality(context, &bop, &el, &er);
             let ty = join(context, bop.loc, msg, el.ty.clone(), er.ty.clone());
             context.add_single_type_constraint(loc, msg(), ty.clone());
+            warn_on_large_struct_equality(context, &bop, &ty);
             (Type_::bool(loc), ty)
         }
 
@@ -1687,12 +2298,11 @@ fn binop(
         }
 
         Range | Implies | Iff => {
-            context
-                .env
-                .add_diag(ice!((loc, "ICE unexpect specification operator")));
+            context.add_diag(ice!((loc, "ICE unexpect specification operator")));
             (context.error_type(loc), context.error_type(loc))
         }
     };
+    count_typed_node(context);
     Box::new(T::exp(
         ty,
         sp(loc, TE::BinopExp(el, bop, Box::new(operand_ty), er)),
@@ -1719,7 +2329,9 @@ fn loop_body(
         );
     }
 
+    context.loop_depth += 1;
     let eloop = exp(context, nloop);
+    context.loop_depth -= 1;
     let lloc = eloop.exp.loc;
     subtype(
         context,
@@ -1795,6 +2407,13 @@ fn lvalue_list(
 ) -> T::LValueList {
     use LValueCase as C;
     let arity = nlvalues.len();
+    if let (C::Bind, [sp!(lvalue_loc, _)], Some(ty)) = (&case, &nlvalues[..], &ty_opt) {
+        if let sp!(_, Type_::Apply(_, sp!(_, TypeName_::Multiple(n)), _)) =
+            core::unfold_type(&context.subst, ty.clone())
+        {
+            context.expression_list_binds.insert(*lvalue_loc, n);
+        }
+    }
     let locs = nlvalues.iter().map(|sp!(loc, _)| *loc).collect();
     let msg = "Invalid type for local";
     let ty_vars = core::make_expr_list_tvars(context, loc, msg, locs);
@@ -1937,9 +2556,7 @@ fn lvalue(
                      deconstructed in the module in which they are declared",
                     verb, &m, &n,
                 );
-                context
-                    .env
-                    .add_diag(diag!(TypeSafety::Visibility, (loc, msg)));
+                context.add_diag(diag!(TypeSafety::Visibility, (loc, msg)));
             }
             match ref_mut {
                 None => TL::Unpack(m, n, targs, tfields),
@@ -1987,11 +2604,12 @@ fn check_mutability(context: &mut Context, eloc: Loc, usage: &str, v: &N::Var) {
         let decl_msg =
             format!("To use the variable mutably, it must be declared 'mut', e.g. 'mut {v}'");
         if context.env.edition(context.current_package()) == Edition::E2024_MIGRATION {
-            context
-                .env
-                .add_diag(diag!(Migration::NeedsLetMut, (decl_loc, decl_msg.clone()),))
+            let mut diag = diag!(Migration::NeedsLetMut, (decl_loc, decl_msg.clone()));
+            let decl_start = Loc::new(decl_loc.file_hash(), decl_loc.start(), decl_loc.start());
+            diag.add_fix(decl_start, "mut ");
+            context.add_diag(diag)
         }
-        context.env.add_diag(diag!(
+        context.add_diag(diag!(
             TypeSafety::InvalidImmVariableUsage,
             (eloc, usage_msg),
             (decl_loc, decl_msg),
@@ -1999,6 +2617,32 @@ fn check_mutability(context: &mut Context, eloc: Loc, usage: &str, v: &N::Var) {
     }
 }
 
+/// A targeted error for dereferencing something that is known not to be a reference. Unresolved
+/// (non-numeric) types are left to the usual subtyping check
+fn invalid_dereference(context: &Context, loc: Loc, ty: &Type) -> Option<Diagnostic> {
+    use Type_ as T;
+    let ty = core::ready_tvars(&context.subst, ty.clone());
+    let msg = match &ty.value {
+        T::Var(i) if context.subst.is_num_var(*i) => format!(
+            "Cannot dereference a value of type {}. '*' requires a reference",
+            core::error_format(&ty, &context.subst)
+        ),
+        T::Ref(_, _) | T::Var(_) | T::Anything | T::Hole | T::UnresolvedError => return None,
+        T::Unit => "Cannot dereference the unit value '()'. '*' requires a reference".to_owned(),
+        T::Apply(_, sp!(_, TypeName_::Multiple(_)), _) => format!(
+            "Cannot dereference an expression list of type {}. '*' requires a single reference",
+            core::error_format(&ty, &context.subst)
+        ),
+        T::Param(_) | T::Apply(_, _, _) | T::Fun(_, _) => format!(
+            "Cannot dereference a value of type {}. '*' requires a reference",
+            core::error_format(&ty, &context.subst)
+        ),
+    };
+    let mut diag = diag!(TypeSafety::ExpectedSpecificType, (loc, msg));
+    diag.add_note("Try removing the '*', or borrowing the value first with '&'");
+    Some(diag)
+}
+
 //**************************************************************************************************
 // Fields
 //**************************************************************************************************
@@ -2009,22 +2653,24 @@ fn resolve_field(context: &mut Context, loc: Loc, ty: Type, field: &Field) -> Ty
     const UNINFERRED_MSG: &str =
         "Could not infer the type before field access. Try annotating here";
     let msg = || format!("Unbound field '{}'", field);
-    match core::ready_tvars(&context.subst, ty) {
+    match core::ready_tvars(&context.subst, ty.clone()) {
         sp!(_, UnresolvedError) => context.error_type(loc),
         sp!(tloc, Anything) => {
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::UninferredType,
                 (loc, msg()),
                 (tloc, UNINFERRED_MSG),
             ));
+            context.record_inference_hole(tloc, InferenceHoleKind::FieldAccess, &ty);
             context.error_type(loc)
         }
         sp!(tloc, Var(i)) if !context.subst.is_num_var(i) => {
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::UninferredType,
                 (loc, msg()),
                 (tloc, UNINFERRED_MSG),
             ));
+            context.record_inference_hole(tloc, InferenceHoleKind::FieldAccess, &ty);
             context.error_type(loc)
         }
         sp!(_, Apply(_, sp!(_, ModuleType(m, n)), targs)) => {
@@ -2034,9 +2680,9 @@ fn resolve_field(context: &mut Context, loc: Loc, ty: Type, field: &Field) -> Ty
                      the struct's module",
                     field, &m, &n
                 );
-                context
-                    .env
-                    .add_diag(diag!(TypeSafety::Visibility, (loc, msg)));
+                let mut diag = diag!(TypeSafety::Visibility, (loc, msg));
+                add_wrapper_accessor_notes(context, &mut diag, &m, &n);
+                context.add_diag(diag);
             }
             core::make_field_type(context, loc, &m, &n, targs, field)
         }
@@ -2045,7 +2691,7 @@ fn resolve_field(context: &mut Context, loc: Loc, ty: Type, field: &Field) -> Ty
                 "Expected a struct type in the current module but got: {}",
                 core::error_format(&t, &context.subst)
             );
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::ExpectedSpecificType,
                 (loc, msg()),
                 (t.loc, smsg),
@@ -2055,6 +2701,128 @@ fn resolve_field(context: &mut Context, loc: Loc, ty: Type, field: &Field) -> Ty
     }
 }
 
+/// Resolves the index functions for an access `e[i]`, where `ty` is the type of `e`. The type of
+/// the indexed element is given by the immutable index function, or by the mutable one if there
+/// is no immutable index function. Returns the instantiated type of that function along with the
+/// type of the element
+fn resolve_index(
+    context: &mut Context,
+    loc: Loc,
+    ty: Type,
+) -> Option<(
+    IndexMethods,
+    ModuleIdent,
+    FunctionName,
+    ResolvedFunctionType,
+    Type,
+)> {
+    use Type_::*;
+    const UNINFERRED_MSG: &str =
+        "Could not infer the type before index access. Try annotating here";
+    let tn = match core::ready_tvars(&context.subst, ty.clone()) {
+        sp!(_, UnresolvedError) => return None,
+        sp!(tloc, Anything) => {
+            context.add_diag(diag!(
+                TypeSafety::UninferredType,
+                (loc, "Invalid index access"),
+                (tloc, UNINFERRED_MSG),
+            ));
+            context.record_inference_hole(tloc, InferenceHoleKind::IndexAccess, &ty);
+            return None;
+        }
+        sp!(tloc, Var(i)) if !context.subst.is_num_var(i) => {
+            context.add_diag(diag!(
+                TypeSafety::UninferredType,
+                (loc, "Invalid index access"),
+                (tloc, UNINFERRED_MSG),
+            ));
+            context.record_inference_hole(tloc, InferenceHoleKind::IndexAccess, &ty);
+            return None;
+        }
+        sp!(
+            _,
+            Apply(_, tn @ sp!(_, TypeName_::ModuleType(_, _) | TypeName_::Builtin(_)), _)
+        ) => tn,
+        t => {
+            let msg = format!(
+                "Index access is only supported on struct and builtin types. \
+                Got an expression of type: {}",
+                core::error_format(&t, &context.subst)
+            );
+            context.add_diag(diag!(
+                TypeSafety::InvalidIndexAccess,
+                (loc, "Invalid index access"),
+                (t.loc, msg),
+            ));
+            return None;
+        }
+    };
+    let methods = context.find_index_methods_and_mark_used(&tn);
+    let Some((m, f)) = methods.index.or(methods.index_mut) else {
+        let msg = format!(
+            "No index function is registered for type {}",
+            core::error_format(&ty, &context.subst)
+        );
+        let mut diag = diag!(
+            TypeSafety::InvalidIndexAccess,
+            (loc, "Invalid index access"),
+            (ty.loc, msg),
+        );
+        diag.add_note(format!(
+            "Index functions are declared with '#[{}({})]'. The first parameter must be a \
+            reference to the indexed type, and the function must be in scope as a method for it, \
+            e.g. via 'use fun'",
+            SyntaxAttribute::SYNTAX,
+            SyntaxAttribute::INDEX,
+        ));
+        context.add_diag(diag);
+        return None;
+    };
+    let fty = core::make_function_type(context, loc, &m, &f, None);
+    let (sp!(_, Ref(_, param_ty)), sp!(_, Ref(_, elem_ty))) = (&fty.params[0].1, &fty.return_)
+    else {
+        // invalid index functions are reported where they are declared
+        assert!(context.env.has_errors());
+        return None;
+    };
+    let (param_ty, elem_ty) = ((**param_ty).clone(), (**elem_ty).clone());
+    subtype(context, loc, || "Invalid index access", ty, param_ty);
+    Some((methods, m, f, fty, elem_ty))
+}
+
+fn add_wrapper_accessor_notes(
+    context: &mut Context,
+    diag: &mut Diagnostic,
+    m: &ModuleIdent,
+    n: &StructName,
+) {
+    let Some(wrapper) = context.env.wrapper_type(m, n.value()).cloned() else {
+        return;
+    };
+    // only suggest accessors that actually exist and are callable, e.g. in case the module is
+    // not the one from the framework
+    let functions = &context.modules.module(m).functions;
+    let accessors = wrapper
+        .accessors
+        .iter()
+        .filter(|accessor| {
+            functions.get_(accessor).is_some_and(|finfo| {
+                matches!(finfo.visibility, Visibility::Public(_)) && finfo.macro_.is_none()
+            })
+        })
+        .map(|accessor| format!("'{m}::{accessor}'"))
+        .collect::<Vec<_>>();
+    if accessors.is_empty() {
+        return;
+    }
+    diag.add_note(format!(
+        "The value inside of '{m}::{n}' is accessed through functions, not fields. \
+        Consider using {}",
+        format_comma(accessors)
+    ));
+    diag.add_note(format!("For example: '{}'", wrapper.example));
+}
+
 fn add_field_types<T>(
     context: &mut Context,
     loc: Loc,
@@ -2066,14 +2834,14 @@ fn add_field_types<T>(
 ) -> Fields<(Type, T)> {
     let maybe_fields_ty = core::make_field_types(context, loc, m, n, targs);
     let mut fields_ty = match maybe_fields_ty {
-        N::StructFields::Defined(m) => m,
+        N::StructFields::Defined(_, m) => m,
         N::StructFields::Native(nloc) => {
             let msg = format!(
                 "Invalid {} usage for native struct '{}::{}'. Native structs cannot be directly \
                  constructed/deconstructed, and their fields cannot be dirctly accessed",
                 verb, m, n
             );
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 TypeSafety::InvalidNativeUsage,
                 (loc, msg),
                 (nloc, "Struct declared 'native' here")
@@ -2081,36 +2849,107 @@ fn add_field_types<T>(
             return fields.map(|f, (idx, x)| (idx, (context.error_type(f.loc()), x)));
         }
     };
-    for (_, f_, _) in &fields_ty {
-        if fields.get_(f_).is_none() {
-            let msg = format!("Missing {} for field '{}' in '{}::{}'", verb, f_, m, n);
-            context
-                .env
-                .add_diag(diag!(TypeSafety::TooFewArguments, (loc, msg)))
+    let (alias_suffix, alias_label) = member_alias(context, m, n.0);
+    // all missing fields are reported together, in the order they are declared
+    let mut missing = fields_ty
+        .iter()
+        .filter(|(_, f_, _)| fields.get_(f_).is_none())
+        .map(|(_, f_, (idx, _))| (*idx, *f_))
+        .collect::<Vec<_>>();
+    missing.sort_by_key(|(idx, _)| *idx);
+    if !missing.is_empty() {
+        let msg = match &missing[..] {
+            [(_, f_)] => format!(
+                "Missing {} for field '{}' in '{}::{}'{}",
+                verb, f_, m, n, alias_suffix
+            ),
+            _ => format!(
+                "Missing {}s for fields {} in '{}::{}'{}",
+                verb,
+                format_comma(missing.iter().map(|(_, f_)| format!("'{}'", f_))),
+                m,
+                n,
+                alias_suffix
+            ),
+        };
+        let decl_msg = format!("Struct '{}' declared here", n);
+        let decl_loc = context.struct_declared_loc(m, n);
+        let mut diag = diag!(
+            TypeSafety::TooFewArguments,
+            (loc, msg),
+            (decl_loc, decl_msg)
+        );
+        if let Some(label) = alias_label.clone() {
+            diag.add_secondary_label(label)
         }
+        context.add_diag(diag)
     }
-    fields.map(|f, (idx, x)| {
+    let mut unbound = vec![];
+    let fields = fields.map(|f, (idx, x)| {
         let fty = match fields_ty.remove(&f) {
             None => {
-                context.env.add_diag(diag!(
-                    NameResolution::UnboundField,
-                    (loc, format!("Unbound field '{}' in '{}::{}'", &f, m, n))
-                ));
+                unbound.push((idx, f));
                 context.error_type(f.loc())
             }
             Some((_, fty)) => fty,
         };
         (idx, (fty, x))
-    })
+    });
+    // all unbound fields are reported together, in the order they are given
+    unbound.sort_by_key(|(idx, _)| *idx);
+    let diag = match &unbound[..] {
+        [] => None,
+        [(_, f)] => Some(diag!(
+            NameResolution::UnboundField,
+            (
+                loc,
+                format!("Unbound field '{}' in '{}::{}'{}", f, m, n, alias_suffix)
+            )
+        )),
+        _ => {
+            let msg = format!(
+                "Unbound fields {} in '{}::{}'{}",
+                format_comma(unbound.iter().map(|(_, f)| format!("'{}'", f))),
+                m,
+                n,
+                alias_suffix
+            );
+            let mut diag = diag!(NameResolution::UnboundField, (loc, msg));
+            let mut labeled = BTreeSet::from([loc]);
+            for (_, f) in &unbound {
+                if labeled.insert(f.loc()) {
+                    diag.add_secondary_label((f.loc(), format!("Unbound field '{}'", f)));
+                }
+            }
+            Some(diag)
+        }
+    };
+    if let Some(mut diag) = diag {
+        if let Some(label) = alias_label {
+            diag.add_secondary_label(label)
+        }
+        context.add_diag(diag)
+    }
+    fields
 }
 
 enum ExpDotted_ {
     Exp(Box<T::Exp>),
     TmpBorrow(Box<T::Exp>, Box<Type>),
     Dot(Box<ExpDotted>, Field, Box<Type>),
+    // An index access, with the type of the indexed element
+    Index(Box<ExpDotted>, Box<IndexCall>, Box<Type>),
 }
 type ExpDotted = Spanned<ExpDotted_>;
 
+// The call an index access will be translated to, once it is known whether the access is mutable.
+// The target is `None` if no index function could be resolved
+struct IndexCall {
+    methods: IndexMethods,
+    target: Option<(ModuleIdent, FunctionName, ResolvedFunctionType)>,
+    index: T::Exp,
+}
+
 // if constraint_verb is None, no single typeconstraint is applied
 fn exp_dotted(
     context: &mut Context,
@@ -2121,8 +2960,20 @@ fn exp_dotted(
     let (edot_, ty) = match ndot_ {
         NE::Exp(ne) => {
             use Type_::*;
-            let e = exp(context, ne);
+            let mut e = exp(context, ne);
             warn_on_constant_borrow(context, dloc, &e);
+            // References to references are reported when they are created, so look through to the
+            // innermost reference instead of reporting the field access as invalid as well
+            while let Ref(_, inner) = core::unfold_type(&context.subst, e.ty.clone()).value {
+                if !matches!(
+                    core::unfold_type(&context.subst, (*inner).clone()).value,
+                    Ref(_, _)
+                ) {
+                    break;
+                }
+                let eloc = e.exp.loc;
+                e = Box::new(T::exp(*inner, sp(eloc, T::UnannotatedExp_::Dereference(e))));
+            }
             let ety = &e.ty;
             let unfolded = core::unfold_type(&context.subst, ety.clone());
             let (borrow_needed, ty) = match unfolded.value {
@@ -2151,7 +3002,35 @@ fn exp_dotted(
                 field_ty,
             )
         }
+        NE::Index(nlhs, nindex) => {
+            let (lhs, inner) = exp_dotted(context, Some("index access"), *nlhs);
+            let index = *exp(context, nindex);
+            let (methods, target, elem_ty) = match resolve_index(context, dloc, inner) {
+                Some((methods, m, f, fty, elem_ty)) => (methods, Some((m, f, fty)), elem_ty),
+                None => (IndexMethods::default(), None, context.error_type(dloc)),
+            };
+            let call = IndexCall {
+                methods,
+                target,
+                index,
+            };
+            (
+                ExpDotted_::Index(Box::new(lhs), Box::new(call), Box::new(elem_ty.clone())),
+                elem_ty,
+            )
+        }
     };
+    // Segments after an invalid one are typed against the error type without reporting further
+    // errors. The types of the valid prefix are kept for tooling, even if the path as a whole or a
+    // method call on it cannot be typed
+    if context.env.flags().record_type_locations()
+        && !matches!(
+            core::unfold_type(&context.subst, ty.clone()).value,
+            Type_::UnresolvedError
+        )
+    {
+        context.dotted_segments.push((dloc, ty.clone()));
+    }
     (sp(dloc, edot_), ty)
 }
 
@@ -2200,7 +3079,7 @@ fn exp_dotted_to_borrow(
             };
             // lhs is immutable and current borrow is mutable
             if !lhs_mut && mut_ {
-                context.env.add_diag(diag!(
+                context.add_diag(diag!(
                     ReferenceSafety::RefTrans,
                     (loc, "Invalid mutable borrow from an immutable reference"),
                     (tyloc, "Immutable because of this position"),
@@ -2210,6 +3089,63 @@ fn exp_dotted_to_borrow(
             let ty = sp(loc, Ref(mut_, field_ty));
             T::exp(ty, sp(dloc, e_))
         }
+        ExpDotted_::Index(lhs, call, elem_ty) => {
+            let IndexCall {
+                methods,
+                target,
+                index,
+            } = *call;
+            let lhs_borrow = exp_dotted_to_borrow(context, dloc, mut_, *lhs);
+            let ty = sp(loc, Ref(mut_, elem_ty));
+            let Some((m, f, fty)) = target else {
+                assert!(context.env.has_errors());
+                return T::exp(ty, sp(dloc, TE::UnresolvedError));
+            };
+            let target = match (mut_, methods.index, methods.index_mut) {
+                (false, Some(_), _) => Some((m, f, fty)),
+                (true, _, Some((mm, mf))) if methods.index.is_none() => Some((mm, mf, fty)),
+                (true, _, Some((mm, mf))) => {
+                    let fty = core::make_function_type(context, dloc, &mm, &mf, None);
+                    Some((mm, mf, fty))
+                }
+                (false, None, _) | (true, _, None) => None,
+            };
+            let Some((m, f, fty)) = target else {
+                let (case, available) = if mut_ {
+                    ("mutable", "an immutable")
+                } else {
+                    ("immutable", "a mutable")
+                };
+                let lhs_ty = core::unfold_type(&context.subst, lhs_borrow.ty.clone());
+                let msg = format!(
+                    "Invalid {case} index access. \
+                    No {case} index function is registered for type {}",
+                    core::error_format(edotted_ty_base(&lhs_ty), &context.subst)
+                );
+                let defined_msg =
+                    format!("Only {available} index function, '{m}::{f}', is registered");
+                let defined_loc = context.function_info(&m, &f).defined_loc;
+                context.add_diag(diag!(
+                    TypeSafety::InvalidIndexAccess,
+                    (loc, msg),
+                    (defined_loc, defined_msg),
+                ));
+                return T::exp(ty, sp(dloc, TE::UnresolvedError));
+            };
+            let argloc = index.exp.loc;
+            let (call, ret_ty) = module_call_impl(
+                context,
+                dloc,
+                m,
+                f,
+                fty,
+                None,
+                argloc,
+                vec![lhs_borrow, index],
+            );
+            subtype(context, dloc, || "Invalid index access", ret_ty, ty.clone());
+            T::exp(ty, sp(dloc, TE::ModuleCall(Box::new(call))))
+        }
     }
 }
 
@@ -2231,35 +3167,48 @@ fn exp_dotted_to_owned_value(
             *lhs
         }
         edot => {
-            let name = match &edot {
+            let accessed = match &edot {
                 sp!(_, ExpDotted_::Exp(_)) | sp!(_, ExpDotted_::TmpBorrow(_, _)) => {
                     panic!("ICE covered above")
                 }
-                sp!(_, ExpDotted_::Dot(_, name, _)) => *name,
+                sp!(_, ExpDotted_::Dot(_, name, _)) => format!("field '{}'", name),
+                sp!(_, ExpDotted_::Index(_, _, _)) => "indexed element".to_owned(),
             };
+            let path = exp_dotted_path(&edot);
+            let destructure = exp_dotted_destructure(context, &edot);
             let eborrow = exp_dotted_to_borrow(context, eloc, false, edot);
             let case = match usage {
                 DottedUsage::Move(loc) => {
-                    let new_syntax = context.env.check_feature(
-                        FeatureGate::Move2024Paths,
-                        context.current_package(),
-                        loc,
-                    );
+                    let new_syntax = context.check_feature(FeatureGate::Move2024Paths, loc);
                     if new_syntax {
                         let msg = "Invalid 'move'. 'move' works only with \
                             variables, e.g. 'move x'. 'move' on a path access is not supported";
-                        context
-                            .env
-                            .add_diag(diag!(TypeSafety::InvalidMoveOp, (loc, msg)));
+                        let mut diag = diag!(TypeSafety::InvalidMoveOp, (loc, msg));
+                        if let Some(destructure) = destructure {
+                            diag.add_note(format!(
+                                "To move the field out, destructure the struct instead, e.g. '{}'",
+                                destructure
+                            ));
+                        }
+                        if let Some((path_loc, path)) = &path {
+                            if is_copyable(context, &inner_ty) {
+                                diag.add_note(format!(
+                                    "The value has the '{}' ability, so it can be copied \
+                                    instead, e.g. '{}'",
+                                    Ability_::COPY,
+                                    path
+                                ));
+                                let move_loc =
+                                    Loc::new(loc.file_hash(), loc.start(), path_loc.start());
+                                diag.add_fix(move_loc, "");
+                            }
+                        }
+                        context.add_diag(diag);
                     }
                     None
                 }
                 DottedUsage::Copy(loc) => {
-                    context.env.check_feature(
-                        FeatureGate::Move2024Paths,
-                        context.current_package(),
-                        loc,
-                    );
+                    context.check_feature(FeatureGate::Move2024Paths, loc);
                     Some("'copy'")
                 }
                 DottedUsage::Use => Some("implicit copy"),
@@ -2269,14 +3218,18 @@ fn exp_dotted_to_owned_value(
                 context.add_ability_constraint(
                     eloc,
                     Some(format!(
-                        "Invalid {} of field '{}' without the '{}' ability",
+                        "Invalid {} of {} without the '{}' ability",
                         case,
-                        name,
+                        accessed,
                         Ability_::COPY,
                     )),
                     inner_ty.clone(),
                     Ability_::Copy,
                 );
+                if usage == DottedUsage::Use {
+                    let path = path.map(|(_, path)| path);
+                    warn_on_large_copy_in_loop(context, eloc, &inner_ty, path.as_deref());
+                }
                 T::exp(inner_ty, sp(eloc, TE::Dereference(Box::new(eborrow))))
             } else {
                 // 'move' case, which is not supported
@@ -2286,13 +3239,252 @@ fn exp_dotted_to_owned_value(
     }
 }
 
+/// The path written for a dotted expression, e.g. 'x.f.g', and the location of its root local.
+/// `None` if the path is not rooted in a local or contains an index access
+fn exp_dotted_path(edot: &ExpDotted) -> Option<(Loc, String)> {
+    use T::UnannotatedExp_ as TE;
+    match &edot.value {
+        ExpDotted_::Exp(e) | ExpDotted_::TmpBorrow(e, _) => match &e.exp.value {
+            TE::Use(var)
+            | TE::Move { var, .. }
+            | TE::Copy { var, .. }
+            | TE::BorrowLocal(_, var) => Some((e.exp.loc, var.value.name.to_string())),
+            _ => None,
+        },
+        ExpDotted_::Dot(lhs, field, _) => {
+            let (loc, path) = exp_dotted_path(lhs)?;
+            Some((loc, format!("{}.{}", path, field)))
+        }
+        ExpDotted_::Index(_, _, _) => None,
+    }
+}
+
+/// For a single named field of an owned local, e.g. 'x.f', the destructuring that moves the field
+/// out of the local, e.g. 'let S { f, g: _ } = x;'. Only given if the struct can be unpacked in the
+/// current module
+fn exp_dotted_destructure(context: &Context, edot: &ExpDotted) -> Option<String> {
+    use T::UnannotatedExp_ as TE;
+    let sp!(_, ExpDotted_::Dot(lhs, field, _)) = edot else {
+        return None;
+    };
+    let sp!(_, ExpDotted_::TmpBorrow(e, ty)) = &**lhs else {
+        return None;
+    };
+    let TE::Use(var) = &e.exp.value else {
+        return None;
+    };
+    let field_name = field.value();
+    if field_name
+        .as_str()
+        .starts_with(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let ty = core::unfold_type(&context.subst, (**ty).clone());
+    let sp!(_, Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, n)), _)) = &ty else {
+        return None;
+    };
+    if !context.is_current_module(m) {
+        return None;
+    }
+    let N::StructFields::Defined(_, fields) = &context.modules.struct_definition(m, n).fields
+    else {
+        return None;
+    };
+    let mut fields = fields
+        .key_cloned_iter()
+        .map(|(f, (idx, _))| (*idx, f.value()))
+        .collect::<Vec<_>>();
+    fields.sort_by_key(|(idx, _)| *idx);
+    let binds = fields
+        .into_iter()
+        .map(|(_, f)| {
+            if f == field_name {
+                f.to_string()
+            } else {
+                format!("{f}: _")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!("let {} {{ {} }} = {};", n, binds, var.value.name))
+}
+
+fn is_copyable(context: &Context, ty: &Type) -> bool {
+    let ty = core::unfold_type(&context.subst, ty.clone());
+    if matches!(ty.value, Type_::Anything | Type_::UnresolvedError) {
+        return false;
+    }
+    core::infer_abilities(&context.modules, &context.subst, ty).has_ability_(Ability_::Copy)
+}
+
+/// Warns on an implicit copy of a struct with many fields inside of a loop, since the copy is made
+/// on every iteration. Off by default, see `FILTER_LARGE_COPY_IN_LOOP`
+fn warn_on_large_copy_in_loop(context: &mut Context, loc: Loc, ty: &Type, path: Option<&str>) {
+    if context.loop_depth == 0 || !context.env.is_warning_enabled(FILTER_LARGE_COPY_IN_LOOP) {
+        return;
+    }
+    let ty = core::unfold_type(&context.subst, ty.clone());
+    let sp!(_, Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, n)), _)) = &ty else {
+        return;
+    };
+    let num_fields = match &context.modules.struct_definition(m, n).fields {
+        N::StructFields::Defined(_, fields) => fields.len(),
+        N::StructFields::Native(_) => return,
+    };
+    if num_fields <= context.env.flags().large_copy_field_threshold() {
+        return;
+    }
+    let msg = format!(
+        "Implicit copy of {}, a struct with {} fields, on every iteration of the loop",
+        core::error_format(&ty, &context.subst),
+        num_fields
+    );
+    let mut diag = diag!(TypeSafety::LargeCopyInLoop, (loc, msg));
+    if let Some(path) = path {
+        diag.add_note(format!(
+            "'{}' is copied implicitly, as if written 'copy {}'",
+            path, path
+        ));
+    }
+    diag.add_note(format!(
+        "Consider borrowing the value instead, e.g. '&{}', or copying it once before the loop",
+        path.unwrap_or("s.f")
+    ));
+    context.add_diag(diag);
+}
+
+/// Warns on constructing a positional struct with many fields, since the arguments are matched to
+/// the fields only by their position. Off by default, see `FILTER_LARGE_POSITIONAL_STRUCT`
+fn warn_on_large_positional_pack(context: &mut Context, loc: Loc, m: &ModuleIdent, n: &StructName) {
+    if !context
+        .env
+        .is_warning_enabled(FILTER_LARGE_POSITIONAL_STRUCT)
+    {
+        return;
+    }
+    let num_fields = match &context.modules.struct_definition(m, n).fields {
+        N::StructFields::Defined(/* positional */ true, fields) => fields.len(),
+        N::StructFields::Defined(false, _) | N::StructFields::Native(_) => return,
+    };
+    if num_fields < context.env.flags().positional_struct_field_threshold() {
+        return;
+    }
+    let msg = format!(
+        "Construction of the positional struct '{}::{}' from {} arguments, matched to its fields \
+        only by their position",
+        m, n, num_fields
+    );
+    let decl_msg = format!(
+        "'{}' is declared with {} positional fields here",
+        n, num_fields
+    );
+    let decl_loc = context.struct_declared_loc(m, n);
+    let mut diag = diag!(
+        TypeSafety::LargePositionalStruct,
+        (loc, msg),
+        (decl_loc, decl_msg)
+    );
+    diag.add_note(
+        "Consider declaring the struct with named fields, or adding a comment for each argument",
+    );
+    context.add_diag(diag);
+}
+
+/// Warns on '==' and '!=' comparing a mutable reference with an immutable one, as the mutable
+/// reference is implicitly frozen. Off by default, see `FILTER_IMPLICIT_FREEZE_EQUALITY`
+fn warn_on_implicit_freeze_in_equality(
+    context: &mut Context,
+    bop: &BinOp,
+    el: &T::Exp,
+    er: &T::Exp,
+) {
+    if !context
+        .env
+        .is_warning_enabled(FILTER_IMPLICIT_FREEZE_EQUALITY)
+    {
+        return;
+    }
+    let lty = core::unfold_type(&context.subst, el.ty.clone());
+    let rty = core::unfold_type(&context.subst, er.ty.clone());
+    let mut_loc = match (&lty.value, &rty.value) {
+        (Type_::Ref(true, _), Type_::Ref(false, _)) => el.exp.loc,
+        (Type_::Ref(false, _), Type_::Ref(true, _)) => er.exp.loc,
+        _ => return,
+    };
+    let msg = format!(
+        "This mutable reference is implicitly frozen to be compared with '{}'",
+        bop
+    );
+    let mut diag = diag!(TypeSafety::ImplicitFreezeInEquality, (mut_loc, msg));
+    diag.add_note("Consider comparing immutable references, e.g. with '&' or 'freeze'");
+    context.add_diag(diag);
+}
+
+/// Warns on '==' and '!=' comparing structs with many fields, counting the fields of nested
+/// structs, since every field is compared. Off by default, see `FILTER_LARGE_STRUCT_EQUALITY`
+fn warn_on_large_struct_equality(context: &mut Context, bop: &BinOp, ty: &Type) {
+    if !context.env.is_warning_enabled(FILTER_LARGE_STRUCT_EQUALITY) {
+        return;
+    }
+    let ty = core::unfold_type(&context.subst, ty.clone());
+    let struct_ty = match &ty.value {
+        Type_::Ref(_, inner) => core::unfold_type(&context.subst, *inner.clone()),
+        _ => ty,
+    };
+    let num_fields = struct_field_count(context, &struct_ty, &mut BTreeSet::new());
+    if num_fields <= context.env.flags().large_equality_field_threshold() {
+        return;
+    }
+    let msg = format!(
+        "'{}' on {} compares all of its {} fields, including the fields of nested structs",
+        bop,
+        core::error_format(&struct_ty, &context.subst),
+        num_fields
+    );
+    let mut diag = diag!(TypeSafety::LargeStructEquality, (bop.loc, msg));
+    diag.add_note(
+        "Consider comparing a field that identifies the value instead, e.g. 's1.id == s2.id'",
+    );
+    context.add_diag(diag);
+}
+
+/// The number of fields of a struct, where each field of a struct type counts the fields of that
+/// struct instead. Returns 0 for types that are not structs
+fn struct_field_count(
+    context: &mut Context,
+    ty: &Type,
+    visiting: &mut BTreeSet<(ModuleIdent, StructName)>,
+) -> usize {
+    let ty = core::unfold_type(&context.subst, ty.clone());
+    let sp!(
+        loc,
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, n)), ty_args)
+    ) = ty
+    else {
+        return 0;
+    };
+    let N::StructFields::Defined(_, fields) = core::make_field_types(context, loc, &m, &n, ty_args)
+    else {
+        return 0;
+    };
+    // structs cannot be recursive, but that error might have already been reported
+    if !visiting.insert((m, n)) {
+        return 0;
+    }
+    let count = fields
+        .iter()
+        .map(|(_, _, (_, field_ty))| struct_field_count(context, field_ty, visiting).max(1))
+        .sum();
+    visiting.remove(&(m, n));
+    count
+}
+
 fn warn_on_constant_borrow(context: &mut Context, loc: Loc, e: &T::Exp) {
     use T::UnannotatedExp_ as TE;
     if matches!(&e.exp.value, TE::Constant(_, _)) {
         let msg = "This access will make a new copy of the constant. Consider binding the value to a variable first to make this copy explicit";
-        context
-            .env
-            .add_diag(diag!(TypeSafety::ImplicitConstantCopy, (loc, msg)))
+        context.add_diag(diag!(TypeSafety::ImplicitConstantCopy, (loc, msg)))
     }
 }
 
@@ -2310,6 +3502,12 @@ impl crate::shared::ast_debug::AstDebug for ExpDotted_ {
                 w.write(".");
                 w.annotate(|w| w.write(&format!("{}", n)), ty)
             }
+            D::Index(e, call, ty) => {
+                e.ast_debug(w);
+                w.write("[");
+                w.annotate(|w| call.index.ast_debug(w), ty);
+                w.write("]")
+            }
         }
     }
 }
@@ -2329,10 +3527,11 @@ fn method_call(
     mut args: Vec<T::Exp>,
 ) -> Option<(Type, T::UnannotatedExp_)> {
     use T::UnannotatedExp_ as TE;
+    let ty_args_loc = ty_args_opt.as_ref().map(|_| between(method.loc, argloc));
     let (m, f, fty, first_arg) =
         method_call_resolve(context, loc, edotted, edotted_ty, method, ty_args_opt)?;
     args.insert(0, first_arg);
-    let (mut call, ret_ty) = module_call_impl(context, loc, m, f, fty, argloc, args);
+    let (mut call, ret_ty) = module_call_impl(context, loc, m, f, fty, ty_args_loc, argloc, args);
     call.method_name = Some(method);
     Some((ret_ty, TE::ModuleCall(Box::new(call))))
 }
@@ -2352,8 +3551,14 @@ fn method_call_resolve(
     let tn = match &edotted_bty.value {
         Ty::Apply(_, tn @ sp!(_, TN::ModuleType(_, _) | TN::Builtin(_)), _) => tn,
         t => {
+            let mut notes = vec![];
             let msg = match t {
-                Ty::Anything => {
+                Ty::Anything | Ty::Hole => {
+                    context.record_inference_hole(
+                        edotted_ty.loc,
+                        InferenceHoleKind::MethodReceiver,
+                        &edotted_ty,
+                    );
                     "Unable to infer type for method call. Try annotating this type".to_owned()
                 }
                 Ty::Unit | Ty::Apply(_, sp!(_, TN::Multiple(_)), _) | Ty::Fun(_, _) => {
@@ -2364,6 +3569,7 @@ fn method_call_resolve(
                     )
                 }
                 Ty::Param(_) => {
+                    notes = type_param_method_call_notes(context, method, &edotted_ty_unfolded);
                     let tsubst = core::error_format_(t, &context.subst);
                     format!(
                         "Method calls are not supported on type parameters. \
@@ -2377,11 +3583,15 @@ fn method_call_resolve(
                 Ty::Ref(_, _) | Ty::Var(_) => panic!("ICE unfolding failed"),
                 Ty::Apply(_, _, _) => unreachable!(),
             };
-            context.env.add_diag(diag!(
+            let mut diag = diag!(
                 TypeSafety::InvalidMethodCall,
                 (loc, "Invalid method call"),
                 (edotted_ty.loc, msg),
-            ));
+            );
+            for note in notes {
+                diag.add_note(note);
+            }
+            context.add_diag(diag);
             return None;
         }
     };
@@ -2403,7 +3613,9 @@ fn method_call_resolve(
                         break;
                     }
                     sp!(_, ExpDotted_::TmpBorrow(_, _)) => break,
-                    sp!(_, ExpDotted_::Dot(l, _, _)) => cur = l,
+                    sp!(_, ExpDotted_::Dot(l, _, _)) | sp!(_, ExpDotted_::Index(l, _, _)) => {
+                        cur = l
+                    }
                 };
             }
             exp_dotted_to_borrow(context, loc, *mut_, edotted)
@@ -2413,11 +3625,66 @@ fn method_call_resolve(
     Some((m, f, fty, first_arg))
 }
 
+/// Explains that a type parameter has no methods, and suggests functions named like the method
+/// that could be called directly instead, with the `receiver` as their first argument
+fn type_param_method_call_notes(context: &Context, method: Name, receiver: &Type) -> Vec<String> {
+    let mut notes = vec![
+        "Methods are resolved by the type of the receiver, which must be a struct or a builtin type \
+        such as 'u64' or 'vector<u8>'. A type parameter could be instantiated with any type, so it \
+        has no methods"
+            .to_owned(),
+    ];
+    for (m, f) in context.method_call_alternatives(&method) {
+        let finfo = context.modules.function_info(&m, &f);
+        let Some((_, _, first_ty)) = finfo.signature.parameters.first() else {
+            continue;
+        };
+        if !type_param_receiver_compatible(receiver, first_ty) {
+            continue;
+        }
+        let ty = core::error_format(first_ty, &Subst::empty());
+        let bang = if finfo.macro_.is_some() { "!" } else { "" };
+        notes.push(format!(
+            "'{m}::{f}' takes a first argument of type {ty}. \
+            Consider calling it directly, e.g. '{m}::{f}{bang}(...)'"
+        ));
+    }
+    notes
+}
+
+/// Whether the `receiver`, a possibly borrowed type parameter, could be given for a first parameter
+/// of type `param`. The parameter must be a type parameter without abilities beyond those of the
+/// receiver, and must not need a mutable borrow of an immutable reference. A reference is only
+/// dereferenced for a parameter taken by value if the receiver can be copied
+fn type_param_receiver_compatible(receiver: &Type, param: &Type) -> bool {
+    let (receiver_ref, receiver_ty) = match &receiver.value {
+        Type_::Ref(mut_, inner) => (Some(*mut_), &inner.value),
+        ty => (None, ty),
+    };
+    let (param_ref, param_ty) = match &param.value {
+        Type_::Ref(mut_, inner) => (Some(*mut_), &inner.value),
+        ty => (None, ty),
+    };
+    let (Type_::Param(receiver_tparam), Type_::Param(param_tparam)) = (receiver_ty, param_ty)
+    else {
+        return false;
+    };
+    if !param_tparam.abilities.is_subset(&receiver_tparam.abilities) {
+        return false;
+    }
+    match (receiver_ref, param_ref) {
+        (_, Some(false)) | (None, _) | (Some(true), Some(true)) => true,
+        (Some(false), Some(true)) => false,
+        (Some(_), None) => receiver_tparam.abilities.has_ability_(Ability_::Copy),
+    }
+}
+
 fn edotted_ty_base(ty: &Type) -> &Type {
     match &ty.value {
         Type_::Unit
         | Type_::Param(_)
         | Type_::Anything
+        | Type_::Hole
         | Type_::UnresolvedError
         | Type_::Apply(_, _, _)
         | Type_::Fun(_, _) => ty,
@@ -2435,8 +3702,9 @@ fn module_call(
     argloc: Loc,
     args: Vec<T::Exp>,
 ) -> (Type, T::UnannotatedExp_) {
+    let ty_args_loc = ty_args_opt.as_ref().map(|_| between(f.0.loc, argloc));
     let fty = core::make_function_type(context, loc, &m, &f, ty_args_opt);
-    let (call, ret_ty) = module_call_impl(context, loc, m, f, fty, argloc, args);
+    let (call, ret_ty) = module_call_impl(context, loc, m, f, fty, ty_args_loc, argloc, args);
     (ret_ty, T::UnannotatedExp_::ModuleCall(Box::new(call)))
 }
 
@@ -2446,6 +3714,7 @@ fn module_call_impl(
     m: ModuleIdent,
     f: FunctionName,
     fty: ResolvedFunctionType,
+    ty_args_loc: Option<Loc>,
     argloc: Loc,
     args: Vec<T::Exp>,
 ) -> (T::ModuleCall, Type) {
@@ -2455,27 +3724,48 @@ fn module_call_impl(
         ty_args,
         params: parameters,
         return_,
+        use_fun,
     } = fty;
     check_call_target(
-        context, loc, /* is_macro_call */ None, macro_, declared, f,
+        context, loc, /* is_macro_call */ None, macro_, declared, use_fun, f,
     );
+    let (alias_suffix, alias_label) = member_alias(context, &m, f.0);
+    // point out the 'use fun' in case the user expected the method to resolve elsewhere
+    let use_fun_label = use_fun.map(|(method, use_fun_loc)| {
+        let msg = format!("'{method}' resolved to '{m}::{f}' from this 'use fun'");
+        (use_fun_loc, msg)
+    });
     let (arguments, arg_tys) = call_args(
         context,
         loc,
-        || format!("Invalid call of '{}::{}'", &m, &f),
-        parameters.len(),
+        || format!("Invalid call of '{}::{}'{}", &m, &f, alias_suffix),
+        &declared_parameters(context, &m, &f),
         argloc,
         args,
     );
     assert!(arg_tys.len() == parameters.len());
+    // the explicit type arguments are only redundant if they can be inferred without the
+    // constraints added by checking the arguments against them
+    let redundancy_check = ty_args_loc
+        .filter(|_| macro_.is_none() && context.env.is_warning_enabled(FILTER_REDUNDANT_TYPE_ARGS))
+        .map(|ty_args_loc| (ty_args_loc, context.subst.clone(), arg_tys.clone()));
     for (arg_ty, (param, param_ty)) in arg_tys.into_iter().zip(parameters.clone()) {
         let msg = || {
             format!(
-                "Invalid call of '{}::{}'. Invalid argument for parameter '{}'",
-                &m, &f, &param.value.name
+                "Invalid call of '{}::{}'{}. Invalid argument for parameter '{}'",
+                &m, &f, alias_suffix, &param.value.name
             )
         };
-        subtype(context, loc, msg, arg_ty, param_ty);
+        let labels = alias_label.clone().into_iter().chain(use_fun_label.clone());
+        subtype_labeled(context, loc, msg, labels, arg_ty, param_ty);
+    }
+    if let Some((ty_args_loc, subst, arg_tys)) = redundancy_check {
+        if inferable_type_arguments(context, loc, subst, &m, &f, &ty_args, &arg_tys) {
+            let msg = "Redundant type arguments. They can be inferred from the arguments";
+            let mut diag = diag!(TypeSafety::RedundantTypeArguments, (ty_args_loc, msg));
+            diag.add_fix(ty_args_loc, "");
+            context.add_diag(diag);
+        }
     }
     let params_ty_list = parameters.into_iter().map(|(_, ty)| ty).collect();
     let call = T::ModuleCall {
@@ -2491,15 +3781,113 @@ fn module_call_impl(
         .entry(m.value)
         .or_default()
         .insert(f.value());
+    context.record_external_function_call(&m, &f);
     (call, return_)
 }
 
+/// The location strictly between `before` and `after`, e.g. the type arguments between the name
+/// of a function and its arguments
+fn between(before: Loc, after: Loc) -> Loc {
+    Loc::new(before.file_hash(), before.end(), after.start())
+}
+
+/// Returns true if inferring the type arguments of a call to `m::f` from its arguments alone would
+/// pick exactly `ty_args`. Inference is re-run on `subst`, the substitution from before the call
+/// was checked, so it has no effect on the rest of type checking. If any type argument cannot be
+/// determined from the arguments, or is determined to be something else, they are not considered
+/// inferable.
+fn inferable_type_arguments(
+    context: &mut Context,
+    loc: Loc,
+    mut subst: Subst,
+    m: &ModuleIdent,
+    f: &FunctionName,
+    ty_args: &[Type],
+    arg_tys: &[Type],
+) -> bool {
+    let tparams = context
+        .function_info(m, f)
+        .signature
+        .type_parameters
+        .clone();
+    if tparams.len() != ty_args.len() {
+        return false;
+    }
+    let tvars = tparams
+        .iter()
+        .map(|_| core::make_tvar(context, loc))
+        .collect::<Vec<_>>();
+    let tparam_subst = core::make_tparam_subst(&tparams, tvars.clone());
+    for (arg_ty, (_, param_ty)) in arg_tys.iter().zip(declared_parameters(context, m, f)) {
+        let arg_ty = core::ready_tvars(&subst, arg_ty.clone());
+        let param_ty = core::subst_tparams(&tparam_subst, param_ty);
+        if core::subtype(&mut subst, &arg_ty, &param_ty).is_err() {
+            return false;
+        }
+    }
+    tvars.into_iter().zip(ty_args).all(|(tvar, ty_arg)| {
+        let inferred = core::ready_tvars(&subst, tvar);
+        let ty_arg = core::ready_tvars(&context.subst, ty_arg.clone());
+        is_fully_inferred(&inferred) && core::same_type(&inferred, &ty_arg)
+    })
+}
+
+/// true iff the type has no type variables left to infer. Numeric type variables are considered
+/// uninferred, as their type is only picked by a default
+fn is_fully_inferred(ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Unit | Type_::Param(_) => true,
+        Type_::Var(_) | Type_::Anything | Type_::Hole | Type_::UnresolvedError => false,
+        Type_::Ref(_, ty) => is_fully_inferred(ty),
+        Type_::Apply(_, _, ty_args) => ty_args.iter().all(is_fully_inferred),
+        Type_::Fun(args, result) => args.iter().all(is_fully_inferred) && is_fully_inferred(result),
+    }
+}
+
+/// Checks a constant given as the code for 'abort' or 'assert!'. An '#[error]' constant can have
+/// any type, and is replaced with an 'ErrorConstant' of type 'u64'. Any other constant must be a
+/// 'u64', and is reported here if it is not. All other codes are returned as is, to be checked
+/// against 'u64' by the caller
+fn abort_code(context: &mut Context, code: T::Exp) -> T::Exp {
+    use T::UnannotatedExp_ as TE;
+    let cloc = code.exp.loc;
+    let TE::Constant(m, c) = &code.exp.value else {
+        return code;
+    };
+    let (m, c) = (*m, *c);
+    if context.is_error_constant(&m, &c) {
+        return T::exp(Type_::u64(cloc), sp(cloc, TE::ErrorConstant(m, c)));
+    }
+    let is_u64 = code
+        .ty
+        .value
+        .builtin_name()
+        .is_some_and(|sp!(_, b_)| *b_ == BuiltinTypeName_::U64);
+    if is_u64 || matches!(code.ty.value, Type_::UnresolvedError) {
+        return code;
+    }
+    let msg = format!(
+        "Invalid abort code. The constant '{c}' has type {}, but only constants annotated with \
+        '#[{}]' can be used as an abort code without being a 'u64'",
+        core::error_format(&code.ty, &context.subst),
+        ErrorAttribute::ERROR,
+    );
+    let mut diag = diag!(TypeSafety::SubtypeError, (cloc, msg));
+    diag.add_note(format!(
+        "Try annotating the constant with '#[{}]', e.g. '#[{}] const {c}: ...'",
+        ErrorAttribute::ERROR,
+        ErrorAttribute::ERROR,
+    ));
+    context.add_diag(diag);
+    T::exp(context.error_type(cloc), sp(cloc, TE::UnresolvedError))
+}
+
 fn builtin_call(
     context: &mut Context,
     loc: Loc,
     sp!(bloc, nb_): N::BuiltinFunction,
     argloc: Loc,
-    args: Vec<T::Exp>,
+    mut args: Vec<T::Exp>,
 ) -> (Type, T::UnannotatedExp_) {
     use N::BuiltinFunction_ as NB;
     use T::BuiltinFunction_ as TB;
@@ -2516,16 +3904,40 @@ fn builtin_call(
             ret_ty = sp(loc, Type_::Ref(false, Box::new(ty_arg)));
         }
         NB::Assert(is_macro) => {
-            b_ = TB::Assert(is_macro);
+            // `assert!(cond)` aborts with an implicit code, as `abort` does without a code
+            let implicit_code = args.len() == 1;
+            if implicit_code {
+                if !context
+                    .env
+                    .supports_feature(context.current_package, FeatureGate::AssertWithoutCode)
+                {
+                    let edition = context.env.edition(context.current_package);
+                    let mut diag =
+                        create_feature_error(edition, FeatureGate::AssertWithoutCode, loc);
+                    diag.add_note(format!(
+                        "Try providing an abort code, e.g. '{}!(cond, 0)'",
+                        NB::ASSERT_MACRO
+                    ));
+                    context.add_diag(diag);
+                }
+                let code = sp(argloc, Value_::U64(IMPLICIT_ABORT_CODE));
+                let code_ = sp(argloc, T::UnannotatedExp_::Value(code));
+                args.push(T::exp(Type_::u64(argloc), code_));
+            } else if args.len() == 2 {
+                let code = args.pop().unwrap();
+                args.push(abort_code(context, code));
+            }
+            b_ = TB::Assert(is_macro, implicit_code);
             params_ty = vec![Type_::bool(bloc), Type_::u64(bloc)];
             ret_ty = sp(loc, Type_::Unit);
         }
     };
+    let params: Vec<_> = params_ty.iter().map(|ty| (None, ty.clone())).collect();
     let (arguments, arg_tys) = call_args(
         context,
         loc,
         || format!("Invalid call of '{}'", &b_),
-        params_ty.len(),
+        &params,
         argloc,
         args,
     );
@@ -2552,26 +3964,32 @@ fn vector_pack(
     args_: Vec<T::Exp>,
 ) -> (Type, T::UnannotatedExp_) {
     let arity = args_.len();
+    let params: Vec<_> = args_.iter().map(|e| (None, e.ty.clone())).collect();
     let (eargs, args_ty) = call_args(
         context,
         eloc,
         || -> String { panic!("ICE. could not create vector args") },
-        arity,
+        &params,
         argloc,
         args_,
     );
     let mut inferred_vec_ty_arg = core::make_tvar(context, eloc);
     for arg_ty in args_ty {
-        // TODO this could be improved... A LOT
-        // this ends up generating a new tvar chain for each element in the vector
-        // which ends up being n^2 chains
-        inferred_vec_ty_arg = join(
-            context,
-            eloc,
-            || "Invalid 'vector' instantiation. Incompatible argument",
-            inferred_vec_ty_arg,
-            arg_ty,
-        );
+        // elements with types still to be inferred, e.g. number literals, share the type variable
+        // of the vector, rather than each being joined into a longer chain of type variables
+        let acc_ty = core::ready_tvars(&context.subst, inferred_vec_ty_arg);
+        let arg_ty = core::ready_tvars(&context.subst, arg_ty);
+        inferred_vec_ty_arg = match core::merge_unbound_tvars(&mut context.subst, &acc_ty, &arg_ty)
+        {
+            Some(ty) => ty,
+            None => join(
+                context,
+                eloc,
+                || "Invalid 'vector' instantiation. Incompatible argument",
+                acc_ty,
+                arg_ty,
+            ),
+        };
     }
     let vec_ty_arg = match ty_arg_opt {
         None => inferred_vec_ty_arg,
@@ -2597,13 +4015,13 @@ fn call_args<S: std::fmt::Display, F: Fn() -> S>(
     context: &mut Context,
     loc: Loc,
     msg: F,
-    arity: usize,
+    params: &[(Option<Symbol>, Type)],
     argloc: Loc,
     mut args: Vec<T::Exp>,
 ) -> (Box<T::Exp>, Vec<Type>) {
     use T::UnannotatedExp_ as TE;
-    let tys = args.iter().map(|e| e.ty.clone()).collect();
-    let tys = make_arg_types(context, loc, msg, arity, argloc, tys);
+    let given = args.iter().map(|e| (e.exp.loc, e.ty.clone())).collect();
+    let tys = make_arg_types(context, loc, msg, params, argloc, given);
     let arg = match args.len() {
         0 => T::exp(
             sp(argloc, Type_::Unit),
@@ -2623,12 +4041,13 @@ fn make_arg_types<S: std::fmt::Display, F: Fn() -> S>(
     context: &mut Context,
     loc: Loc,
     msg: F,
-    arity: usize,
+    params: &[(Option<Symbol>, Type)],
     argloc: Loc,
-    mut given: Vec<Type>,
+    given: Vec<(Loc, Type)>,
 ) -> Vec<Type> {
-    let given_len = given.len();
-    core::check_call_arity(context, loc, msg, arity, argloc, given_len);
+    let arity = params.len();
+    let (given_locs, mut given): (Vec<_>, Vec<_>) = given.into_iter().unzip();
+    core::check_call_arity(context, loc, msg, params, argloc, &given_locs);
     while given.len() < arity {
         given.push(context.error_type(argloc))
     }
@@ -2638,12 +4057,29 @@ fn make_arg_types<S: std::fmt::Display, F: Fn() -> S>(
     given
 }
 
+/// The declared name and type of each parameter of the function, used to describe any missing
+/// arguments in arity errors
+fn declared_parameters(
+    context: &Context,
+    m: &ModuleIdent,
+    f: &FunctionName,
+) -> Vec<(Option<Symbol>, Type)> {
+    context
+        .function_info(m, f)
+        .signature
+        .parameters
+        .iter()
+        .map(|(_, v, ty)| (Some(v.value.name), ty.clone()))
+        .collect()
+}
+
 fn check_call_target(
     context: &mut Context,
     call_loc: Loc,
     is_macro_call: Option<Loc>,
     declared_macro_modifier: Option<Loc>,
     declared: Loc,
+    use_fun: Option<(Name, Loc)>,
     f: FunctionName,
 ) {
     let decl_is_macro = declared_macro_modifier.is_some();
@@ -2653,15 +4089,20 @@ fn check_call_target(
 
     let macro_call_loc = is_macro_call.unwrap_or(call_loc);
     let decl_loc = declared_macro_modifier.unwrap_or(declared);
+    // method calls through a 'use fun' are written with the alias, not the function name
+    let name = match use_fun {
+        Some((method, _)) => method.value,
+        None => f.value(),
+    };
     let call_msg = if decl_is_macro {
         format!(
-            "'{f}' is a macro function and must be called with a `!`. \
-            Try replacing with '{f}!'"
+            "'{name}' is a macro function and must be called with a `!`. \
+            Try replacing with '{name}!'"
         )
     } else {
         format!(
-            "'{f}' is not a macro function and cannot be called with a `!`. \
-            Try replacing with '{f}'"
+            "'{name}' is not a macro function and cannot be called with a `!`. \
+            Try replacing with '{name}'"
         )
     };
     let decl_msg = if decl_is_macro {
@@ -2669,11 +4110,16 @@ fn check_call_target(
     } else {
         "Normal (non-'macro') function is declared here"
     };
-    context.env.add_diag(diag!(
+    let mut diag = diag!(
         TypeSafety::InvalidCallTarget,
         (macro_call_loc, call_msg),
         (decl_loc, decl_msg),
-    ));
+    );
+    if let Some((method, use_fun_loc)) = use_fun {
+        let use_fun_msg = format!("'{method}' is declared as an alias for '{f}' here");
+        diag.add_secondary_label((use_fun_loc, use_fun_msg));
+    }
+    context.add_diag(diag);
 }
 
 //**************************************************************************************************
@@ -2749,6 +4195,7 @@ fn macro_call_impl(
         ty_args,
         params: parameters,
         return_,
+        use_fun,
     } = fty;
     check_call_target(
         context,
@@ -2756,20 +4203,30 @@ fn macro_call_impl(
         /* is_macro_call */ Some(macro_call_loc),
         macro_,
         declared,
+        use_fun,
         f,
     );
+    let arg_locs: Vec<_> = args
+        .iter()
+        .map(|arg| match arg {
+            EvalStrategy::ByValue(e) => e.exp.loc,
+            EvalStrategy::ByName(ne) => ne.loc,
+        })
+        .collect();
     core::check_call_arity(
         context,
         loc,
         || format!("Invalid call of '{}::{}'", &m, &f),
-        parameters.len(),
+        &declared_parameters(context, &m, &f),
         argloc,
-        args.len(),
+        &arg_locs,
     );
-    // instantiate the param types to check for constraints, even if the argument isn't used
-    for (_, param_ty) in &parameters {
-        core::instantiate(context, param_ty.clone());
-    }
+    // instantiate the param types once, up front, so that their constraints are checked even if
+    // the argument isn't used. The instantiated types are then used for checking the arguments
+    let parameters: Vec<_> = parameters
+        .into_iter()
+        .map(|(param, param_ty)| (param, core::instantiate(context, param_ty)))
+        .collect();
     while args.len() < parameters.len() {
         args.push(EvalStrategy::ByName(sp(loc, N::Exp_::UnresolvedError)));
     }
@@ -2788,12 +4245,12 @@ fn macro_call_impl(
                         &m, &f, &param.value.name
                     )
                 };
-                subtype(context, loc, msg, e.ty.clone(), param_ty.clone());
+                subtype(context, loc, msg, e.ty.clone(), param_ty);
                 EvalStrategy::ByValue(e)
             }
             EvalStrategy::ByName(ne) => {
                 let expected_ty =
-                    expected_by_name_arg_type(context, loc, &m, &f, &param, &ne, param_ty.clone());
+                    expected_by_name_arg_type(context, loc, &m, &f, &param, &ne, param_ty);
                 EvalStrategy::ByName((ne, expected_ty))
             }
         })
@@ -2809,6 +4266,7 @@ fn macro_call_impl(
 // If the argument is a lambda, we need to check that the lambda's type matches the expected type
 // so that any calls to the lambda can be properly expanded
 // Otherwise, we just return the parameters type
+// The parameter type must already be instantiated, only the lambda's annotations are instantiated
 fn expected_by_name_arg_type(
     context: &mut Context,
     call_loc: Loc,
@@ -2863,11 +4321,16 @@ fn expand_macro(
     use T::SequenceItem_ as TS;
     use T::UnannotatedExp_ as TE;
 
-    let valid = context.add_macro_expansion(m, f, call_loc);
+    // The color of the scope at the call site, before the body is expanded. The body may contain
+    // further expansions (e.g. from a receiver that is itself a macro call), so this must not be
+    // read after expanding
+    let call_color = context.current_call_color();
+    let valid = context.add_macro_expansion(m, f, &type_args, call_loc);
     if !valid {
         assert!(context.env.has_errors());
         return (context.error_type(call_loc), TE::UnresolvedError);
     }
+    context.record_macro_expansion(call_loc, &m, &f);
     let res = match macro_expand::call(context, call_loc, m, f, type_args, args, return_ty) {
         None => {
             assert!(context.env.has_errors());
@@ -2897,10 +4360,17 @@ fn expand_macro(
                 })
                 .collect();
             // add the body
+            let metrics_before = context.metrics.clone();
             let body = exp(context, body);
+            if let (Some(metrics), Some(before)) = (&mut context.metrics, metrics_before) {
+                // nested expansions have already added their nodes
+                let nested = metrics.macro_expanded_nodes - before.macro_expanded_nodes;
+                metrics.macro_expansions += 1;
+                metrics.macro_expanded_nodes += metrics.typed_nodes - before.typed_nodes - nested;
+            }
             let ty = body.ty.clone();
             seq.push_back(sp(body.exp.loc, TS::Seq(body)));
-            let use_funs = N::UseFuns::new(context.current_call_color());
+            let use_funs = N::UseFuns::new(call_color);
             let e_ = TE::Block((use_funs, seq));
             (ty, e_)
         }
@@ -2919,11 +4389,23 @@ fn expand_macro(
 /// 1) We can track the use_fun_scope, which is used for resolving method calls correctly
 /// 2) After substitution, we can mark the Block as coming from a macro expansion which is used
 ///    for tracking recursive macro calls
+/// An argument that was itself substituted by an outer expansion keeps the color of the scope it
+/// was written in, so that wrapping it again does not introduce a new use fun scope
 fn convert_macro_arg_to_block(context: &Context, sp!(loc, ne_): N::Exp) -> N::Exp {
     let ne_ = match ne_ {
         N::Exp_::Block(_) | N::Exp_::Lambda(_) | N::Exp_::UnresolvedError => ne_,
         ne_ => {
-            let color = context.current_call_color();
+            let color = match &ne_ {
+                N::Exp_::Annotate(inner, _) => match &inner.value {
+                    N::Exp_::Block(N::Block {
+                        from_macro_argument: Some(_),
+                        seq: (use_funs, _),
+                        ..
+                    }) => use_funs.color,
+                    _ => context.current_call_color(),
+                },
+                _ => context.current_call_color(),
+            };
             let seq_ = VecDeque::from([sp(loc, N::SequenceItem_::Seq(Box::new(sp(loc, ne_))))]);
             let seq = (N::UseFuns::new(color), seq_);
             let block = N::Block {
@@ -2983,7 +4465,7 @@ fn unused_let_muts(context: &mut Context) {
         if used_mut.is_none() && !v.value.starts_with_underscore() {
             let decl_msg = format!("The variable '{}' is never used mutably", v.value.name);
             let mut_msg = "Consider removing the 'mut' declaration here";
-            context.env.add_diag(diag!(
+            context.add_diag(diag!(
                 UnusedItem::MutModifier,
                 (v.loc, decl_msg),
                 (mut_loc, mut_msg)
@@ -2992,9 +4474,19 @@ fn unused_let_muts(context: &mut Context) {
     }
 }
 
-/// Generates warnings for unused (private) functions and unused constants.
+/// Generates warnings for unused (private) functions, unused constants, and unused structs. A
+/// private function is unused if it is not transitively called from a function visible outside of
+/// the module, and a private macro is unused if it is not transitively expanded from one. A struct
+/// is unused if no type naming it is used anywhere in the program, including as a phantom type
+/// argument. 'public' structs are not reported, as they may be used by other packages.
 /// Should be called after the whole program has been processed.
-fn unused_module_members(context: &mut Context, mident: &ModuleIdent_, mdef: &T::ModuleDefinition) {
+fn unused_module_members(
+    context: &mut Context,
+    mident: ModuleIdent,
+    mdef: &T::ModuleDefinition,
+    call_graph: &CallGraph,
+    package_calls: &PackageCalls,
+) {
     if !mdef.is_source_module {
         // generate warnings only for modules compiled in this pass rather than for all modules
         // including pre-compiled libraries for which we do not have source code available and
@@ -3007,22 +4499,119 @@ fn unused_module_members(context: &mut Context, mident: &ModuleIdent_, mdef: &T:
         .env
         .add_warning_filter_scope(mdef.warning_filter.clone());
 
+    // usages from tests only count for test-only items, as other items should be marked
+    // '#[test_only]' if only tests use them
+    let is_used = |used: &BTreeMap<ModuleIdent_, BTreeSet<Symbol>>, name: &Symbol| {
+        used.get(&mident.value)
+            .is_some_and(|names| names.contains(name))
+    };
     for (loc, name, c) in &mdef.constants {
         context
             .env
             .add_warning_filter_scope(c.warning_filter.clone());
 
-        let members = context.used_module_members.get(mident);
-        if members.is_none() || !members.unwrap().contains(name) {
-            let msg = format!("The constant '{name}' is never used. Consider removing it.");
-            context
-                .env
-                .add_diag(diag!(UnusedItem::Constant, (loc, msg)))
+        let used_in_tests = is_used(&context.test_used_module_members, name);
+        let is_test_only = c
+            .attributes
+            .contains_key_(&TestingAttribute::TestOnly.into());
+        let used = is_used(&context.used_module_members, name) || (is_test_only && used_in_tests);
+        if !used {
+            let msg = if used_in_tests {
+                format!(
+                    "The constant '{name}' is only used in tests. Consider marking it '#[{}]'.",
+                    TestingAttribute::TEST_ONLY
+                )
+            } else {
+                format!("The constant '{name}' is never used. Consider removing it.")
+            };
+            context.add_diag(diag!(UnusedItem::Constant, (loc, msg)))
         }
 
         context.env.pop_warning_filter_scope();
     }
 
+    let otw_name = Symbol::from(mident.value.module.value().as_str().to_uppercase());
+    for (loc, name, sdef) in &mdef.structs {
+        if matches!(sdef.fields, N::StructFields::Native(_))
+            || matches!(sdef.visibility, Visibility::Public(_))
+            || sdef.test_only.is_some()
+            // a Sui-specific filter to avoid signaling that the one-time witness is unused
+            || (is_sui_mode && *name == otw_name)
+        {
+            continue;
+        }
+        if is_used(&context.used_module_types, name) {
+            continue;
+        }
+        context
+            .env
+            .add_warning_filter_scope(sdef.warning_filter.clone());
+        let msg = if is_used(&context.test_used_module_types, name) {
+            format!(
+                "The struct '{name}' is only used in tests. Consider marking it '#[{}]'.",
+                TestingAttribute::TEST_ONLY
+            )
+        } else {
+            format!("The struct '{name}' is never used. Consider removing it.")
+        };
+        context.add_diag(diag!(UnusedItem::Struct, (loc, msg)));
+        context.env.pop_warning_filter_scope();
+    }
+
+    let mut call_graph = module_call_graph(mident, mdef, call_graph);
+    let called_in_module: BTreeSet<Symbol> = call_graph.values().flatten().copied().collect();
+    // A macro is expanded at its call sites, so each expansion is an edge from the function it was
+    // expanded in. This includes nested expansions, so a macro only expanded by another macro is
+    // used only if that macro is
+    let mut expanded_in_module = BTreeSet::new();
+    let mut expanded_elsewhere = BTreeSet::new();
+    for (name, fun) in mdef.functions.key_cloned_iter() {
+        let name = name.value();
+        let expansions = fun
+            .macro_
+            .and_then(|_| context.macro_expansions.get(&(mident.value, name)));
+        for expanded_in in expansions.into_iter().flatten() {
+            match expanded_in {
+                Some((m, f)) if *m == mident.value => {
+                    call_graph.entry(*f).or_default().insert(name);
+                    expanded_in_module.insert(name);
+                }
+                _ => {
+                    expanded_elsewhere.insert(name);
+                }
+            }
+        }
+    }
+    // Roots are functions that can be called from outside of the module, along with functions
+    // used from somewhere other than a function body in this module, e.g. from a constant
+    // initializer or from the body of a macro expanded in another module. Tests are tracked
+    // separately so that functions only used by tests can be reported
+    let mut roots = vec![];
+    let mut test_roots = vec![];
+    for (name, fun) in mdef.functions.key_cloned_iter() {
+        let name = name.value();
+        if fun.attributes.contains_key_(&TestingAttribute::Test.into()) {
+            test_roots.push(name);
+            continue;
+        }
+        let used_outside_of_module_calls = if fun.macro_.is_some() {
+            expanded_elsewhere.contains(&name)
+        } else {
+            is_used(&context.external_function_calls, &name)
+        };
+        if fun.entry.is_some()
+            || !matches!(fun.visibility, Visibility::Internal)
+            || (is_sui_mode && name == sui_mode::INIT_FUNCTION_NAME)
+            || used_outside_of_module_calls
+        {
+            roots.push(name);
+        } else if is_used(&context.test_external_function_calls, &name) {
+            test_roots.push(name);
+        }
+    }
+    let reachable = reachable_functions(&call_graph, roots);
+    let reachable_from_tests = reachable_functions(&call_graph, test_roots);
+
     for (loc, name, fun) in &mdef.functions {
         if fun.attributes.contains_key_(&TestingAttribute::Test.into()) {
             // functions with #[test] attribute are implicitly used
@@ -3032,27 +4621,307 @@ fn unused_module_members(context: &mut Context, mident: &ModuleIdent_, mdef: &T:
             // a Sui-specific filter to avoid signaling that the init function is unused
             continue;
         }
-        context
-            .env
-            .add_warning_filter_scope(fun.warning_filter.clone());
-
-        let members = context.used_module_members.get(mident);
-        if fun.entry.is_none()
-            && matches!(fun.visibility, Visibility::Internal)
-            && (members.is_none() || !members.unwrap().contains(name))
+        if fun.entry.is_some()
+            || !matches!(fun.visibility, Visibility::Internal)
+            || reachable.contains(name)
         {
             // TODO: postponing handling of friend functions until we decide what to do with them
             // vis-a-vis ideas around package-private
+            continue;
+        }
+        let is_test_only = fun
+            .attributes
+            .contains_key_(&TestingAttribute::TestOnly.into());
+        if is_test_only && reachable_from_tests.contains(name) {
+            continue;
+        }
+        context
+            .env
+            .add_warning_filter_scope(fun.warning_filter.clone());
+        let diag = if fun.macro_.is_some() {
+            if reachable_from_tests.contains(name) {
+                let msg = format!(
+                    "The non-'public' macro '{name}' is only expanded in tests. \
+                    Consider marking it '#[{}]'.",
+                    TestingAttribute::TEST_ONLY
+                );
+                diag!(UnusedItem::TestOnlyFunction, (loc, msg))
+            } else if expanded_in_module.contains(name) {
+                let msg = format!(
+                    "The non-'public' macro '{name}' is only expanded in functions that are never \
+                    called. Consider removing it."
+                );
+                diag!(UnusedItem::Function, (loc, msg))
+            } else {
+                let msg = format!(
+                    "The non-'public' macro '{name}' is never expanded. Consider removing it."
+                );
+                diag!(UnusedItem::Function, (loc, msg))
+            }
+        } else if reachable_from_tests.contains(name) {
+            let msg = format!(
+                "The non-'public', non-'entry' function '{name}' is only called from tests. \
+                Consider marking it '#[{}]'.",
+                TestingAttribute::TEST_ONLY
+            );
+            diag!(UnusedItem::TestOnlyFunction, (loc, msg))
+        } else if called_in_module.contains(name) {
+            let msg = format!(
+                "The non-'public', non-'entry' function '{name}' is only called from functions \
+                that are never called. Consider removing it."
+            );
+            diag!(UnusedItem::Function, (loc, msg))
+        } else {
             let msg = format!(
                 "The non-'public', non-'entry' function '{name}' is never called. \
                 Consider removing it."
             );
-            context
-                .env
-                .add_diag(diag!(UnusedItem::Function, (loc, msg)))
+            diag!(UnusedItem::Function, (loc, msg))
+        };
+        context.add_diag(diag);
+        context.env.pop_warning_filter_scope();
+    }
+
+    for (loc, name, fun) in &mdef.functions {
+        if !matches!(fun.visibility, Visibility::Package(_))
+            || fun.entry.is_some()
+            || fun.macro_.is_some()
+            || fun
+                .attributes
+                .contains_key_(&TestingAttribute::TestOnly.into())
+            || package_calls.calls.contains(&(mident.value, *name))
+        {
+            continue;
         }
+        context
+            .env
+            .add_warning_filter_scope(fun.warning_filter.clone());
+        let msg = if reachable.contains(name) && called_in_module.contains(name) {
+            format!(
+                "The '{}' function '{name}' is never called from another module. \
+                Consider making it private.",
+                Visibility::PACKAGE
+            )
+        } else if package_calls.test_calls.contains(&(mident.value, *name))
+            || reachable_from_tests.contains(name)
+        {
+            format!(
+                "The '{}' function '{name}' is only called from tests. \
+                Consider marking it '#[{}]'.",
+                Visibility::PACKAGE,
+                TestingAttribute::TEST_ONLY
+            )
+        } else {
+            format!(
+                "The '{}' function '{name}' is never called. Consider removing it.",
+                Visibility::PACKAGE
+            )
+        };
+        context.add_diag(diag!(UnusedItem::PackageFunction, (loc, msg)));
         context.env.pop_warning_filter_scope();
     }
 
     context.env.pop_warning_filter_scope();
 }
+
+struct PackageCalls {
+    /// Functions called from another module by non-test code
+    calls: BTreeSet<(ModuleIdent_, Symbol)>,
+    /// Functions called from another module only by tests or test only code
+    test_calls: BTreeSet<(ModuleIdent_, Symbol)>,
+}
+
+/// The functions called from outside of their own module, with calls made by tests or test only
+/// code tracked separately
+fn package_calls(
+    modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    call_graph: &CallGraph,
+) -> PackageCalls {
+    let is_test_code = |attributes: &Attributes| {
+        attributes.contains_key_(&TestingAttribute::Test.into())
+            || attributes.contains_key_(&TestingAttribute::TestOnly.into())
+    };
+    let mut package_calls = PackageCalls {
+        calls: BTreeSet::new(),
+        test_calls: BTreeSet::new(),
+    };
+    for (mident, mdef) in modules.key_cloned_iter() {
+        let test_module = is_test_code(&mdef.attributes);
+        for (fname, fdef) in mdef.functions.key_cloned_iter() {
+            let calls = if test_module || is_test_code(&fdef.attributes) {
+                &mut package_calls.test_calls
+            } else {
+                &mut package_calls.calls
+            };
+            let callees = call_graph
+                .callees(&mident, &fname)
+                .filter(|(m, _)| *m != mident)
+                .map(|(m, f)| (m.value, f.value()));
+            calls.extend(callees);
+        }
+    }
+    package_calls
+}
+
+/// The functions called by each function in the module, limited to calls to functions of that same
+/// module
+fn module_call_graph(
+    mident: ModuleIdent,
+    mdef: &T::ModuleDefinition,
+    call_graph: &CallGraph,
+) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+    mdef.functions
+        .key_cloned_iter()
+        .map(|(fname, _)| {
+            let callees = call_graph
+                .callees(&mident, &fname)
+                .filter(|(m, _)| *m == mident)
+                .map(|(_, f)| f.value())
+                .collect();
+            (fname.value(), callees)
+        })
+        .collect()
+}
+
+/// The calls made by each function in the program. Macros are expanded at their call sites, so
+/// calls made by a macro body are attributed to the function that invoked the macro. This is the
+/// only walk over the calls of the program, and is kept as `TypingProgramInfo::call_graph`
+fn call_graph(modules: &mut UniqueMap<ModuleIdent, T::ModuleDefinition>) -> CallGraph {
+    use T::UnannotatedExp_ as TE;
+
+    struct Context {
+        current: Option<(ModuleIdent, FunctionName)>,
+        calls: BTreeMap<(ModuleIdent, FunctionName), FunctionCalls>,
+    }
+
+    impl TypingVisitorContext for Context {
+        fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+        fn pop_warning_filter_scope(&mut self) {}
+
+        fn visit_function_custom(
+            &mut self,
+            module: ModuleIdent,
+            function_name: FunctionName,
+            _fdef: &mut T::Function,
+        ) -> bool {
+            self.current = Some((module, function_name));
+            self.calls.entry((module, function_name)).or_default();
+            false
+        }
+
+        fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+            let Some(current) = self.current else {
+                return false;
+            };
+            let fcalls = self.calls.entry(current).or_default();
+            match &exp.exp.value {
+                TE::ModuleCall(call) => {
+                    fcalls.callees.insert((call.module, call.name));
+                }
+                TE::Builtin(b, _) => match &b.value {
+                    T::BuiltinFunction_::Freeze(_) => fcalls.uses_freeze = true,
+                    T::BuiltinFunction_::Assert(..) => fcalls.uses_assert = true,
+                },
+                _ => (),
+            }
+            false
+        }
+    }
+
+    let mut context = Context {
+        current: None,
+        calls: BTreeMap::new(),
+    };
+    for (mident, mdef) in modules.key_cloned_iter_mut() {
+        for (fname, fdef) in mdef.functions.key_cloned_iter_mut() {
+            context.visit_function(mident, fname, fdef);
+        }
+    }
+    CallGraph::new(context.calls)
+}
+
+fn reachable_functions(
+    call_graph: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+    roots: Vec<Symbol>,
+) -> BTreeSet<Symbol> {
+    let mut reachable = BTreeSet::new();
+    let mut worklist = roots;
+    while let Some(f) = worklist.pop() {
+        if !reachable.insert(f) {
+            continue;
+        }
+        if let Some(callees) = call_graph.get(&f) {
+            worklist.extend(callees.iter().filter(|g| !reachable.contains(*g)));
+        }
+    }
+    reachable
+}
+
+//**************************************************************************************************
+// While (true)
+//**************************************************************************************************
+
+/// Lowers each `while (true)` that is exited by a `break` into a `loop`. These are typed as loops,
+/// so their breaks may carry a value, and they must be compiled as loops to produce it. This runs
+/// after the visitors, so lints still see the `while` as written
+fn while_true_to_loop(modules: &mut UniqueMap<ModuleIdent, T::ModuleDefinition>) {
+    use T::UnannotatedExp_ as TE;
+
+    struct WhileTrue;
+
+    impl TypingVisitorContext for WhileTrue {
+        fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+        fn pop_warning_filter_scope(&mut self) {}
+
+        fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+            let TE::While(name, cond, body) = &mut exp.exp.value else {
+                return false;
+            };
+            if T::bool_literal_value(cond) == Some(true) && breaks_from(*name, body) {
+                let TE::While(name, _, body) =
+                    std::mem::replace(&mut exp.exp.value, TE::UnresolvedError)
+                else {
+                    unreachable!()
+                };
+                exp.exp.value = TE::Loop {
+                    name,
+                    has_break: true,
+                    body,
+                };
+            }
+            // continue into the body, which may have loops of its own
+            false
+        }
+    }
+
+    for (mident, mdef) in modules.key_cloned_iter_mut() {
+        for (fname, fdef) in mdef.functions.key_cloned_iter_mut() {
+            WhileTrue.visit_function(mident, fname, fdef);
+        }
+    }
+}
+
+fn breaks_from(name: BlockLabel, body: &mut T::Exp) -> bool {
+    use T::UnannotatedExp_ as TE;
+
+    struct Breaks {
+        name: BlockLabel,
+        found: bool,
+    }
+
+    impl TypingVisitorContext for Breaks {
+        fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+        fn pop_warning_filter_scope(&mut self) {}
+
+        fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+            if let TE::Give(name, _) = &exp.exp.value {
+                self.found |= *name == self.name;
+            }
+            self.found
+        }
+    }
+
+    let mut breaks = Breaks { name, found: false };
+    breaks.visit_exp(body);
+    breaks.found
+}
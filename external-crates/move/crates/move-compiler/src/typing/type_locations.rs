@@ -0,0 +1,127 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use move_ir_types::location::*;
+
+use crate::{
+    diagnostics::WarningFilters,
+    expansion::ast::ModuleIdent,
+    naming::ast::Type,
+    parser::ast::{ConstantName, FunctionName},
+    typing::{ast as T, visitor::TypingVisitorContext},
+};
+
+/// The fully expanded types of expressions and variable bindings, keyed by their source location.
+/// Only collected when `Flags::record_type_locations` is set.
+///
+/// Only locations within the constant or function being typed are recorded. The expressions of a
+/// macro body that are substituted in at a macro call are not, as they are located in the macro,
+/// and are typed differently at each call. The macro call itself and its arguments are recorded.
+#[derive(Debug, Clone, Default)]
+pub struct TypeLocations {
+    types: HashMap<Loc, Type>,
+    // The types of the valid segments of dotted paths, looking through references, recorded while
+    // typing. Kept separately since a path with an invalid segment might not be in the typed AST
+    dotted_segments: HashMap<Loc, Type>,
+}
+
+struct Context {
+    types: HashMap<Loc, Type>,
+    // The location of the constant or function body being visited. Expressions outside of it were
+    // substituted in from a macro body, and are covered by the type of the macro call instead
+    definition_loc: Option<Loc>,
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn program(prog: &mut T::Program_, dotted_segments: HashMap<Loc, Type>) -> TypeLocations {
+    let mut context = Context {
+        types: HashMap::new(),
+        definition_loc: None,
+    };
+    context.visit(prog);
+    TypeLocations {
+        types: context.types,
+        dotted_segments,
+    }
+}
+
+impl TypeLocations {
+    /// The type of the expression or variable binding with exactly this location. If several
+    /// expressions share a location, e.g. an implicit borrow or copy, the outermost one is kept.
+    /// Locations inside of macro bodies have no type, see `TypeLocations`
+    pub fn type_at(&self, loc: Loc) -> Option<&Type> {
+        self.types.get(&loc)
+    }
+
+    /// The type of the segment of a dotted path with exactly this location, e.g. 'a.b' in
+    /// 'a.b.c', looking through references. Recorded for the segments before an invalid one, even
+    /// if the path as a whole could not be typed
+    pub fn dotted_segment_type_at(&self, loc: Loc) -> Option<&Type> {
+        self.dotted_segments.get(&loc)
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+//**************************************************************************************************
+// Visitor
+//**************************************************************************************************
+
+impl Context {
+    fn record(&mut self, loc: Loc, ty: &Type) {
+        let Some(definition_loc) = self.definition_loc else {
+            return;
+        };
+        if definition_loc.contains(loc) {
+            self.types.entry(loc).or_insert_with(|| ty.clone());
+        }
+    }
+}
+
+impl TypingVisitorContext for Context {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {}
+    fn pop_warning_filter_scope(&mut self) {}
+
+    fn visit_constant_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _constant_name: ConstantName,
+        cdef: &mut T::Constant,
+    ) -> bool {
+        self.definition_loc = Some(cdef.loc);
+        false
+    }
+
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.definition_loc = Some(fdef.body.loc);
+        false
+    }
+
+    fn visit_lvalue_custom(&mut self, sp!(loc, lvalue_): &mut T::LValue) -> bool {
+        if let T::LValue_::Var { ty, .. } = lvalue_ {
+            self.record(*loc, ty)
+        }
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        self.record(exp.exp.loc, &exp.ty);
+        false
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports usages of a non-'copy' local after it was already moved, e.g. `let y = x; let z = x;`.
+//! This runs after types are expanded, since whether a usage copies or moves a local depends on
+//! its inferred type. Only straight-line code within a single block is checked. Moves inside of
+//! branches, loops, and nested blocks are left to the locals analysis in CFGIR.
+
+use super::core::{self, Context};
+use crate::{
+    diag,
+    diagnostics::codes::MoveSafety,
+    naming::ast::{Type, Var},
+    parser::ast::{Ability_, BinOp_},
+    typing::ast as T,
+};
+use move_ir_types::location::*;
+
+//**************************************************************************************************
+// Functions
+//**************************************************************************************************
+
+pub fn function_body_(context: &mut Context, b_: &mut T::FunctionBody_) {
+    match b_ {
+        T::FunctionBody_::Native | T::FunctionBody_::Macro => (),
+        T::FunctionBody_::Defined(seq) => {
+            context.forget_local_moves();
+            sequence(context, seq);
+            context.forget_local_moves();
+        }
+    }
+}
+
+//**************************************************************************************************
+// Expressions
+//**************************************************************************************************
+
+fn sequence(context: &mut Context, (_, seq): &mut T::Sequence) {
+    for item in seq {
+        sequence_item(context, item)
+    }
+}
+
+fn sequence_item(context: &mut Context, item: &mut T::SequenceItem) {
+    use T::SequenceItem_ as S;
+    match &mut item.value {
+        S::Seq(te) => exp(context, te),
+        S::Declare(tbind) => lvalues(context, tbind),
+        S::Bind(tbind, _, te) => {
+            exp(context, te);
+            lvalues(context, tbind);
+        }
+    }
+}
+
+/// Checks `e` in isolation from the surrounding sequence, as it might not be evaluated exactly
+/// once. Any moves before or inside of it are forgotten.
+fn nested_exp(context: &mut Context, e: &mut T::Exp) {
+    context.forget_local_moves();
+    exp(context, e);
+    context.forget_local_moves();
+}
+
+fn nested_sequence(context: &mut Context, seq: &mut T::Sequence) {
+    context.forget_local_moves();
+    sequence(context, seq);
+    context.forget_local_moves();
+}
+
+fn exp(context: &mut Context, e: &mut T::Exp) {
+    use T::UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::Move { var, .. } => {
+            if !has_copy(context, &e.ty) {
+                let var = *var;
+                if let Some(prev) = context.record_local_move(&var, e.exp.loc) {
+                    report_use_after_move(context, &var, &e.ty, e.exp.loc, prev);
+                    // the error has been reported, so do not report it again in CFGIR
+                    e.exp.value = E::UnresolvedError;
+                }
+            }
+        }
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Constant(_, _)
+        | E::ErrorConstant(_, _)
+        | E::Copy { .. }
+        | E::Use(_)
+        | E::BorrowLocal(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => exp(context, &mut call.arguments),
+        E::Builtin(_, args) | E::Vector(_, _, _, args) => exp(context, args),
+
+        E::IfElse(eb, et, ef) => {
+            exp(context, eb);
+            nested_exp(context, et);
+            nested_exp(context, ef);
+        }
+        E::While(_, eb, eloop) => {
+            nested_exp(context, eb);
+            nested_exp(context, eloop);
+        }
+        E::Loop { body: eloop, .. } => nested_exp(context, eloop),
+        E::NamedBlock(_, seq) | E::Block(seq) => nested_sequence(context, seq),
+        E::Assign(assigns, _, er) => {
+            exp(context, er);
+            lvalues(context, assigns);
+        }
+
+        E::Return(er) | E::Abort(er) | E::Give(_, er) => {
+            exp(context, er);
+            context.forget_local_moves();
+        }
+        E::Continue(_) => context.forget_local_moves(),
+        E::Dereference(er)
+        | E::UnaryExp(_, er)
+        | E::Borrow(_, er, _)
+        | E::TempBorrow(_, er)
+        | E::Cast(er, _)
+        | E::Annotate(er, _) => exp(context, er),
+        E::Mutate(el, er) => {
+            exp(context, er);
+            exp(context, el)
+        }
+        E::BinopExp(el, sp!(_, BinOp_::And | BinOp_::Or), _, er) => {
+            exp(context, el);
+            nested_exp(context, er);
+        }
+        E::BinopExp(el, _, _, er) => {
+            exp(context, el);
+            exp(context, er);
+        }
+
+        E::Pack(_, _, _, fields) => {
+            let mut fields = fields
+                .iter_mut()
+                .map(|(_, _, (idx, (_, fe)))| (*idx, fe))
+                .collect::<Vec<_>>();
+            fields.sort_by_key(|(idx, _)| *idx);
+            for (_, fe) in fields {
+                exp(context, fe)
+            }
+        }
+        E::ExpList(el) => {
+            for item in el {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        exp(context, e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lvalues(context: &mut Context, binds: &mut T::LValueList) {
+    for b in &mut binds.value {
+        lvalue(context, b)
+    }
+}
+
+fn lvalue(context: &mut Context, b: &mut T::LValue) {
+    use T::LValue_ as L;
+    match &mut b.value {
+        L::Ignore => (),
+        L::Var { var, .. } => context.forget_local_move(var),
+        L::BorrowUnpack(_, _, _, _, fields) | L::Unpack(_, _, _, fields) => {
+            for (_, _, (_, (_, innerb))) in fields.iter_mut() {
+                lvalue(context, innerb)
+            }
+        }
+    }
+}
+
+fn has_copy(context: &Context, ty: &Type) -> bool {
+    let abilities = core::infer_abilities(&context.modules, &context.subst, ty.clone());
+    abilities.has_ability_(Ability_::Copy)
+}
+
+fn report_use_after_move(context: &mut Context, var: &Var, ty: &Type, loc: Loc, prev: Loc) {
+    let name = var.value.name;
+    let ty_str = core::error_format(ty, &context.subst);
+    let mut diag = diag!(
+        MoveSafety::UnassignedVariable,
+        (
+            loc,
+            format!(
+                "Invalid usage of previously moved variable '{name}'. Used again here after move"
+            ),
+        ),
+        (prev, "Value moved here"),
+    );
+    diag.add_note(format!(
+        "'{name}' has type {ty_str}, which does not have the 'copy' ability. \
+        Using it by value moves it"
+    ));
+    context.add_diag(diag);
+}
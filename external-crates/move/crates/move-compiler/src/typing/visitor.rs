@@ -140,8 +140,38 @@ pub trait TypingVisitorContext {
         use T::SequenceItem_ as SI;
         match seq_item {
             SI::Seq(e) => self.visit_exp(e),
-            SI::Declare(_) => (),
-            SI::Bind(_, _, e) => self.visit_exp(e),
+            SI::Declare(lvalues) => self.visit_lvalue_list(lvalues),
+            SI::Bind(lvalues, _, e) => {
+                self.visit_lvalue_list(lvalues);
+                self.visit_exp(e)
+            }
+        }
+    }
+
+    fn visit_lvalue_list(&mut self, sp!(_, lvalues): &mut T::LValueList) {
+        for lvalue in lvalues {
+            self.visit_lvalue(lvalue)
+        }
+    }
+
+    /// Custom visit for an lvalue. It will skip `visit_lvalue` if `visit_lvalue_custom` returns
+    /// true.
+    fn visit_lvalue_custom(&mut self, _lvalue: &mut T::LValue) -> bool {
+        false
+    }
+
+    fn visit_lvalue(&mut self, lvalue: &mut T::LValue) {
+        use T::LValue_ as L;
+        if self.visit_lvalue_custom(lvalue) {
+            return;
+        }
+        match &mut lvalue.value {
+            L::Ignore | L::Var { .. } => (),
+            L::Unpack(_, _, _, fields) | L::BorrowUnpack(_, _, _, _, fields) => {
+                for (_, _, (_, (_, lvalue))) in fields.iter_mut() {
+                    self.visit_lvalue(lvalue)
+                }
+            }
         }
     }
 
@@ -172,7 +202,10 @@ pub trait TypingVisitorContext {
             E::Loop { body, .. } => self.visit_exp(body),
             E::NamedBlock(_, seq) => self.visit_seq(seq),
             E::Block(seq) => self.visit_seq(seq),
-            E::Assign(_, _, e) => self.visit_exp(e),
+            E::Assign(lvalues, _, e) => {
+                self.visit_lvalue_list(lvalues);
+                self.visit_exp(e)
+            }
             E::Mutate(e1, e2) => {
                 self.visit_exp(e1);
                 self.visit_exp(e2);
@@ -207,6 +240,7 @@ pub trait TypingVisitorContext {
             | E::Copy { .. }
             | E::Use(_)
             | E::Constant(..)
+            | E::ErrorConstant(..)
             | E::Continue(_)
             | E::BorrowLocal(..)
             | E::UnresolvedError => (),
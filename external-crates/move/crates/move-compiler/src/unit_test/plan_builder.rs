@@ -125,23 +125,8 @@ fn build_test_info<'func>(
     let abort_attribute_opt = get_attrs(TestingAttribute::ExpectedFailure);
     let test_only_attribute_opt = get_attrs(TestingAttribute::TestOnly);
 
-    let test_attribute = match test_attribute_opt {
-        None => {
-            // expected failures cannot be annotated on non-#[test] functions
-            if let Some(abort_attribute) = abort_attribute_opt {
-                let fn_msg = "Only functions defined as a test with #[test] can also have an \
-                              #[expected_failure] attribute";
-                let abort_msg = "Attributed as #[expected_failure] here";
-                context.env.add_diag(diag!(
-                    Attributes::InvalidUsage,
-                    (fn_loc, fn_msg),
-                    (abort_attribute.loc, abort_msg),
-                ))
-            }
-            return None;
-        }
-        Some(test_attribute) => test_attribute,
-    };
+    // expected failures on non-#[test] functions are reported during expansion
+    let test_attribute = test_attribute_opt?;
 
     // A #[test] function cannot also be annotated #[test_only]
     if let Some(test_only_attribute) = test_only_attribute_opt {
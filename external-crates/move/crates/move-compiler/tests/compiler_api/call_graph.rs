@@ -0,0 +1,95 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use move_compiler::{
+    expansion::ast::ModuleIdent, parser::ast::FunctionName, shared::Flags, typing::ast as T,
+};
+use move_symbol_pool::Symbol;
+
+use crate::common;
+
+const MODULES: &str = r#"module 0x42::a {
+    use 0x42::b;
+
+    public fun run(): u64 {
+        let c = b::make();
+        assert!(c.value() > 0, 0);
+        b::twice!(c.value())
+    }
+}
+
+module 0x42::b {
+    use 0x42::c::{Self, C};
+
+    public fun make(): C {
+        c::new(1)
+    }
+
+    public macro fun twice($v: u64): u64 {
+        c::twice($v)
+    }
+}
+
+module 0x42::c {
+    public struct C has drop {
+        v: u64,
+    }
+
+    public fun new(v: u64): C {
+        C { v }
+    }
+
+    public fun value(self: &C): u64 {
+        self.v
+    }
+
+    public fun twice(v: u64): u64 {
+        v * 2
+    }
+}
+"#;
+
+fn function(prog: &T::Program, m: &str, f: &str) -> (ModuleIdent, FunctionName) {
+    let (mident, mdef) = prog
+        .inner
+        .modules
+        .key_cloned_iter()
+        .find(|(mident, _)| mident.value.module.value() == Symbol::from(m))
+        .unwrap();
+    let (fname, _) = mdef
+        .functions
+        .key_cloned_iter()
+        .find(|(fname, _)| fname.value() == Symbol::from(f))
+        .unwrap();
+    (mident, fname)
+}
+
+#[test]
+fn call_graph_edges() {
+    let (_, prog) = common::compile(MODULES, Flags::empty());
+    let run = function(&prog, "a", "run");
+    let make = function(&prog, "b", "make");
+    let new = function(&prog, "c", "new");
+    let value = function(&prog, "c", "value");
+    let twice = function(&prog, "c", "twice");
+    let callees = |(m, f): (ModuleIdent, FunctionName)| {
+        prog.info.callees(&m, &f).copied().collect::<BTreeSet<_>>()
+    };
+    let callers = |(m, f): (ModuleIdent, FunctionName)| {
+        prog.info.callers(&m, &f).copied().collect::<BTreeSet<_>>()
+    };
+
+    // 'c::twice' is only called through the expansion of 'b::twice!'
+    assert_eq!(callees(run), BTreeSet::from([make, value, twice]));
+    assert_eq!(callees(make), BTreeSet::from([new]));
+    assert_eq!(callees(value), BTreeSet::new());
+    assert_eq!(callers(twice), BTreeSet::from([run]));
+    assert_eq!(callers(new), BTreeSet::from([make]));
+    assert_eq!(callers(run), BTreeSet::new());
+
+    let run_calls = prog.info.function_calls(&run.0, &run.1).unwrap();
+    assert!(run_calls.uses_assert);
+    assert!(!run_calls.uses_freeze);
+}
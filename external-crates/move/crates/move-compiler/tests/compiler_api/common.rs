@@ -0,0 +1,69 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, path::Path};
+
+use move_compiler::{
+    compiled_unit::AnnotatedCompiledUnit,
+    editions::Edition,
+    shared::{CompilationEnv, Flags, NumericalAddress, PackageConfig, PackagePaths},
+    typing::ast as T,
+    Compiler, PASS_TYPING,
+};
+
+/// Writes `source` to `file` in `dir`, as the only file of a package without a name, with the
+/// address 'a' bound to '0x42'
+pub fn package_paths(dir: &Path, file: &str, source: &str) -> Vec<PackagePaths<String, String>> {
+    let path = dir.join(file);
+    std::fs::write(&path, source).unwrap();
+    vec![PackagePaths {
+        name: None,
+        paths: vec![path.to_string_lossy().to_string()],
+        named_address_map: BTreeMap::from([(
+            "a".to_string(),
+            NumericalAddress::parse_str("0x42").unwrap(),
+        )]),
+    }]
+}
+
+/// Compiles `source` through typing with the 2024 edition
+pub fn compile(source: &str, flags: Flags) -> (CompilationEnv, T::Program) {
+    compile_with(source, flags, |compiler| compiler)
+}
+
+/// Compiles `source` through typing with the 2024 edition, after applying `configure` to the
+/// compiler
+pub fn compile_with(
+    source: &str,
+    flags: Flags,
+    configure: impl FnOnce(Compiler<'static>) -> Compiler<'static>,
+) -> (CompilationEnv, T::Program) {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, res) = configure(compiler(dir.path(), source, flags))
+        .run::<PASS_TYPING>()
+        .unwrap();
+    let (_, compiler) = res.unwrap();
+    let (compiler, prog) = compiler.into_ast();
+    (compiler.into_compilation_env(), prog)
+}
+
+/// Compiles `source` to bytecode with the 2024 edition
+pub fn build(source: &str) -> Vec<AnnotatedCompiledUnit> {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, res) = compiler(dir.path(), source, Flags::empty())
+        .build()
+        .unwrap();
+    let (units, _warnings) = res.unwrap();
+    units
+}
+
+fn compiler(dir: &Path, source: &str, flags: Flags) -> Compiler<'static> {
+    let targets = package_paths(dir, "m.move", source);
+    Compiler::from_package_paths(targets, vec![])
+        .unwrap()
+        .set_flags(flags)
+        .set_default_config(PackageConfig {
+            edition: Edition::E2024_ALPHA,
+            ..PackageConfig::default()
+        })
+}
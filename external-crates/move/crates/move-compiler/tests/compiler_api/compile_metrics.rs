@@ -0,0 +1,67 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::shared::{metrics::CompilationMetrics, Flags};
+
+use crate::common;
+
+const MODULE: &str = r#"module 0x42::m {
+    macro fun inc($x: u64): u64 {
+        $x + 1
+    }
+
+    macro fun sum($n: u64): u64 {
+        let mut i = 0;
+        let mut s = 0;
+        while (i < $n) {
+            s = s + i;
+            i = inc!(i);
+        };
+        s
+    }
+
+    public fun t(): u64 {
+        sum!(10)
+    }
+
+    public fun no_macros(x: u64): u64 {
+        x + 1
+    }
+}
+"#;
+
+fn compile(flags: Flags) -> CompilationMetrics {
+    let (mut env, _) = common::compile(MODULE, flags);
+    env.take_metrics()
+}
+
+#[test]
+fn metrics_not_recorded_by_default() {
+    let metrics = compile(Flags::empty());
+    assert!(metrics.is_empty());
+}
+
+#[test]
+fn macro_expansion_metrics() {
+    let metrics = compile(Flags::empty().set_record_metrics(true));
+    let by_name = |name: &str| {
+        metrics
+            .functions
+            .iter()
+            .find(|((_, f), _)| f.0.value.as_str() == name)
+            .map(|(_, metrics)| metrics.clone())
+            .unwrap()
+    };
+
+    let t = by_name("t");
+    // 'sum!' is expanded once, and it expands 'inc!' once
+    assert_eq!(t.macro_expansions, 2);
+    assert!(t.macro_expanded_nodes > 0);
+    assert!(t.typed_nodes >= t.macro_expanded_nodes);
+    assert!(t.max_constraints > 0);
+
+    let no_macros = by_name("no_macros");
+    assert_eq!(no_macros.macro_expansions, 0);
+    assert_eq!(no_macros.macro_expanded_nodes, 0);
+    assert!(no_macros.typed_nodes > 0);
+}
@@ -0,0 +1,70 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{
+    compile_to_naming, compile_to_typing,
+    naming::ast::{BuiltinTypeName_, TypeName_, Type_},
+    parser::ast::BinOp_,
+    shared::Flags,
+    typing::ast as T,
+};
+use move_symbol_pool::Symbol;
+
+use crate::common;
+
+const MODULE: &str = r#"module 0x42::m {
+    public fun add(x: u64, y: u64): u64 {
+        x + y
+    }
+
+    fun invalid(): u64 {
+        true
+    }
+}
+"#;
+
+fn is_u64(ty: &Type_) -> bool {
+    match ty {
+        Type_::Apply(_, tn, _) => {
+            matches!(&tn.value, TypeName_::Builtin(bt) if bt.value == BuiltinTypeName_::U64)
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn naming_and_typing_asts() {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = common::package_paths(dir.path(), "m.move", MODULE);
+
+    let (_, res) = compile_to_naming(targets.clone(), vec![], Flags::empty(), None).unwrap();
+    let (_, nprog) = res.unwrap();
+    let (_, mdef) = nprog.inner.modules.key_cloned_iter().next().unwrap();
+    assert!(mdef.functions.get_(&Symbol::from("add")).is_some());
+
+    let (_, res) = compile_to_typing(targets, vec![], Flags::empty(), None).unwrap();
+    let (env, tprog) = res.unwrap();
+    // the invalid return type is reported, but the typed program is still available
+    assert!(env.has_errors());
+    let (_, mdef) = tprog.inner.modules.key_cloned_iter().next().unwrap();
+    let add = mdef.functions.get_(&Symbol::from("add")).unwrap();
+
+    let params = &add.signature.parameters;
+    assert_eq!(params.len(), 2);
+    assert_eq!(params[0].1.value.name, Symbol::from("x"));
+    assert!(params.iter().all(|(_, _, ty)| is_u64(&ty.value)));
+    assert!(is_u64(&add.signature.return_type.value));
+
+    let T::FunctionBody_::Defined((_, seq)) = &add.body.value else {
+        panic!("expected a defined function body");
+    };
+    assert_eq!(seq.len(), 1);
+    let T::SequenceItem_::Seq(e) = &seq[0].value else {
+        panic!("expected an expression");
+    };
+    assert!(is_u64(&e.ty.value));
+    assert!(matches!(
+        &e.exp.value,
+        T::UnannotatedExp_::BinopExp(_, op, ty, _) if op.value == BinOp_::Add && is_u64(&ty.value)
+    ));
+}
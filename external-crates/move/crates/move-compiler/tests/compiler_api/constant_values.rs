@@ -0,0 +1,84 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the values of constants folded during typing
+
+use move_compiler::shared::Flags;
+use move_core_types::{account_address::AccountAddress, runtime_value::MoveValue};
+use move_symbol_pool::Symbol;
+
+use crate::common;
+
+const SOURCE: &str = r#"module 0x42::m {
+    const NESTED: vector<vector<u16>> = vector[
+        vector[1, 2 + 3],
+        vector[],
+        vector[(300u64 as u16) * 2, 1 << 15 >> 14],
+    ];
+    const BYTES: vector<vector<u8>> = vector[b"ab", x"ff"];
+    const ADDRESSES: vector<address> = vector[@0x1, @0x42];
+    const FLAGS: vector<bool> = vector[!false && true, 1 == 2, b"a" != x"61"];
+    const CASTS: vector<u8> = vector[(255u16 as u8), ((200u16 + 55) as u8), ((7u64 as u32) as u8)];
+    const INLINED: u64 = double(double(5)) + 1;
+
+    #[const_fun]
+    fun double(x: u64): u64 {
+        x * 2
+    }
+
+    public fun inlined(): u64 { INLINED }
+}
+"#;
+
+#[test]
+fn folded_nested_vectors_and_const_fun_calls() {
+    use MoveValue as MV;
+    let (_, prog) = common::compile(SOURCE, Flags::empty());
+    let (_, mdef) = prog.inner.modules.key_cloned_iter().next().unwrap();
+    let folded_value = |name: &str| {
+        let (_, constant) = mdef
+            .constants
+            .key_cloned_iter()
+            .find(|(cname, _)| cname.value() == Symbol::from(name))
+            .unwrap();
+        constant.folded_value.clone()
+    };
+    let vector = |values: Vec<MoveValue>| MV::Vector(values);
+
+    assert_eq!(
+        folded_value("NESTED"),
+        Some(vector(vec![
+            vector(vec![MV::U16(1), MV::U16(5)]),
+            vector(vec![]),
+            vector(vec![MV::U16(600), MV::U16(2)]),
+        ]))
+    );
+    assert_eq!(
+        folded_value("BYTES"),
+        Some(vector(vec![
+            vector(vec![MV::U8(b'a'), MV::U8(b'b')]),
+            vector(vec![MV::U8(0xff)]),
+        ]))
+    );
+    assert_eq!(
+        folded_value("ADDRESSES"),
+        Some(vector(vec![
+            MV::Address(AccountAddress::ONE),
+            MV::Address(AccountAddress::from_hex_literal("0x42").unwrap()),
+        ]))
+    );
+    assert_eq!(
+        folded_value("FLAGS"),
+        Some(vector(vec![
+            MV::Bool(true),
+            MV::Bool(false),
+            MV::Bool(false)
+        ]))
+    );
+    assert_eq!(
+        folded_value("CASTS"),
+        Some(vector(vec![MV::U8(255), MV::U8(255), MV::U8(7)]))
+    );
+    // the calls of the '#[const_fun]' function are inlined before folding
+    assert_eq!(folded_value("INLINED"), Some(MV::U64(21)));
+}
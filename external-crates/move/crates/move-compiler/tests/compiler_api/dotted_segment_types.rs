@@ -0,0 +1,117 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    naming::ast::{TypeName_, Type_},
+    shared::Flags,
+    typing::ast as T,
+};
+use move_ir_types::location::Loc;
+
+use crate::common;
+
+const STRUCTS: &str = r#"
+    public struct A has copy, drop { b: B }
+    public struct B has copy, drop { c: C }
+    public struct C has copy, drop { d: u64 }
+"#;
+
+fn compile(body: &str, flags: Flags) -> (String, usize, T::Program) {
+    let module = format!(
+        "module 0x42::m {{{STRUCTS}\n    public fun t(a: &A): u64 {{\n        {body}\n    }}\n}}\n"
+    );
+    let (env, prog) = common::compile(&module, flags);
+    (module, env.count_diags(), prog)
+}
+
+fn recording() -> Flags {
+    Flags::empty().set_record_type_locations(true)
+}
+
+/// The location of the first occurrence of `segment` in the body of `t`
+fn segment_loc(module: &str, segment: &str) -> Loc {
+    let body_start = module.find("\n        ").unwrap();
+    let start = body_start + module[body_start..].find(segment).unwrap();
+    let end = start + segment.len();
+    Loc::new(FileHash::new(module), start as u32, end as u32)
+}
+
+fn struct_name(prog: &T::Program, loc: Loc) -> Option<String> {
+    match &prog.info.dotted_segment_type_at(loc)?.value {
+        Type_::Apply(_, n, _) => match &n.value {
+            TypeName_::ModuleType(_, s) => Some(s.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[test]
+fn invalid_segment_keeps_prefix_types() {
+    let (module, num_diags, prog) = compile("a.b.x.d", recording());
+    // only the unbound field 'x' is reported
+    assert_eq!(num_diags, 1);
+    assert!(prog
+        .info
+        .dotted_segment_type_at(segment_loc(&module, "a.b.x.d"))
+        .is_none());
+    assert!(prog
+        .info
+        .dotted_segment_type_at(segment_loc(&module, "a.b.x"))
+        .is_none());
+    assert_eq!(
+        struct_name(&prog, segment_loc(&module, "a.b")).as_deref(),
+        Some("B")
+    );
+    assert_eq!(
+        struct_name(&prog, segment_loc(&module, "a")).as_deref(),
+        Some("A")
+    );
+}
+
+#[test]
+fn invalid_method_receiver_keeps_prefix_types() {
+    let (module, num_diags, prog) = compile("a.b.x.d.foo()", recording());
+    // only the unbound field 'x' is reported, not the method call on it
+    assert_eq!(num_diags, 1);
+    assert!(prog
+        .info
+        .dotted_segment_type_at(segment_loc(&module, "a.b.x.d"))
+        .is_none());
+    assert_eq!(
+        struct_name(&prog, segment_loc(&module, "a.b")).as_deref(),
+        Some("B")
+    );
+    assert_eq!(
+        struct_name(&prog, segment_loc(&module, "a")).as_deref(),
+        Some("A")
+    );
+}
+
+#[test]
+fn segment_types_not_recorded_by_default() {
+    let (module, num_diags, prog) = compile("a.b.x.d", Flags::empty());
+    assert_eq!(num_diags, 1);
+    assert!(prog
+        .info
+        .dotted_segment_type_at(segment_loc(&module, "a.b"))
+        .is_none());
+}
+
+#[test]
+fn valid_path_records_all_segments() {
+    let (module, num_diags, prog) = compile("a.b.c.d", recording());
+    assert_eq!(num_diags, 0);
+    for (segment, name) in [("a.b.c", "C"), ("a.b", "B"), ("a", "A")] {
+        assert_eq!(
+            struct_name(&prog, segment_loc(&module, segment)).as_deref(),
+            Some(name)
+        );
+    }
+    let d_ty = prog
+        .info
+        .dotted_segment_type_at(segment_loc(&module, "a.b.c.d"))
+        .unwrap();
+    assert!(matches!(d_ty.value, Type_::Apply(_, _, _)));
+}
@@ -0,0 +1,63 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::{Bytecode, CompiledModule};
+
+use crate::common;
+
+const MODULE: &str = r#"module a::m {
+    #[error]
+    const ENotAuthorized: vector<u8> = b"not authorized";
+    #[error]
+    const EInvalidState: u64 = 1;
+    #[error]
+    const EOverflow: vector<u8> = b"overflow";
+
+    public fun not_authorized() {
+        abort ENotAuthorized
+    }
+
+    public fun invalid_state(cond: bool) {
+        assert!(cond, EInvalidState);
+    }
+
+    public fun overflow(cond: bool) {
+        assert!(cond, EOverflow);
+    }
+}
+"#;
+
+/// The tag set in the abort code of an '#[error]' constant that is not a 'u64'
+const TAG: u64 = 1 << 63;
+
+fn abort_codes(module: &CompiledModule, name: &str) -> Vec<u64> {
+    let fdef = module
+        .function_defs
+        .iter()
+        .find(|fdef| {
+            let handle = module.function_handle_at(fdef.function);
+            module.identifier_at(handle.name).as_str() == name
+        })
+        .unwrap();
+    let code = &fdef.code.as_ref().unwrap().code;
+    code.windows(2)
+        .filter_map(|instrs| match instrs {
+            [Bytecode::LdU64(code), Bytecode::Abort] => Some(*code),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn error_constant_abort_codes() {
+    let units = common::build(MODULE);
+    let [unit] = &units[..] else {
+        panic!("expected a single compiled module")
+    };
+    let module = &unit.named_module.module;
+    // a non-'u64' constant aborts with the tag and the index of its declaration
+    assert_eq!(abort_codes(module, "not_authorized"), vec![TAG]);
+    assert_eq!(abort_codes(module, "overflow"), vec![TAG | 2]);
+    // a 'u64' constant aborts with its value
+    assert_eq!(abort_codes(module, "invalid_state"), vec![1]);
+}
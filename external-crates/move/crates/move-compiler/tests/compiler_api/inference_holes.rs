@@ -0,0 +1,48 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::shared::{
+    inference_holes::{InferenceHole, InferenceHoleKind},
+    Flags,
+};
+
+use crate::common;
+
+const MODULE: &str = r#"module 0x42::m {
+    public struct S has copy, drop { f: u64 }
+
+    fun make<T>(): T { abort 0 }
+
+    public fun t(): u64 {
+        let x = make();
+        let y = x + x;
+        y.f
+    }
+}
+"#;
+
+fn compile(flags: Flags) -> Vec<InferenceHole> {
+    let (mut env, _) = common::compile(MODULE, flags);
+    assert!(env.has_errors());
+    env.take_inference_holes()
+}
+
+#[test]
+fn field_access_hole_records_constraints() {
+    let holes = compile(Flags::empty().set_record_inference_holes(true));
+    let hole = holes
+        .iter()
+        .find(|hole| hole.kind == InferenceHoleKind::FieldAccess)
+        .unwrap();
+    assert!(hole.tvar.is_some());
+    // from the addition, which has not been checked yet when the field is accessed
+    assert!(hole.numeric);
+    let (m, f) = hole.function.unwrap();
+    assert_eq!(m.value.module.value().as_str(), "m");
+    assert_eq!(f.value().as_str(), "t");
+}
+
+#[test]
+fn holes_not_recorded_by_default() {
+    assert!(compile(Flags::empty()).is_empty());
+}
@@ -0,0 +1,116 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the lints that apply to any Move code outside of Sui mode, checking that each is reported
+//! with its own category, after those of the Sui lints
+
+use move_compiler::{
+    linters::{self, LinterDiagCategory},
+    shared::Flags,
+    sui_mode::linters::LinterDiagCategory as SuiLinterDiagCategory,
+};
+
+use crate::common;
+
+/// The categories of the lints reported for `source`
+fn lint_categories(source: &str) -> Vec<u8> {
+    let (mut env, _) = common::compile_with(source, Flags::empty(), |compiler| {
+        let (prefix, filters) = linters::known_filters();
+        compiler
+            .add_visitors(linters::linter_visitors())
+            .add_custom_known_filters(prefix, filters)
+    });
+    env.take_final_warning_diags()
+        .into_vec()
+        .into_iter()
+        .filter(|diag| diag.info().is_external())
+        .map(|diag| diag.info().category())
+        .collect()
+}
+
+#[test]
+fn categories_follow_sui_lints() {
+    assert_eq!(
+        LinterDiagCategory::RedundantCast as u8,
+        SuiLinterDiagCategory::LAST as u8 + 1
+    );
+}
+
+#[test]
+fn redundant_cast() {
+    let source = r#"module 0x42::m {
+    public fun t(x: u64): u64 {
+        (x as u64)
+    }
+}
+"#;
+    assert_eq!(
+        lint_categories(source),
+        vec![LinterDiagCategory::RedundantCast as u8]
+    );
+}
+
+#[test]
+fn while_true() {
+    let source = r#"module 0x42::m {
+    public fun t(): u64 {
+        while (true) { break 5 }
+    }
+}
+"#;
+    assert_eq!(
+        lint_categories(source),
+        vec![LinterDiagCategory::WhileTrue as u8]
+    );
+}
+
+#[test]
+fn self_assignment() {
+    let source = r#"module 0x42::m {
+    public fun t(x: u64): u64 {
+        x = x;
+        x
+    }
+}
+"#;
+    assert_eq!(
+        lint_categories(source),
+        vec![LinterDiagCategory::SelfAssignment as u8]
+    );
+}
+
+#[test]
+fn redundant_ref_deref() {
+    let source = r#"module 0x42::m {
+    public fun t(r: &u64): u64 {
+        let y = &*r;
+        *y
+    }
+}
+"#;
+    assert_eq!(
+        lint_categories(source),
+        vec![LinterDiagCategory::RedundantRefDeref as u8]
+    );
+}
+
+#[test]
+fn unmodified_loop_condition() {
+    let source = r#"module 0x42::m {
+    fun read(i: &u64): u64 {
+        *i
+    }
+
+    public fun t(n: u64) {
+        let i = 0;
+        while (i < n) {
+            read(&i);
+        };
+    }
+}
+"#;
+    assert_eq!(
+        lint_categories(source),
+        vec![LinterDiagCategory::UnmodifiedLoopCondition as u8]
+    );
+}
@@ -0,0 +1,25 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests of the compiler as a library, for outputs and options that are not visible in the
+//! diagnostics checked by `move_check_testsuite`
+
+mod common;
+
+mod call_graph;
+mod compile_metrics;
+mod compile_to_ast;
+mod constant_values;
+mod dotted_segment_types;
+mod error_constant_abort_codes;
+mod inference_holes;
+mod lints;
+mod many_macros;
+mod methods_for_type;
+mod package_warning_filters;
+mod primitive_definers;
+mod print_typed_ast;
+mod type_locations;
+mod typing_visitor;
+mod warn_flag;
+mod warnings_are_errors;
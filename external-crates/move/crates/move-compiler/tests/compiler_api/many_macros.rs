@@ -0,0 +1,51 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compiles a generated module with many macros and many module level `use fun`s, all of which
+//! are needed when expanding each of the macros.
+
+use std::fmt::Write;
+
+use move_compiler::shared::Flags;
+
+use crate::common;
+
+const MACROS: usize = 200;
+const USE_FUNS: usize = 50;
+
+fn generated_module() -> String {
+    let mut module =
+        "module 0x42::m {\n    public struct S has copy, drop { f: u64 }\n".to_string();
+    for i in 0..USE_FUNS {
+        writeln!(module, "    fun get_{i}(s: &S): u64 {{ s.f + {i} }}").unwrap();
+        writeln!(module, "    use fun get_{i} as S.method_{i};").unwrap();
+    }
+    for i in 0..MACROS {
+        let method = i % USE_FUNS;
+        writeln!(
+            module,
+            "    macro fun m_{i}($s: S): u64 {{ $s.method_{method}() }}"
+        )
+        .unwrap();
+    }
+    writeln!(module, "    public fun t(s: S): u64 {{").unwrap();
+    writeln!(module, "        let mut x = 0;").unwrap();
+    for i in 0..MACROS {
+        writeln!(module, "        x = x + m_{i}!(s);").unwrap();
+    }
+    writeln!(module, "        x\n    }}\n}}").unwrap();
+    module
+}
+
+#[test]
+fn many_macros_with_module_use_funs() {
+    let (mut env, _) =
+        common::compile(&generated_module(), Flags::empty().set_record_metrics(true));
+    let metrics = env.take_metrics();
+    let (_, t) = metrics
+        .functions
+        .iter()
+        .find(|((_, f), _)| f.0.value.as_str() == "t")
+        .unwrap();
+    assert_eq!(t.macro_expansions, MACROS);
+}
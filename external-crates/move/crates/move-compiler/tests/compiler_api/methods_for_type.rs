@@ -0,0 +1,135 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{
+    expansion::ast::Visibility,
+    naming::ast::{TypeName_, UseFunKind},
+    shared::Flags,
+};
+use move_ir_types::location::sp;
+use move_symbol_pool::Symbol;
+
+use crate::common;
+
+const MODULES: &str = r#"module 0x42::m {
+    public struct S has drop {}
+
+    public use fun renamed as S.alt;
+
+    public fun get(_: &S): u64 { 0 }
+
+    fun internal(_: &S) {}
+
+    fun renamed(_: &S) {}
+}
+
+module 0x42::n {
+    use 0x42::m::{S, get as m_get};
+
+    use fun helper as S.helper;
+
+    fun helper(_: &S) {}
+
+    public fun run(s: &S): u64 {
+        s.helper();
+        s.m_get()
+    }
+}
+"#;
+
+#[test]
+fn methods_for_type() {
+    let (_, prog) = common::compile(MODULES, Flags::empty());
+    let (m, mdef) = prog
+        .inner
+        .modules
+        .key_cloned_iter()
+        .find(|(mident, _)| mident.value.module.value() == Symbol::from("m"))
+        .unwrap();
+    let (s, _) = mdef.structs.key_cloned_iter().next().unwrap();
+    let tn = sp(s.loc(), TypeName_::ModuleType(m, s));
+
+    let methods = prog
+        .info
+        .methods_for(&tn)
+        .into_iter()
+        .map(|info| {
+            let is_public_target = matches!(info.target_visibility, Visibility::Public(_));
+            (
+                info.module.value.module.value(),
+                info.method.value,
+                info.target_function.1.value(),
+                is_public_target,
+                info.is_public,
+                info.kind,
+            )
+        })
+        .collect::<Vec<_>>();
+    let sym = Symbol::from;
+    assert_eq!(
+        methods,
+        vec![
+            // 'public use fun renamed as S.alt'
+            (
+                sym("m"),
+                sym("alt"),
+                sym("renamed"),
+                false,
+                true,
+                UseFunKind::Explicit
+            ),
+            // implicit from 'public fun get'
+            (
+                sym("m"),
+                sym("get"),
+                sym("get"),
+                true,
+                true,
+                UseFunKind::FunctionDeclaration
+            ),
+            // implicit from 'fun internal'
+            (
+                sym("m"),
+                sym("internal"),
+                sym("internal"),
+                false,
+                true,
+                UseFunKind::FunctionDeclaration
+            ),
+            // implicit from 'fun renamed'
+            (
+                sym("m"),
+                sym("renamed"),
+                sym("renamed"),
+                false,
+                true,
+                UseFunKind::FunctionDeclaration
+            ),
+            // 'use fun helper as S.helper', not visible outside of 'n'
+            (
+                sym("n"),
+                sym("helper"),
+                sym("helper"),
+                false,
+                false,
+                UseFunKind::Explicit
+            ),
+            // implicit from 'use 0x42::m::get as m_get'
+            (
+                sym("n"),
+                sym("m_get"),
+                sym("get"),
+                true,
+                false,
+                UseFunKind::UseAlias
+            ),
+        ]
+    );
+    assert_eq!(
+        prog.info.methods_for(&tn)[0].loc,
+        prog.info.module(&m).use_funs[&tn]
+            .get_(&sym("alt"))
+            .unwrap()
+            .loc
+    );
+}
@@ -0,0 +1,160 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, path::Path};
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    diagnostics::{
+        codes::{Category, Severity, UnusedItem, WarningFilter},
+        Diagnostic, WarningFilters,
+    },
+    editions::Edition,
+    shared::{NumericalAddress, PackageConfig, PackagePaths},
+    Compiler, PASS_TYPING,
+};
+use move_symbol_pool::Symbol;
+
+const ROOT: &str = r#"module 0x42::root {
+    fun unused() {}
+}
+"#;
+
+const DEP: &str = r#"module 0x43::dep {
+    fun unused() {}
+}
+"#;
+
+const DEP_WITH_ERROR: &str = r#"module 0x43::dep {
+    fun unused(): u64 { false }
+}
+"#;
+
+/// Compiled in both packages, under a different address in each
+const SHARED: &str = r#"module a::shared {
+    fun unused() {}
+}
+"#;
+
+const DEP_WITH_STRAY_DOC_COMMENT: &str = r#"module 0x43::dep {
+    fun unused() {}
+}
+/// not attached to any item
+"#;
+
+fn package(
+    dir: &Path,
+    name: &str,
+    source: &str,
+    warning_filter: WarningFilters,
+) -> PackagePaths<String, String> {
+    let path = dir.join(format!("{name}.move"));
+    std::fs::write(&path, source).unwrap();
+    let config = PackageConfig {
+        edition: Edition::E2024_ALPHA,
+        warning_filter,
+        ..PackageConfig::default()
+    };
+    PackagePaths {
+        name: Some((Symbol::from(name), config)),
+        paths: vec![path.to_string_lossy().to_string()],
+        named_address_map: BTreeMap::<String, NumericalAddress>::new(),
+    }
+}
+
+/// Compiles the root package and the dependency, where the dependency is compiled as a target so
+/// that warnings are reported for it
+fn compile(dep_source: &str, dep_filter: WarningFilters) -> Vec<Diagnostic> {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = vec![
+        package(dir.path(), "root", ROOT, WarningFilters::new_for_source()),
+        package(dir.path(), "dep", dep_source, dep_filter),
+    ];
+    warnings(targets)
+}
+
+fn warnings(targets: Vec<PackagePaths<String, String>>) -> Vec<Diagnostic> {
+    let (_, res) = Compiler::from_package_paths(targets, vec![])
+        .unwrap()
+        .run::<PASS_TYPING>()
+        .unwrap();
+    let (_, compiler) = res.unwrap();
+    let (mut compiler, _) = compiler.into_ast();
+    match compiler
+        .compilation_env()
+        .check_diags_at_or_above_severity(Severity::Warning)
+    {
+        Ok(()) => vec![],
+        Err(diags) => diags.into_vec(),
+    }
+}
+
+fn unused_function_filter() -> WarningFilters {
+    let mut filter = WarningFilters::new_for_source();
+    filter.add(WarningFilter::code(
+        None,
+        Category::UnusedItem as u8,
+        UnusedItem::Function as u8,
+        None,
+    ));
+    filter
+}
+
+fn file_of(diag: &Diagnostic) -> FileHash {
+    diag.primary_loc().file_hash()
+}
+
+#[test]
+fn unfiltered_package_warns() {
+    let diags = compile(DEP, WarningFilters::new_for_source());
+    assert_eq!(diags.len(), 2);
+}
+
+#[test]
+fn package_filter_only_applies_to_its_package() {
+    let diags = compile(DEP, unused_function_filter());
+    assert_eq!(diags.len(), 1);
+    assert_eq!(file_of(&diags[0]), FileHash::new(ROOT));
+}
+
+#[test]
+fn package_filter_does_not_filter_errors() {
+    let mut filter = WarningFilters::new_for_source();
+    filter.add(WarningFilter::All(None));
+    let diags = compile(DEP_WITH_ERROR, filter);
+    let dep_diags = diags
+        .iter()
+        .filter(|diag| file_of(diag) == FileHash::new(DEP_WITH_ERROR))
+        .collect::<Vec<_>>();
+    assert_eq!(dep_diags.len(), 1);
+    assert!(dep_diags[0].info().severity() >= Severity::NonblockingError);
+}
+
+#[test]
+fn package_filter_applies_outside_of_modules() {
+    // the warning for the doc comment is reported when parsing, outside of any module's scope
+    let mut filter = WarningFilters::new_for_source();
+    filter.add(WarningFilter::All(None));
+    let diags = compile(DEP_WITH_STRAY_DOC_COMMENT, filter);
+    assert_eq!(diags.len(), 1);
+    assert_eq!(file_of(&diags[0]), FileHash::new(ROOT));
+}
+
+#[test]
+fn package_filter_does_not_apply_to_identical_file_of_another_package() {
+    // both copies of the file have the same hash, so the filter of the dependency must not hide
+    // the warning of the root package
+    let dir = tempfile::tempdir().unwrap();
+    let with_address = |mut paths: PackagePaths<String, String>, address: &str| {
+        let address = NumericalAddress::parse_str(address).unwrap();
+        paths.named_address_map.insert("a".to_string(), address);
+        paths
+    };
+    let root = package(dir.path(), "root", SHARED, WarningFilters::new_for_source());
+    let dep = package(dir.path(), "dep", SHARED, unused_function_filter());
+    let diags = warnings(vec![with_address(root, "0x42"), with_address(dep, "0x43")]);
+    assert!(!diags.is_empty());
+    assert!(diags
+        .iter()
+        .all(|diag| file_of(diag) == FileHash::new(SHARED)));
+}
@@ -0,0 +1,40 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{naming::ast::BuiltinTypeName_, shared::Flags};
+
+use crate::common;
+
+const VEC_EXT: &str = r#"module a::vec_ext {
+    public use fun first as vector.first;
+    public fun first(_v: &vector<u64>): u64 { 0 }
+}
+
+module a::m {
+    public fun t(): u64 {
+        let v = vector[1, 2];
+        v.first()
+    }
+}
+"#;
+
+fn compile(register: bool) -> usize {
+    let (env, _) = common::compile_with(VEC_EXT, Flags::empty(), |compiler| {
+        if register {
+            compiler.add_primitive_definer(BuiltinTypeName_::Vector, "a", "vec_ext")
+        } else {
+            compiler
+        }
+    });
+    env.count_diags()
+}
+
+#[test]
+fn registered_definer_allows_public_use_fun() {
+    assert_eq!(compile(true), 0);
+}
+
+#[test]
+fn unregistered_definer_rejects_public_use_fun() {
+    assert!(compile(false) > 0);
+}
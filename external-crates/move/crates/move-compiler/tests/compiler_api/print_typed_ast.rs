@@ -0,0 +1,60 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{shared::Flags, typing::ast as T};
+
+use crate::common;
+
+const MODULES: &str = r#"module a::m {
+    public struct Box<T> has copy, drop { value: T }
+
+    public fun value<T: copy>(b: &Box<T>): T { b.value }
+
+    macro fun double($x: u64): u64 { $x + $x }
+
+    public fun t(b: Box<u64>): u64 {
+        double!(b.value())
+    }
+}
+
+module a::other {
+    public fun t(): u64 { 0 }
+}
+"#;
+
+fn compile() -> T::Program {
+    let (env, prog) = common::compile(MODULES, Flags::empty());
+    assert_eq!(env.count_diags(), 0);
+    prog
+}
+
+#[test]
+fn function_filter() {
+    let out = T::print_program(&compile(), Some("t"));
+    assert!(out.contains("module a::m"));
+    assert!(out.contains("module a::other"));
+    // only the functions named 't' are printed
+    assert!(!out.contains("macro "));
+    assert!(!out.contains("struct "));
+    // calls keep the method they were resolved from, and expanded macros are marked
+    assert!(out.contains("a::m::value/*method value*/"));
+    assert!(out.contains("/*expanded a::m::double!*/"));
+    // expressions are annotated with their expanded types
+    assert!(out.contains(": a::m::Box<") || out.contains(": (a::m::Box<"));
+}
+
+#[test]
+fn module_filter() {
+    let out = T::print_program(&compile(), Some("other"));
+    assert!(out.contains("module a::other"));
+    assert!(!out.contains("module a::m"));
+}
+
+#[test]
+fn output_is_stable() {
+    let out = T::print_program(&compile(), None);
+    assert!(out.contains("module a::m"));
+    assert!(out.contains("module a::other"));
+    assert!(out.contains("macro "));
+    assert_eq!(out, T::print_program(&compile(), None));
+}
@@ -0,0 +1,106 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    naming::ast::{BuiltinTypeName_, TypeName_, Type_},
+    shared::Flags,
+    typing::ast as T,
+};
+use move_ir_types::location::Loc;
+
+use crate::common;
+
+const MODULE: &str = r#"module 0x42::m {
+    public struct S has copy, drop { f: u64 }
+
+    macro fun double($x: u64): u64 { $x + $x }
+
+    public fun plain(x: u64): bool { x < 10 }
+
+    public fun dotted(s: &S): u64 { s.f }
+
+    public fun call(y: u64): u64 { double!(y + 1) }
+}
+"#;
+
+fn compile(flags: Flags) -> T::Program {
+    let (env, prog) = common::compile(MODULE, flags);
+    assert_eq!(env.count_diags(), 0);
+    prog
+}
+
+/// The location of the first occurrence of `text` after `after`
+fn loc_of(after: &str, text: &str) -> Loc {
+    let from = MODULE.find(after).unwrap();
+    let start = from + MODULE[from..].find(text).unwrap();
+    let end = start + text.len();
+    Loc::new(FileHash::new(MODULE), start as u32, end as u32)
+}
+
+fn builtin(ty: &Type_) -> Option<BuiltinTypeName_> {
+    match ty {
+        Type_::Apply(_, n, _) => match &n.value {
+            TypeName_::Builtin(b) => Some(b.value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn builtin_at(prog: &T::Program, loc: Loc) -> Option<BuiltinTypeName_> {
+    builtin(&prog.info.type_at(loc)?.value)
+}
+
+#[test]
+fn plain_function() {
+    let prog = compile(Flags::empty().set_record_type_locations(true));
+    assert_eq!(
+        builtin_at(&prog, loc_of("fun plain", "x < 10")),
+        Some(BuiltinTypeName_::Bool)
+    );
+    assert_eq!(
+        builtin_at(&prog, loc_of("fun plain", "x <")),
+        Some(BuiltinTypeName_::U64)
+    );
+    assert_eq!(
+        builtin_at(&prog, loc_of("fun plain", "10")),
+        Some(BuiltinTypeName_::U64)
+    );
+}
+
+#[test]
+fn dotted_path() {
+    let prog = compile(Flags::empty().set_record_type_locations(true));
+    assert_eq!(
+        builtin_at(&prog, loc_of("fun dotted", "s.f")),
+        Some(BuiltinTypeName_::U64)
+    );
+    let s_ty = prog.info.type_at(loc_of("fun dotted", "s.")).unwrap();
+    assert!(matches!(&s_ty.value, Type_::Ref(false, _)));
+}
+
+#[test]
+fn macro_call() {
+    let prog = compile(Flags::empty().set_record_type_locations(true));
+    // the call and its argument are recorded
+    assert_eq!(
+        builtin_at(&prog, loc_of("fun call", "double!(y + 1)")),
+        Some(BuiltinTypeName_::U64)
+    );
+    assert_eq!(
+        builtin_at(&prog, loc_of("fun call", "y + 1")),
+        Some(BuiltinTypeName_::U64)
+    );
+    // the macro body is not
+    assert!(prog
+        .info
+        .type_at(loc_of("macro fun double", "$x + $x"))
+        .is_none());
+}
+
+#[test]
+fn not_recorded_by_default() {
+    let prog = compile(Flags::empty());
+    assert!(prog.info.type_at(loc_of("fun plain", "x < 10")).is_none());
+}
@@ -0,0 +1,91 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs a custom `TypingVisitorContext` over a typed program, checking what the default traversal
+//! reaches and that a custom visit can stop it from descending further
+
+use move_compiler::{
+    diagnostics::WarningFilters,
+    shared::Flags,
+    typing::{ast as T, visitor::TypingVisitorContext},
+};
+
+use crate::common;
+
+const SOURCE: &str = r#"module 0x42::m {
+    public struct S has drop { f: u64 }
+
+    const C: u64 = (1u8 as u64);
+
+    public fun t(x: u8): u64 {
+        let S { f } = S { f: (x as u64) };
+        let y = if (f > C) { (x as u64) } else { 0 };
+        while ((y as u128) > 0) { break };
+        f + y
+    }
+}
+"#;
+
+#[derive(Default)]
+struct Counter {
+    skip_blocks: bool,
+    casts: usize,
+    vars: usize,
+    scopes: usize,
+}
+
+impl TypingVisitorContext for Counter {
+    fn add_warning_filter_scope(&mut self, _filter: WarningFilters) {
+        self.scopes += 1
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.scopes -= 1
+    }
+
+    fn visit_lvalue_custom(&mut self, lvalue: &mut T::LValue) -> bool {
+        if let T::LValue_::Var { .. } = &lvalue.value {
+            self.vars += 1
+        }
+        false
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::Cast(_, _) => {
+                self.casts += 1;
+                false
+            }
+            E::Block(_) => self.skip_blocks,
+            _ => false,
+        }
+    }
+}
+
+fn visit(mut counter: Counter) -> Counter {
+    let (_, mut prog) = common::compile(SOURCE, Flags::empty());
+    counter.visit(&mut prog.inner);
+    assert_eq!(counter.scopes, 0);
+    counter
+}
+
+#[test]
+fn visits_constants_functions_and_lvalues() {
+    let counter = visit(Counter::default());
+    // in the constant, the packed field, the 'if' branch, and the 'while' condition
+    assert_eq!(counter.casts, 4);
+    // 'f', bound by the unpack, and 'y'
+    assert_eq!(counter.vars, 2);
+}
+
+#[test]
+fn custom_visit_stops_descent() {
+    let counter = visit(Counter {
+        skip_blocks: true,
+        ..Counter::default()
+    });
+    // the cast in the block of the 'if' branch is not reached
+    assert_eq!(counter.casts, 3);
+    assert_eq!(counter.vars, 2);
+}
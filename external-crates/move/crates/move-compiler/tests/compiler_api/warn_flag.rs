@@ -0,0 +1,162 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::{
+    shared::{
+        Flags, FILTER_IMPLICIT_FREEZE_EQUALITY, FILTER_LARGE_COPY_IN_LOOP,
+        FILTER_LARGE_POSITIONAL_STRUCT, FILTER_LARGE_STRUCT_EQUALITY, FILTER_READ_ONLY_MUT_PARAM,
+        FILTER_REDUNDANT_TYPE_ARGS, FILTER_SHADOWED_VARIABLE,
+    },
+    Compiler, PASS_TYPING,
+};
+
+use crate::common;
+
+const SHADOWING: &str = r#"module 0x42::m {
+    public fun t(): u64 {
+        let x = 0;
+        let x = x + 1;
+        x
+    }
+}
+"#;
+
+fn warn(names: &[&str]) -> Flags {
+    Flags::empty().set_warn(names.iter().map(|name| name.to_string()).collect())
+}
+
+/// The number of diagnostics for `source` without and with `filter` enabled by name
+fn num_diags_off_and_on(source: &str, filter: &str) -> (usize, usize) {
+    let (off, _) = common::compile(source, Flags::empty());
+    let (on, _) = common::compile(source, warn(&[filter]));
+    (off.count_diags(), on.count_diags())
+}
+
+#[test]
+fn known_name_enables_the_warning() {
+    let (env, _) = common::compile(SHADOWING, Flags::empty());
+    assert!(!env.has_warnings_or_errors());
+    let (env, _) = common::compile(SHADOWING, warn(&[FILTER_SHADOWED_VARIABLE]));
+    assert!(env.has_warnings_or_errors());
+    assert!(!env.has_errors());
+}
+
+#[test]
+fn unknown_name_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = common::package_paths(dir.path(), "m.move", SHADOWING);
+    let res = Compiler::from_package_paths(targets, vec![])
+        .unwrap()
+        .set_flags(warn(&["shadowed_varaible"]))
+        .run::<PASS_TYPING>();
+    let Err(err) = res else {
+        panic!("expected '--warn=shadowed_varaible' to be rejected")
+    };
+    assert!(err.to_string().contains("'shadowed_varaible'"));
+}
+
+#[test]
+fn redundant_type_args() {
+    let source = r#"module 0x42::m {
+    public fun id<T>(x: T): T { x }
+
+    public fun t(x: u64): u64 { id<u64>(x) }
+}
+"#;
+    assert_eq!(
+        num_diags_off_and_on(source, FILTER_REDUNDANT_TYPE_ARGS),
+        (0, 1)
+    );
+}
+
+#[test]
+fn large_copy_in_loop() {
+    let source = r#"module 0x42::m {
+    public struct Nine has copy, drop {
+        f0: u64, f1: u64, f2: u64, f3: u64, f4: u64, f5: u64, f6: u64, f7: u64, f8: u64
+    }
+    public struct Holder has copy, drop { nine: Nine }
+
+    public fun consume(_: Nine) {}
+
+    public fun t(h: &Holder) {
+        let mut i = 0;
+        while (i < 10) {
+            consume(h.nine);
+            i = i + 1;
+        }
+    }
+}
+"#;
+    assert_eq!(
+        num_diags_off_and_on(source, FILTER_LARGE_COPY_IN_LOOP),
+        (0, 1)
+    );
+}
+
+#[test]
+fn implicit_freeze_equality() {
+    let source = r#"module 0x42::m {
+    public struct Small has copy, drop { a: u64, b: u64 }
+
+    public fun t(s: &mut Small, other: &Small): bool {
+        s == other
+    }
+}
+"#;
+    assert_eq!(
+        num_diags_off_and_on(source, FILTER_IMPLICIT_FREEZE_EQUALITY),
+        (0, 1)
+    );
+}
+
+#[test]
+fn large_struct_equality() {
+    let source = r#"module 0x42::m {
+    public struct Eight has copy, drop {
+        f0: u64, f1: u64, f2: u64, f3: u64, f4: u64, f5: u64, f6: u64, f7: u64
+    }
+    public struct Wide has copy, drop { e0: Eight, e1: Eight, extra: u64 }
+
+    public fun t(w1: &Wide, w2: &Wide): bool {
+        w1 == w2
+    }
+}
+"#;
+    assert_eq!(
+        num_diags_off_and_on(source, FILTER_LARGE_STRUCT_EQUALITY),
+        (0, 1)
+    );
+}
+
+#[test]
+fn large_positional_struct() {
+    let source = r#"module 0x42::m {
+    public struct Four(u64, u64, bool, address) has drop;
+
+    public fun t(): Four {
+        Four(0, 1, false, @0x1)
+    }
+}
+"#;
+    assert_eq!(
+        num_diags_off_and_on(source, FILTER_LARGE_POSITIONAL_STRUCT),
+        (0, 1)
+    );
+}
+
+#[test]
+fn read_only_mut_parameter() {
+    let source = r#"module 0x42::m {
+    public struct S has drop { f: u64 }
+
+    public fun t(s: &mut S): u64 {
+        s.f
+    }
+}
+"#;
+    assert_eq!(
+        num_diags_off_and_on(source, FILTER_READ_ONLY_MUT_PARAM),
+        (0, 1)
+    );
+}
@@ -0,0 +1,65 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::shared::{CompilationEnv, Flags};
+
+use crate::common;
+
+const UNUSED_FUNCTION: &str = r#"module 0x42::m {
+    fun unused() {}
+}
+"#;
+
+const UNUSED_FUNCTION_ALLOWED: &str = r#"module 0x42::m {
+    #[allow(unused_function)]
+    fun unused() {}
+}
+"#;
+
+fn compile(module: &str, flags: Flags) -> CompilationEnv {
+    let (env, _) = common::compile(module, flags);
+    env
+}
+
+fn escalating(except: &[&str]) -> Flags {
+    Flags::empty()
+        .set_warnings_are_errors(true)
+        .set_warnings_are_errors_except(except.iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn unused_function_is_a_warning() {
+    let env = compile(UNUSED_FUNCTION, Flags::empty());
+    assert!(env.has_warnings_or_errors());
+    assert!(!env.has_errors());
+}
+
+#[test]
+fn unused_function_is_escalated() {
+    let env = compile(UNUSED_FUNCTION, escalating(&[]));
+    assert!(env.has_errors());
+}
+
+#[test]
+fn exceptions_stay_warnings() {
+    // by code
+    let env = compile(UNUSED_FUNCTION, escalating(&["unused_function"]));
+    assert!(env.has_warnings_or_errors());
+    assert!(!env.has_errors());
+    // by category
+    let env = compile(UNUSED_FUNCTION, escalating(&["unused"]));
+    assert!(env.has_warnings_or_errors());
+    assert!(!env.has_errors());
+    // other codes are still escalated
+    let env = compile(
+        UNUSED_FUNCTION,
+        escalating(&["unused_variable", "dead_code"]),
+    );
+    assert!(env.has_errors());
+}
+
+#[test]
+fn allow_suppresses_escalated_warnings() {
+    let env = compile(UNUSED_FUNCTION_ALLOWED, escalating(&[]));
+    assert!(!env.has_warnings_or_errors());
+}
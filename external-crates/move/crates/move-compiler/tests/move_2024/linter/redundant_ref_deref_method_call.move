@@ -0,0 +1,27 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// the borrows added for the receiver of a method call are not flagged
+module a::test {
+    public struct S has copy, drop { f: u64 }
+
+    public fun get(s: &S): u64 {
+        s.f
+    }
+
+    public fun set(s: &mut S, f: u64) {
+        s.f = f;
+    }
+
+    public fun deref_receiver(r: &S): u64 {
+        (*r).get()
+    }
+
+    public fun deref_mut_receiver(r: &mut S) {
+        (*r).set(0);
+    }
+
+    public fun freeze_receiver(r: &mut S): u64 {
+        (&*r).get()
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    public struct Counter has drop { count: u64 }
+
+    public fun inc(c: &mut Counter) {
+        c.count = c.count + 1;
+    }
+
+    public fun read(i: &u64): u64 {
+        *i
+    }
+
+    public fun unmodified(n: u64) {
+        let i = 0;
+        while (i < n) {
+            read(&i);
+        };
+    }
+
+    public fun unmodified_field(c: Counter, n: u64) {
+        while (c.count < n) {
+            let _ = c.count + 1;
+        };
+    }
+
+    public fun assigned(n: u64) {
+        let mut i = 0;
+        while (i < n) {
+            i = i + 1;
+        };
+    }
+
+    public fun mut_borrowed(n: u64) {
+        let mut c = Counter { count: 0 };
+        while (c.count < n) {
+            inc(&mut c);
+        };
+    }
+
+    public fun mut_receiver(n: u64) {
+        let mut c = Counter { count: 0 };
+        while (c.count < n) {
+            c.inc();
+        };
+    }
+
+    public fun field_mutated(n: u64) {
+        let mut c = Counter { count: 0 };
+        while (c.count < n) {
+            c.count = c.count + 1;
+        };
+    }
+
+    public fun with_break(n: u64) {
+        let i = 0;
+        while (i < n) {
+            if (read(&i) > 10) break;
+        };
+    }
+
+    #[allow(lint(unmodified_loop_condition))]
+    public fun suppressed(n: u64) {
+        let i = 0;
+        while (i < n) {};
+    }
+}
@@ -0,0 +1,8 @@
+module a::m {
+    fun t(x: u64) {
+        assert(x > 0, 0);
+        let y = 1; y = y + x; assert(y > 1, 1);
+        x = y;
+        assert(x > 1, 2);
+    }
+}
@@ -1,7 +1,7 @@
 module a::m {
     macro fun do<$T>($f: || -> $T): $T { $f() }
 
-    // lambdas capture break/continue
+    // lambdas capture break/continue, which is an error outside of a loop
     fun t() {
         do!(|| {
             if (false) break;
@@ -9,6 +9,7 @@ module a::m {
         });
     }
 
+    // inside of a loop, break/continue refer to the loop around the lambda
     fun tloop() {
         loop {
             do!(|| {
@@ -0,0 +1,22 @@
+module a::m {
+    public struct S has copy, drop { f: u64 }
+
+    macro fun set($s: S, $r: &mut S): u64 {
+        $s.f = 0;
+        $r.f = 0;
+        $s.f
+    }
+
+    macro fun reset($x: u64): u64 {
+        $x = 0;
+        $x
+    }
+
+    // each error is reported once, at the macro definition, regardless of the number of calls
+    fun t(r: &mut S) {
+        set!(S { f: 1 }, r);
+        set!(S { f: 2 }, r);
+        reset!(1);
+        reset!(2);
+    }
+}
@@ -0,0 +1,19 @@
+module a::m {
+    public struct S {}
+    public fun f() {}
+    const C: u64 = 0;
+}
+
+module a::n {
+    use a::m;
+
+    // unbound members, the member segment is labeled
+    public fun t0(_: m::T) {}
+    public fun t1() { m::g() }
+    public fun t2(): u64 { m::D }
+    public fun t3(_: a::m::T) { a::m::g() }
+
+    // unbound modules, the module segment is labeled
+    public fun t4(_: a::x::S) { a::x::f() }
+    public fun t5(): u64 { a::x::C }
+}
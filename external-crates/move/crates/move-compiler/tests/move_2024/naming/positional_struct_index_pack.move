@@ -0,0 +1,16 @@
+module 0x42::m {
+    public struct Foo(u64, bool) has copy, drop;
+    public struct Bar<T>(T) has copy, drop;
+
+    fun in_order(): Foo {
+        Foo { 0: 0, 1: true }
+    }
+
+    fun out_of_order(): Foo {
+        Foo { 1: true, 0: 0 }
+    }
+
+    fun generic(): Bar<Foo> {
+        Bar<Foo> { 0: Foo(0, false) }
+    }
+}
@@ -0,0 +1,7 @@
+module 0x42::m {
+    public struct Foo(u64, bool) has copy, drop;
+
+    fun missing(): Foo {
+        Foo { 1: true }
+    }
+}
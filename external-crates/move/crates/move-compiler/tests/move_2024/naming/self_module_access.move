@@ -0,0 +1,20 @@
+// 'Self' refers to the current module in types, packs, unpacks, constants, calls, and 'use fun's
+module a::m {
+    public struct S has copy, drop { f: u64 }
+
+    const C: u64 = 0;
+
+    use fun Self::get as Self::S.value;
+
+    fun get(s: &S): u64 { s.f }
+
+    public fun make(): Self::S {
+        Self::S { f: Self::C }
+    }
+
+    public fun t(): u64 {
+        let Self::S { f } = Self::make();
+        let s = Self::make();
+        f + s.value() + Self::get(&s)
+    }
+}
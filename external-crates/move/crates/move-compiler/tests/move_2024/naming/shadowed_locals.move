@@ -0,0 +1,37 @@
+// shadowing is only reported when enabled, and never for names starting with '_'
+module a::m {
+    fun parameter(x: u64): u64 {
+        let x = x + 1;
+        x
+    }
+
+    fun nested_block(): u64 {
+        let y = 0;
+        let inner = {
+            let y = 1;
+            y
+        };
+        inner + y
+    }
+
+    fun sequential_lets(): u64 {
+        let z = 0;
+        let z = z + 1;
+        z
+    }
+
+    fun lambda_parameter(): u64 {
+        let w = 1;
+        apply!(w, |w| w + 1)
+    }
+
+    macro fun apply($x: u64, $f: |u64| -> u64): u64 {
+        $f($x)
+    }
+
+    fun underscore(_u: u64) {
+        let _u = 0;
+        let _v = 0;
+        let _v = 1;
+    }
+}
@@ -0,0 +1,21 @@
+// public 'use fun' declarations can only be made by the type's defining module, so two public
+// 'use fun' declarations from different modules can never collide for the same method
+module a::m {
+    public struct X() has drop;
+    public fun foo(_: &X): u64 { 0 }
+    public use fun foo as X.bar;
+}
+
+module a::n {
+    use a::m::X;
+    public fun baz(_: &X): bool { false }
+    public use fun baz as X.bar;
+}
+
+module a::o {
+    use a::m::X;
+
+    fun t(x: &X) {
+        (x.bar(): u64);
+    }
+}
@@ -0,0 +1,22 @@
+// 'use fun' declarations always target a function, never another method, so two modules aliasing
+// each other's functions under the same method cannot form a chain. Only the defining module's
+// public 'use fun' is kept, regardless of module order
+module a::n {
+    use a::m::X;
+    public fun baz(_: &X): bool { false }
+    public use fun a::m::foo as X.bar;
+}
+
+module a::m {
+    public struct X() has drop;
+    public fun foo(_: &X): u64 { 0 }
+    public use fun a::n::baz as X.bar;
+}
+
+module a::o {
+    use a::m::X;
+
+    fun t(x: &X) {
+        (x.bar(): bool);
+    }
+}
@@ -0,0 +1,31 @@
+// explicit 'use fun' declarations that shadow a method from the type's function declarations
+// are warned about, and the explicit 'use fun' wins
+module a::m {
+    public struct X() has drop;
+    public fun size(_: &X): u64 { 0 }
+    fun hidden(_: &X): u64 { 0 }
+    public fun other(_: &X): bool { false }
+
+    fun t(x: &X) {
+        use fun other as X.size;
+        (x.size(): bool);
+    }
+
+    // re-declaring the same function is not shadowing
+    fun same(x: &X) {
+        use fun size as X.size;
+        (x.size(): u64);
+    }
+}
+
+module a::n {
+    use a::m::X;
+    use fun a::m::other as X.size;
+    // 'a::m::hidden' is not visible here, so nothing is shadowed
+    use fun a::m::other as X.hidden;
+
+    fun t(x: &X) {
+        (x.size(): bool);
+        (x.hidden(): bool);
+    }
+}
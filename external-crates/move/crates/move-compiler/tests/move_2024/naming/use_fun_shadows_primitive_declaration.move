@@ -0,0 +1,14 @@
+// shadowing applies to the methods of primitive types from their defining module
+#[defines_primitive(vector)]
+module std::vector {
+    public fun length<T>(_: &vector<T>): u64 { 0 }
+}
+
+module a::m {
+    public fun zero<T>(_: &vector<T>): u8 { 0 }
+
+    fun t(v: &vector<u64>) {
+        use fun zero as vector.length;
+        (v.length(): u8);
+    }
+}
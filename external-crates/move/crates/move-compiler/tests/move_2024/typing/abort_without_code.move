@@ -0,0 +1,31 @@
+module a::m {
+    fun statement(cond: bool) {
+        if (cond) abort;
+        if (!cond) abort
+    }
+
+    fun branch(cond: bool): u64 {
+        if (cond) abort else 0
+    }
+
+    fun binding(cond: bool): u64 {
+        let x: u64 = if (cond) 1 else abort;
+        x
+    }
+
+    fun tail(): u64 {
+        abort
+    }
+
+    fun in_loop(mut i: u64) {
+        while (true) {
+            if (i == 0) abort;
+            i = i - 1;
+        }
+    }
+
+    fun explicit_code(cond: bool) {
+        if (cond) abort 0;
+        if (!cond) abort (1 + 1)
+    }
+}
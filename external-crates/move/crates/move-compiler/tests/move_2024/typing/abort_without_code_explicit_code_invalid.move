@@ -0,0 +1,6 @@
+module a::m {
+    fun explicit_code_still_checked(cond: bool) {
+        if (cond) abort;
+        abort false
+    }
+}
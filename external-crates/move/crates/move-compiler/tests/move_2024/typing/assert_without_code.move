@@ -0,0 +1,24 @@
+module a::m {
+    macro fun check($cond: bool) {
+        assert!($cond);
+    }
+
+    fun one_arg(x: u64) {
+        assert!(x > 0);
+        assert!(x != 1, 1);
+        if (x > 2) assert!(x > 3);
+    }
+
+    fun in_macro(x: u64) {
+        check!(x > 0);
+        check!({ assert!(x < 10); true })
+    }
+
+    fun in_lambda(x: u64) {
+        apply!(x, |y| assert!(y > 0))
+    }
+
+    macro fun apply($x: u64, $f: |u64|) {
+        $f($x)
+    }
+}
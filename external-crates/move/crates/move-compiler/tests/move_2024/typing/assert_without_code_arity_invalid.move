@@ -0,0 +1,9 @@
+module a::m {
+    fun zero() {
+        assert!();
+    }
+
+    fun three() {
+        assert!(true, 0, 1);
+    }
+}
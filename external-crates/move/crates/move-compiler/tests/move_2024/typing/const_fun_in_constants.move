@@ -0,0 +1,23 @@
+// '#[const_fun]' functions can be shared between constants, and can call each other
+module a::m {
+    const BPS_DENOMINATOR: u64 = 10000;
+
+    #[const_fun]
+    fun bps(amount: u64, basis_points: u64): u64 {
+        amount * basis_points / BPS_DENOMINATOR
+    }
+
+    #[const_fun]
+    fun fee(basis_points: u64): u64 {
+        bps(1000000, basis_points)
+    }
+
+    const MAX_FEE: u64 = fee(250);
+    const MIN_FEE: u64 = bps(1000000, 5) + 1;
+
+    public fun max_fee(): u64 { MAX_FEE }
+    public fun min_fee(): u64 { MIN_FEE }
+
+    // they are still normal functions outside of constants
+    public fun fee_of(amount: u64): u64 { bps(amount, 30) }
+}
@@ -0,0 +1,11 @@
+module a::m {
+    #[const_fun]
+    fun spin(n: u64): u64 {
+        while (n > 0) {};
+        n
+    }
+
+    const SPUN: u64 = spin(10);
+
+    public fun spun(): u64 { SPUN }
+}
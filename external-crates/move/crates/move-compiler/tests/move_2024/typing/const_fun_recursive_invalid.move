@@ -0,0 +1,26 @@
+module a::m {
+    #[const_fun]
+    fun is_even(n: u64): bool {
+        n == 0 || is_odd(n - 1)
+    }
+
+    #[const_fun]
+    fun is_odd(n: u64): bool {
+        n != 0 && is_even(n - 1)
+    }
+
+    const EVEN: bool = is_even(4);
+
+    public fun even(): bool { EVEN }
+}
+
+module a::n {
+    #[const_fun]
+    fun forever(n: u64): u64 {
+        forever(n) + 1
+    }
+
+    const N: u64 = forever(0);
+
+    public fun n(): u64 { N }
+}
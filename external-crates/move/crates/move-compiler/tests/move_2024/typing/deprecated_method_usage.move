@@ -0,0 +1,29 @@
+// deprecated functions are warned about when called as methods or targeted by a 'use fun'
+module a::m {
+    public struct X() has drop;
+
+    #[deprecated(note = b"Use 'X.new_size' instead")]
+    public fun size(_: &X): u64 { 0 }
+    public fun new_size(_: &X): u64 { 0 }
+
+    // expanding a macro from the defining module is allowed
+    public macro fun apply($x: &X): u64 {
+        size($x)
+    }
+}
+
+module a::n {
+    use a::m::X;
+
+    use fun a::m::size as X.old_size;
+
+    fun t(x: &X): u64 {
+        let a = x.size();
+        let b = x.old_size();
+        a + b
+    }
+
+    fun by_macro(x: &X): u64 {
+        a::m::apply!(x)
+    }
+}
@@ -0,0 +1,36 @@
+module 0x42::m {
+    public struct Small has copy, drop { a: u64, b: u64 }
+    public struct Eight has copy, drop { f0: u64, f1: u64, f2: u64, f3: u64, f4: u64, f5: u64, f6: u64, f7: u64 }
+    public struct Wide has copy, drop { e0: Eight, e1: Eight, extra: u64 }
+    public struct Sixteen has copy, drop { e0: Eight, e1: Eight }
+
+    fun freeze_left(s: &mut Small, t: &Small): bool {
+        s == t
+    }
+
+    fun freeze_right(s: &Small, t: &mut Small): bool {
+        s != t
+    }
+
+    fun large(w1: &Wide, w2: &Wide): bool {
+        w1 == w2 && *w1 != *w2
+    }
+
+    fun not_flagged(s1: &Small, s2: &Small, m1: &mut Small, m2: &mut Small, x: u64, y: u64): bool {
+        let a = x == y;
+        let b = s1 == s2;
+        let c = *s1 != *s2;
+        let d = m1 == m2;
+        let e = freeze(m1) == s1;
+        a && b && c && d && e
+    }
+
+    fun boundary(s1: Sixteen, s2: Sixteen): bool {
+        s1 == s2
+    }
+
+    #[allow(implicit_freeze_equality, large_struct_equality)]
+    fun allowed(w: &mut Wide, v: &Wide): bool {
+        w == v
+    }
+}
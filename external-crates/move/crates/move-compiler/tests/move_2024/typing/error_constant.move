@@ -0,0 +1,25 @@
+module a::m {
+    #[error]
+    const ENotAuthorized: vector<u8> = b"not authorized";
+    #[error]
+    const EInvalidState: u64 = 1;
+    const ECode: u64 = 2;
+
+    fun abort_with_error() {
+        abort ENotAuthorized
+    }
+
+    fun assert_with_error(cond: bool) {
+        assert!(cond, ENotAuthorized);
+        assert!(cond, EInvalidState);
+    }
+
+    fun branch(cond: bool): u64 {
+        if (cond) abort ENotAuthorized else 0
+    }
+
+    fun untagged_u64(cond: bool) {
+        assert!(cond, ECode);
+        abort ECode
+    }
+}
@@ -0,0 +1,20 @@
+module a::m {
+    const ENotTagged: vector<u8> = b"not tagged";
+
+    #[error]
+    fun not_a_constant() {}
+
+    #[error]
+    public struct NotAConstant {}
+
+    #[error(code = 1)]
+    const EWithArgs: vector<u8> = b"with args";
+
+    fun abort_untagged() {
+        abort ENotTagged
+    }
+
+    fun assert_untagged(cond: bool) {
+        assert!(cond, ENotTagged);
+    }
+}
@@ -0,0 +1,19 @@
+// 'u64' error constants abort with their own value, so they must stay below the abort codes of the
+// other error constants, which start at 1 << 63
+module a::m {
+    #[error]
+    const EMax: u64 = 9223372036854775807;
+    #[error]
+    const ETooLarge: u64 = 9223372036854775808;
+    #[error]
+    const EMaxU64: u64 = 18446744073709551615;
+    // not an error constant, so any value can be used
+    const NotAnError: u64 = 18446744073709551615;
+
+    public fun t(cond: bool) {
+        assert!(cond, EMax);
+        assert!(cond, ETooLarge);
+        assert!(cond, EMaxU64);
+        assert!(cond, NotAnError);
+    }
+}
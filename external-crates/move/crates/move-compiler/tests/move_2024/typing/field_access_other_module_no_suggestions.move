@@ -0,0 +1,12 @@
+// field access on a struct in another module has no accessor suggestions
+module a::n {
+    public struct Wrapper<T> has drop { value: T }
+}
+
+module a::m {
+    use a::n::Wrapper;
+
+    fun t(w: Wrapper<u64>): u64 {
+        w.value
+    }
+}
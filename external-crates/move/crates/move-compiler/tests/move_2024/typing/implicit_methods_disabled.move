@@ -0,0 +1,21 @@
+// method calls resolve through function declarations unless implicit methods are disabled, while
+// 'use fun' declarations and 'use' aliases are unaffected
+module a::m {
+    public struct S has drop { f: u64 }
+
+    public fun value(s: &S): u64 { s.f }
+
+    public fun double(s: &S): u64 { s.f * 2 }
+
+    public fun triple(s: &S): u64 { s.f * 3 }
+}
+
+module a::n {
+    use a::m::{S, triple};
+
+    use fun a::m::double as S.twice;
+
+    public fun t(s: &S): u64 {
+        s.value() + s.twice() + s.triple()
+    }
+}
@@ -0,0 +1,73 @@
+module 0x42::table {
+
+public struct Table<V> has drop {
+    keys: vector<u64>,
+    values: vector<V>,
+}
+
+public fun new<V>(): Table<V> {
+    Table { keys: vector[], values: vector[] }
+}
+
+public fun add<V>(self: &mut Table<V>, key: u64, value: V) {
+    self.keys.push_back(key);
+    self.values.push_back(value);
+}
+
+#[syntax(index)]
+public fun borrow<V>(self: &Table<V>, key: u64): &V {
+    let (found, i) = self.keys.index_of(&key);
+    assert!(found, 0);
+    self.values.borrow(i)
+}
+
+#[syntax(index)]
+public fun borrow_mut<V>(self: &mut Table<V>, key: u64): &mut V {
+    let (found, i) = self.keys.index_of(&key);
+    assert!(found, 0);
+    self.values.borrow_mut(i)
+}
+
+}
+
+module 0x42::m {
+use 0x42::table::{Self, Table};
+
+public struct Point has copy, drop { x: u64, y: u64 }
+
+public struct Grid has drop { points: Table<Point> }
+
+public fun lookup(t: &Table<u64>, key: u64): u64 {
+    t[key] + *&t[key]
+}
+
+public fun owned(mut t: Table<u64>): u64 {
+    t.add(0, 1);
+    let x = t[0];
+    let r = &t[0];
+    x + *r
+}
+
+public fun fields(grid: &Grid): u64 {
+    grid.points[0].x + (&grid.points[1]).y
+}
+
+public fun nested(t: &Table<Table<u64>>): u64 {
+    t[0][1]
+}
+
+public fun method(grid: &Grid): Point {
+    grid.points[0].copy_point()
+}
+
+fun copy_point(p: &Point): Point {
+    *p
+}
+
+public fun make(): Table<Point> {
+    let mut t = table::new();
+    t.add(0, Point { x: 0, y: 0 });
+    t
+}
+
+}
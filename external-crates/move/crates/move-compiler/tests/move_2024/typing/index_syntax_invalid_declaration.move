@@ -0,0 +1,25 @@
+module 0x42::m {
+
+public struct S has drop { v: vector<u64> }
+
+#[syntax(index)]
+public fun by_value(_self: S, _i: u64): &u64 {
+    abort 0
+}
+
+#[syntax(index)]
+public fun mismatched(_self: &mut S, _i: u64): &u64 {
+    abort 0
+}
+
+#[syntax(index)]
+public fun no_params(): &u64 {
+    abort 0
+}
+
+#[syntax]
+public fun missing_kind(self: &S, i: u64): &u64 {
+    self.v.borrow(i)
+}
+
+}
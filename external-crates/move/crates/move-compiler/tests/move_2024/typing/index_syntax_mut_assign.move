@@ -0,0 +1,61 @@
+module 0x42::counters {
+
+public struct Counters has drop { values: vector<u64> }
+
+public fun new(): Counters {
+    Counters { values: vector[0, 0, 0] }
+}
+
+public fun get(self: &Counters, i: u64): &u64 {
+    self.values.borrow(i)
+}
+
+public fun get_mut(self: &mut Counters, i: u64): &mut u64 {
+    self.values.borrow_mut(i)
+}
+
+}
+
+module 0x42::index {
+use 0x42::counters::Counters;
+
+#[syntax(index)]
+public fun at(c: &Counters, i: u64): &u64 {
+    c.get(i)
+}
+
+#[syntax(index)]
+public fun at_mut(c: &mut Counters, i: u64): &mut u64 {
+    c.get_mut(i)
+}
+
+}
+
+module 0x42::m {
+use 0x42::counters::{Self, Counters};
+use fun 0x42::index::at as Counters.at;
+use fun 0x42::index::at_mut as Counters.at_mut;
+
+public struct Holder has drop { counters: Counters }
+
+public fun increment(c: &mut Counters, i: u64) {
+    c[i] = c[i] + 1;
+}
+
+public fun reset(mut c: Counters): Counters {
+    c[0] = 0;
+    *&mut c[1] = 0;
+    let r = &mut c[2];
+    *r = 0;
+    c
+}
+
+public fun set(h: &mut Holder, i: u64, v: u64) {
+    h.counters[i] = v;
+}
+
+public fun make(): Holder {
+    Holder { counters: counters::new() }
+}
+
+}
@@ -0,0 +1,27 @@
+module 0x42::m {
+
+public struct NoIndex has drop { v: vector<u64> }
+public struct ImmOnly has drop { v: vector<u64> }
+
+#[syntax(index)]
+public fun borrow(self: &ImmOnly, i: u64): &u64 {
+    self.v.borrow(i)
+}
+
+public fun no_index(s: &NoIndex): u64 {
+    s[0]
+}
+
+public fun primitive(x: u64): u64 {
+    x[0]
+}
+
+public fun imm_only(s: &mut ImmOnly) {
+    s[0] = 1;
+}
+
+public fun invalid_index(s: &ImmOnly): u64 {
+    s[true]
+}
+
+}
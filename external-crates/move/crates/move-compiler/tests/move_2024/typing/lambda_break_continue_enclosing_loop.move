@@ -0,0 +1,41 @@
+module a::m {
+    macro fun do_ref<$T>($v: &vector<$T>, $f: |&$T|) {
+        let v = $v;
+        let mut i = 0;
+        let n = v.length();
+        while (i < n) {
+            $f(v.borrow(i));
+            i = i + 1;
+        }
+    }
+
+    // 'break' and 'continue' in a lambda refer to the loop around the macro call
+    fun t0(v: &vector<u64>): u64 {
+        let mut count = 0;
+        loop {
+            do_ref!(v, |x| {
+                if (*x == 0) break;
+                if (*x == 1) continue;
+                count = count + *x;
+            });
+            break
+        };
+        count
+    }
+
+    fun t1(v: &vector<u64>, cond: bool): u64 {
+        let mut count = 0;
+        while (cond) {
+            do_ref!(v, |x| if (*x == 0) break else count = count + 1);
+        };
+        count
+    }
+
+    // nested lambdas
+    fun t2(v: &vector<vector<u64>>) {
+        loop {
+            do_ref!(v, |inner| do_ref!(inner, |x| if (*x == 0) break));
+            break
+        }
+    }
+}
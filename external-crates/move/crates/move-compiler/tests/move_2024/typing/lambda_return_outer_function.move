@@ -0,0 +1,23 @@
+// 'return' inside of a lambda returns from the lambda, not from the enclosing function
+module a::m {
+    macro fun range_do($n: u64, $f: |u64| -> ()) {
+        let n = $n;
+        let mut i = 0;
+        while (i < n) {
+            $f(i);
+            i = i + 1;
+        }
+    }
+
+    fun contains(n: u64, x: u64): bool {
+        range_do!(n, |i| if (i == x) return true);
+        false
+    }
+
+    fun contains_labeled(n: u64, x: u64): bool {
+        'outer: {
+            range_do!(n, |i| if (i == x) return 'outer true);
+            false
+        }
+    }
+}
@@ -0,0 +1,39 @@
+module 0x42::m {
+    public struct Eight has copy, drop { f0: u64, f1: u64, f2: u64, f3: u64, f4: u64, f5: u64, f6: u64, f7: u64 }
+    public struct Nine has copy, drop { f0: u64, f1: u64, f2: u64, f3: u64, f4: u64, f5: u64, f6: u64, f7: u64, f8: u64 }
+    public struct Holder has copy, drop { eight: Eight, nine: Nine, n: u64, v: vector<u64> }
+
+    fun consume_eight(_: Eight) {}
+    fun consume_nine(_: Nine) {}
+    fun consume_vec(_: vector<u64>) {}
+
+    fun boundary(h: &Holder) {
+        let mut i = 0;
+        while (i < 10) {
+            consume_eight(h.eight);
+            consume_nine(h.nine);
+            i = i + h.n;
+        }
+    }
+
+    fun nested(h: &Holder) {
+        loop {
+            let mut i = 0;
+            while (i < 10) {
+                consume_nine(h.nine);
+                i = i + 1;
+            };
+            break
+        }
+    }
+
+    fun not_flagged(h: &Holder) {
+        consume_nine(h.nine);
+        let mut i = 0;
+        while (i < 10) {
+            consume_nine(copy h.nine);
+            consume_vec(h.v);
+            i = i + 1;
+        }
+    }
+}
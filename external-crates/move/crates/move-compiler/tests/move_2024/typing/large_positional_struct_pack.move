@@ -0,0 +1,27 @@
+module 0x42::m {
+    public struct Three(u64, u64, bool) has drop;
+    public struct Four(u64, u64, bool, address) has drop;
+    public struct NamedFour has drop { a: u64, b: u64, c: bool, d: address }
+
+    fun below_threshold(): Three {
+        Three(0, 1, false)
+    }
+
+    fun at_threshold(): Four {
+        Four(0, 1, false, @0x1)
+    }
+
+    fun named(): NamedFour {
+        NamedFour { a: 0, b: 1, c: false, d: @0x1 }
+    }
+
+    fun unpack_not_flagged(f: Four): u64 {
+        let Four(a, b, _, _) = f;
+        a + b
+    }
+
+    #[allow(large_positional_struct)]
+    fun allowed(): Four {
+        Four(0, 1, false, @0x1)
+    }
+}
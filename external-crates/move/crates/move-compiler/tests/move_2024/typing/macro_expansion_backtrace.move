@@ -0,0 +1,14 @@
+module a::m {
+    macro fun inner($x: u64): u64 {
+        let b: bool = $x;
+        b
+    }
+
+    macro fun outer($x: u64): u64 {
+        inner!($x)
+    }
+
+    fun t() {
+        outer!(0);
+    }
+}
@@ -0,0 +1,16 @@
+module a::m {
+    macro fun m0($x: u64): u64 {
+        let b: bool = $x;
+        b
+    }
+
+    macro fun m1($x: u64): u64 { m0!($x) }
+    macro fun m2($x: u64): u64 { m1!($x) }
+    macro fun m3($x: u64): u64 { m2!($x) }
+    macro fun m4($x: u64): u64 { m3!($x) }
+    macro fun m5($x: u64): u64 { m4!($x) }
+
+    fun t() {
+        m5!(0);
+    }
+}
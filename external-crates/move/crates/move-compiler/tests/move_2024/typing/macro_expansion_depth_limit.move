@@ -0,0 +1,18 @@
+// expanding 'one' nests three macro expansions, which is over the limit set for this test
+module a::m {
+    macro fun one(): u64 {
+        two!()
+    }
+
+    macro fun two(): u64 {
+        three!()
+    }
+
+    macro fun three(): u64 {
+        1
+    }
+
+    public fun t(): u64 {
+        one!()
+    }
+}
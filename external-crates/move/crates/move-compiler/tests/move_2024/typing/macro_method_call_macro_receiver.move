@@ -0,0 +1,37 @@
+// method calls on a receiver that is itself a macro call should resolve methods in the scope where
+// they were written
+module a::m {
+    public struct S has copy, drop { f: u64 }
+
+    public fun new(f: u64): S { S { f } }
+
+    public fun val(s: &S): u64 { s.f }
+
+    public macro fun id($s: S): S { $s }
+
+    public macro fun map($s: S, $g: |u64| -> u64): S {
+        let s = $s;
+        // resolves to a::m::val
+        S { f: $g(s.val()) }
+    }
+}
+
+module b::n {
+    use a::m::{Self, S};
+
+    fun is_zero(s: &S): bool { s.val() == 0 }
+
+    fun t(s: S): bool {
+        use fun is_zero as S.val;
+        s.id!().map!(|x| x + 1).id!().val()
+    }
+
+    fun t2(s: S): bool {
+        use fun is_zero as S.val;
+        s.id!().id!().map!(|x| if (m::new(x).val()) 1 else 0).val()
+    }
+
+    fun t3(s: S): u64 {
+        m::id!(m::id!(s).map!(|x| x).id!()).map!(|x| x * 2).val()
+    }
+}
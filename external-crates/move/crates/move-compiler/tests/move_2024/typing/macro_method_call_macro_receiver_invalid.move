@@ -0,0 +1,24 @@
+// errors in method calls on a receiver that is itself a macro call are reported once
+module a::m {
+    public struct S has copy, drop { f: u64 }
+
+    public fun new(f: u64): S { S { f } }
+
+    public fun val(s: &S): u64 { s.f }
+
+    public macro fun id($s: S): S { $s }
+
+    public macro fun map($s: S, $g: |u64| -> u64): S {
+        let s = $s;
+        S { f: $g(s.val()) }
+    }
+}
+
+module b::n {
+    use a::m::{Self, S};
+
+    fun t(s: S) {
+        s.id!().id!().something_else();
+        s.id!().map!(|x| m::new(x).something_else()).id!();
+    }
+}
@@ -0,0 +1,47 @@
+module a::other {
+    public fun size<T>(_: &T): u64 { 0 }
+
+    fun hidden<T>(_: &T): u64 { 0 }
+
+    public macro fun each<$T>($x: $T, $f: |$T|) {
+        $f($x)
+    }
+}
+
+module a::m {
+    public struct S has drop {}
+
+    public fun hidden(_: &S): u64 { 0 }
+
+    fun size_of<T>(x: &T): u64 {
+        x.size()
+    }
+
+    fun each_of<T: drop>(x: T) {
+        x.each!(|_| ())
+    }
+
+    fun private_alternative<T>(x: &T): u64 {
+        x.hidden()
+    }
+}
+
+module a::more {
+    public fun bump<T>(_: &mut T) {}
+
+    public fun dup<T: copy>(x: &T): T { *x }
+}
+
+module a::n {
+    fun bump_ref<T>(x: &T) {
+        x.bump()
+    }
+
+    fun bump_mut<T>(x: &mut T) {
+        x.bump()
+    }
+
+    fun dup_of<T>(x: &T): T {
+        x.dup()
+    }
+}
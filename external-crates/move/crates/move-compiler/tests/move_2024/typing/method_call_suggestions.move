@@ -0,0 +1,61 @@
+module a::m {
+    public struct S has copy, drop { x: u64 }
+
+    public fun value(s: &S): u64 { s.x }
+    public fun set_value(s: &mut S, x: u64) { s.x = x }
+    public fun vaule_or_zero(_: &S): u64 { 0 }
+    public macro fun apply($s: &S, $f: |u64|) { $f($s.x) }
+}
+
+module a::n {
+    use a::m::S;
+
+    public fun read(s: &S): u64 { s.value() }
+    use fun read as S.get;
+
+    // typos on struct methods, including a 'use fun' alias
+    fun t0(s: S) {
+        s.valeu();
+        s.gett();
+        s.set_vale(1);
+    }
+
+    // typos on vector methods
+    fun t1(v: vector<u64>) {
+        v.lenght();
+        v.push_bak(1);
+    }
+
+    // nothing close enough to suggest
+    fun t2(s: S) {
+        s.something_else();
+    }
+
+    // macro method calls
+    fun t3(s: S) {
+        s.aply!(|_| ());
+    }
+}
+
+module a::p {
+    public struct T has copy, drop { x: u64 }
+
+    fun clear(t: &mut T) { t.x = 0 }
+    macro fun inspect($t: &T, $f: |u64|) { $f($t.x) }
+
+    // private functions and macros are suggested in their own module
+    fun t0(t: &mut T) {
+        t.clea();
+        t.inspct!(|_| ());
+    }
+}
+
+module a::q {
+    use a::p::T;
+
+    // but not outside of it
+    fun t0(t: &mut T) {
+        t.clea();
+        t.inspct!(|_| ());
+    }
+}
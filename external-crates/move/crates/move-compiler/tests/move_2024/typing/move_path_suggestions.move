@@ -0,0 +1,21 @@
+module a::m {
+    public struct Coin has store, drop { value: u64 }
+    public struct Wallet has drop { coin: Coin, n: u64 }
+    public struct Outer has drop { wallet: Wallet }
+
+    fun move_field(w: Wallet): Coin {
+        move w.coin
+    }
+
+    fun move_nested(o: Outer): Coin {
+        move o.wallet.coin
+    }
+
+    fun move_nested_copyable(o: Outer): u64 {
+        move o.wallet.n
+    }
+
+    fun move_field_of_ref(w: &Wallet): u64 {
+        move w.n
+    }
+}
@@ -0,0 +1,16 @@
+// no suggestions are made for accessors that do not exist
+module std::option {
+    public struct Option<T> has drop { vec: vector<T> }
+
+    public fun none<T>(): Option<T> { Option { vec: vector[] } }
+
+    fun borrow<T>(_opt: &Option<T>): &T { abort 0 }
+}
+
+module a::m {
+    use std::option::Option;
+
+    fun t(opt: Option<u64>): vector<u64> {
+        opt.vec
+    }
+}
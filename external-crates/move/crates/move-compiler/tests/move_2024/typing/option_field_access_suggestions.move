@@ -0,0 +1,12 @@
+// accessing the fields of an option suggests the accessor functions
+module a::m {
+    use std::option::Option;
+
+    fun t1(opt: &Option<u64>): u64 {
+        *&opt.value
+    }
+
+    fun t2(opt: Option<u64>): vector<u64> {
+        opt.vec
+    }
+}
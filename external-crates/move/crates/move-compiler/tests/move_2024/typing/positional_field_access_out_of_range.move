@@ -0,0 +1,29 @@
+module a::m {
+    public struct Single(u64) has copy, drop;
+    public struct Pair(Single, u64) has copy, drop;
+    public struct Named { f: u64 } has copy, drop;
+
+    fun value(s: Single): u64 { s.0 }
+
+    fun by_value(p: Pair): u64 {
+        p.2 + p.0.1
+    }
+
+    fun by_ref(p: &Pair): u64 {
+        p.3
+    }
+
+    fun by_mut_ref(p: &mut Pair) {
+        p.0.1 = 0;
+        *&mut p.2 = 0;
+    }
+
+    fun method_receiver(p: &Pair): u64 {
+        p.2.value()
+    }
+
+    // named structs keep reporting unbound fields
+    fun named(n: Named): u64 {
+        n.0
+    }
+}
@@ -0,0 +1,28 @@
+module a::m {
+    public struct Inner(u64, bool) has copy, drop;
+    public struct Outer(Inner, u64) has copy, drop;
+
+    fun inner(i: &Inner): u64 { i.0 }
+
+    // borrowing a reference is invalid, but field access through it should not add errors
+    fun by_double_ref(o: &mut Outer): u64 {
+        let r = &o;
+        r.0.0 + r.1
+    }
+
+    fun by_double_mut_ref(mut o: &mut Outer) {
+        let r = &mut o;
+        r.0.0 = 1;
+        *&mut r.0.1 = true;
+    }
+
+    fun borrow_through(mut o: &mut Outer): &u64 {
+        let r = &mut o;
+        &r.0.0
+    }
+
+    fun method_receiver(o: &mut Outer): u64 {
+        let r = &o;
+        r.0.inner() + o.0.inner()
+    }
+}
@@ -0,0 +1,47 @@
+// '&mut' parameters that are only read through are reported, unless they are passed to a '&mut'
+// parameter, returned, mutated through, or mutably borrowed from
+module a::m {
+    public struct S has copy, drop { f: u64 }
+
+    public fun read(s: &mut S): u64 {
+        s.f
+    }
+
+    public fun compare(s: &mut S, other: &S): bool {
+        *s == *other
+    }
+
+    public fun read_through(s: &mut S): u64 {
+        imm(s)
+    }
+
+    public fun imm(s: &S): u64 {
+        s.f
+    }
+
+    public fun set(s: &mut S, v: u64) {
+        s.f = v
+    }
+
+    public fun set_through(s: &mut S) {
+        set(s, 0)
+    }
+
+    public fun replace(s: &mut S, v: u64) {
+        *s = S { f: v }
+    }
+
+    public fun id(s: &mut S): &mut S {
+        s
+    }
+
+    public fun field(s: &mut S): &mut u64 {
+        &mut s.f
+    }
+
+    public fun ignored(_s: &mut S) {}
+
+    public macro fun read_macro($s: &mut S): u64 {
+        $s.f
+    }
+}
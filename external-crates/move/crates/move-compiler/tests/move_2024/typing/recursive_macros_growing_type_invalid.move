@@ -0,0 +1,26 @@
+module a::m {
+    // invalid cycle, the type argument grows at each expansion
+    macro fun grow<$T>($x: $T): u64 {
+        grow!<vector<$T>>(vector[$x])
+    }
+
+    // invalid cycle of more than 1 node, the type argument grows at each expansion
+    macro fun ping<$T>($x: $T): u64 {
+        pong!<vector<$T>>(vector[$x])
+    }
+
+    macro fun pong<$T>($x: $T): u64 {
+        ping!<$T>($x)
+    }
+
+    // invalid cycle, but the type argument does not grow
+    macro fun same<$T>($x: $T): u64 {
+        same!<$T>($x)
+    }
+
+    fun t() {
+        grow!<u64>(0);
+        ping!<u64>(0);
+        same!<u64>(0);
+    }
+}
@@ -0,0 +1,29 @@
+module a::m {
+    public struct S<T> has copy, drop { f: T }
+
+    public fun id<T>(x: T): T { x }
+    public fun pair<T1, T2>(_: T1, _: T2) {}
+    public fun empty<T>(): vector<T> { vector[] }
+    public fun get<T>(s: &S<T>): &T { &s.f }
+
+    // the type arguments are determined by the arguments
+    fun redundant<T: drop>(s: S<u64>, v: vector<bool>, addr: address, t: T) {
+        id<S<u64>>(s);
+        pair<vector<bool>, address>(v, addr);
+        a::m::id<address>(addr);
+        get<u64>(&s);
+        s.get<u64>();
+        id<T>(t);
+    }
+
+    // the type arguments cannot be inferred from the arguments, or inference would pick others
+    fun not_redundant(s: S<u64>, x: u64) {
+        empty<u64>();
+        id<u8>(0);
+        id<u64>(0);
+        id<vector<u64>>(vector[]);
+        pair<u64, u16>(x, 1);
+        id(x);
+        pair(s, x);
+    }
+}
@@ -0,0 +1,5 @@
+module a::m {
+    public struct S { f: |u64| -> bool }
+
+    public struct V { v: vector<|u64| -> bool> }
+}
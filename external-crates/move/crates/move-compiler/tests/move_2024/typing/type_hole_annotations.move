@@ -0,0 +1,25 @@
+module 0x42::m {
+    fun pair(): (u64, bool) { (0, false) }
+
+    fun tuple_partial(): u64 {
+        let (a, _b): (u64, _) = pair();
+        a
+    }
+
+    fun nested_vector(): vector<vector<u8>> {
+        let v: vector<_> = vector[1u8, 2];
+        let w: vector<vector<_>> = vector[v];
+        w
+    }
+
+    fun annotation(): u64 {
+        (0: _)
+    }
+
+    fun consume<T>() {}
+
+    // a placeholder is not a type by itself, it still has to be inferred
+    fun uninferred() {
+        consume<_>();
+    }
+}
@@ -0,0 +1,13 @@
+module 0x42::m {
+    public struct S { f: _ }
+
+    const C: vector<_> = vector[0];
+
+    fun sig(x: _): vector<_> { vector[x] }
+
+    fun cast(x: u64): u8 { (x as _) }
+
+    fun type_args() {
+        let _x: _<u64> = 0;
+    }
+}
@@ -0,0 +1,11 @@
+module a::m {
+    // called from a constant initializer, which is not part of the call graph, and from a function
+    // that is never called
+    #[const_fun]
+    fun scaled(x: u64): u64 { x * 10 }
+    fun unused(): u64 { scaled(1) }
+
+    const SCALED: u64 = scaled(2);
+
+    public fun scaled_const(): u64 { SCALED }
+}
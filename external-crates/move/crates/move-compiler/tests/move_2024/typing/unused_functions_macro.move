@@ -0,0 +1,14 @@
+module a::m {
+    public fun f(): u64 { call!() }
+
+    // calls in macro bodies are attributed to the caller of the macro
+    macro fun call(): u64 { used_by_macro() }
+    fun used_by_macro(): u64 { 0 }
+
+    // the macro, and so its calls, are only expanded in a function that is never called
+    macro fun unused_call(): u64 { used_by_unused_macro() }
+    fun used_by_unused_macro(): u64 { 1 }
+    fun unused(): u64 { unused_call!() }
+
+    macro fun never_called(): u64 { 2 }
+}
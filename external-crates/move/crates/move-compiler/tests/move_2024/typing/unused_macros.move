@@ -0,0 +1,27 @@
+module a::m {
+    public fun f(): u64 { used!() }
+
+    macro fun used(): u64 { 0 }
+
+    macro fun never_expanded(): u64 { 1 }
+
+    // only expanded by a macro that is never expanded
+    macro fun expanded_by_unused_macro(): u64 { 2 }
+    macro fun unused_outer(): u64 { expanded_by_unused_macro!() }
+
+    // expanded, along with the macro expanding it, in a function that is never called
+    macro fun expanded_by_unused_function(): u64 { 3 }
+    macro fun outer(): u64 { expanded_by_unused_function!() }
+    fun unused(): u64 { outer!() }
+
+    macro fun only_in_tests(): u64 { 4 }
+
+    #[test_only]
+    macro fun test_only_macro(): u64 { 5 }
+
+    #[test]
+    fun test() {
+        only_in_tests!();
+        test_only_macro!();
+    }
+}
@@ -0,0 +1,23 @@
+module a::m {
+    public(package) fun used(): u64 { 0 }
+
+    public(package) fun unused(): u64 { 1 }
+
+    public(package) fun only_used_in_module(): u64 { 2 }
+
+    public(package) fun only_used_by_test_only_code(): u64 { 3 }
+
+    #[allow(unused_package_function)]
+    public(package) fun unused_allowed(): u64 { 4 }
+
+    public(package) macro fun unused_macro(): u64 { 5 }
+
+    public fun call(): u64 { only_used_in_module() }
+}
+
+module a::n {
+    public fun call(): u64 { a::m::used() }
+
+    #[test_only]
+    public fun test_helper(): u64 { a::m::only_used_by_test_only_code() }
+}
@@ -0,0 +1,18 @@
+// 'public' structs are never reported as unused, as other packages may use them
+module a::m {
+    public struct Unused has drop {}
+
+    // used only as a phantom type argument
+    public struct Marker {}
+
+    public struct Wrapper<phantom T> has drop {}
+
+    #[allow(unused_struct)]
+    public struct Suppressed {}
+
+    public native struct Native;
+
+    public fun wrapper(): Wrapper<Marker> {
+        Wrapper {}
+    }
+}
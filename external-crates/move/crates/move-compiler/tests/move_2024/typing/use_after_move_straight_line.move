@@ -0,0 +1,22 @@
+// moving a non-copy local twice in the same block is reported during typing
+module a::m {
+    public struct R {}
+
+    public fun take(self: R) {
+        let R {} = self;
+    }
+
+    fun double_move() {
+        let x = R {};
+        let y = move x;
+        let z = move x;
+        y.take();
+        z.take();
+    }
+
+    fun use_after_method() {
+        let x = R {};
+        x.take();
+        x.take();
+    }
+}
@@ -0,0 +1,60 @@
+// block-level use funs inside lambda arguments are resolved in the scope of the lambda, even once
+// the lambda is expanded inside of the macro body
+module a::m {
+    public struct Foo has copy, drop { x: u64 }
+
+    public fun get(foo: &Foo): bool { foo.x == 0 }
+
+    public fun my_get(foo: &Foo): u64 { foo.x }
+
+    public macro fun apply<$T>($foo: Foo, $f: |Foo| -> $T): $T {
+        let foo = $foo;
+        // resolves to 'get' regardless of the use funs in the lambda
+        if (foo.get()) $f(foo) else $f(foo)
+    }
+
+    public macro fun apply_nested<$T>($foo: Foo, $f: |Foo| -> $T): $T {
+        let foo = $foo;
+        apply!(foo, |x| $f(x))
+    }
+}
+
+module a::in_lambda {
+    use a::m::{Self, Foo};
+
+    fun t(foo: Foo): u64 {
+        m::apply!(foo, |x| {
+            use fun a::m::my_get as Foo.get;
+            x.get()
+        })
+    }
+}
+
+module a::in_nested_macro {
+    use a::m::{Self, Foo};
+
+    fun t(foo: Foo): u64 {
+        m::apply_nested!(foo, |x| {
+            use fun a::m::my_get as Foo.get;
+            x.get()
+        })
+    }
+}
+
+module a::shadowing {
+    use a::m::{Self, Foo};
+
+    fun other_get(_: &Foo): vector<u8> { b"" }
+
+    use fun other_get as Foo.get;
+
+    fun t(foo: Foo): u64 {
+        let n = m::apply!(foo, |x| {
+            use fun a::m::my_get as Foo.get;
+            x.get()
+        });
+        // the use fun in the lambda does not leak out of it
+        let v: vector<u8> = foo.get();
+        if (v == b"") n else 0
+    }
+}
@@ -0,0 +1,21 @@
+module a::x {
+    public struct X() has drop;
+
+    public use fun destroy_x as X.destroy;
+    public use fun consume as X.eat;
+
+    public macro fun destroy_x($x: X) {
+        let _ = $x;
+    }
+
+    public fun consume(_: X) {}
+}
+
+module a::user {
+    use a::x::X;
+
+    fun t(x1: X, x2: X) {
+        x1.destroy();
+        x2.eat!();
+    }
+}
@@ -0,0 +1,29 @@
+module a::x {
+    public struct X(u64) has copy, drop;
+
+    public use fun apply_to_value as X.apply;
+
+    public fun new(v: u64): X {
+        X(v)
+    }
+
+    public fun value(self: &X): u64 {
+        self.0
+    }
+
+    public macro fun apply_to_value($x: X, $f: |u64| -> u64): u64 {
+        let x = $x;
+        $f(x.value())
+    }
+}
+
+module a::user {
+    use a::x;
+
+    fun t() {
+        let v = x::new(1);
+        let res = v.apply!(|n| n + 1);
+        assert!(res == 2, 0);
+        assert!(x::new(2).apply!(|n| n * 2) == 4, 0);
+    }
+}
@@ -0,0 +1,18 @@
+// 'use' aliases take precedence over function declarations, and explicit 'use fun' declarations
+// take precedence over both
+module a::m {
+    public struct X() has drop;
+    public fun baz(_: &X): u64 { 0 }
+    public fun other(_: &X): bool { false }
+    public fun another(_: &X): vector<u8> { vector[] }
+
+    fun t1(x: &X) {
+        use a::m::other as baz;
+        (x.baz(): bool);
+    }
+
+    fun t2(x: &X) {
+        use fun another as X.baz;
+        (x.baz(): vector<u8>);
+    }
+}
@@ -0,0 +1,16 @@
+// explicit 'use fun' declarations take precedence over 'use' aliases, even from an outer scope
+module a::m {
+    public struct X() has drop;
+    public fun foo(_: &X): u64 { 0 }
+    public fun bar(_: &X): bool { false }
+}
+
+module a::n {
+    use a::m::X;
+    use fun a::m::bar as X.baz;
+
+    fun t(x: &X) {
+        use a::m::foo as baz;
+        (x.baz(): bool);
+    }
+}
@@ -0,0 +1,28 @@
+// method resolution in macro bodies follows the same precedence as in normal code
+module a::m {
+    public struct X() has drop;
+    public fun foo(_: &X): u64 { 0 }
+    public fun bar(_: &X): bool { false }
+    public fun x(): X { X() }
+
+    use fun bar as X.baz;
+
+    public macro fun in_macro($x: &X): bool {
+        use a::m::foo as baz;
+        let x = $x;
+        x.baz()
+    }
+
+    fun in_function(x: &X): bool {
+        use a::m::foo as baz;
+        x.baz()
+    }
+}
+
+module a::n {
+    use a::m::{X, in_macro};
+
+    fun t(x: &X) {
+        (in_macro!(x): bool);
+    }
+}
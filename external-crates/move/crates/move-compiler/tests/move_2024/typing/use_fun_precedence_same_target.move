@@ -0,0 +1,15 @@
+// no ambiguity if the candidates resolve to the same function
+module a::m {
+    public struct X() has drop;
+    public fun foo(_: &X): u64 { 0 }
+
+    fun t(x: &X) {
+        use a::m::foo;
+        use fun foo as X.bar;
+        {
+            use a::m::foo as bar;
+            (x.bar(): u64);
+        };
+        (x.foo(): u64);
+    }
+}
@@ -0,0 +1,28 @@
+module 0x42::m {
+    public struct X() has drop;
+
+    public fun foo(_x: &X, _n: u64): u64 { 0 }
+    public fun bar(_x: &X, _n: u64): u64 { 1 }
+    public fun baz(_x: &X, flag: bool): u64 { if (flag) 2 else 3 }
+
+    use fun foo as X.f;
+
+    fun outer(x: &X): u64 {
+        x.f(0)
+    }
+
+    fun shadowed(x: &X): u64 {
+        use fun bar as X.f;
+        x.f(0)
+    }
+
+    fun identical(x: &X): u64 {
+        use fun foo as X.f;
+        x.f(0)
+    }
+
+    fun shadowed_invalid_call(x: &X): u64 {
+        use fun baz as X.f;
+        x.f(0)
+    }
+}
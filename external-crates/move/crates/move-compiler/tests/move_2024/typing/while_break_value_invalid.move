@@ -0,0 +1,9 @@
+module a::m {
+    fun t0(cond: bool): u64 {
+        while (cond) { break 5 }
+    }
+
+    fun t1(): u64 {
+        while (false || false) { break 5 }
+    }
+}
@@ -0,0 +1,18 @@
+module a::m {
+    fun t0(): u64 {
+        while (true) { break 5 }
+    }
+
+    fun t1(cond: bool): vector<u8> {
+        let v = while (!false && true) {
+            if (cond) break b"hello";
+        };
+        v
+    }
+
+    fun t2(): u64 {
+        'a: while (true) {
+            while (true) { break 'a 0 };
+        }
+    }
+}
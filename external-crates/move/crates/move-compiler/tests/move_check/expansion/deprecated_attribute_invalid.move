@@ -0,0 +1,13 @@
+module a::m {
+    #[deprecated = b"old"]
+    public fun f() {}
+
+    #[deprecated(message = b"old")]
+    public fun g() {}
+
+    #[deprecated(note = 0)]
+    public fun h() {}
+
+    #[deprecated(note = b"old")]
+    public fun ok() {}
+}
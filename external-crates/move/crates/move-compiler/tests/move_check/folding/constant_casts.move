@@ -0,0 +1,14 @@
+module 0x42::m {
+    // casts that fit in the target type
+    const FITS: u8 = (255u16 as u8);
+    const WIDENS: u256 = (255u8 as u256);
+    const FOLDED_FITS: u8 = ((200u16 + 55) as u8);
+
+    // casts that do not fit in the target type are not truncated
+    const OVERFLOWS: u8 = (300u16 as u8);
+    const FOLDED_OVERFLOWS: u8 = ((200u16 + 100) as u8);
+    // chains of casts are evaluated from the innermost cast outwards
+    const CHAIN_FITS: u8 = ((255u64 as u16) as u8);
+    const CHAIN_OVERFLOWS: u8 = ((300u64 as u32) as u8);
+    const CHAIN_OVERFLOWS_FIRST: u16 = ((65536u64 as u8) as u16);
+}
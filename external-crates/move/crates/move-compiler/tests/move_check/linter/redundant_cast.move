@@ -0,0 +1,46 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    struct S has drop { f: u8 }
+
+    public fun same_type(x: u64): u64 {
+        (x as u64)
+    }
+
+    public fun field(s: &S): u8 {
+        (s.f as u8)
+    }
+
+    public fun in_binop(x: u128, y: u128): u128 {
+        (x as u128) + y
+    }
+
+    public fun in_let(x: u16): u16 {
+        let y = (x as u16);
+        y
+    }
+
+    public fun in_assign(x: u32, cond: bool): u32 {
+        let y = 0;
+        if (cond) { y = (x as u32) };
+        y
+    }
+
+    public fun widening(x: u8): u64 {
+        (x as u64)
+    }
+
+    public fun narrowing(x: u256): u32 {
+        (x as u32)
+    }
+
+    public fun literal(): u8 {
+        (1 as u8)
+    }
+
+    #[allow(lint(redundant_cast))]
+    public fun suppressed(x: u64): u64 {
+        (x as u64)
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    struct S has copy, drop { f: u64 }
+
+    fun make(): S {
+        S { f: 0 }
+    }
+
+    public fun borrow_deref(r: &u64): u64 {
+        let y = &*r;
+        *y
+    }
+
+    public fun mut_borrow_deref(r: &mut S) {
+        let y = &mut *r;
+        y.f = 0;
+    }
+
+    public fun deref_borrow(x: u64): u64 {
+        *&x
+    }
+
+    public fun deref_mut_borrow(x: u64): u64 {
+        *&mut x
+    }
+
+    public fun deref_borrow_call(): S {
+        *&make()
+    }
+
+    public fun parenthesized(r: &u64): u64 {
+        let y = &(*r);
+        *y
+    }
+
+    public fun freezes(r: &mut u64): u64 {
+        let y = &*r;
+        *y
+    }
+
+    public fun field(s: &S): u64 {
+        s.f
+    }
+
+    #[allow(lint(redundant_ref_deref))]
+    public fun suppressed(x: u64): u64 {
+        *&x
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    struct S has copy, drop { f: u64, inner: Inner }
+    struct Inner has copy, drop { g: u64 }
+
+    public fun local(x: u64): u64 {
+        x = x;
+        x
+    }
+
+    public fun deref(r: &mut u64) {
+        *r = *r;
+    }
+
+    public fun field(s: S): S {
+        s.f = s.f;
+        s.inner.g = s.inner.g;
+        s
+    }
+
+    public fun field_through_ref(s: &mut S) {
+        s.f = s.f;
+    }
+
+    public fun get(s: &S): u64 {
+        s.f
+    }
+
+    public fun not_self_assigned(x: u64, y: u64, s: &mut S, r1: &mut u64, r2: &mut u64) {
+        x = y;
+        *r1 = *r2;
+        s.f = s.inner.g;
+        s.f = get(s);
+        let t = *s;
+        s.inner.g = t.inner.g;
+    }
+
+    #[allow(lint(self_assignment))]
+    public fun suppressed(x: u64, r: &mut u64): u64 {
+        x = x;
+        *r = *r;
+        x
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    public fun literal(): u64 {
+        let x = 0;
+        while (true) {
+            x = x + 1;
+            if (x == 10) break;
+        };
+        x
+    }
+
+    public fun folded(): u64 {
+        while (!false) { break 5 }
+    }
+
+    public fun condition(cond: bool) {
+        while (cond) {}
+    }
+
+    #[allow(lint(while_true))]
+    public fun suppressed(): u64 {
+        while (true) { break 5 }
+    }
+}
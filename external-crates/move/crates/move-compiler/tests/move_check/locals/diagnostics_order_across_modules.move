@@ -0,0 +1,19 @@
+// Modules are checked in the order of their names, not in source order, and the unused assignment
+// warnings are reported before the errors at the same location. Diagnostics are still reported in
+// source order, with errors before warnings at the same location
+module 0x8675309::b {
+    struct R {}
+
+    fun t(cond: bool) {
+        let r;
+        while (cond) { r = R{} };
+    }
+}
+
+module 0x8675309::a {
+    struct R {}
+
+    fun t() {
+        loop { let r = R{}; }
+    }
+}
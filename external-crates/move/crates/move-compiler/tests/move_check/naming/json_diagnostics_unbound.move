@@ -0,0 +1,6 @@
+module 0x42::m {
+    fun f() {
+        0x42::n::g();
+        let _: S = y;
+    }
+}
@@ -0,0 +1,10 @@
+module 0x42::m {
+    struct Table<K, V> has copy, drop { k: K, v: V }
+
+    // the missing type arguments are filled in with 'K' and 'V', which are then used
+    public fun set<K: copy + drop, V: copy + drop>(t: Table, k: K): Table {
+        let _v = t.v;
+        t.k = k;
+        t
+    }
+}
@@ -0,0 +1,21 @@
+module 0x42::m {
+    const CAPACITY: u64 = 100;
+    const MIN_SIZE: u64 = 0;
+
+    fun t0(): u64 {
+        // typos of constants in this module
+        Self::CAPACTY + Self::MIN_SIZ
+    }
+
+    fun t1(): u64 {
+        // nothing close enough to suggest
+        Self::SOMETHING_ELSE
+    }
+}
+
+module 0x42::n {
+    fun t0(): u64 {
+        // typos of constants in another module are still suggested
+        0x42::m::CAPASITY
+    }
+}
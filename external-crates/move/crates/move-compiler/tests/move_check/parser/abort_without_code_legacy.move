@@ -0,0 +1,10 @@
+module 0x42::m {
+    fun statement(cond: bool) {
+        if (cond) abort;
+        abort 0
+    }
+
+    fun tail(): u64 {
+        abort
+    }
+}
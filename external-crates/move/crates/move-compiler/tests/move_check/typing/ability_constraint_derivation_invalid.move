@@ -0,0 +1,23 @@
+module 0x42::m {
+    struct NoDrop {}
+    struct Inner<T> has drop { value: T }
+    struct Outer<T> has drop { inner: Inner<T> }
+    struct Tagged<phantom T, U> has drop { u: U }
+
+    fun needs_drop<T: drop>() {}
+
+    // the missing ability comes from a field of a field
+    fun two_levels() {
+        needs_drop<Outer<NoDrop>>();
+    }
+
+    // the requirement is followed through a struct with a phantom type parameter
+    fun through_phantom() {
+        needs_drop<Tagged<u64, Inner<NoDrop>>>();
+    }
+
+    // the missing ability comes from an immediate type argument, so there is no chain
+    fun phantom_adjacent() {
+        needs_drop<Tagged<u64, NoDrop>>();
+    }
+}
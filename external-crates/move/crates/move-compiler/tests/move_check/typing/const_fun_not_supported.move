@@ -0,0 +1,10 @@
+module 0x42::m {
+    #[const_fun]
+    fun double(n: u64): u64 {
+        n * 2
+    }
+
+    const TWO: u64 = double(1);
+
+    public fun two(): u64 { TWO }
+}
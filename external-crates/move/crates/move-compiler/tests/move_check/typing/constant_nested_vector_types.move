@@ -0,0 +1,6 @@
+module 0x42::M {
+    const V3: vector<vector<vector<u8>>> = vector[vector[b"a"], vector[]];
+    const V4: vector<vector<vector<vector<u64>>>> = vector[vector[vector[vector[0]]], vector[]];
+    const E3: vector<vector<vector<address>>> = vector[vector[vector[@0x1]], vector[vector[]]];
+    const EMPTY: vector<vector<vector<bool>>> = vector[];
+}
@@ -0,0 +1,8 @@
+module 0x42::M {
+    struct S has copy, drop {}
+
+    const S3: vector<vector<vector<signer>>> = vector[];
+    const S4: vector<vector<vector<vector<S>>>> = vector[];
+    const R3: vector<vector<vector<&u8>>> = vector[];
+    const U3: vector<vector<vector<()>>> = vector[];
+}
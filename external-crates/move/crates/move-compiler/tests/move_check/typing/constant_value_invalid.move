@@ -0,0 +1,6 @@
+module 0x42::m {
+    const OVERFLOW: u8 = 200 + 100;
+    const DIV_ZERO: u64 = 1 / 0;
+    const SHIFT: u64 = 1 << 200;
+    const IN_VECTOR: vector<u8> = vector[1, 2 * 128, 3];
+}
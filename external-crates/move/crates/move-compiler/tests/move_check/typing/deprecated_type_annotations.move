@@ -0,0 +1,41 @@
+// deprecated structs named in the type annotations of function bodies are warned about
+module a::m {
+    #[deprecated(note = b"Use 'a::m::T' instead")]
+    struct S has copy, drop { x: u64 }
+
+    public fun s(): S { S { x: 0 } }
+
+    // usages inside of the defining module are allowed
+    public fun x(): u64 {
+        let s: S = S { x: 0 };
+        s.x
+    }
+}
+
+module a::n {
+    use a::m::{Self, S};
+
+    public fun annotated_let() {
+        let s: S = m::s();
+        s;
+    }
+
+    public fun annotated_exp() {
+        (m::s(): S);
+    }
+
+    public fun declared() {
+        let s: S;
+        s = m::s();
+        s;
+    }
+
+    public fun nested(): vector<S> {
+        (vector[]: vector<S>)
+    }
+
+    #[allow(deprecated_usage)]
+    public fun allowed() {
+        (m::s(): S);
+    }
+}
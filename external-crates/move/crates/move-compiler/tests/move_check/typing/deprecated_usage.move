@@ -0,0 +1,46 @@
+// usages of deprecated items outside of the defining module are warned about
+module a::m {
+    #[deprecated(note = b"Use 'a::m::new_f' instead")]
+    public fun f(): u64 { 0 }
+    public fun new_f(): u64 { 0 }
+
+    #[deprecated]
+    struct S has drop { x: u64 }
+
+    #[deprecated(note = b"The limit is no longer enforced")]
+    const LIMIT: u64 = 100;
+
+    // usages inside of the defining module are allowed
+    public fun s(): S { S { x: LIMIT } }
+    public fun x(s: &S): u64 { s.x + f() }
+}
+
+module a::n {
+    use a::m::{Self, S};
+
+    // a deprecated struct used as a field type
+    struct T has drop { s: S }
+
+    public fun t(): u64 {
+        m::f()
+    }
+
+    public fun id(s: S): S {
+        s
+    }
+
+    public fun inner(t: &T): u64 {
+        m::x(&t.s)
+    }
+
+    // usages inside of deprecated functions are allowed
+    #[deprecated]
+    public fun old(): u64 {
+        m::f()
+    }
+
+    #[allow(deprecated_usage)]
+    public fun allowed(): u64 {
+        m::f()
+    }
+}
@@ -0,0 +1,32 @@
+module 0x42::m {
+    struct S has copy, drop { f: u64 }
+
+    fun value(x: u64, s: S) {
+        *x;
+        *s;
+        *0;
+    }
+
+    fun deref_chain(x: u64) {
+        // the inner dereference is valid, the outer one is not
+        **&x;
+        *&*&x;
+    }
+
+    fun generic<T: copy>(t: T) {
+        *t;
+    }
+
+    fun unit() {
+        *();
+    }
+
+    fun tuple(x: u64) {
+        *(x, true);
+    }
+
+    fun valid(x: &u64, s: &mut S): u64 {
+        let S { f } = *s;
+        *x + f + *&*&f
+    }
+}
@@ -0,0 +1,8 @@
+module 0x42::m {
+    #[error]
+    const ENotAuthorized: vector<u8> = b"not authorized";
+
+    fun f() {
+        abort ENotAuthorized
+    }
+}
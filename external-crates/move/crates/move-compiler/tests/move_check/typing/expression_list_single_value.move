@@ -0,0 +1,17 @@
+module 0x8675309::M {
+    struct S has drop { f: u64 }
+
+    fun take(_x: u64) {}
+
+    fun t0() {
+        let _x = (0u64, false);
+    }
+
+    fun t1(): S {
+        S { f: (0, false) }
+    }
+
+    fun t2() {
+        take((0, false));
+    }
+}
@@ -0,0 +1,13 @@
+module 0x42::m {
+    fun missing_else_bound(cond: bool) {
+        let _x = if (cond) 5;
+    }
+
+    fun missing_else_returned(cond: bool): u64 {
+        if (cond) 5
+    }
+
+    fun mismatched_branches(cond: bool) {
+        let _x = if (cond) 5 else false;
+    }
+}
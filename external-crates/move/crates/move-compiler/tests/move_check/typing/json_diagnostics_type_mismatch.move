@@ -0,0 +1,6 @@
+module 0x42::m {
+    fun f(): u64 {
+        let x: bool = 0;
+        x
+    }
+}
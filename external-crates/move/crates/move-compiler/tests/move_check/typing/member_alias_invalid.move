@@ -0,0 +1,13 @@
+module 0x42::m {
+    use 0x42::m::{S as Bar, foo as baz};
+
+    struct S has drop { f: u64 }
+
+    fun foo(_x: u64) {}
+
+    fun t0() {
+        Bar { f: false };
+        Bar { f: 0, g: 0 };
+        baz(false);
+    }
+}
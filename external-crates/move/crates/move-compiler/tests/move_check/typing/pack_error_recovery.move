@@ -0,0 +1,18 @@
+module 0x42::m {
+    struct S has drop { f: u64, g: bool }
+
+    // no further errors are reported for the fields of 's'
+    fun t0(): S {
+        let s = x;
+        S { f: s.f, g: s.g }
+    }
+
+    // the value of a diverging expression is accepted for any field, but the fields are checked
+    fun t1(): S {
+        S { f: abort 0, g: true, h: 0 }
+    }
+
+    fun t2(): S {
+        S { f: return S { f: 0, g: false } }
+    }
+}
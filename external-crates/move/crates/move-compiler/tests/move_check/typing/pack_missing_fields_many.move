@@ -0,0 +1,11 @@
+module 0x42::m {
+    struct S has drop { z: u64, y: bool, c: address, x: vector<u8>, w: u8, v: u16 }
+
+    fun missing() {
+        S { c: @0x1 };
+    }
+
+    fun unbound() {
+        S { z: 0, y: false, c: @0x1, x: vector[], w: 0, v: 0, q: 0, p: 0 };
+    }
+}
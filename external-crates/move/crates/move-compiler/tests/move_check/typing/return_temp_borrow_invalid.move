@@ -0,0 +1,22 @@
+module 0x42::m {
+    struct Foo has drop { f: u64 }
+
+    fun make(): Foo { Foo { f: 0 } }
+
+    fun t0(): &Foo {
+        &Foo { f: 0 }
+    }
+
+    fun t1(): &Foo {
+        &make()
+    }
+
+    fun t2(): &mut vector<u64> {
+        &mut vector[0]
+    }
+
+    fun t3(cond: bool): &Foo {
+        if (cond) return &make();
+        abort 0
+    }
+}
@@ -0,0 +1,22 @@
+module 0x42::m {
+    struct Foo has drop { f: u64 }
+
+    fun field(foo: &Foo): &u64 { &foo.f }
+
+    fun t0(foo: &Foo): &u64 {
+        &foo.f
+    }
+
+    fun t1(foo: &mut Foo): &mut u64 {
+        &mut foo.f
+    }
+
+    fun t2(foo: &Foo): &u64 {
+        field(foo)
+    }
+
+    fun t3(foo: &Foo, cond: bool): &u64 {
+        if (cond) return &foo.f;
+        field(foo)
+    }
+}
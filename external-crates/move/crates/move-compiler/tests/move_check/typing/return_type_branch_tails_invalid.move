@@ -0,0 +1,26 @@
+module 0x42::M {
+    fun t0(cond: bool, x: &mut u64, y: &mut u64, z: &u64): &mut u64 {
+        if (cond) {
+            x
+        } else if (*z == 0) {
+            y
+        } else {
+            z
+        }
+    }
+
+    fun t1(cond: bool): u64 {
+        if (cond) {
+            true
+        } else {
+            abort 0
+        }
+    }
+
+    fun t2(): u64 {
+        {
+            let x = false;
+            x
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// usages from tests count as usages of test-only constants, but not of regular ones
+module 0x42::m {
+    #[test_only]
+    const TEST_VALUE: u64 = 42;
+
+    const ONLY_IN_TESTS: u64 = 0;
+
+    #[test]
+    fun test_value() {
+        assert!(TEST_VALUE == 42, ONLY_IN_TESTS);
+    }
+}
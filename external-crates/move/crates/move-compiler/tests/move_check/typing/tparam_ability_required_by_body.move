@@ -0,0 +1,29 @@
+module 0x42::m {
+    struct NeedsKey<phantom T: key> has drop {}
+
+    fun needs_copy<T: copy>(x: &T): T {
+        *x
+    }
+
+    // 'key' is required by packing, and only reported once
+    fun by_pack<T: copy + drop>(x: T): T {
+        let _ = NeedsKey<T> {};
+        let _ = NeedsKey<T> {};
+        x
+    }
+
+    // 'drop' is required by '=='
+    fun by_eq<T: copy>(x: T, y: T): bool {
+        x == y
+    }
+
+    // 'copy' is required by a call to another generic function
+    fun by_call<T: drop>(x: &T): T {
+        needs_copy(x)
+    }
+
+    // the callers are not affected
+    fun call(): u64 {
+        by_pack(0)
+    }
+}
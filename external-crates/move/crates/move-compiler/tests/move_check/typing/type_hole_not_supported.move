@@ -0,0 +1,6 @@
+module 0x42::m {
+    fun t(): u64 {
+        let x: _ = 0;
+        x
+    }
+}
@@ -0,0 +1,34 @@
+module 0x42::m {
+    friend 0x42::n;
+
+    public fun f() {
+        used_by_public()
+    }
+
+    public(friend) fun g() {
+        used_by_friend()
+    }
+
+    entry fun e() {
+        used_by_entry()
+    }
+
+    fun used_by_public() { used_transitively() }
+    fun used_by_friend() {}
+    fun used_by_entry() {}
+    fun used_transitively() {}
+
+    // only called by functions that are themselves never called
+    fun unused_root() { unused_callee(); used_transitively() }
+    fun unused_callee() { unused_callee_callee() }
+    fun unused_callee_callee() {}
+
+    // only called recursively
+    fun recursive(x: u64) { if (x > 0) recursive(x - 1) }
+    fun mutually_recursive_a() { mutually_recursive_b() }
+    fun mutually_recursive_b() { mutually_recursive_a() }
+}
+
+module 0x42::n {
+    public fun call_friend() { 0x42::m::g() }
+}
@@ -0,0 +1,30 @@
+module 0x42::n {
+    struct Wrapper<phantom T> has drop {}
+
+    public fun wrapper<T>(): Wrapper<T> {
+        Wrapper {}
+    }
+
+    public fun drop_wrapper<T>(_w: Wrapper<T>) {}
+}
+
+module 0x42::m {
+    use 0x42::n::{Self, Wrapper};
+
+    struct Unused has drop {}
+
+    // used only as phantom type arguments, in a signature and in a call
+    struct Marker {}
+    struct OtherMarker {}
+
+    #[allow(unused_struct)]
+    struct Suppressed {}
+
+    public fun wrapper(): Wrapper<Marker> {
+        n::wrapper()
+    }
+
+    public fun other() {
+        n::drop_wrapper<OtherMarker>(n::wrapper())
+    }
+}
@@ -0,0 +1,7 @@
+module 0x42::m {
+    fun t() {
+        // the incompatible element is reported against the elements before it
+        vector[0, 1, 2, false, 3, 4];
+        vector[0u8, 1, 2, 3u64, 4];
+    }
+}
@@ -0,0 +1,21 @@
+// test-only members cannot be used outside of a test, as they are removed from non-test builds
+module 0x42::m {
+    #[test_only]
+    struct Helper has drop { x: u64 }
+
+    #[test_only]
+    const LIMIT: u64 = 10;
+
+    #[test_only]
+    public fun helper(): u64 { 0 }
+
+    public fun uses_struct(_h: &Helper) {}
+
+    public fun uses_constant(): u64 {
+        LIMIT
+    }
+
+    public fun uses_function(): u64 {
+        helper()
+    }
+}
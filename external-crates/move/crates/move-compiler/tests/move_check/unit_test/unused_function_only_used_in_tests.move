@@ -0,0 +1,24 @@
+module 0x42::m {
+    public fun f(): u64 { used() }
+
+    fun used(): u64 { 0 }
+
+    // only called from tests
+    fun test_helper(): u64 { test_helper_callee() }
+    fun test_helper_callee(): u64 { 1 }
+
+    // already marked as test only
+    #[test_only]
+    fun test_only_helper(): u64 { 2 }
+
+    #[allow(unused_function)]
+    fun allowed_test_helper(): u64 { 3 }
+
+    #[test]
+    fun test_f() {
+        assert!(f() == 0, 0);
+        assert!(test_helper() == 1, 0);
+        assert!(test_only_helper() == 2, 0);
+        assert!(allowed_test_helper() == 3, 0);
+    }
+}
@@ -12,9 +12,13 @@ use move_compiler::{
     command_line::compiler::move_check_for_errors,
     diagnostics::*,
     editions::{Edition, Flavor},
-    shared::{Flags, NumericalAddress, PackageConfig, PackagePaths},
-    sui_mode::linters::{known_filters, linter_visitors},
-    Compiler, PASS_PARSER,
+    linters,
+    shared::{
+        Flags, NumericalAddress, PackageConfig, PackagePaths, FILTER_IMPLICIT_FREEZE_EQUALITY,
+        FILTER_LARGE_COPY_IN_LOOP, FILTER_LARGE_POSITIONAL_STRUCT, FILTER_LARGE_STRUCT_EQUALITY,
+        FILTER_READ_ONLY_MUT_PARAM, FILTER_REDUNDANT_TYPE_ARGS, FILTER_SHADOWED_VARIABLE,
+    },
+    sui_mode, Compiler, PASS_PARSER,
 };
 
 /// Shared flag to keep any temporary results of the test
@@ -49,6 +53,40 @@ fn default_testing_addresses(flavor: Flavor) -> BTreeMap<String, NumericalAddres
         .collect()
 }
 
+/// The marker extensions of the additional runs of a test, with the flags of each run. Warnings
+/// that are off by default are enabled by the name of their filter
+fn marked_runs() -> Vec<(&'static str, Flags)> {
+    let warn = |filters: &[&str]| {
+        Flags::empty().set_warn(filters.iter().map(|name| name.to_string()).collect())
+    };
+    vec![
+        ("json", Flags::empty().set_json_errors(true)),
+        ("shadowing", warn(&[FILTER_SHADOWED_VARIABLE])),
+        ("redundant_type_args", warn(&[FILTER_REDUNDANT_TYPE_ARGS])),
+        ("large_copies", warn(&[FILTER_LARGE_COPY_IN_LOOP])),
+        (
+            "equality",
+            warn(&[
+                FILTER_IMPLICIT_FREEZE_EQUALITY,
+                FILTER_LARGE_STRUCT_EQUALITY,
+            ]),
+        ),
+        (
+            "positional_structs",
+            warn(&[FILTER_LARGE_POSITIONAL_STRUCT]),
+        ),
+        (
+            "no_implicit_methods",
+            Flags::empty().set_no_implicit_methods(true),
+        ),
+        ("read_only_mut_params", warn(&[FILTER_READ_ONLY_MUT_PARAM])),
+        (
+            "macro_depth",
+            Flags::empty().set_max_macro_expansion_depth(2),
+        ),
+    ]
+}
+
 fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
     let lint = path.components().any(|c| c.as_os_str() == LINTER_DIR);
     let flavor = if path.components().any(|c| c.as_os_str() == SUI_MODE_DIR) {
@@ -149,6 +187,29 @@ fn testsuite(path: &Path, mut config: PackageConfig, lint: bool) -> datatest_sta
         )?;
     }
 
+    // A test is marked that it should also be checked with the flags of a marked run by having a
+    // `path.<ext>` file, e.g. `path.shadowing`, with the expected output in `path.<ext>.exp`
+    for (ext, flags) in marked_runs() {
+        if !path.with_extension(ext).exists() {
+            continue;
+        }
+        let stem = path.with_extension("").to_string_lossy().to_string();
+        let marked_exp_path = format!("{}.{}.{}", stem, ext, EXP_EXT);
+        let marked_out_path = format!("{}.{}.{}", stem, ext, OUT_EXT);
+        let mut config = config.clone();
+        config
+            .warning_filter
+            .union(&WarningFilters::unused_warnings_filter_for_test());
+        run_test(
+            path,
+            Path::new(&marked_exp_path),
+            Path::new(&marked_out_path),
+            flags,
+            config,
+            lint,
+        )?;
+    }
+
     let exp_path = path.with_extension(EXP_EXT);
     let out_path = path.with_extension(OUT_EXT);
 
@@ -203,17 +264,26 @@ pub fn run_test_inner(
         named_address_map,
     }];
 
+    let format = flags.diagnostics_format();
     let flags = flags.set_sources_shadow_deps(true);
 
+    let sui_mode = default_config.flavor == Flavor::Sui;
     let mut compiler = Compiler::from_package_paths(targets, deps)
         .unwrap()
         .set_flags(flags)
         .set_default_config(default_config);
 
     if lint {
-        let (prefix, filters) = known_filters();
+        let ((prefix, filters), visitors) = if sui_mode {
+            (
+                sui_mode::linters::known_filters(),
+                sui_mode::linters::linter_visitors(),
+            )
+        } else {
+            (linters::known_filters(), linters::linter_visitors())
+        };
         compiler = compiler
-            .add_visitors(linter_visitors())
+            .add_visitors(visitors)
             .add_custom_known_filters(prefix, filters);
     }
 
@@ -224,6 +294,8 @@ pub fn run_test_inner(
     let diag_buffer = if has_diags {
         if migration_mode {
             report_migration_to_buffer(&files, diags)
+        } else if format == DiagnosticsFormat::Json {
+            report_diagnostics_to_json_buffer(&files, diags)
         } else {
             report_diagnostics_to_buffer(&files, diags)
         }
@@ -0,0 +1,13 @@
+// invalid, references to non key structs are not supported
+
+module a::m {
+    struct S has copy, drop, store { value: u64 }
+
+    public entry fun no(_: &S) {
+        abort 0
+    }
+
+    public entry fun no_mut(_: &mut S) {
+        abort 0
+    }
+}
@@ -0,0 +1,12 @@
+// return values from entry functions must have drop, including each element of a tuple
+
+module a::m {
+    struct NoDrop has store {}
+    public entry fun t0(): NoDrop {
+        abort 0
+    }
+    public entry fun t1(): (u64, NoDrop, bool) {
+        abort 0
+    }
+    public entry fun t2() {}
+}
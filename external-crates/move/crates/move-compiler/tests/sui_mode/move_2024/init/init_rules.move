@@ -0,0 +1,35 @@
+// each module violates one rule for 'init', except the last one
+module a::package_visibility {
+    public(package) fun init(_ctx: &mut sui::tx_context::TxContext) {}
+}
+
+module a::generic {
+    fun init<T>(_ctx: &mut sui::tx_context::TxContext) {}
+}
+
+module a::returns_value {
+    fun init(_ctx: &mut sui::tx_context::TxContext): u64 { 0 }
+}
+
+module a::no_tx_context {
+    fun init(_ctx: u64) {}
+}
+
+module a::too_many {
+    public struct TOO_MANY has drop {}
+    fun init(_otw: TOO_MANY, _x: u64, _ctx: &mut sui::tx_context::TxContext) {}
+}
+
+module a::not_otw {
+    public struct Other has drop {}
+    fun init(_otw: Other, _ctx: &mut sui::tx_context::TxContext) {}
+}
+
+module a::compliant {
+    public struct COMPLIANT has drop {}
+    fun init(_otw: COMPLIANT, _ctx: &mut sui::tx_context::TxContext) {}
+}
+
+module sui::tx_context {
+    public struct TxContext has drop {}
+}
@@ -0,0 +1,4 @@
+// native structs are not checked
+module sui::m {
+    public native struct S has key;
+}
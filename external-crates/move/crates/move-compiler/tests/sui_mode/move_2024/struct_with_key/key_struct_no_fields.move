@@ -0,0 +1,6 @@
+// invalid, objects must have at least an 'id' field
+module a::m {
+    public struct S has key {}
+
+    public struct R() has key;
+}
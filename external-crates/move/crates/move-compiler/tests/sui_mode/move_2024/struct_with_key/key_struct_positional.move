@@ -0,0 +1,14 @@
+// invalid, positional structs cannot be objects, even with a UID as their first field
+module a::m {
+    use sui::object::UID;
+
+    public struct S(UID) has key;
+
+    public struct R(bool, UID) has key;
+}
+
+module sui::object {
+    public struct UID has store {
+        id: address,
+    }
+}
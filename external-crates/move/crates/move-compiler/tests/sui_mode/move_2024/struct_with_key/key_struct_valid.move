@@ -0,0 +1,19 @@
+// valid
+module a::m {
+    use sui::object::UID;
+
+    public struct S has key {
+        id: UID,
+    }
+
+    public struct R has key, store {
+        id: UID,
+        flag: bool,
+    }
+}
+
+module sui::object {
+    public struct UID has store {
+        id: address,
+    }
+}
@@ -0,0 +1,4 @@
+// the one-time witness is not reported as unused, even if it is never used
+module a::m {
+    struct M has drop {}
+}
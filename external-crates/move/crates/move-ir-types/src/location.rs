@@ -63,6 +63,11 @@ impl Loc {
         self.end
     }
 
+    /// Whether `other` is within this location, in the same file
+    pub fn contains(self, other: Loc) -> bool {
+        self.file_hash == other.file_hash && self.start <= other.start && other.end <= self.end
+    }
+
     pub fn usize_range(self) -> Range<usize> {
         Range {
             start: self.start as usize,
@@ -28,9 +28,9 @@ use move_compiler::{
     compiled_unit::{AnnotatedCompiledUnit, CompiledUnit, NamedCompiledModule},
     diagnostics::FilesSourceText,
     editions::Flavor,
+    linters,
     shared::{NamedAddressMap, NumericalAddress, PackageConfig, PackagePaths},
-    sui_mode::linters::{known_filters, linter_visitors},
-    Compiler,
+    sui_mode, Compiler,
 };
 use move_docgen::{Docgen, DocgenOptions};
 use move_model::{model::GlobalEnv, options::ModelBuilderOptions, run_model_builder_with_options};
@@ -489,12 +489,17 @@ impl CompiledPackage {
         let mut compiler = Compiler::from_package_paths(paths, bytecode_deps)
             .unwrap()
             .set_flags(flags);
-        if sui_mode {
-            let (filter_attr_name, filters) = known_filters();
-            compiler = compiler.add_custom_known_filters(filter_attr_name, filters);
-            if lint {
-                compiler = compiler.add_visitors(linter_visitors())
-            }
+        let ((filter_attr_name, filters), visitors) = if sui_mode {
+            (
+                sui_mode::linters::known_filters(),
+                sui_mode::linters::linter_visitors(),
+            )
+        } else {
+            (linters::known_filters(), linters::linter_visitors())
+        };
+        compiler = compiler.add_custom_known_filters(filter_attr_name, filters);
+        if lint {
+            compiler = compiler.add_visitors(visitors)
         }
         Ok(BuildResult {
             root_package_name,